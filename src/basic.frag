@@ -3,7 +3,16 @@
 precision mediump float;
 
 uniform vec4 u_color;
+uniform sampler2D u_diffuse;
+uniform int u_textured;// 0 = sólo color, 1 = color * textura
+
+in vec2 v_tex_coord;
+
+out vec4 frag_color;
 
 void main() {
-    gl_FragColor = u_color;
+    frag_color = u_color;
+    if (u_textured != 0) {
+        frag_color *= texture(u_diffuse, v_tex_coord);
+    }
 }
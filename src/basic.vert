@@ -1,10 +1,14 @@
 #version 430
 
-attribute vec3 a_position;
+in vec3 a_position;
+in vec2 a_tex_coord;
 
 uniform mat4 u_projection;
 uniform mat4 u_model;// matriz de transformación
 
+out vec2 v_tex_coord;
+
 void main() {
+    v_tex_coord = a_tex_coord;
     gl_Position = u_projection * u_model * vec4(a_position, 1.0);
 }
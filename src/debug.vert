@@ -0,0 +1,13 @@
+#version 430
+
+in vec3 a_position;
+in vec4 a_color;
+
+uniform mat4 u_projection;
+
+out vec4 v_color;
+
+void main() {
+    v_color = a_color;
+    gl_Position = u_projection * vec4(a_position, 1.0);
+}
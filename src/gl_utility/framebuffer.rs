@@ -0,0 +1,367 @@
+use crate::gl_utility::texture::{Texture, TextureFilter, TextureFormat};
+use crate::graphics::color::Color;
+use crate::math::rect::Rect;
+
+// Buffer de profundidad (y stencil) del framebuffer. Va en un renderbuffer porque no
+// se suele leer como textura
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum DepthAttachment {
+    None,
+    Depth,
+    DepthStencil,
+}
+
+// Descripción de un framebuffer antes de crearlo
+pub struct FramebufferBuilder {
+    name: String,
+    width: u32,
+    height: u32,
+    colors: Vec<TextureFormat>,
+    depth: DepthAttachment,
+    samples: u32,
+    filter: TextureFilter,
+}
+
+impl FramebufferBuilder {
+    // Añade una textura de color. Con varias se dibuja en todas a la vez (MRT): la salida
+    // layout(location = i) del fragment shader va a la textura i
+    pub fn with_color(mut self, format: TextureFormat) -> FramebufferBuilder {
+        self.colors.push(format);
+        self
+    }
+
+    pub fn with_depth(mut self, depth: DepthAttachment) -> FramebufferBuilder {
+        self.depth = depth;
+        self
+    }
+
+    // Antialiasing multisample. Se dibuja en renderbuffers con samples muestras por
+    // píxel y resolve() las combina en las texturas
+    pub fn with_samples(mut self, samples: u32) -> FramebufferBuilder {
+        self.samples = samples;
+        self
+    }
+
+    // Filtro de las texturas de color (Nearest para escalar pixel art)
+    pub fn with_filter(mut self, filter: TextureFilter) -> FramebufferBuilder {
+        self.filter = filter;
+        self
+    }
+
+    pub fn build(self) -> Result<Framebuffer, String> {
+        let mut framebuffer = Framebuffer {
+            name: self.name,
+            width: 0,
+            height: 0,
+            formats: if self.colors.is_empty() { vec![TextureFormat::Rgba8] } else { self.colors },
+            depth: self.depth,
+            samples: self.samples,
+            filter: self.filter,
+
+            id: 0,
+            resolve_id: 0,
+            textures: Vec::new(),
+            color_renderbuffers: Vec::new(),
+            depth_renderbuffer: 0,
+        };
+
+        framebuffer.resize(self.width, self.height)?;
+        Ok(framebuffer)
+    }
+}
+
+// Destino de dibujo fuera de pantalla. Las texturas de color se pueden poner en un
+// Sprite (minimapas, espejos) o dibujar ampliadas en pantalla (pixel art a baja resolución).
+// OpenGL guarda la fila 0 abajo, así que en un Sprite hay que usar uv() como región
+// para que no salga dada la vuelta
+pub struct Framebuffer {
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+
+    formats: Vec<TextureFormat>,
+    depth: DepthAttachment,
+    samples: u32,
+    filter: TextureFilter,
+
+    // Donde se dibuja. Sin multisample las texturas van aquí directamente
+    id: u32,
+    // Con multisample, framebuffer con las texturas donde se resuelven los renderbuffers
+    resolve_id: u32,
+    textures: Vec<Texture>,
+    color_renderbuffers: Vec<u32>,
+    depth_renderbuffer: u32,
+}
+
+impl Drop for Framebuffer {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+impl Framebuffer {
+    // Por defecto una textura RGBA8, sin profundidad ni multisample
+    pub fn builder(name: &str, width: u32, height: u32) -> FramebufferBuilder {
+        FramebufferBuilder {
+            name: String::from(name),
+            width,
+            height,
+            colors: Vec::new(),
+            depth: DepthAttachment::None,
+            samples: 0,
+            filter: TextureFilter::Linear,
+        }
+    }
+
+    pub fn is_multisampled(&self) -> bool {
+        self.samples > 1
+    }
+
+    pub fn color_count(&self) -> usize {
+        self.formats.len()
+    }
+
+    // Textura de color i. Con multisample tiene lo dibujado hasta el último resolve()
+    pub fn texture(&self, index: usize) -> &Texture {
+        &self.textures[index]
+    }
+
+    // Región para Sprite::set_region que muestra la textura del derecho
+    pub fn uv() -> Rect {
+        Rect::new(0.0, 1.0, 1.0, -1.0)
+    }
+
+    // Vuelve a crear todas las texturas y buffers con el nuevo tamaño (el contenido se pierde)
+    pub fn resize(&mut self, width: u32, height: u32) -> Result<(), String> {
+        if width == 0 || height == 0 {
+            return Err(format!("El framebuffer {} no puede medir {}x{}", self.name, width, height));
+        }
+
+        self.release();
+        self.width = width;
+        self.height = height;
+
+        unsafe {
+            gl::GenFramebuffers(1, &mut self.id);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.id);
+        }
+
+        for (i, &format) in self.formats.iter().enumerate() {
+            let texture = Texture::empty(&format!("{}_{}", self.name, i), width, height, format);
+            texture.set_filter(self.filter);
+            self.textures.push(texture);
+        }
+
+        if self.is_multisampled() {
+            for (i, &format) in self.formats.iter().enumerate() {
+                let renderbuffer = self.create_renderbuffer(format.internal_format());
+                unsafe {
+                    gl::FramebufferRenderbuffer(gl::FRAMEBUFFER, gl::COLOR_ATTACHMENT0 + i as u32, gl::RENDERBUFFER, renderbuffer);
+                }
+                self.color_renderbuffers.push(renderbuffer);
+            }
+        } else {
+            self.attach_textures();
+        }
+
+        let depth = match self.depth {
+            DepthAttachment::None => None,
+            DepthAttachment::Depth => Some((gl::DEPTH_COMPONENT24, gl::DEPTH_ATTACHMENT)),
+            DepthAttachment::DepthStencil => Some((gl::DEPTH24_STENCIL8, gl::DEPTH_STENCIL_ATTACHMENT)),
+        };
+        if let Some((format, attachment)) = depth {
+            self.depth_renderbuffer = self.create_renderbuffer(format);
+            unsafe {
+                gl::FramebufferRenderbuffer(gl::FRAMEBUFFER, attachment, gl::RENDERBUFFER, self.depth_renderbuffer);
+            }
+        }
+
+        self.set_draw_buffers();
+        let mut result = Framebuffer::check_status(&self.name);
+
+        if result.is_ok() && self.is_multisampled() {
+            unsafe {
+                gl::GenFramebuffers(1, &mut self.resolve_id);
+                gl::BindFramebuffer(gl::FRAMEBUFFER, self.resolve_id);
+            }
+            self.attach_textures();
+            self.set_draw_buffers();
+            result = Framebuffer::check_status(&self.name);
+        }
+
+        // También si ha fallado, para no dejar enlazado un framebuffer incompleto
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        }
+        result
+    }
+
+    fn attach_textures(&self) {
+        for (i, texture) in self.textures.iter().enumerate() {
+            unsafe {
+                gl::FramebufferTexture2D(gl::FRAMEBUFFER, gl::COLOR_ATTACHMENT0 + i as u32, gl::TEXTURE_2D, texture.id(), 0);
+            }
+        }
+    }
+
+    // Dibuja en todas las texturas de color (por defecto sólo se dibuja en la primera)
+    fn set_draw_buffers(&self) {
+        let buffers: Vec<gl::types::GLenum> = (0..self.formats.len() as u32).map(|i| gl::COLOR_ATTACHMENT0 + i).collect();
+        unsafe {
+            gl::DrawBuffers(buffers.len() as i32, buffers.as_ptr());
+        }
+    }
+
+    fn create_renderbuffer(&self, format: gl::types::GLenum) -> u32 {
+        let mut renderbuffer = 0;
+        unsafe {
+            gl::GenRenderbuffers(1, &mut renderbuffer);
+            gl::BindRenderbuffer(gl::RENDERBUFFER, renderbuffer);
+            if self.is_multisampled() {
+                gl::RenderbufferStorageMultisample(gl::RENDERBUFFER, self.samples as i32, format, self.width as i32, self.height as i32);
+            } else {
+                gl::RenderbufferStorage(gl::RENDERBUFFER, format, self.width as i32, self.height as i32);
+            }
+            gl::BindRenderbuffer(gl::RENDERBUFFER, 0);
+        }
+        renderbuffer
+    }
+
+    fn check_status(name: &str) -> Result<(), String> {
+        let status = unsafe { gl::CheckFramebufferStatus(gl::FRAMEBUFFER) };
+        if status != gl::FRAMEBUFFER_COMPLETE {
+            return Err(format!("El framebuffer {} está incompleto ({:#X})", name, status));
+        }
+        Ok(())
+    }
+
+    fn release(&mut self) {
+        unsafe {
+            if self.id != 0 {
+                gl::DeleteFramebuffers(1, &self.id);
+            }
+            if self.resolve_id != 0 {
+                gl::DeleteFramebuffers(1, &self.resolve_id);
+            }
+            if !self.color_renderbuffers.is_empty() {
+                gl::DeleteRenderbuffers(self.color_renderbuffers.len() as i32, self.color_renderbuffers.as_ptr());
+            }
+            if self.depth_renderbuffer != 0 {
+                gl::DeleteRenderbuffers(1, &self.depth_renderbuffer);
+            }
+        }
+
+        self.id = 0;
+        self.resolve_id = 0;
+        self.textures.clear();
+        self.color_renderbuffers.clear();
+        self.depth_renderbuffer = 0;
+    }
+
+    // A partir de aquí se dibuja en el framebuffer. Ajusta el viewport a su tamaño
+    pub fn bind(&self) {
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.id);
+            gl::Viewport(0, 0, self.width as i32, self.height as i32);
+        }
+    }
+
+    // Vuelve a dibujar en la ventana
+    pub fn bind_default(window_width: u32, window_height: u32) {
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            gl::Viewport(0, 0, window_width as i32, window_height as i32);
+        }
+    }
+
+    // Borra color, profundidad y stencil (los que tenga). Hay que llamarlo con el framebuffer activo
+    pub fn clear(&self, color: Color) {
+        let mut mask = gl::COLOR_BUFFER_BIT;
+        match self.depth {
+            DepthAttachment::None => {}
+            DepthAttachment::Depth => mask |= gl::DEPTH_BUFFER_BIT,
+            DepthAttachment::DepthStencil => mask |= gl::DEPTH_BUFFER_BIT | gl::STENCIL_BUFFER_BIT,
+        }
+
+        unsafe {
+            gl::ClearColor(color.r, color.g, color.b, color.a);
+            gl::Clear(mask);
+        }
+    }
+
+    // Con multisample, combina las muestras de cada renderbuffer en su textura.
+    // Hay que llamarlo después de dibujar y antes de usar las texturas. Sin multisample no hace nada
+    pub fn resolve(&self) {
+        if !self.is_multisampled() {
+            return;
+        }
+
+        unsafe {
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, self.id);
+            gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, self.resolve_id);
+
+            for i in 0..self.formats.len() as u32 {
+                gl::ReadBuffer(gl::COLOR_ATTACHMENT0 + i);
+                let draw_buffer = gl::COLOR_ATTACHMENT0 + i;
+                gl::DrawBuffers(1, &draw_buffer);
+                gl::BlitFramebuffer(
+                    0, 0, self.width as i32, self.height as i32,
+                    0, 0, self.width as i32, self.height as i32,
+                    gl::COLOR_BUFFER_BIT,
+                    gl::NEAREST,
+                );
+            }
+
+            // Dejamos el framebuffer de resolución dibujando en todas sus texturas otra vez
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.resolve_id);
+            self.set_draw_buffers();
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        }
+    }
+
+    // Copia la textura de color 0 a la ventana en el rectángulo (x, y, ancho, alto) en
+    // píxeles de la ventana (y hacia abajo). Resuelve antes el multisample si hace falta
+    pub fn blit_to_screen(&self, window_height: u32, destination: Rect, filter: TextureFilter) {
+        self.resolve();
+
+        let source = if self.is_multisampled() { self.resolve_id } else { self.id };
+        let gl_filter = match filter {
+            TextureFilter::Nearest => gl::NEAREST,
+            TextureFilter::Linear => gl::LINEAR,
+        };
+
+        // En la ventana la y de OpenGL va hacia arriba
+        let x0 = destination.x as i32;
+        let x1 = (destination.x + destination.width) as i32;
+        let y0 = window_height as i32 - (destination.y + destination.height) as i32;
+        let y1 = window_height as i32 - destination.y as i32;
+
+        unsafe {
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, source);
+            gl::ReadBuffer(gl::COLOR_ATTACHMENT0);
+            gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, 0);
+            gl::BlitFramebuffer(
+                0, 0, self.width as i32, self.height as i32,
+                x0, y0, x1, y1,
+                gl::COLOR_BUFFER_BIT,
+                gl_filter,
+            );
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        }
+    }
+
+    // Rectángulo centrado en la ventana con el mayor escalado entero que cabe, para
+    // ampliar pixel art sin que unos píxeles salgan más gordos que otros
+    pub fn integer_scaled_rect(&self, window_width: u32, window_height: u32) -> Rect {
+        let scale = (window_width / self.width).min(window_height / self.height).max(1);
+        let width = self.width * scale;
+        let height = self.height * scale;
+
+        Rect::new(
+            (window_width as i32 - width as i32) as f32 / 2.0,
+            (window_height as i32 - height as i32) as f32 / 2.0,
+            width as f32,
+            height as f32,
+        )
+    }
+}
@@ -11,8 +11,16 @@ pub struct GLBuffer {
     data_len: usize,
     stride: i32,
 
+    // Primitiva con la que se dibuja (TRIANGLES, LINES, LINE_STRIP...)
+    mode: gl::types::GLenum,
+    // Pista para el driver: STATIC_DRAW si casi no cambia, STREAM_DRAW si se sube cada frame
+    usage: gl::types::GLenum,
+
     vao: u32,
     vbo: u32,
+    // Buffer de índices, 0 hasta que se sube alguno con upload_indices
+    ebo: u32,
+    index_count: usize,
 
     //data: Vec<f32>,
 }
@@ -21,11 +29,20 @@ impl Drop for GLBuffer {
     fn drop(&mut self) {
         unsafe {
             gl::DeleteBuffers(1, &self.vbo);
+            if self.ebo != 0 {
+                gl::DeleteBuffers(1, &self.ebo);
+            }
             gl::DeleteVertexArrays(1, &self.vao);
         }
     }
 }
 
+impl Default for GLBuffer {
+    fn default() -> GLBuffer {
+        GLBuffer::new()
+    }
+}
+
 impl GLBuffer {
     pub fn new() -> GLBuffer {
         let mut gl_buffer = GLBuffer {
@@ -35,8 +52,13 @@ impl GLBuffer {
             data_len: 0,
             stride: 0,
 
+            mode: gl::TRIANGLES,
+            usage: gl::STATIC_DRAW,
+
             vao: 0,
             vbo: 0,
+            ebo: 0,
+            index_count: 0,
 
             //data: Vec::new(),
         };
@@ -49,35 +71,70 @@ impl GLBuffer {
         gl_buffer
     }
 
+    pub fn set_mode(&mut self, mode: gl::types::GLenum) {
+        self.mode = mode;
+    }
+
+    pub fn set_usage(&mut self, usage: gl::types::GLenum) {
+        self.usage = usage;
+    }
+
     pub fn configure(&mut self, attributes: Vec<AttributeInfo>, normalized: bool) {
-        unsafe {
-            gl::BindVertexArray(self.vao);
-            gl::BindBuffer(gl::ARRAY_BUFFER, self.vbo);
+        let (element_size, stride) = GLBuffer::set_attributes(self.vao, self.vbo, &attributes, normalized, 0, self.type_size);
+        self.element_size = element_size;
+        self.stride = stride;
+    }
 
-            self.element_size = attributes
-                .iter()
-                .map(|attribute| attribute.component_size)
-                .sum();
-            self.stride = self.element_size * self.type_size as i32;
+    // Configura los atributos por instancia que se leen de instances (uno por copia en
+    // vez de uno por vértice). Se guardan en el VAO de este buffer, así que después
+    // basta con draw_instanced. Las matrices (16 componentes) ocupan 4 posiciones seguidas
+    pub fn configure_instances(&mut self, instances: &mut GLBuffer, attributes: Vec<AttributeInfo>, divisor: u32) {
+        let (element_size, stride) = GLBuffer::set_attributes(self.vao, instances.vbo, &attributes, false, divisor, instances.type_size);
+        instances.element_size = element_size;
+        instances.stride = stride;
+    }
 
-            let mut offset = 0;
+    // Devuelve el tamaño de un elemento en componentes y el stride en bytes
+    fn set_attributes(vao: u32, vbo: u32, attributes: &[AttributeInfo], normalized: bool, divisor: u32, type_size: usize) -> (i32, i32) {
+        let element_size: i32 = attributes
+            .iter()
+            .map(|attribute| attribute.component_size)
+            .sum();
+        let stride = element_size * type_size as i32;
 
-            for attribute in &attributes {
-                gl::VertexAttribPointer(
-                    attribute.location,     // Indice del atributo de vertices (a_position)
-                    attribute.component_size,         // Número de componentes de cada vértice
-                    gl::FLOAT,              // Tipo de dato
-                    normalized as gl::types::GLboolean,              // Normalizado
-                    self.stride,            // stride (byte offset entre atributos)
-                    offset as *const std::ffi::c_void,     // Offset en bytes
-                );
-                gl::EnableVertexAttribArray(attribute.location);
+        unsafe {
+            gl::BindVertexArray(vao);
+            gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
 
-                offset += attribute.component_size;
+            let mut offset = 0;
+
+            for attribute in attributes {
+                // Un atributo tiene como mucho 4 componentes: los más grandes se reparten
+                let slots = (attribute.component_size + 3) / 4;
+                for slot in 0..slots {
+                    let location = attribute.location + slot as u32;
+                    let components = (attribute.component_size - slot * 4).min(4);
+
+                    gl::VertexAttribPointer(
+                        location,     // Indice del atributo de vertices (a_position)
+                        components,         // Número de componentes de cada vértice
+                        gl::FLOAT,              // Tipo de dato
+                        normalized as gl::types::GLboolean,              // Normalizado
+                        stride,            // stride (byte offset entre atributos)
+                        offset as *const std::ffi::c_void,     // Offset en bytes
+                    );
+                    gl::EnableVertexAttribArray(location);
+                    gl::VertexAttribDivisor(location, divisor);
+
+                    // El offset va en bytes, no en número de componentes
+                    offset += components as usize * type_size;
+                }
             }
             gl::BindBuffer(gl::ARRAY_BUFFER, 0);
             gl::BindVertexArray(0);
         }
+
+        (element_size, stride)
     }
 
 
@@ -91,20 +148,71 @@ impl GLBuffer {
                 // tamaño de data tipe en bytes
                 (self.data_len * self.type_size) as gl::types::GLsizeiptr,
                 data.as_ptr() as *const gl::types::GLvoid, // puntero a datos
-                gl::STATIC_DRAW,
+                self.usage,
             );
             gl::BindBuffer(gl::ARRAY_BUFFER, 0);
         }
     }
 
-    pub fn draw(&self) {
+    // Con índices los vértices se dibujan en el orden que marcan (y se pueden repetir)
+    pub fn upload_indices(&mut self, indices: &[u32]) {
+        self.index_count = indices.len();
         unsafe {
+            if self.ebo == 0 {
+                gl::GenBuffers(1, &mut self.ebo);
+            }
+
+            // El ELEMENT_ARRAY_BUFFER se queda guardado en el VAO
             gl::BindVertexArray(self.vao);
-            gl::DrawArrays(
-                gl::TRIANGLES, // modo
-                0, // Indice inicial de los arreglos
-                self.data_len as i32 / self.element_size, // Número de índices
+            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, self.ebo);
+            gl::BufferData(
+                gl::ELEMENT_ARRAY_BUFFER,
+                std::mem::size_of_val(indices) as gl::types::GLsizeiptr,
+                indices.as_ptr() as *const gl::types::GLvoid,
+                self.usage,
             );
+            gl::BindVertexArray(0);
+        }
+    }
+
+    pub fn index_count(&self) -> usize {
+        self.index_count
+    }
+
+    // Número de elementos (vértices, o instancias en un buffer de instancias)
+    pub fn count(&self) -> usize {
+        if self.element_size == 0 { 0 } else { self.data_len / self.element_size as usize }
+    }
+
+    pub fn draw(&self) {
+        unsafe {
+            gl::BindVertexArray(self.vao);
+            if self.ebo != 0 {
+                gl::DrawElements(self.mode, self.index_count as i32, gl::UNSIGNED_INT, std::ptr::null());
+            } else {
+                gl::DrawArrays(
+                    self.mode, // modo
+                    0, // Indice inicial de los arreglos
+                    self.data_len as i32 / self.element_size, // Número de índices
+                );
+            }
+        }
+    }
+
+    // Dibuja instances copias en una sola llamada. Los atributos por instancia vienen
+    // del buffer configurado con configure_instances
+    pub fn draw_instanced(&self, instances: usize) {
+        if instances == 0 {
+            return;
+        }
+
+        unsafe {
+            gl::BindVertexArray(self.vao);
+            if self.ebo != 0 {
+                gl::DrawElementsInstanced(self.mode, self.index_count as i32, gl::UNSIGNED_INT, std::ptr::null(), instances as i32);
+            } else {
+                gl::DrawArraysInstanced(self.mode, 0, self.count() as i32, instances as i32);
+            }
         }
     }
 }
\ No newline at end of file
@@ -1,2 +1,5 @@
 pub mod shader;
-pub mod gl_buffer;
\ No newline at end of file
+pub mod gl_buffer;
+pub mod texture;
+pub mod framebuffer;
+pub mod render_state;
\ No newline at end of file
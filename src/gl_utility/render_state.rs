@@ -0,0 +1,313 @@
+use crate::math::rect::Rect;
+
+// Cómo se mezcla lo que se dibuja con lo que ya hay en pantalla
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum BlendMode {
+    // Sin mezcla: el alfa no se tiene en cuenta
+    Opaque,
+    // Transparencia normal con alfa sin premultiplicar
+    Alpha,
+    // Suma de colores (fuego, luces, brillos)
+    Additive,
+    // Oscurece multiplicando (sombras, tintes)
+    Multiply,
+    // Para colores que ya vienen multiplicados por su alfa
+    Premultiplied,
+}
+
+impl BlendMode {
+    pub fn is_opaque(self) -> bool {
+        self == BlendMode::Opaque
+    }
+
+    // Factores (origen RGB, destino RGB, origen alfa, destino alfa) de glBlendFuncSeparate
+    pub fn factors(self) -> (gl::types::GLenum, gl::types::GLenum, gl::types::GLenum, gl::types::GLenum) {
+        match self {
+            BlendMode::Opaque => (gl::ONE, gl::ZERO, gl::ONE, gl::ZERO),
+            BlendMode::Alpha => (gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA, gl::ONE, gl::ONE_MINUS_SRC_ALPHA),
+            BlendMode::Additive => (gl::SRC_ALPHA, gl::ONE, gl::ZERO, gl::ONE),
+            BlendMode::Multiply => (gl::DST_COLOR, gl::ONE_MINUS_SRC_ALPHA, gl::ZERO, gl::ONE),
+            BlendMode::Premultiplied => (gl::ONE, gl::ONE_MINUS_SRC_ALPHA, gl::ONE, gl::ONE_MINUS_SRC_ALPHA),
+        }
+    }
+}
+
+// Caras que no se dibujan
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum CullFace {
+    None,
+    Back,
+    Front,
+}
+
+// Zona de la ventana fuera de la que no se dibuja, en píxeles de OpenGL
+// (x e y desde la esquina inferior izquierda)
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Scissor {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Scissor {
+    pub fn new(x: i32, y: i32, width: i32, height: i32) -> Scissor {
+        Scissor { x, y, width, height }
+    }
+
+    // A partir de un rectángulo con y hacia abajo, como el resto del motor
+    pub fn from_rect(rect: &Rect, window_height: u32) -> Scissor {
+        Scissor::new(
+            rect.x.floor() as i32,
+            window_height as i32 - (rect.y + rect.height).ceil() as i32,
+            rect.width.ceil() as i32,
+            rect.height.ceil() as i32,
+        )
+    }
+}
+
+// Estado de OpenGL con el que se hace una llamada de dibujo
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct RenderState {
+    pub blend: BlendMode,
+    pub depth_test: bool,
+    pub depth_write: bool,
+    pub cull: CullFace,
+    pub scissor: Option<Scissor>,
+    // Canales que se escriben (r, g, b, a)
+    pub color_mask: [bool; 4],
+}
+
+impl Default for RenderState {
+    fn default() -> RenderState {
+        RenderState::new()
+    }
+}
+
+impl RenderState {
+    // Transparencia normal, sin profundidad ni recortes
+    pub fn new() -> RenderState {
+        RenderState {
+            blend: BlendMode::Alpha,
+            depth_test: false,
+            depth_write: true,
+            cull: CullFace::None,
+            scissor: None,
+            color_mask: [true; 4],
+        }
+    }
+
+    pub fn with_blend(mut self, blend: BlendMode) -> RenderState {
+        self.blend = blend;
+        self
+    }
+
+    pub fn with_depth(mut self, test: bool, write: bool) -> RenderState {
+        self.depth_test = test;
+        self.depth_write = write;
+        self
+    }
+
+    pub fn with_cull(mut self, cull: CullFace) -> RenderState {
+        self.cull = cull;
+        self
+    }
+
+    pub fn with_scissor(mut self, scissor: Option<Scissor>) -> RenderState {
+        self.scissor = scissor;
+        self
+    }
+
+    pub fn with_color_mask(mut self, red: bool, green: bool, blue: bool, alpha: bool) -> RenderState {
+        self.color_mask = [red, green, blue, alpha];
+        self
+    }
+}
+
+// Recuerda el estado que tiene OpenGL y al aplicar uno nuevo sólo hace las llamadas
+// de lo que cambia. Si algún código cambia el estado por su cuenta hay que llamar a
+// invalidate para que la siguiente vez se aplique todo.
+// En modo headless no llama a OpenGL, para poder comprobar el estado en los tests
+pub struct StateCache {
+    current: RenderState,
+    valid: bool,
+    headless: bool,
+    gl_calls: usize,
+}
+
+impl Default for StateCache {
+    fn default() -> StateCache {
+        StateCache::new()
+    }
+}
+
+impl StateCache {
+    pub fn new() -> StateCache {
+        StateCache {
+            current: RenderState::new(),
+            valid: false,
+            headless: false,
+            gl_calls: 0,
+        }
+    }
+
+    pub fn headless() -> StateCache {
+        StateCache {
+            headless: true,
+            ..StateCache::new()
+        }
+    }
+
+    // Estado que tiene OpenGL según la caché (None si no se sabe)
+    pub fn current(&self) -> Option<&RenderState> {
+        if self.valid { Some(&self.current) } else { None }
+    }
+
+    // Llamadas a OpenGL hechas desde el principio (para medir cuántas se ahorran)
+    pub fn gl_calls(&self) -> usize {
+        self.gl_calls
+    }
+
+    pub fn invalidate(&mut self) {
+        self.valid = false;
+    }
+
+    pub fn apply(&mut self, state: &RenderState) {
+        let previous = if self.valid { Some(self.current) } else { None };
+        let changed = |field: &dyn Fn(&RenderState) -> bool| previous.map_or(true, |previous| field(&previous));
+
+        if changed(&|previous| previous.blend.is_opaque() != state.blend.is_opaque()) {
+            self.set_enabled(gl::BLEND, !state.blend.is_opaque());
+        }
+        if !state.blend.is_opaque() && changed(&|previous| previous.blend != state.blend) {
+            let (source, destination, source_alpha, destination_alpha) = state.blend.factors();
+            self.call(|| unsafe { gl::BlendFuncSeparate(source, destination, source_alpha, destination_alpha) });
+        }
+
+        if changed(&|previous| previous.depth_test != state.depth_test) {
+            self.set_enabled(gl::DEPTH_TEST, state.depth_test);
+            if state.depth_test {
+                self.call(|| unsafe { gl::DepthFunc(gl::LEQUAL) });
+            }
+        }
+        if changed(&|previous| previous.depth_write != state.depth_write) {
+            let mask = if state.depth_write { gl::TRUE } else { gl::FALSE };
+            self.call(|| unsafe { gl::DepthMask(mask) });
+        }
+
+        if changed(&|previous| previous.cull != state.cull) {
+            self.set_enabled(gl::CULL_FACE, state.cull != CullFace::None);
+            match state.cull {
+                CullFace::None => {}
+                CullFace::Back => self.call(|| unsafe { gl::CullFace(gl::BACK) }),
+                CullFace::Front => self.call(|| unsafe { gl::CullFace(gl::FRONT) }),
+            }
+        }
+
+        if changed(&|previous| previous.scissor.is_some() != state.scissor.is_some()) {
+            self.set_enabled(gl::SCISSOR_TEST, state.scissor.is_some());
+        }
+        if let Some(scissor) = state.scissor {
+            if changed(&|previous| previous.scissor != state.scissor) {
+                self.call(|| unsafe { gl::Scissor(scissor.x, scissor.y, scissor.width, scissor.height) });
+            }
+        }
+
+        if changed(&|previous| previous.color_mask != state.color_mask) {
+            let [red, green, blue, alpha] = state.color_mask.map(|channel| if channel { gl::TRUE } else { gl::FALSE });
+            self.call(|| unsafe { gl::ColorMask(red, green, blue, alpha) });
+        }
+
+        self.current = *state;
+        self.valid = true;
+    }
+
+    fn set_enabled(&mut self, capability: gl::types::GLenum, enabled: bool) {
+        self.call(|| unsafe {
+            if enabled {
+                gl::Enable(capability);
+            } else {
+                gl::Disable(capability);
+            }
+        });
+    }
+
+    fn call(&mut self, f: impl FnOnce()) {
+        self.gl_calls += 1;
+        if !self.headless {
+            f();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn applied(state: &RenderState) -> StateCache {
+        let mut cache = StateCache::headless();
+        cache.apply(state);
+        cache
+    }
+
+    // Llamadas que hace apply pasando del estado from al estado to
+    fn calls_between(from: &RenderState, to: &RenderState) -> usize {
+        let mut cache = applied(from);
+        let before = cache.gl_calls();
+        cache.apply(to);
+        assert_eq!(cache.current(), Some(to));
+        cache.gl_calls() - before
+    }
+
+    #[test]
+    fn first_apply_sets_everything() {
+        let cache = applied(&RenderState::new());
+        assert!(cache.gl_calls() > 0);
+        assert_eq!(cache.current(), Some(&RenderState::new()));
+    }
+
+    #[test]
+    fn repeated_apply_makes_no_calls() {
+        let state = RenderState::new()
+            .with_blend(BlendMode::Additive)
+            .with_depth(true, false)
+            .with_cull(CullFace::Back)
+            .with_scissor(Some(Scissor::new(1, 2, 3, 4)))
+            .with_color_mask(true, false, true, false);
+        assert_eq!(calls_between(&state, &state), 0);
+        assert_eq!(calls_between(&RenderState::new(), &RenderState::new()), 0);
+    }
+
+    #[test]
+    fn changing_one_field_only_sets_that_field() {
+        let base = RenderState::new().with_scissor(Some(Scissor::new(0, 0, 10, 10)));
+
+        // glBlendFuncSeparate
+        assert_eq!(calls_between(&base, &base.with_blend(BlendMode::Additive)), 1);
+        // glDisable(GL_BLEND), sin cambiar los factores
+        assert_eq!(calls_between(&base, &base.with_blend(BlendMode::Opaque)), 1);
+        // glDepthMask
+        assert_eq!(calls_between(&base, &base.with_depth(false, false)), 1);
+        // glEnable(GL_DEPTH_TEST) y glDepthFunc
+        assert_eq!(calls_between(&base, &base.with_depth(true, true)), 2);
+        // glEnable(GL_CULL_FACE) y glCullFace
+        assert_eq!(calls_between(&base, &base.with_cull(CullFace::Front)), 2);
+        // glScissor, el test ya estaba activo
+        assert_eq!(calls_between(&base, &base.with_scissor(Some(Scissor::new(5, 5, 10, 10)))), 1);
+        // glDisable(GL_SCISSOR_TEST)
+        assert_eq!(calls_between(&base, &base.with_scissor(None)), 1);
+        // glColorMask
+        assert_eq!(calls_between(&base, &base.with_color_mask(false, true, true, true)), 1);
+    }
+
+    #[test]
+    fn invalidate_applies_everything_again() {
+        let mut cache = applied(&RenderState::new());
+        let first = cache.gl_calls();
+
+        cache.invalidate();
+        assert_eq!(cache.current(), None);
+        cache.apply(&RenderState::new());
+        assert_eq!(cache.gl_calls(), first * 2);
+    }
+}
@@ -56,6 +56,27 @@ impl ShaderManager {
         self.get(name)
     }
 
+    // Registra un programa con un único compute shader (para cálculos en la GPU)
+    pub fn register_compute(&mut self, name: &str, compute_source: &str) -> &Shader {
+        let mut shader = Shader {
+            name: String::from(name),
+            program: 0,
+            attributes: HashMap::new(),
+            uniforms: HashMap::new(),
+        };
+
+        shader.load_compute(&CString::new(compute_source).expect("Fallo en CS CString::new"));
+
+        self.shaders.insert(String::from(name), shader);
+
+        self.get(name)
+    }
+
+    // Como get pero sin pedir el manager mutable, para poder tener varios shaders a la vez
+    pub fn find(&self, name: &str) -> Option<&Shader> {
+        self.shaders.get(name)
+    }
+
     // Obtiene un puntero a programa shader de un nombre
     pub fn get(&mut self, name: &str) -> &Shader {
         match self.shaders.get(name) {
@@ -65,13 +86,54 @@ impl ShaderManager {
     }
 }
 
+// Tipo de un uniform según OpenGL (los que no se usan en el motor quedan en Other)
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum UniformType {
+    Float,
+    Vec2,
+    Vec3,
+    Vec4,
+    Int,
+    Bool,
+    Mat3,
+    Mat4,
+    Sampler2D,
+    Other(gl::types::GLenum),
+}
+
+impl UniformType {
+    fn from_gl(gl_type: gl::types::GLenum) -> UniformType {
+        match gl_type {
+            gl::FLOAT => UniformType::Float,
+            gl::FLOAT_VEC2 => UniformType::Vec2,
+            gl::FLOAT_VEC3 => UniformType::Vec3,
+            gl::FLOAT_VEC4 => UniformType::Vec4,
+            gl::INT => UniformType::Int,
+            gl::BOOL => UniformType::Bool,
+            gl::FLOAT_MAT3 => UniformType::Mat3,
+            gl::FLOAT_MAT4 => UniformType::Mat4,
+            gl::SAMPLER_2D => UniformType::Sampler2D,
+            other => UniformType::Other(other),
+        }
+    }
+}
+
+// Uniform activo de un programa
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct UniformInfo {
+    pub location: i32,
+    pub uniform_type: UniformType,
+    // Número de elementos (1 si no es un array)
+    pub size: i32,
+}
+
 pub struct Shader {
     pub name: String,
     pub program: u32,
     // Nombre y posición del atributo
     attributes: HashMap<String, u32>,
-    // Nombre y posición del uniform
-    uniforms: HashMap<String, i32>,
+    // Nombre, posición y tipo del uniform
+    uniforms: HashMap<String, UniformInfo>,
 }
 
 impl Drop for Shader {
@@ -97,18 +159,52 @@ impl Shader {
         }
     }
 
+    // None si el programa no tiene ese atributo (o el compilador lo ha quitado por no usarse)
+    pub fn attribute(&self, name: &str) -> Option<u32> {
+        self.attributes.get(name).copied()
+    }
+
     pub fn get_uniform_location(&self, name: &str) -> i32 {
         match self.uniforms.get(name) {
-            Some(&uniform) => return uniform,
+            Some(uniform) => return uniform.location,
             _ => panic!("No puedo encontrar el uniform llamado {} en el shader {}", name, self.name)
         }
     }
 
+    // None si el programa no tiene ese uniform (o el compilador lo ha quitado por no usarse)
+    pub fn uniform(&self, name: &str) -> Option<&UniformInfo> {
+        self.uniforms.get(name)
+    }
+
+    pub fn uniforms(&self) -> &HashMap<String, UniformInfo> {
+        &self.uniforms
+    }
+
+    // Shader sin programa con los uniforms dados, para probar sin contexto de OpenGL
+    // lo que depende de la reflexión. No se libera nunca: su Drop llamaría a OpenGL
+    #[cfg(test)]
+    pub(crate) fn headless(name: &str, uniforms: &[(&str, UniformType)]) -> &'static Shader {
+        let uniforms = uniforms
+            .iter()
+            .enumerate()
+            .map(|(location, &(uniform, uniform_type))| {
+                (String::from(uniform), UniformInfo { location: location as i32, uniform_type, size: 1 })
+            })
+            .collect();
+
+        Box::leak(Box::new(Shader {
+            name: String::from(name),
+            program: 0,
+            attributes: HashMap::new(),
+            uniforms,
+        }))
+    }
+
     fn load(&mut self, vertex_source: &CString, fragment_source: &CString) {
         let vertex_shader = Shader::load_shader(vertex_source, gl::VERTEX_SHADER);
         let fragment_shader = Shader::load_shader(fragment_source, gl::FRAGMENT_SHADER);
 
-        self.program = Shader::create_program(vertex_shader, fragment_shader);
+        self.program = Shader::create_program(&[vertex_shader, fragment_shader]);
 
         self.detect_attributes();
         self.detect_uniforms();
@@ -119,6 +215,28 @@ impl Shader {
         }
     }
 
+    fn load_compute(&mut self, compute_source: &CString) {
+        let compute_shader = Shader::load_shader(compute_source, gl::COMPUTE_SHADER);
+
+        self.program = Shader::create_program(&[compute_shader]);
+
+        // Un compute shader no tiene atributos
+        self.detect_uniforms();
+
+        unsafe {
+            gl::DeleteShader(compute_shader);
+        }
+    }
+
+    // Activa el programa y lanza el compute shader en grupos de trabajo de x * y * z.
+    // Hay que llamar después a gl::MemoryBarrier antes de leer lo que ha escrito
+    pub fn dispatch(&self, groups_x: u32, groups_y: u32, groups_z: u32) {
+        self.use_shader();
+        unsafe {
+            gl::DispatchCompute(groups_x, groups_y, groups_z);
+        }
+    }
+
     fn load_shader(source: &CString, shader_type: gl::types::GLenum) -> u32 {
         let shader_id = unsafe { gl::CreateShader(shader_type) };
 
@@ -161,12 +279,13 @@ impl Shader {
     }
 
     // Creamos programa Shader
-    fn create_program(vertex_shader: u32, fragment_shader: u32) -> u32 {
+    fn create_program(shaders: &[u32]) -> u32 {
         let program_id = unsafe { gl::CreateProgram() };
         unsafe {
             //self.program = gl::CreateProgram();
-            gl::AttachShader(program_id, vertex_shader);
-            gl::AttachShader(program_id, fragment_shader);
+            for &shader in shaders {
+                gl::AttachShader(program_id, shader);
+            }
 
             gl::LinkProgram(program_id);
         }
@@ -197,8 +316,9 @@ impl Shader {
             println!("{}", error_msg.into_string().expect("fallo into_string()"));
         }
         unsafe {
-            gl::DetachShader(program_id, vertex_shader);
-            gl::DetachShader(program_id, fragment_shader);
+            for &shader in shaders {
+                gl::DetachShader(program_id, shader);
+            }
         }
         program_id
     }
@@ -209,17 +329,21 @@ impl Shader {
             let mut attributes_number: gl::types::GLint = 0;
             gl::GetProgramiv(self.program, gl::ACTIVE_ATTRIBUTES, &mut attributes_number);
 
+            // Buffer para el nombre de la variable, del tamaño del nombre más largo
+            let mut max_length: gl::types::GLint = 0;
+            gl::GetProgramiv(self.program, gl::ACTIVE_ATTRIBUTE_MAX_LENGTH, &mut max_length);
+            let buf_size = (max_length as usize).max(1);
+
             for i in 0..attributes_number {
                 let mut size: gl::types::GLint = 0;       // Tamaño de la variable
                 let mut var_type: gl::types::GLenum = 0;  // Tipo (float, vec3,vec4, mat4, etc)
-                const BUF_SIZE: usize = 16;               // Buffer para el nombre de la variable
-                let name = [0; BUF_SIZE];
+                let name = vec![0; buf_size];
                 let mut length: gl::types::GLsizei = 0;   // Longitud del nombre de la variable
 
                 gl::GetActiveAttrib(
                     self.program,
                     i as gl::types::GLuint,
-                    BUF_SIZE as gl::types::GLint,
+                    buf_size as gl::types::GLint,
                     &mut length,
                     &mut size,
                     &mut var_type,
@@ -250,17 +374,21 @@ impl Shader {
             let mut uniforms_number: gl::types::GLint = 0;
             gl::GetProgramiv(self.program, gl::ACTIVE_UNIFORMS, &mut uniforms_number);
 
+            // Los nombres de arrays de structs ("u_lights[3].position") pueden ser largos
+            let mut max_length: gl::types::GLint = 0;
+            gl::GetProgramiv(self.program, gl::ACTIVE_UNIFORM_MAX_LENGTH, &mut max_length);
+            let buf_size = (max_length as usize).max(1);
+
             for i in 0..uniforms_number {
                 let mut size: gl::types::GLint = 0;       // Tamaño de la variable
                 let mut var_type: gl::types::GLenum = 0;  // Tipo (float, vec3,vec4, mat4, etc)
-                const BUF_SIZE: usize = 16;               // Buffer para el nombre de la variable
-                let name = [0; BUF_SIZE];
+                let name = vec![0; buf_size];
                 let mut length: gl::types::GLsizei = 0;   // Longitud del nombre de la variable
 
                 gl::GetActiveUniform(
                     self.program,
                     i as gl::types::GLuint,
-                    BUF_SIZE as gl::types::GLint,
+                    buf_size as gl::types::GLint,
                     &mut length,
                     &mut size,
                     &mut var_type,
@@ -275,12 +403,20 @@ impl Shader {
                     name.as_ptr() as *mut gl::types::GLchar,
                 );
 
-                self.uniforms.insert(
-                    String::from_str(
-                        std::ffi::CStr::from_ptr(name.as_ptr()).to_str().unwrap()
-                    ).unwrap(),
+                let name = String::from_str(
+                    std::ffi::CStr::from_ptr(name.as_ptr()).to_str().unwrap()
+                ).unwrap();
+                let info = UniformInfo {
                     location,
-                );
+                    uniform_type: UniformType::from_gl(var_type),
+                    size,
+                };
+
+                // Los arrays aparecen como "nombre[0]": también se guardan como "nombre"
+                if let Some(base) = name.strip_suffix("[0]") {
+                    self.uniforms.insert(String::from(base), info);
+                }
+                self.uniforms.insert(name, info);
             }
         }
     }
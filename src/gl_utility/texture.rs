@@ -0,0 +1,205 @@
+// Filtro al ampliar o reducir la textura. Nearest es el que interesa para pixel art
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum TextureFilter {
+    Nearest,
+    Linear,
+}
+
+// Formato de los píxeles en la GPU. Los de coma flotante sirven para render targets HDR
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum TextureFormat {
+    Rgba8,
+    Rgba16F,
+    Rgba32F,
+}
+
+impl TextureFormat {
+    pub fn internal_format(self) -> gl::types::GLenum {
+        match self {
+            TextureFormat::Rgba8 => gl::RGBA8,
+            TextureFormat::Rgba16F => gl::RGBA16F,
+            TextureFormat::Rgba32F => gl::RGBA32F,
+        }
+    }
+
+    fn data_type(self) -> gl::types::GLenum {
+        match self {
+            TextureFormat::Rgba8 => gl::UNSIGNED_BYTE,
+            TextureFormat::Rgba16F | TextureFormat::Rgba32F => gl::FLOAT,
+        }
+    }
+}
+
+pub struct Texture {
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+
+    id: u32,
+}
+
+impl Drop for Texture {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteTextures(1, &self.id);
+        }
+    }
+}
+
+impl Texture {
+    // Textura sin objeto de OpenGL, para los tests. Igual que Shader::headless, no se libera
+    #[cfg(test)]
+    pub(crate) fn headless(name: &str, width: u32, height: u32) -> &'static Texture {
+        Box::leak(Box::new(Texture {
+            name: String::from(name),
+            width,
+            height,
+            id: 0,
+        }))
+    }
+
+    // Crea una textura RGBA8 a partir de píxeles en memoria (fila 0 = fila superior de la imagen)
+    pub fn from_rgba(name: &str, width: u32, height: u32, pixels: &[u8]) -> Texture {
+        if pixels.len() != (width * height * 4) as usize {
+            panic!(
+                "La textura {} de {}x{} necesita {} bytes y tiene {}",
+                name, width, height, width * height * 4, pixels.len()
+            );
+        }
+
+        let mut texture = Texture {
+            name: String::from(name),
+            width,
+            height,
+
+            id: 0,
+        };
+
+        unsafe {
+            gl::GenTextures(1, &mut texture.id);
+            gl::BindTexture(gl::TEXTURE_2D, texture.id);
+
+            // Las filas de 4 bytes por píxel siempre están alineadas, pero
+            // por si alguien cambió el alineamiento antes
+            gl::PixelStorei(gl::UNPACK_ALIGNMENT, 4);
+            gl::TexImage2D(
+                gl::TEXTURE_2D,
+                0,                                   // nivel de mipmap
+                gl::RGBA8 as i32,                    // formato interno
+                width as i32,
+                height as i32,
+                0,                                   // borde (siempre 0)
+                gl::RGBA,                            // formato de los datos
+                gl::UNSIGNED_BYTE,                   // tipo de los datos
+                pixels.as_ptr() as *const gl::types::GLvoid,
+            );
+
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+        }
+
+        texture.set_filter(TextureFilter::Linear);
+        texture
+    }
+
+    // Textura sin contenido inicial, para usarla como destino de un Framebuffer
+    pub fn empty(name: &str, width: u32, height: u32, format: TextureFormat) -> Texture {
+        let mut texture = Texture {
+            name: String::from(name),
+            width,
+            height,
+
+            id: 0,
+        };
+
+        unsafe {
+            gl::GenTextures(1, &mut texture.id);
+            gl::BindTexture(gl::TEXTURE_2D, texture.id);
+            gl::TexImage2D(
+                gl::TEXTURE_2D,
+                0,
+                format.internal_format() as i32,
+                width as i32,
+                height as i32,
+                0,
+                gl::RGBA,
+                format.data_type(),
+                std::ptr::null(),
+            );
+
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+        }
+
+        texture.set_filter(TextureFilter::Linear);
+        texture
+    }
+
+    // Carga una imagen PNG del disco
+    pub fn load(name: &str, path: &str) -> Texture {
+        let image = image::open(path)
+            .unwrap_or_else(|e| panic!("No se puede cargar la textura {} ({}): {}", name, path, e))
+            .to_rgba8();
+
+        Texture::from_rgba(name, image.width(), image.height(), image.as_raw())
+    }
+
+    // Sustituye los píxeles de una zona de la textura (RGBA8, fila 0 = fila superior).
+    // Para atlas que se rellenan poco a poco sin crear la textura de nuevo
+    pub fn update_region(&self, x: u32, y: u32, width: u32, height: u32, pixels: &[u8]) -> Result<(), String> {
+        let fits = |start: u32, size: u32, limit: u32| start.checked_add(size).is_some_and(|end| end <= limit);
+        let expected_len = (width as usize).checked_mul(height as usize).and_then(|area| area.checked_mul(4));
+        if !fits(x, width, self.width) || !fits(y, height, self.height) || expected_len != Some(pixels.len()) {
+            return Err(format!(
+                "Zona {}x{} en ({}, {}) no válida para la textura {} de {}x{}",
+                width, height, x, y, self.name, self.width, self.height
+            ));
+        }
+
+        unsafe {
+            gl::BindTexture(gl::TEXTURE_2D, self.id);
+            gl::PixelStorei(gl::UNPACK_ALIGNMENT, 4);
+            gl::TexSubImage2D(
+                gl::TEXTURE_2D,
+                0,
+                x as i32,
+                y as i32,
+                width as i32,
+                height as i32,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                pixels.as_ptr() as *const gl::types::GLvoid,
+            );
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+        }
+        Ok(())
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn set_filter(&self, filter: TextureFilter) {
+        let gl_filter = match filter {
+            TextureFilter::Nearest => gl::NEAREST,
+            TextureFilter::Linear => gl::LINEAR,
+        };
+
+        unsafe {
+            gl::BindTexture(gl::TEXTURE_2D, self.id);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl_filter as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl_filter as i32);
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+        }
+    }
+
+    // Activa la textura en la unidad de textura indicada (0, 1, 2...)
+    pub fn bind(&self, unit: u32) {
+        unsafe {
+            gl::ActiveTexture(gl::TEXTURE0 + unit);
+            gl::BindTexture(gl::TEXTURE_2D, self.id);
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,19 @@
+#version 430
+
+precision mediump float;
+
+uniform vec4 u_color;// tinte de todo el grupo
+uniform sampler2D u_diffuse;
+uniform int u_textured;// 0 = sólo color, 1 = color * textura
+
+in vec2 v_tex_coord;
+in vec4 v_color;
+
+out vec4 frag_color;
+
+void main() {
+    frag_color = u_color * v_color;
+    if (u_textured != 0) {
+        frag_color *= texture(u_diffuse, v_tex_coord);
+    }
+}
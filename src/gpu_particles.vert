@@ -0,0 +1,55 @@
+#version 430
+
+// Sin atributos: cada instancia es una partícula y cada vértice una esquina del quad
+struct Particle {
+    vec4 motion;
+    vec4 spin;
+    vec4 life;
+};
+
+layout(std430, binding = 0) readonly buffer Particles {
+    Particle particles[];
+};
+
+// Muestras de size_over_life y color_over_life repartidas en la vida
+const int CURVE_SAMPLES = 16;
+uniform float u_sizes[CURVE_SAMPLES];
+uniform vec4 u_colors[CURVE_SAMPLES];
+
+uniform mat4 u_projection;
+uniform mat4 u_model;
+
+out vec2 v_tex_coord;
+out vec4 v_color;
+
+const vec2 CORNERS[6] = vec2[](
+    vec2(0.0, 0.0), vec2(0.0, 1.0), vec2(1.0, 1.0),
+    vec2(1.0, 1.0), vec2(1.0, 0.0), vec2(0.0, 0.0)
+);
+
+void main() {
+    Particle particle = particles[gl_InstanceID];
+    vec2 corner = CORNERS[gl_VertexID];
+
+    // Las muertas quedan en un triángulo de área 0 fuera de la pantalla
+    if (particle.spin.w >= particle.life.x) {
+        gl_Position = vec4(2.0, 2.0, 2.0, 1.0);
+        v_tex_coord = corner;
+        v_color = vec4(0.0);
+        return;
+    }
+
+    float t = clamp(particle.spin.w / particle.life.x, 0.0, 1.0) * float(CURVE_SAMPLES - 1);
+    int i = min(int(t), CURVE_SAMPLES - 2);
+    float f = t - float(i);
+    float size = particle.spin.z * mix(u_sizes[i], u_sizes[i + 1], f);
+
+    vec2 local = (corner - 0.5) * size;
+    float c = cos(particle.spin.x);
+    float s = sin(particle.spin.x);
+    vec2 rotated = vec2(c * local.x - s * local.y, s * local.x + c * local.y);
+
+    v_tex_coord = corner;
+    v_color = mix(u_colors[i], u_colors[i + 1], f);
+    gl_Position = u_projection * u_model * vec4(particle.motion.xy + rotated, 0.0, 1.0);
+}
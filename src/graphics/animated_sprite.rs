@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+
+use crate::gl_utility::render_state::BlendMode;
+use crate::gl_utility::shader::Shader;
+use crate::graphics::sprite::Sprite;
+use crate::graphics::sprite_sheet::SpriteSheet;
+use crate::graphics::animation::{Animation, Playback};
+use crate::graphics::color::Color;
+use crate::graphics::render_queue::Drawable;
+use crate::math::matrix4x4::Matrix4x4;
+
+// Sprite que reproduce clips de animación de una SpriteSheet
+pub struct AnimatedSprite<'a> {
+    pub sprite: Sprite<'a>,
+    // Multiplicador de velocidad (1 = normal, 0 = pausa)
+    pub speed: f32,
+
+    sheet: &'a SpriteSheet<'a>,
+    clips: HashMap<String, Animation>,
+
+    current: Option<String>,
+    playback: Playback,
+}
+
+impl<'a> AnimatedSprite<'a> {
+    // Empieza mostrando el primer fotograma, así que la hoja no puede estar vacía
+    pub fn new(name: &str, shader: &'a Shader, sheet: &'a SpriteSheet<'a>) -> Result<AnimatedSprite<'a>, String> {
+        if sheet.frame_count() == 0 {
+            return Err(format!("La hoja {} del sprite {} no tiene fotogramas", sheet.texture.name, name));
+        }
+
+        let first = sheet.frame(0);
+        let mut sprite = Sprite::new(name, shader, Some(first.width), Some(first.height));
+        sprite.set_texture(sheet.texture);
+        sprite.set_color(Color::white());
+
+        Ok(AnimatedSprite {
+            sprite,
+            speed: 1.0,
+
+            sheet,
+            clips: HashMap::new(),
+
+            current: None,
+            playback: Playback::new(),
+        })
+    }
+
+    pub fn load(&mut self) {
+        self.sprite.load();
+        self.apply_frame(0);
+    }
+
+    pub fn add_clip(&mut self, clip: Animation) {
+        self.clips.insert(clip.name.clone(), clip);
+    }
+
+    // Cambia al clip indicado. Si ya se está reproduciendo no hace nada,
+    // para poder llamarlo en cada frame según el estado del personaje
+    pub fn play(&mut self, name: &str) {
+        let is_playing = self.current.as_deref() == Some(name) && !self.playback.finished;
+        if !is_playing {
+            self.restart(name);
+        }
+    }
+
+    // Empieza el clip desde el principio aunque ya se estuviera reproduciendo
+    pub fn restart(&mut self, name: &str) {
+        let first_frame = match self.clips.get(name) {
+            Some(clip) => clip.frames.first().map(|f| f.frame),
+            _ => panic!("El sprite {} no tiene el clip {}", self.sprite.name, name),
+        };
+
+        self.current = Some(String::from(name));
+        self.playback = Playback::new();
+
+        if let Some(frame) = first_frame {
+            self.apply_frame(frame);
+        }
+    }
+
+    pub fn current_clip(&self) -> Option<&str> {
+        self.current.as_deref()
+    }
+
+    // Índice en la SpriteSheet del fotograma que se está mostrando
+    pub fn current_frame(&self) -> Option<usize> {
+        let clip = self.clips.get(self.current.as_ref()?)?;
+        clip.frames.get(self.playback.position).map(|f| f.frame)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.playback.finished
+    }
+
+    // Avanza la animación y devuelve los eventos de los fotogramas por los que ha pasado
+    pub fn update(&mut self, delta: f32) -> Vec<String> {
+        let mut fired = Vec::new();
+
+        let clip = match self.current.as_ref() {
+            Some(name) => &self.clips[name],
+            None => return fired,
+        };
+
+        let previous = self.playback.position;
+        clip.advance(&mut self.playback, delta * self.speed, &mut fired);
+
+        if self.playback.position != previous {
+            let frame = clip.frames[self.playback.position].frame;
+            self.apply_frame(frame);
+        }
+
+        fired
+    }
+
+    // Ajusta el tamaño del quad y la zona de la textura al fotograma
+    fn apply_frame(&mut self, frame: usize) {
+        let rect = self.sheet.frame(frame);
+        self.sprite.width = rect.width;
+        self.sprite.height = rect.height;
+        if let Some(origin) = self.sheet.origin(frame) {
+            self.sprite.origin = origin;
+        }
+        self.sprite.set_region(self.sheet.uv(frame));
+    }
+
+    pub fn draw(&self, model: &Matrix4x4) {
+        self.sprite.draw(model);
+    }
+}
+
+impl<'a> Drawable for AnimatedSprite<'a> {
+    fn draw(&self, model: &Matrix4x4) {
+        AnimatedSprite::draw(self, model);
+    }
+
+    fn blend(&self) -> Option<BlendMode> {
+        Drawable::blend(&self.sprite)
+    }
+}
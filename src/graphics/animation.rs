@@ -0,0 +1,157 @@
+// Cómo se comporta una animación al llegar al último fotograma
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum PlayMode {
+    // Se queda en el último fotograma
+    Once,
+    // Vuelve al primero
+    Loop,
+    // Va hacia atrás hasta el primero y vuelve a empezar (0 1 2 1 0 1 ...)
+    PingPong,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct AnimationFrame {
+    // Índice del fotograma en la SpriteSheet
+    pub frame: usize,
+    // Segundos que se muestra
+    pub duration: f32,
+}
+
+// Clip de animación: secuencia de fotogramas de una SpriteSheet con su duración.
+// Los eventos se disparan al entrar en un fotograma (posición dentro del clip)
+#[derive(Clone)]
+pub struct Animation {
+    pub name: String,
+    pub frames: Vec<AnimationFrame>,
+    pub mode: PlayMode,
+    events: Vec<(usize, String)>,
+}
+
+impl Animation {
+    pub fn new(name: &str, mode: PlayMode) -> Animation {
+        Animation {
+            name: String::from(name),
+            frames: Vec::new(),
+            mode,
+            events: Vec::new(),
+        }
+    }
+
+    // Clip con todos los fotogramas de la misma duración
+    pub fn from_frames(name: &str, frames: &[usize], frame_duration: f32, mode: PlayMode) -> Animation {
+        let mut animation = Animation::new(name, mode);
+        for &frame in frames {
+            animation.add_frame(frame, frame_duration);
+        }
+        animation
+    }
+
+    pub fn add_frame(&mut self, frame: usize, duration: f32) {
+        self.frames.push(AnimationFrame { frame, duration });
+    }
+
+    // Añade un evento que se dispara al entrar en la posición position del clip
+    pub fn add_event(&mut self, position: usize, name: &str) {
+        self.events.push((position, String::from(name)));
+    }
+
+    pub fn duration(&self) -> f32 {
+        self.frames.iter().map(|f| f.duration).sum()
+    }
+
+    fn fire_events(&self, position: usize, fired: &mut Vec<String>) {
+        fired.extend(
+            self.events
+                .iter()
+                .filter(|(p, _)| *p == position)
+                .map(|(_, name)| name.clone())
+        );
+    }
+
+    // Siguiente posición según el modo, o None si el clip ha terminado
+    fn next_position(&self, playback: &mut Playback) -> Option<usize> {
+        let last = self.frames.len() - 1;
+        let position = playback.position;
+
+        match self.mode {
+            PlayMode::Once => if position < last { Some(position + 1) } else { None },
+            PlayMode::Loop => Some(if position < last { position + 1 } else { 0 }),
+            PlayMode::PingPong => {
+                if last == 0 {
+                    return Some(0);
+                }
+                if playback.forward && position == last {
+                    playback.forward = false;
+                } else if !playback.forward && position == 0 {
+                    playback.forward = true;
+                }
+                Some(if playback.forward { position + 1 } else { position - 1 })
+            }
+        }
+    }
+
+    // Avanza la reproducción delta segundos. Los nombres de los eventos de los
+    // fotogramas por los que pasa se añaden a fired, en orden
+    pub fn advance(&self, playback: &mut Playback, delta: f32, fired: &mut Vec<String>) {
+        if playback.finished || self.frames.is_empty() {
+            return;
+        }
+
+        if playback.just_started {
+            playback.just_started = false;
+            self.fire_events(playback.position, fired);
+        }
+
+        // Sin duración no se puede avanzar sin entrar en un bucle infinito
+        if self.duration() <= 0.0 {
+            return;
+        }
+
+        playback.elapsed += delta;
+        while playback.elapsed >= self.frames[playback.position].duration {
+            playback.elapsed -= self.frames[playback.position].duration;
+
+            match self.next_position(playback) {
+                Some(position) => {
+                    playback.position = position;
+                    self.fire_events(position, fired);
+                }
+                None => {
+                    playback.finished = true;
+                    playback.elapsed = 0.0;
+                    break;
+                }
+            }
+        }
+    }
+}
+
+// Estado de reproducción de un clip. Se guarda aparte para que varios
+// sprites puedan compartir el mismo Animation
+#[derive(Copy, Clone, Debug)]
+pub struct Playback {
+    // Posición dentro del clip (no el índice en la SpriteSheet)
+    pub position: usize,
+    pub elapsed: f32,
+    pub finished: bool,
+    forward: bool,
+    just_started: bool,
+}
+
+impl Default for Playback {
+    fn default() -> Playback {
+        Playback::new()
+    }
+}
+
+impl Playback {
+    pub fn new() -> Playback {
+        Playback {
+            position: 0,
+            elapsed: 0.0,
+            finished: false,
+            forward: true,
+            just_started: true,
+        }
+    }
+}
\ No newline at end of file
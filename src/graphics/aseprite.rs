@@ -0,0 +1,240 @@
+use serde_json::Value;
+
+use crate::graphics::atlas::{TextureAtlas, parse_frames, parse_meta, parse_rect, parse_point, ratio};
+use crate::graphics::animation::{Animation, PlayMode};
+use crate::math::vector2::Vector2;
+
+// Importa el JSON que exporta Aseprite (File > Export Sprite Sheet), en formato
+// hash o array. Las etiquetas (frame tags) se convierten en clips con la duración
+// de cada fotograma, y si hay un slice con pivote se usa como pivote de las regiones
+pub fn parse(json: &str) -> Result<TextureAtlas, String> {
+    let root: Value = serde_json::from_str(json)
+        .map_err(|e| format!("JSON de Aseprite no válido: {}", e))?;
+
+    let (image, width, height) = parse_meta(&root)?;
+    let frames = parse_frames(&root)?;
+    let meta = &root["meta"];
+
+    let regions = frames
+        .iter()
+        .enumerate()
+        .map(|(i, frame)| {
+            let pivot = slice_pivot(meta, i)
+                .map(|p| Vector2::new(ratio(p.x, frame.source_size.x), ratio(p.y, frame.source_size.y)))
+                .unwrap_or_else(Vector2::zero);
+            frame.to_region(width as f32, height as f32, pivot)
+        })
+        .collect();
+
+    // Aseprite guarda la duración en milisegundos, 100 si no se ha cambiado
+    let durations: Vec<f32> = frames
+        .iter()
+        .map(|f| f.duration.unwrap_or(100.0) / 1000.0)
+        .collect();
+
+    let mut clips = Vec::new();
+    if let Some(tags) = meta.get("frameTags").and_then(Value::as_array) {
+        for tag in tags {
+            clips.push(parse_tag(tag, &durations)?);
+        }
+    }
+
+    Ok(TextureAtlas {
+        image,
+        width,
+        height,
+
+        regions,
+        clips,
+    })
+}
+
+pub fn load(path: &str) -> Result<TextureAtlas, String> {
+    let json = std::fs::read_to_string(path)
+        .map_err(|e| format!("No se puede leer {}: {}", path, e))?;
+    parse(&json)
+}
+
+fn parse_tag(tag: &Value, durations: &[f32]) -> Result<Animation, String> {
+    let name = tag.get("name").and_then(Value::as_str).ok_or("Etiqueta de Aseprite sin nombre")?;
+    let from = tag.get("from").and_then(Value::as_u64).ok_or("Etiqueta de Aseprite sin \"from\"")? as usize;
+    let to = tag.get("to").and_then(Value::as_u64).ok_or("Etiqueta de Aseprite sin \"to\"")? as usize;
+
+    if from > to || to >= durations.len() {
+        return Err(format!("La etiqueta {} usa fotogramas que no existen ({}-{})", name, from, to));
+    }
+
+    let direction = tag.get("direction").and_then(Value::as_str).unwrap_or("forward");
+    // "repeat" sólo aparece si se ha puesto un número de repeticiones; con 1 no se repite
+    let plays_once = tag.get("repeat").and_then(Value::as_str) == Some("1");
+
+    let mode = match (direction, plays_once) {
+        (_, true) => PlayMode::Once,
+        ("pingpong", _) | ("pingpong_reverse", _) => PlayMode::PingPong,
+        _ => PlayMode::Loop,
+    };
+
+    let mut indices: Vec<usize> = (from..=to).collect();
+    if direction == "reverse" || direction == "pingpong_reverse" {
+        indices.reverse();
+    }
+
+    let mut animation = Animation::new(name, mode);
+    for index in indices {
+        animation.add_frame(index, durations[index]);
+    }
+    Ok(animation)
+}
+
+// Pivote en píxeles de la imagen original para el fotograma dado: el del primer
+// slice con pivote, usando la última clave que empieza en ese fotograma o antes
+fn slice_pivot(meta: &Value, frame: usize) -> Option<Vector2> {
+    let slices = meta.get("slices")?.as_array()?;
+
+    slices.iter().find_map(|slice| {
+        let key = slice
+            .get("keys")?
+            .as_array()?
+            .iter()
+            .rev()
+            .find(|key| key.get("frame").and_then(Value::as_u64).is_some_and(|f| f as usize <= frame))?;
+
+        let bounds = parse_rect(key.get("bounds"))?;
+        let pivot = parse_point(key.get("pivot"))?;
+        Some(Vector2::new(bounds.x + pivot.x, bounds.y + pivot.y))
+    })
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tres fotogramas de 16x16 en una imagen de 48x16; el segundo recortado a 8x12
+    const HASH: &str = r#"{
+        "frames": {
+            "hero 0.aseprite": {
+                "frame": {"x": 0, "y": 0, "w": 16, "h": 16},
+                "rotated": false, "trimmed": false,
+                "spriteSourceSize": {"x": 0, "y": 0, "w": 16, "h": 16},
+                "sourceSize": {"w": 16, "h": 16},
+                "duration": 100
+            },
+            "hero 1.aseprite": {
+                "frame": {"x": 16, "y": 0, "w": 8, "h": 12},
+                "rotated": false, "trimmed": true,
+                "spriteSourceSize": {"x": 4, "y": 2, "w": 8, "h": 12},
+                "sourceSize": {"w": 16, "h": 16},
+                "duration": 200
+            },
+            "hero 2.aseprite": {
+                "frame": {"x": 32, "y": 0, "w": 16, "h": 16},
+                "sourceSize": {"w": 16, "h": 16}
+            }
+        },
+        "meta": {
+            "image": "hero.png",
+            "size": {"w": 48, "h": 16},
+            "frameTags": [
+                {"name": "walk", "from": 0, "to": 2, "direction": "forward"},
+                {"name": "back", "from": 1, "to": 2, "direction": "reverse"},
+                {"name": "bounce", "from": 0, "to": 1, "direction": "pingpong"},
+                {"name": "hit", "from": 2, "to": 2, "direction": "forward", "repeat": "1"}
+            ],
+            "slices": [
+                {"name": "feet", "keys": [
+                    {"frame": 0, "bounds": {"x": 0, "y": 0, "w": 16, "h": 16}, "pivot": {"x": 8, "y": 16}},
+                    {"frame": 2, "bounds": {"x": 0, "y": 0, "w": 16, "h": 16}, "pivot": {"x": 4, "y": 16}}
+                ]}
+            ]
+        }
+    }"#;
+
+    const ARRAY: &str = r#"{
+        "frames": [
+            {"filename": "a", "frame": {"x": 0, "y": 0, "w": 8, "h": 8}, "duration": 50},
+            {"filename": "b", "frame": {"x": 8, "y": 0, "w": 8, "h": 8}, "duration": 150}
+        ],
+        "meta": {"image": "a.png", "size": {"w": 16, "h": 8}}
+    }"#;
+
+    fn frame_indices(animation: &Animation) -> Vec<usize> {
+        animation.frames.iter().map(|f| f.frame).collect()
+    }
+
+    #[test]
+    fn parses_hash_layout() {
+        let atlas = parse(HASH).unwrap();
+
+        assert_eq!(atlas.image, "hero.png");
+        assert_eq!((atlas.width, atlas.height), (48, 16));
+        let names: Vec<&str> = atlas.regions.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["hero 0.aseprite", "hero 1.aseprite", "hero 2.aseprite"]);
+
+        let trimmed = &atlas.regions[1];
+        assert_eq!((trimmed.frame.width, trimmed.frame.height), (8.0, 12.0));
+        assert_eq!((trimmed.trim_offset.x, trimmed.trim_offset.y), (4.0, 2.0));
+        assert_eq!((trimmed.uv.x, trimmed.uv.width), (16.0 / 48.0, 8.0 / 48.0));
+    }
+
+    #[test]
+    fn parses_array_layout() {
+        let atlas = parse(ARRAY).unwrap();
+
+        assert_eq!(atlas.region_index("b"), Some(1));
+        assert!(atlas.clips.is_empty());
+        // Sin slices el pivote es la esquina superior izquierda
+        let pivot = atlas.regions[0].pivot;
+        assert_eq!((pivot.x, pivot.y), (0.0, 0.0));
+    }
+
+    #[test]
+    fn frame_tags_become_clips() {
+        let atlas = parse(HASH).unwrap();
+
+        let walk = atlas.clip("walk").unwrap();
+        assert_eq!(walk.mode, PlayMode::Loop);
+        assert_eq!(frame_indices(walk), vec![0, 1, 2]);
+        // Milisegundos a segundos, 100 ms si el fotograma no tiene duración
+        let durations: Vec<f32> = walk.frames.iter().map(|f| f.duration).collect();
+        assert_eq!(durations, vec![0.1, 0.2, 0.1]);
+
+        assert_eq!(frame_indices(atlas.clip("back").unwrap()), vec![2, 1]);
+        assert_eq!(atlas.clip("bounce").unwrap().mode, PlayMode::PingPong);
+        assert_eq!(atlas.clip("hit").unwrap().mode, PlayMode::Once);
+    }
+
+    // El pivote del slice se normaliza con el tamaño original y el origen compensa el recorte
+    #[test]
+    fn slice_pivot_and_origin_follow_the_trim() {
+        let atlas = parse(HASH).unwrap();
+
+        let first = &atlas.regions[0];
+        assert_eq!((first.pivot.x, first.pivot.y), (0.5, 1.0));
+
+        // El fotograma 1 usa todavía la clave del fotograma 0: pivote (8, 16) en la
+        // imagen original, (4, 14) dentro del quad de 8x12
+        let trimmed = atlas.regions[1].origin();
+        assert_eq!((trimmed.x, trimmed.y), (0.5, 14.0 / 12.0));
+
+        let last = &atlas.regions[2];
+        assert_eq!((last.pivot.x, last.pivot.y), (0.25, 1.0));
+    }
+
+    #[test]
+    fn rejects_tags_outside_the_frames() {
+        let json = ARRAY.replace(
+            r#""size": {"w": 16, "h": 8}"#,
+            r#""size": {"w": 16, "h": 8}, "frameTags": [{"name": "bad", "from": 1, "to": 5}]"#,
+        );
+
+        let error = parse(&json).err().unwrap();
+        assert!(error.contains("bad"), "{}", error);
+    }
+
+    #[test]
+    fn rejects_missing_meta_and_rotated_frames() {
+        assert!(parse(r#"{"frames": {}}"#).is_err());
+
+        let rotated = ARRAY.replace(r#""duration": 50"#, r#""duration": 50, "rotated": true"#);
+        assert!(parse(&rotated).is_err());
+    }
+}
@@ -0,0 +1,291 @@
+use serde_json::Value;
+
+use crate::gl_utility::texture::Texture;
+use crate::graphics::sprite::Sprite;
+use crate::graphics::sprite_sheet::SpriteSheet;
+use crate::graphics::animation::Animation;
+use crate::math::rect::Rect;
+use crate::math::vector2::Vector2;
+use crate::math::vector3::Vector3;
+
+// Zona de un atlas con la información de recorte (trim) que exportan
+// Aseprite y TexturePacker
+#[derive(Clone)]
+pub struct AtlasRegion {
+    pub name: String,
+    // Rectángulo en píxeles dentro de la textura del atlas
+    pub frame: Rect,
+    // Rectángulo en coordenadas UV, listo para Sprite::set_region
+    pub uv: Rect,
+    // Tamaño de la imagen original antes de recortar los bordes transparentes
+    pub source_size: Vector2,
+    // Posición del fotograma recortado dentro de la imagen original
+    pub trim_offset: Vector2,
+    // Pivote normalizado respecto a la imagen original ((0, 0) = esquina superior izquierda)
+    pub pivot: Vector2,
+    // Página (textura) del atlas en la que está. Los atlas importados tienen una sola
+    pub page: usize,
+}
+
+impl AtlasRegion {
+    // Origen para Sprite::origin. Al recortar los bordes el quad es más pequeño que
+    // la imagen original, así que hay que desplazar el origen para que el pivote
+    // siga en el mismo píxel y el fotograma no "baile" al animar.
+    // Un fotograma completamente transparente queda de tamaño 0: su origen es (0, 0)
+    pub fn origin(&self) -> Vector3 {
+        Vector3::new(
+            ratio(self.pivot.x * self.source_size.x - self.trim_offset.x, self.frame.width),
+            ratio(self.pivot.y * self.source_size.y - self.trim_offset.y, self.frame.height),
+            0.0,
+        )
+    }
+
+    // Ajusta tamaño, origen y zona de textura del sprite a esta región
+    pub fn apply(&self, sprite: &mut Sprite) {
+        sprite.width = self.frame.width;
+        sprite.height = self.frame.height;
+        sprite.origin = self.origin();
+        sprite.set_region(self.uv);
+    }
+}
+
+// Resultado de importar un atlas: regiones y clips de animación.
+// Los fotogramas de los clips son índices en regions
+pub struct TextureAtlas {
+    // Ruta de la imagen tal y como viene en el JSON (relativa al JSON)
+    pub image: String,
+    pub width: u32,
+    pub height: u32,
+
+    pub regions: Vec<AtlasRegion>,
+    pub clips: Vec<Animation>,
+}
+
+impl TextureAtlas {
+    pub fn region(&self, name: &str) -> Option<&AtlasRegion> {
+        self.regions.iter().find(|r| r.name == name)
+    }
+
+    pub fn region_index(&self, name: &str) -> Option<usize> {
+        self.regions.iter().position(|r| r.name == name)
+    }
+
+    pub fn clip(&self, name: &str) -> Option<&Animation> {
+        self.clips.iter().find(|c| c.name == name)
+    }
+
+    // SpriteSheet con un fotograma por región (en el mismo orden) y sus orígenes,
+    // para usarla con AnimatedSprite y los clips del atlas
+    pub fn sprite_sheet<'a>(&self, texture: &'a Texture) -> SpriteSheet<'a> {
+        SpriteSheet::from_regions(texture, &self.regions)
+    }
+}
+
+// Fotograma tal y como viene en el JSON, común a Aseprite y TexturePacker
+pub(crate) struct JsonFrame {
+    pub name: String,
+    pub frame: Rect,
+    pub sprite_source_size: Rect,
+    pub source_size: Vector2,
+    // En milisegundos (sólo Aseprite)
+    pub duration: Option<f32>,
+    // Normalizado (sólo TexturePacker)
+    pub pivot: Option<Vector2>,
+}
+
+impl JsonFrame {
+    pub fn to_region(&self, atlas_width: f32, atlas_height: f32, pivot: Vector2) -> AtlasRegion {
+        AtlasRegion {
+            name: self.name.clone(),
+            frame: self.frame,
+            uv: Rect::new(
+                self.frame.x / atlas_width,
+                self.frame.y / atlas_height,
+                self.frame.width / atlas_width,
+                self.frame.height / atlas_height,
+            ),
+            source_size: self.source_size,
+            trim_offset: Vector2::new(self.sprite_source_size.x, self.sprite_source_size.y),
+            pivot,
+            page: 0,
+        }
+    }
+}
+
+// Lee "frames", que puede ser un objeto (nombre -> fotograma) o un array con "filename"
+pub(crate) fn parse_frames(root: &Value) -> Result<Vec<JsonFrame>, String> {
+    let entries: Vec<(String, &Value)> = match root.get("frames") {
+        Some(Value::Object(map)) => map.iter().map(|(k, v)| (k.clone(), v)).collect(),
+        Some(Value::Array(list)) => list
+            .iter()
+            .enumerate()
+            .map(|(i, v)| {
+                let name = v.get("filename").and_then(Value::as_str).map(String::from);
+                (name.unwrap_or_else(|| i.to_string()), v)
+            })
+            .collect(),
+        _ => return Err(String::from("El JSON no tiene \"frames\"")),
+    };
+
+    entries
+        .into_iter()
+        .map(|(name, value)| {
+            let frame = parse_rect(value.get("frame"))
+                .ok_or_else(|| format!("El fotograma {} no tiene \"frame\"", name))?;
+            let rotated = value.get("rotated").and_then(Value::as_bool).unwrap_or(false);
+            if rotated {
+                return Err(format!(
+                    "El fotograma {} está rotado, desactiva la rotación al exportar el atlas",
+                    name
+                ));
+            }
+
+            let sprite_source_size = parse_rect(value.get("spriteSourceSize"))
+                .unwrap_or_else(|| Rect::new(0.0, 0.0, frame.width, frame.height));
+            let source_size = parse_size(value.get("sourceSize"))
+                .unwrap_or_else(|| Vector2::new(frame.width, frame.height));
+
+            Ok(JsonFrame {
+                name,
+                frame,
+                sprite_source_size,
+                source_size,
+                duration: value.get("duration").and_then(Value::as_f64).map(|d| d as f32),
+                pivot: value.get("pivot").and_then(|p| parse_point(Some(p))),
+            })
+        })
+        .collect()
+}
+
+// Lee meta.image y meta.size
+pub(crate) fn parse_meta(root: &Value) -> Result<(String, u32, u32), String> {
+    let meta = root.get("meta").ok_or("El JSON no tiene \"meta\"")?;
+    let image = meta.get("image").and_then(Value::as_str).unwrap_or("").to_string();
+    let size = parse_size(meta.get("size")).ok_or("El JSON no tiene \"meta.size\"")?;
+
+    Ok((image, size.x as u32, size.y as u32))
+}
+
+// Divide sin devolver NaN ni infinito cuando el divisor es 0
+pub(crate) fn ratio(numerator: f32, denominator: f32) -> f32 {
+    if denominator == 0.0 {
+        0.0
+    } else {
+        numerator / denominator
+    }
+}
+
+fn number(value: &Value, key: &str) -> Option<f32> {
+    value.get(key).and_then(Value::as_f64).map(|n| n as f32)
+}
+
+pub(crate) fn parse_rect(value: Option<&Value>) -> Option<Rect> {
+    let value = value?;
+    Some(Rect::new(number(value, "x")?, number(value, "y")?, number(value, "w")?, number(value, "h")?))
+}
+
+pub(crate) fn parse_size(value: Option<&Value>) -> Option<Vector2> {
+    let value = value?;
+    Some(Vector2::new(number(value, "w")?, number(value, "h")?))
+}
+
+pub(crate) fn parse_point(value: Option<&Value>) -> Option<Vector2> {
+    let value = value?;
+    Some(Vector2::new(number(value, "x")?, number(value, "y")?))
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOLERANCE: f32 = 1e-5;
+
+    fn region(frame: Rect, source_size: Vector2, trim_offset: Vector2, pivot: Vector2) -> AtlasRegion {
+        AtlasRegion {
+            name: String::from("region"),
+            frame,
+            uv: Rect::new(0.0, 0.0, 1.0, 1.0),
+            source_size,
+            trim_offset,
+            pivot,
+            page: 0,
+        }
+    }
+
+    #[test]
+    fn untrimmed_origin_is_the_pivot() {
+        let region = region(
+            Rect::new(0.0, 0.0, 32.0, 16.0),
+            Vector2::new(32.0, 16.0),
+            Vector2::zero(),
+            Vector2::new(0.5, 1.0),
+        );
+
+        let origin = region.origin();
+        assert!((origin.x - 0.5).abs() < TOLERANCE && (origin.y - 1.0).abs() < TOLERANCE);
+    }
+
+    // Imagen de 32x32 recortada a 16x8 empezando en (4, 20): el pivote central (16, 16)
+    // queda en (12, -4) dentro del quad recortado
+    #[test]
+    fn trimmed_origin_keeps_the_pivot_pixel() {
+        let region = region(
+            Rect::new(0.0, 0.0, 16.0, 8.0),
+            Vector2::new(32.0, 32.0),
+            Vector2::new(4.0, 20.0),
+            Vector2::new(0.5, 0.5),
+        );
+
+        let origin = region.origin();
+        assert!((origin.x - 0.75).abs() < TOLERANCE, "{}", origin.x);
+        assert!((origin.y + 0.5).abs() < TOLERANCE, "{}", origin.y);
+    }
+
+    #[test]
+    fn fully_trimmed_frame_has_zero_origin() {
+        let region = region(
+            Rect::new(0.0, 0.0, 0.0, 0.0),
+            Vector2::new(32.0, 32.0),
+            Vector2::zero(),
+            Vector2::new(0.5, 0.5),
+        );
+
+        let origin = region.origin();
+        assert_eq!((origin.x, origin.y), (0.0, 0.0));
+    }
+
+    #[test]
+    fn to_region_computes_uv_and_trim_offset() {
+        let frame = JsonFrame {
+            name: String::from("walk"),
+            frame: Rect::new(32.0, 16.0, 16.0, 8.0),
+            sprite_source_size: Rect::new(3.0, 5.0, 16.0, 8.0),
+            source_size: Vector2::new(24.0, 24.0),
+            duration: None,
+            pivot: None,
+        };
+
+        let region = frame.to_region(64.0, 32.0, Vector2::zero());
+        assert_eq!((region.uv.x, region.uv.y, region.uv.width, region.uv.height), (0.5, 0.5, 0.25, 0.25));
+        assert_eq!((region.trim_offset.x, region.trim_offset.y), (3.0, 5.0));
+        assert_eq!((region.source_size.x, region.source_size.y), (24.0, 24.0));
+    }
+
+    #[test]
+    fn parse_frames_rejects_rotated_frames() {
+        let root: Value = serde_json::from_str(r#"{"frames": {"a": {"frame": {"x": 0, "y": 0, "w": 4, "h": 4}, "rotated": true}}}"#).unwrap();
+
+        let error = parse_frames(&root).err().unwrap();
+        assert!(error.contains("rotado"), "{}", error);
+    }
+
+    // Sin spriteSourceSize ni sourceSize el fotograma se toma como no recortado
+    #[test]
+    fn parse_frames_defaults_to_untrimmed() {
+        let root: Value = serde_json::from_str(r#"{"frames": [{"frame": {"x": 2, "y": 4, "w": 6, "h": 8}}]}"#).unwrap();
+
+        let frames = parse_frames(&root).unwrap();
+        assert_eq!(frames[0].name, "0");
+        assert_eq!((frames[0].sprite_source_size.x, frames[0].sprite_source_size.y), (0.0, 0.0));
+        assert_eq!((frames[0].source_size.x, frames[0].source_size.y), (6.0, 8.0));
+    }
+}
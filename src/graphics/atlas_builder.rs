@@ -0,0 +1,366 @@
+use image::RgbaImage;
+
+use crate::gl_utility::texture::Texture;
+use crate::graphics::atlas::AtlasRegion;
+use crate::graphics::sprite::Sprite;
+use crate::graphics::sprite_sheet::SpriteSheet;
+use crate::math::rect::Rect;
+use crate::math::rect_packer::{MaxRectsPacker, PackedRect};
+use crate::math::vector2::Vector2;
+
+// Junta muchas imágenes sueltas en una o varias texturas (páginas) en tiempo de
+// ejecución, para no cambiar de textura en cada sprite.
+// padding son los píxeles vacíos entre imágenes y extrude los píxeles de borde que
+// se repiten alrededor de cada imagen, para que el filtrado no mezcle con la vecina
+pub struct AtlasBuilder {
+    pub max_width: u32,
+    pub max_height: u32,
+    pub padding: u32,
+    pub extrude: u32,
+    // Recorta cada página al tamaño usado, redondeado a potencia de dos
+    pub power_of_two: bool,
+
+    images: Vec<(String, RgbaImage)>,
+}
+
+// Resultado de empaquetar, todavía en memoria (sin subir a la GPU)
+pub struct PackedImages {
+    pub pages: Vec<RgbaImage>,
+    pub regions: Vec<AtlasRegion>,
+}
+
+// Atlas ya subido a la GPU, una textura por página
+pub struct PackedAtlas {
+    pub pages: Vec<Texture>,
+    pub regions: Vec<AtlasRegion>,
+}
+
+impl AtlasBuilder {
+    pub fn new(max_width: u32, max_height: u32) -> AtlasBuilder {
+        AtlasBuilder {
+            max_width,
+            max_height,
+            padding: 2,
+            extrude: 1,
+            power_of_two: true,
+
+            images: Vec::new(),
+        }
+    }
+
+    pub fn with_padding(mut self, padding: u32) -> AtlasBuilder {
+        self.padding = padding;
+        self
+    }
+
+    pub fn with_extrude(mut self, extrude: u32) -> AtlasBuilder {
+        self.extrude = extrude;
+        self
+    }
+
+    pub fn with_power_of_two(mut self, power_of_two: bool) -> AtlasBuilder {
+        self.power_of_two = power_of_two;
+        self
+    }
+
+    pub fn add(&mut self, name: &str, image: RgbaImage) {
+        self.images.push((String::from(name), image));
+    }
+
+    // Carga una imagen del disco y la añade
+    pub fn add_file(&mut self, name: &str, path: &str) -> Result<(), String> {
+        let image = image::open(path)
+            .map_err(|e| format!("No se puede cargar la imagen {} ({}): {}", name, path, e))?
+            .to_rgba8();
+        self.add(name, image);
+        Ok(())
+    }
+
+    // Reparte las imágenes en páginas. Las regiones salen en el orden en que se añadieron
+    pub fn pack(&self) -> Result<PackedImages, String> {
+        // Colocar primero las más grandes deja menos huecos
+        let mut order: Vec<usize> = (0..self.images.len()).collect();
+        order.sort_by_key(|&i| {
+            let image = &self.images[i].1;
+            std::cmp::Reverse((image.width().max(image.height()), image.width() * image.height()))
+        });
+
+        let mut packers: Vec<MaxRectsPacker> = Vec::new();
+        let mut placements: Vec<Option<(usize, PackedRect)>> = vec![None; self.images.len()];
+
+        for i in order {
+            let (name, image) = &self.images[i];
+            let cell_width = image.width() + self.extrude * 2 + self.padding;
+            let cell_height = image.height() + self.extrude * 2 + self.padding;
+
+            // El padding sólo va a la derecha y abajo, así que el del borde de la
+            // página sobra y la celda puede ser padding más grande que la página
+            if cell_width > self.max_width + self.padding || cell_height > self.max_height + self.padding {
+                return Err(format!(
+                    "La imagen {} de {}x{} no cabe en una página de {}x{}",
+                    name, image.width(), image.height(), self.max_width, self.max_height
+                ));
+            }
+
+            let placed = packers
+                .iter_mut()
+                .enumerate()
+                .find_map(|(page, packer)| packer.insert(cell_width, cell_height).map(|rect| (page, rect)));
+
+            placements[i] = Some(match placed {
+                Some(placed) => placed,
+                None => {
+                    let mut packer =
+                        MaxRectsPacker::new(self.max_width + self.padding, self.max_height + self.padding);
+                    let rect = packer
+                        .insert(cell_width, cell_height)
+                        .expect("una celda que cabe en la página cabe en una página vacía");
+                    packers.push(packer);
+                    (packers.len() - 1, rect)
+                }
+            });
+        }
+
+        let page_sizes: Vec<(u32, u32)> = packers.iter().map(|packer| self.page_size(packer)).collect();
+        let mut pages: Vec<RgbaImage> = page_sizes.iter().map(|&(w, h)| RgbaImage::new(w, h)).collect();
+
+        let mut regions = Vec::with_capacity(self.images.len());
+        for ((name, image), placement) in self.images.iter().zip(placements) {
+            let (page, cell) = placement.expect("todas las imágenes tienen sitio");
+            let (page_width, page_height) = page_sizes[page];
+            let x = cell.x + self.extrude;
+            let y = cell.y + self.extrude;
+
+            blit_extruded(&mut pages[page], image, x, y, self.extrude);
+
+            let (width, height) = (image.width() as f32, image.height() as f32);
+            regions.push(AtlasRegion {
+                name: name.clone(),
+                frame: Rect::new(x as f32, y as f32, width, height),
+                uv: Rect::new(
+                    x as f32 / page_width as f32,
+                    y as f32 / page_height as f32,
+                    width / page_width as f32,
+                    height / page_height as f32,
+                ),
+                source_size: Vector2::new(width, height),
+                trim_offset: Vector2::zero(),
+                pivot: Vector2::zero(),
+                page,
+            });
+        }
+
+        Ok(PackedImages { pages, regions })
+    }
+
+    // Empaqueta y sube cada página como textura ("<name>_0", "<name>_1"...)
+    pub fn build(&self, name: &str) -> Result<PackedAtlas, String> {
+        let packed = self.pack()?;
+        let pages = packed
+            .pages
+            .iter()
+            .enumerate()
+            .map(|(i, page)| Texture::from_rgba(&format!("{}_{}", name, i), page.width(), page.height(), page.as_raw()))
+            .collect();
+
+        Ok(PackedAtlas {
+            pages,
+            regions: packed.regions,
+        })
+    }
+
+    // Tamaño final de la página: lo ocupado (sin el padding del borde) o la potencia
+    // de dos que lo contiene, sin pasarse del máximo
+    fn page_size(&self, packer: &MaxRectsPacker) -> (u32, u32) {
+        let used_width = packer.used().iter().map(|r| r.x + r.width).max().unwrap_or(0);
+        let used_height = packer.used().iter().map(|r| r.y + r.height).max().unwrap_or(0);
+        let width = used_width.saturating_sub(self.padding).max(1);
+        let height = used_height.saturating_sub(self.padding).max(1);
+
+        if self.power_of_two {
+            (
+                width.next_power_of_two().min(self.max_width),
+                height.next_power_of_two().min(self.max_height),
+            )
+        } else {
+            (width, height)
+        }
+    }
+}
+
+impl PackedAtlas {
+    pub fn region(&self, name: &str) -> Option<&AtlasRegion> {
+        self.regions.iter().find(|r| r.name == name)
+    }
+
+    pub fn page(&self, region: &AtlasRegion) -> &Texture {
+        &self.pages[region.page]
+    }
+
+    // Pone al sprite la textura de la página y la zona de la región. Devuelve
+    // false si no hay ninguna región con ese nombre
+    pub fn apply<'a>(&'a self, name: &str, sprite: &mut Sprite<'a>) -> bool {
+        match self.region(name) {
+            Some(region) => {
+                sprite.set_texture(self.page(region));
+                region.apply(sprite);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // SpriteSheet con las regiones de una página, en el orden en que se añadieron
+    pub fn sprite_sheet(&self, page: usize) -> SpriteSheet<'_> {
+        let regions: Vec<AtlasRegion> = self.regions.iter().filter(|r| r.page == page).cloned().collect();
+        SpriteSheet::from_regions(&self.pages[page], &regions)
+    }
+}
+
+// Copia la imagen en (x, y) y repite sus píxeles de borde extrude veces hacia fuera
+// (las esquinas se rellenan con el píxel de la esquina)
+fn blit_extruded(page: &mut RgbaImage, image: &RgbaImage, x: u32, y: u32, extrude: u32) {
+    if image.width() == 0 || image.height() == 0 {
+        return;
+    }
+
+    let last_x = image.width() - 1;
+    let last_y = image.height() - 1;
+    let e = extrude as i64;
+
+    for dy in -e..=last_y as i64 + e {
+        for dx in -e..=last_x as i64 + e {
+            let source_x = dx.clamp(0, last_x as i64) as u32;
+            let source_y = dy.clamp(0, last_y as i64) as u32;
+            let pixel = *image.get_pixel(source_x, source_y);
+            page.put_pixel((x as i64 + dx) as u32, (y as i64 + dy) as u32, pixel);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    fn solid(width: u32, height: u32, value: u8) -> RgbaImage {
+        RgbaImage::from_pixel(width, height, Rgba([value, value, value, 255]))
+    }
+
+    // Imagen de 2x2 con un color distinto en cada píxel
+    fn quad() -> RgbaImage {
+        let mut image = RgbaImage::new(2, 2);
+        image.put_pixel(0, 0, Rgba([255, 0, 0, 255]));
+        image.put_pixel(1, 0, Rgba([0, 255, 0, 255]));
+        image.put_pixel(0, 1, Rgba([0, 0, 255, 255]));
+        image.put_pixel(1, 1, Rgba([255, 255, 255, 255]));
+        image
+    }
+
+    // Celda que ocupa cada región: la imagen más el borde repetido
+    fn cell(region: &AtlasRegion, extrude: u32) -> (i64, i64, i64, i64) {
+        let e = extrude as i64;
+        (
+            region.frame.x as i64 - e,
+            region.frame.y as i64 - e,
+            region.frame.x as i64 + region.frame.width as i64 + e,
+            region.frame.y as i64 + region.frame.height as i64 + e,
+        )
+    }
+
+    #[test]
+    fn regions_stay_inside_the_page_and_keep_the_padding() {
+        let (padding, extrude) = (3, 1);
+        let mut builder = AtlasBuilder::new(128, 128).with_padding(padding).with_extrude(extrude);
+        for i in 0..12 {
+            builder.add(&format!("image{}", i), solid(5 + i * 2, 20 - i, i as u8));
+        }
+
+        let packed = builder.pack().unwrap();
+        assert_eq!(packed.pages.len(), 1);
+        let page = &packed.pages[0];
+
+        for (i, a) in packed.regions.iter().enumerate() {
+            let (x0, y0, x1, y1) = cell(a, extrude);
+            assert!(x0 >= 0 && y0 >= 0 && x1 <= page.width() as i64 && y1 <= page.height() as i64);
+
+            // Entre dos celdas hay al menos padding píxeles en algún eje
+            for b in &packed.regions[i + 1..] {
+                let (bx0, by0, bx1, by1) = cell(b, extrude);
+                let gap_x = (bx0 - x1).max(x0 - bx1);
+                let gap_y = (by0 - y1).max(y0 - by1);
+                assert!(gap_x >= padding as i64 || gap_y >= padding as i64, "{} y {} están demasiado cerca", a.name, b.name);
+            }
+        }
+    }
+
+    #[test]
+    fn regions_keep_the_order_they_were_added() {
+        let mut builder = AtlasBuilder::new(64, 64);
+        builder.add("small", solid(2, 2, 1));
+        builder.add("big", solid(20, 20, 2));
+
+        let names: Vec<String> = builder.pack().unwrap().regions.into_iter().map(|r| r.name).collect();
+        assert_eq!(names, vec!["small", "big"]);
+    }
+
+    // Los píxeles de borde se repiten hacia fuera, y en las esquinas el de la esquina
+    #[test]
+    fn extrusion_repeats_the_edge_pixels() {
+        let mut builder = AtlasBuilder::new(16, 16).with_padding(0).with_extrude(2);
+        builder.add("quad", quad());
+
+        let packed = builder.pack().unwrap();
+        let page = &packed.pages[0];
+        let region = &packed.regions[0];
+        let (x, y) = (region.frame.x as u32, region.frame.y as u32);
+        assert_eq!((x, y), (2, 2));
+
+        assert_eq!(*page.get_pixel(x, y), Rgba([255, 0, 0, 255]));
+        assert_eq!(*page.get_pixel(x - 2, y - 2), Rgba([255, 0, 0, 255]));
+        assert_eq!(*page.get_pixel(x + 3, y - 1), Rgba([0, 255, 0, 255]));
+        assert_eq!(*page.get_pixel(x - 1, y + 1), Rgba([0, 0, 255, 255]));
+        assert_eq!(*page.get_pixel(x + 3, y + 3), Rgba([255, 255, 255, 255]));
+    }
+
+    #[test]
+    fn spills_onto_a_second_page_when_full() {
+        let mut builder = AtlasBuilder::new(32, 32).with_padding(0).with_extrude(0);
+        for i in 0..5 {
+            builder.add(&format!("tile{}", i), solid(16, 16, i));
+        }
+
+        let packed = builder.pack().unwrap();
+        assert_eq!(packed.pages.len(), 2);
+        assert_eq!(packed.regions.iter().filter(|r| r.page == 0).count(), 4);
+        assert_eq!(packed.regions.iter().filter(|r| r.page == 1).count(), 1);
+
+        // La segunda página sólo tiene una imagen y se recorta a su tamaño
+        assert_eq!(packed.pages[1].dimensions(), (16, 16));
+        let last = packed.regions.iter().find(|r| r.page == 1).unwrap();
+        assert_eq!((last.uv.width, last.uv.height), (1.0, 1.0));
+    }
+
+    #[test]
+    fn pages_round_up_to_a_power_of_two() {
+        let mut builder = AtlasBuilder::new(256, 256).with_padding(0).with_extrude(0);
+        builder.add("odd", solid(20, 9, 0));
+
+        assert_eq!(builder.pack().unwrap().pages[0].dimensions(), (32, 16));
+
+        let builder = builder.with_power_of_two(false);
+        assert_eq!(builder.pack().unwrap().pages[0].dimensions(), (20, 9));
+    }
+
+    // El padding del borde de la página sobra, pero el borde repetido no
+    #[test]
+    fn rejects_images_larger_than_the_page() {
+        let mut builder = AtlasBuilder::new(32, 32).with_padding(4).with_extrude(1);
+        builder.add("fits", solid(30, 30, 0));
+        assert!(builder.pack().is_ok());
+
+        builder.add("too_big", solid(31, 30, 0));
+        let error = builder.pack().err().unwrap();
+        assert!(error.contains("too_big"), "{}", error);
+    }
+}
@@ -1,3 +1,7 @@
+use auto_ops::*;
+
+// Componentes en [0, 1], en espacio sRGB salvo que se diga lo contrario
+#[derive(Copy, Clone, PartialEq, Debug)]
 pub struct Color {
     pub r: f32,
     pub g: f32,
@@ -5,6 +9,33 @@ pub struct Color {
     pub a: f32,
 }
 
+// Operaciones componente a componente (incluido alfa). No se recorta a [0, 1],
+// para eso está clamped()
+impl_op_ex!(+ |a: &Color, b: &Color| -> Color {
+    Color::from_f32(a.r + b.r, a.g + b.g, a.b + b.b, a.a + b.a)
+});
+
+impl_op_ex!(- |a: &Color, b: &Color| -> Color {
+    Color::from_f32(a.r - b.r, a.g - b.g, a.b - b.b, a.a - b.a)
+});
+
+// Modulación: multiplica cada componente (como el tinte de un sprite)
+impl_op_ex!(* |a: &Color, b: &Color| -> Color {
+    Color::from_f32(a.r * b.r, a.g * b.g, a.b * b.b, a.a * b.a)
+});
+
+impl_op_ex_commutative!(* |a: &Color, b: &f32| -> Color {
+    Color::from_f32(a.r * b, a.g * b, a.b * b, a.a * b)
+});
+
+impl_op_ex!(+= |a: &mut Color, b: &Color| {
+    *a = *a + b;
+});
+
+impl_op_ex!(*= |a: &mut Color, b: &Color| {
+    *a = *a * b;
+});
+
 impl Color {
     pub fn new(r: u8, g: u8, b: u8, a: u8) -> Color {
         Color {
@@ -15,6 +46,158 @@ impl Color {
         }
     }
 
+    pub fn from_f32(r: f32, g: f32, b: f32, a: f32) -> Color {
+        Color { r, g, b, a }
+    }
+
+    // Componentes en bytes (redondeados y recortados a [0, 255])
+    pub fn to_u8(self) -> [u8; 4] {
+        let to_byte = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+        [to_byte(self.r), to_byte(self.g), to_byte(self.b), to_byte(self.a)]
+    }
+
+    pub fn clamped(&self) -> Color {
+        Color::from_f32(
+            self.r.clamp(0.0, 1.0),
+            self.g.clamp(0.0, 1.0),
+            self.b.clamp(0.0, 1.0),
+            self.a.clamp(0.0, 1.0),
+        )
+    }
+
+    pub fn with_alpha(&self, a: f32) -> Color {
+        Color::from_f32(self.r, self.g, self.b, a)
+    }
+
+    // Interpolación lineal componente a componente (t = 0 da self, t = 1 da other)
+    pub fn lerp(&self, other: &Color, t: f32) -> Color {
+        Color::from_f32(
+            self.r + (other.r - self.r) * t,
+            self.g + (other.g - self.g) * t,
+            self.b + (other.b - self.b) * t,
+            self.a + (other.a - self.a) * t,
+        )
+    }
+
+    // Interpola en espacio lineal, que da mezclas más naturales que lerp en sRGB
+    pub fn lerp_linear(&self, other: &Color, t: f32) -> Color {
+        self.to_linear().lerp(&other.to_linear(), t).to_srgb()
+    }
+
+    // Multiplica el color por su alfa (para blending con ONE, ONE_MINUS_SRC_ALPHA)
+    pub fn premultiplied(&self) -> Color {
+        Color::from_f32(self.r * self.a, self.g * self.a, self.b * self.a, self.a)
+    }
+
+    // Deshace premultiplied. Con alfa 0 el color se ha perdido y devuelve transparente
+    pub fn unpremultiplied(&self) -> Color {
+        if self.a <= 0.0 {
+            return Color::transparent();
+        }
+        Color::from_f32(self.r / self.a, self.g / self.a, self.b / self.a, self.a)
+    }
+
+    // Pasa de sRGB a lineal. El alfa ya es lineal y no cambia
+    pub fn to_linear(self) -> Color {
+        Color::from_f32(srgb_to_linear(self.r), srgb_to_linear(self.g), srgb_to_linear(self.b), self.a)
+    }
+
+    // Pasa de lineal a sRGB
+    pub fn to_srgb(self) -> Color {
+        Color::from_f32(linear_to_srgb(self.r), linear_to_srgb(self.g), linear_to_srgb(self.b), self.a)
+    }
+
+    // Tono en grados [0, 360), saturación y valor en [0, 1]
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32, alpha: f32) -> Color {
+        let chroma = value * saturation;
+        let (r, g, b) = hue_to_rgb(hue, chroma);
+        let m = value - chroma;
+
+        Color::from_f32(r + m, g + m, b + m, alpha)
+    }
+
+    // Devuelve (tono, saturación, valor)
+    pub fn to_hsv(self) -> (f32, f32, f32) {
+        let max = self.r.max(self.g).max(self.b);
+        let min = self.r.min(self.g).min(self.b);
+        let chroma = max - min;
+
+        let saturation = if max > 0.0 { chroma / max } else { 0.0 };
+        (self.hue(max, chroma), saturation, max)
+    }
+
+    // Tono en grados [0, 360), saturación y luminosidad en [0, 1]
+    pub fn from_hsl(hue: f32, saturation: f32, lightness: f32, alpha: f32) -> Color {
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        let (r, g, b) = hue_to_rgb(hue, chroma);
+        let m = lightness - chroma / 2.0;
+
+        Color::from_f32(r + m, g + m, b + m, alpha)
+    }
+
+    // Devuelve (tono, saturación, luminosidad)
+    pub fn to_hsl(self) -> (f32, f32, f32) {
+        let max = self.r.max(self.g).max(self.b);
+        let min = self.r.min(self.g).min(self.b);
+        let chroma = max - min;
+        let lightness = (max + min) / 2.0;
+
+        let saturation = if chroma > 0.0 {
+            chroma / (1.0 - (2.0 * lightness - 1.0).abs())
+        } else {
+            0.0
+        };
+        (self.hue(max, chroma), saturation, lightness)
+    }
+
+    // Tono común a HSV y HSL
+    fn hue(&self, max: f32, chroma: f32) -> f32 {
+        if chroma <= 0.0 {
+            return 0.0;
+        }
+
+        let hue = if max == self.r {
+            ((self.g - self.b) / chroma).rem_euclid(6.0)
+        } else if max == self.g {
+            (self.b - self.r) / chroma + 2.0
+        } else {
+            (self.r - self.g) / chroma + 4.0
+        };
+
+        hue * 60.0
+    }
+
+    // Acepta "#RGB", "#RGBA", "#RRGGBB" y "#RRGGBBAA", con o sin '#'.
+    // Devuelve None si el texto no es un color válido
+    pub fn from_hex(hex: &str) -> Option<Color> {
+        let hex = hex.trim();
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        let digit = |i: usize| u8::from_str_radix(&hex[i..i + 1], 16).ok().map(|d| d * 17);
+        let byte = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+
+        match hex.len() {
+            3 => Some(Color::new(digit(0)?, digit(1)?, digit(2)?, 255)),
+            4 => Some(Color::new(digit(0)?, digit(1)?, digit(2)?, digit(3)?)),
+            6 => Some(Color::new(byte(0)?, byte(2)?, byte(4)?, 255)),
+            8 => Some(Color::new(byte(0)?, byte(2)?, byte(4)?, byte(6)?)),
+            _ => None,
+        }
+    }
+
+    // Devuelve el color como "#RRGGBBAA"
+    pub fn to_hex(self) -> String {
+        let [r, g, b, a] = self.to_u8();
+        format!("#{:02X}{:02X}{:02X}{:02X}", r, g, b, a)
+    }
+
+    pub fn transparent() -> Color {
+        Color::new(0, 0, 0, 0)
+    }
+
     pub fn white() -> Color {
         Color::new(255, 255, 255, 255)
     }
@@ -31,4 +214,194 @@ impl Color {
     pub fn blue() -> Color {
         Color::new(0, 0, 255, 255)
     }
-}
\ No newline at end of file
+    pub fn yellow() -> Color {
+        Color::new(255, 255, 0, 255)
+    }
+    pub fn cyan() -> Color {
+        Color::new(0, 255, 255, 255)
+    }
+    pub fn magenta() -> Color {
+        Color::new(255, 0, 255, 255)
+    }
+    pub fn gray() -> Color {
+        Color::new(128, 128, 128, 255)
+    }
+    pub fn light_gray() -> Color {
+        Color::new(192, 192, 192, 255)
+    }
+    pub fn dark_gray() -> Color {
+        Color::new(64, 64, 64, 255)
+    }
+    pub fn orange() -> Color {
+        Color::new(255, 165, 0, 255)
+    }
+    pub fn purple() -> Color {
+        Color::new(128, 0, 128, 255)
+    }
+    pub fn pink() -> Color {
+        Color::new(255, 192, 203, 255)
+    }
+    pub fn brown() -> Color {
+        Color::new(139, 69, 19, 255)
+    }
+    pub fn gold() -> Color {
+        Color::new(255, 215, 0, 255)
+    }
+    pub fn navy() -> Color {
+        Color::new(0, 0, 128, 255)
+    }
+    pub fn teal() -> Color {
+        Color::new(0, 128, 128, 255)
+    }
+    pub fn olive() -> Color {
+        Color::new(128, 128, 0, 255)
+    }
+    pub fn maroon() -> Color {
+        Color::new(128, 0, 0, 255)
+    }
+    pub fn lime() -> Color {
+        Color::new(50, 205, 50, 255)
+    }
+    pub fn sky_blue() -> Color {
+        Color::new(135, 206, 235, 255)
+    }
+    pub fn cornflower_blue() -> Color {
+        Color::new(100, 149, 237, 255)
+    }
+}
+
+// Parte RGB de un color con el tono y croma dados, antes de sumar el mínimo
+fn hue_to_rgb(hue: f32, chroma: f32) -> (f32, f32, f32) {
+    let sector = hue.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+
+    match sector as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    }
+}
+
+// Curvas de transferencia estándar de sRGB (IEC 61966-2-1)
+fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.003_130_8 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOLERANCE: f32 = 1e-4;
+
+    fn assert_color_close(a: Color, b: Color) {
+        assert!(
+            (a.r - b.r).abs() < TOLERANCE && (a.g - b.g).abs() < TOLERANCE
+                && (a.b - b.b).abs() < TOLERANCE && (a.a - b.a).abs() < TOLERANCE,
+            "{:?} != {:?}", a, b
+        );
+    }
+
+    #[test]
+    fn hsv_of_primary_colors() {
+        assert_color_close(Color::from_hsv(0.0, 1.0, 1.0, 1.0), Color::from_f32(1.0, 0.0, 0.0, 1.0));
+        assert_color_close(Color::from_hsv(120.0, 1.0, 1.0, 1.0), Color::from_f32(0.0, 1.0, 0.0, 1.0));
+        assert_color_close(Color::from_hsv(240.0, 1.0, 0.5, 0.25), Color::from_f32(0.0, 0.0, 0.5, 0.25));
+        // El tono da la vuelta
+        assert_color_close(Color::from_hsv(-120.0, 1.0, 1.0, 1.0), Color::from_hsv(240.0, 1.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn hsv_round_trip() {
+        let color = Color::from_f32(0.2, 0.6, 0.9, 1.0);
+        let (hue, saturation, value) = color.to_hsv();
+
+        assert_color_close(Color::from_hsv(hue, saturation, value, 1.0), color);
+        assert_eq!(Color::from_f32(0.5, 0.5, 0.5, 1.0).to_hsv(), (0.0, 0.0, 0.5));
+    }
+
+    #[test]
+    fn hsl_of_known_colors() {
+        assert_color_close(Color::from_hsl(0.0, 1.0, 0.5, 1.0), Color::from_f32(1.0, 0.0, 0.0, 1.0));
+        assert_color_close(Color::from_hsl(60.0, 1.0, 0.25, 1.0), Color::from_f32(0.5, 0.5, 0.0, 1.0));
+        assert_color_close(Color::from_hsl(200.0, 0.0, 1.0, 1.0), Color::white());
+    }
+
+    #[test]
+    fn hsl_round_trip() {
+        for &color in &[Color::from_f32(0.8, 0.3, 0.1, 1.0), Color::from_f32(0.1, 0.2, 0.3, 1.0), Color::orange()] {
+            let (hue, saturation, lightness) = color.to_hsl();
+            assert_color_close(Color::from_hsl(hue, saturation, lightness, color.a), color);
+        }
+    }
+
+    #[test]
+    fn srgb_linear_conversion() {
+        // Valores de referencia de la curva sRGB
+        let linear = Color::from_f32(0.5, 0.0, 1.0, 0.5).to_linear();
+        assert_color_close(linear, Color::from_f32(0.214_041, 0.0, 1.0, 0.5));
+
+        let color = Color::from_f32(0.02, 0.35, 0.8, 1.0);
+        assert_color_close(color.to_linear().to_srgb(), color);
+    }
+
+    #[test]
+    fn hex_parsing() {
+        assert_eq!(Color::from_hex("#FF0000"), Some(Color::new(255, 0, 0, 255)));
+        assert_eq!(Color::from_hex("00ff0080"), Some(Color::new(0, 255, 0, 128)));
+        assert_eq!(Color::from_hex("#0f08"), Some(Color::new(0, 255, 0, 136)));
+        assert_eq!(Color::from_hex(" #abc "), Some(Color::new(170, 187, 204, 255)));
+    }
+
+    #[test]
+    fn hex_parsing_rejects_invalid_text() {
+        assert_eq!(Color::from_hex("##ff0000"), None);
+        assert_eq!(Color::from_hex("#ff000"), None);
+        assert_eq!(Color::from_hex("#gg0000"), None);
+        assert_eq!(Color::from_hex("#ñ00"), None);
+        assert_eq!(Color::from_hex(""), None);
+    }
+
+    #[test]
+    fn hex_round_trip() {
+        for hex in &["#FF8000FF", "#12345678", "#00000000"] {
+            assert_eq!(Color::from_hex(hex).unwrap().to_hex(), *hex);
+        }
+
+        let color = Color::new(10, 200, 30, 40);
+        assert_eq!(Color::from_hex(&color.to_hex()), Some(color));
+    }
+
+    #[test]
+    fn premultiply_round_trip() {
+        let color = Color::from_f32(0.8, 0.4, 0.2, 0.5);
+        assert_color_close(color.premultiplied(), Color::from_f32(0.4, 0.2, 0.1, 0.5));
+        assert_color_close(color.premultiplied().unpremultiplied(), color);
+        assert_eq!(Color::from_f32(1.0, 1.0, 1.0, 0.0).unpremultiplied(), Color::transparent());
+    }
+
+    #[test]
+    fn lerp_and_operators() {
+        let a = Color::from_f32(0.0, 0.2, 0.4, 1.0);
+        let b = Color::from_f32(1.0, 0.4, 0.0, 0.0);
+
+        assert_color_close(a.lerp(&b, 0.5), Color::from_f32(0.5, 0.3, 0.2, 0.5));
+        assert_color_close(a + b, Color::from_f32(1.0, 0.6, 0.4, 1.0));
+        assert_color_close(a * 0.5, Color::from_f32(0.0, 0.1, 0.2, 0.5));
+        assert_color_close((a + b + b).clamped(), Color::from_f32(1.0, 1.0, 0.4, 1.0));
+    }
+}
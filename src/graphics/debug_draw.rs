@@ -0,0 +1,345 @@
+#[cfg(any(debug_assertions, feature = "debug_draw"))]
+use std::cell::RefCell;
+
+use crate::gl_utility::gl_buffer::{GLBuffer, AttributeInfo};
+use crate::gl_utility::render_state::{RenderState, StateCache};
+use crate::gl_utility::shader::Shader;
+use crate::graphics::color::Color;
+use crate::math::matrix4x4::Matrix4x4;
+use crate::math::rect::Rect;
+use crate::math::vector2::Vector2;
+
+// Floats por vértice: posición (3) y color (4)
+const VERTEX_SIZE: usize = 7;
+
+// Rejilla de los caracteres de text(): 4 de ancho, 6 de alto y 2 de separación
+const GLYPH_HEIGHT: f32 = 6.0;
+const GLYPH_ADVANCE: f32 = 6.0;
+const LINE_ADVANCE: f32 = 8.0;
+
+struct DebugLine {
+    from: Vector2,
+    to: Vector2,
+    color: Color,
+    // Segundos que le quedan; con 0 sólo se dibuja en el frame actual
+    remaining: f32,
+}
+
+struct DebugState {
+    lines: Vec<DebugLine>,
+    // Duración que se da a lo que se añade (la cambia for_seconds)
+    duration: f32,
+}
+
+#[cfg(any(debug_assertions, feature = "debug_draw"))]
+thread_local! {
+    static STATE: RefCell<DebugState> = const {
+        RefCell::new(DebugState {
+            lines: Vec::new(),
+            duration: 0.0,
+        })
+    };
+}
+
+#[cfg(any(debug_assertions, feature = "debug_draw"))]
+fn record<R: Default>(f: impl FnOnce(&mut DebugState) -> R) -> R {
+    STATE.with(|state| f(&mut state.borrow_mut()))
+}
+
+// En release sin la feature debug_draw nada de esto llega a ejecutarse
+#[cfg(not(any(debug_assertions, feature = "debug_draw")))]
+fn record<R: Default>(_f: impl FnOnce(&mut DebugState) -> R) -> R {
+    R::default()
+}
+
+impl DebugState {
+    fn line(&mut self, from: Vector2, to: Vector2, color: Color) {
+        let remaining = self.duration;
+        self.lines.push(DebugLine { from, to, color, remaining });
+    }
+
+    fn polyline(&mut self, points: &[Vector2], closed: bool, color: Color) {
+        for pair in points.windows(2) {
+            self.line(pair[0], pair[1], color);
+        }
+        if closed && points.len() > 2 {
+            self.line(points[points.len() - 1], points[0], color);
+        }
+    }
+}
+
+// Dibujo inmediato para depurar: se puede llamar desde cualquier sitio durante el
+// frame y DebugRenderer::draw lo dibuja todo encima de la escena. Lo añadido dentro de
+// for_seconds se mantiene ese tiempo (hay que llamar a update al principio de cada frame).
+// En release sólo está activo si se compila con la feature debug_draw
+pub struct DebugDraw;
+
+impl DebugDraw {
+    pub fn line(from: Vector2, to: Vector2, color: Color) {
+        record(|state| state.line(from, to, color));
+    }
+
+    pub fn polyline(points: &[Vector2], closed: bool, color: Color) {
+        record(|state| state.polyline(points, closed, color));
+    }
+
+    pub fn rect(rect: &Rect, color: Color) {
+        record(|state| {
+            let (left, top) = (rect.x, rect.y);
+            let (right, bottom) = (rect.x + rect.width, rect.y + rect.height);
+            state.polyline(
+                &[
+                    Vector2::new(left, top),
+                    Vector2::new(right, top),
+                    Vector2::new(right, bottom),
+                    Vector2::new(left, bottom),
+                ],
+                true,
+                color,
+            );
+        });
+    }
+
+    pub fn circle(center: Vector2, radius: f32, color: Color) {
+        record(|state| {
+            // Más lados cuanto más grande, para que siempre parezca redondo
+            let segments = (radius * 0.5).clamp(12.0, 64.0) as usize;
+            let points: Vec<Vector2> = (0..segments)
+                .map(|i| {
+                    let angle = i as f32 / segments as f32 * std::f32::consts::TAU;
+                    center + Vector2::new(angle.cos(), angle.sin()) * radius
+                })
+                .collect();
+            state.polyline(&points, true, color);
+        });
+    }
+
+    // Línea con punta en to
+    pub fn arrow(from: Vector2, to: Vector2, color: Color) {
+        record(|state| {
+            state.line(from, to, color);
+
+            let length = from.distance(to);
+            if length <= 0.0 {
+                return;
+            }
+            let direction = (to - from) / length;
+            let head = (length * 0.3).min(10.0);
+            let back = to - direction * head;
+            let side = direction.perpendicular() * (head * 0.5);
+            state.line(to, back + side, color);
+            state.line(to, back - side, color);
+        });
+    }
+
+    // Aspa de size píxeles centrada en center, para marcar puntos
+    pub fn cross(center: Vector2, size: f32, color: Color) {
+        record(|state| {
+            let half = size * 0.5;
+            state.line(center + Vector2::new(-half, -half), center + Vector2::new(half, half), color);
+            state.line(center + Vector2::new(-half, half), center + Vector2::new(half, -half), color);
+        });
+    }
+
+    // Texto con una fuente de líneas integrada (mayúsculas, números y signos básicos).
+    // position es la esquina superior izquierda y size la altura de las letras
+    pub fn text(position: Vector2, text: &str, size: f32, color: Color) {
+        record(|state| {
+            let scale = size / GLYPH_HEIGHT;
+            let mut cursor = position;
+
+            for c in text.chars() {
+                if c == '\n' {
+                    cursor = Vector2::new(position.x, cursor.y + LINE_ADVANCE * scale);
+                    continue;
+                }
+
+                for stroke in stroke_glyph(c).split(' ').filter(|stroke| !stroke.is_empty()) {
+                    let points: Vec<Vector2> = stroke
+                        .as_bytes()
+                        .chunks(2)
+                        .map(|point| {
+                            let x = (point[0] - b'0') as f32;
+                            let y = (point[1] - b'0') as f32;
+                            cursor + Vector2::new(x, y) * scale
+                        })
+                        .collect();
+                    state.polyline(&points, false, color);
+                }
+
+                cursor.x += GLYPH_ADVANCE * scale;
+            }
+        });
+    }
+
+    // Lo que se dibuje dentro de f se mantiene seconds segundos
+    pub fn for_seconds(seconds: f32, f: impl FnOnce()) {
+        let previous = record(|state| std::mem::replace(&mut state.duration, seconds));
+        f();
+        record(|state| state.duration = previous);
+    }
+
+    // Descuenta el tiempo de lo que se mantiene y quita lo que ha caducado
+    pub fn update(delta: f32) {
+        record(|state| {
+            for line in state.lines.iter_mut() {
+                line.remaining -= delta;
+            }
+            state.lines.retain(|line| line.remaining > 0.0);
+        });
+    }
+
+    pub fn clear() {
+        record(|state| state.lines.clear());
+    }
+
+    pub fn line_count() -> usize {
+        record(|state| state.lines.len())
+    }
+
+    // Vértices de todas las líneas (posición y color, dos por línea). Olvida las que
+    // sólo eran para este frame
+    fn take_vertices() -> Vec<f32> {
+        record(|state| {
+            let mut data = Vec::with_capacity(state.lines.len() * VERTEX_SIZE * 2);
+            for line in &state.lines {
+                let color = line.color;
+                for point in [line.from, line.to].iter() {
+                    data.extend_from_slice(&[point.x, point.y, 0.0, color.r, color.g, color.b, color.a]);
+                }
+            }
+            state.lines.retain(|line| line.remaining > 0.0);
+            data
+        })
+    }
+}
+
+// Dibuja lo acumulado en DebugDraw con el shader de debug.vert/debug.frag
+// (a_position, a_color y u_projection)
+pub struct DebugRenderer<'a> {
+    buffer: GLBuffer,
+    u_projection_location: i32,
+
+    shader: &'a Shader,
+}
+
+impl<'a> DebugRenderer<'a> {
+    pub fn new(shader: &'a Shader) -> DebugRenderer<'a> {
+        let mut buffer = GLBuffer::new();
+        buffer.set_mode(gl::LINES);
+        // Se vuelve a subir entero cada frame
+        buffer.set_usage(gl::STREAM_DRAW);
+        buffer.configure(
+            vec![
+                AttributeInfo {
+                    location: shader.get_attribute_location("a_position"),
+                    component_size: 3,
+                },
+                AttributeInfo {
+                    location: shader.get_attribute_location("a_color"),
+                    component_size: 4,
+                },
+            ],
+            false,
+        );
+
+        DebugRenderer {
+            buffer,
+            u_projection_location: shader.get_uniform_location("u_projection"),
+
+            shader,
+        }
+    }
+
+    // Dibuja encima de todo con la cámara projection y devuelve cuántas líneas ha dibujado.
+    // Deja activo su shader. Quita la profundidad a través de cache y luego la restaura
+    pub fn draw(&mut self, projection: &Matrix4x4, cache: &mut StateCache) -> usize {
+        let data = DebugDraw::take_vertices();
+        if data.is_empty() {
+            return 0;
+        }
+        self.buffer.upload(&data);
+
+        self.shader.use_shader();
+        unsafe {
+            gl::UniformMatrix4fv(self.u_projection_location, 1, gl::FALSE, projection.data.as_ptr());
+        }
+
+        let previous = cache.current().copied();
+        let state = previous.unwrap_or_else(RenderState::new);
+        cache.apply(&state.with_depth(false, state.depth_write));
+        self.buffer.draw();
+        if let Some(previous) = previous {
+            cache.apply(&previous);
+        }
+
+        data.len() / (VERTEX_SIZE * 2)
+    }
+}
+
+// Trazos de cada carácter: polilíneas separadas por espacios y cada punto son dos
+// cifras (x de 0 a 4, y de 0 a 6 hacia abajo). Las minúsculas se dibujan como mayúsculas
+fn stroke_glyph(c: char) -> &'static str {
+    match c.to_ascii_uppercase() {
+        'A' => "0602204246 0343",
+        'B' => "06003041423303 3344453606",
+        'C' => "40000646",
+        'D' => "00304145360600",
+        'E' => "40000646 0333",
+        'F' => "400006 0333",
+        'G' => "400006464323",
+        'H' => "0006 4046 0343",
+        'I' => "0040 0646 2026",
+        'J' => "4045361605",
+        'K' => "0006 4004 1346",
+        'L' => "000646",
+        'M' => "0600224046",
+        'N' => "06004640",
+        'O' => "0040460600",
+        'P' => "0600404303",
+        'Q' => "0040460600 2446",
+        'R' => "0600404303 2346",
+        'S' | '5' => "400003434606",
+        'T' => "0040 2026",
+        'U' => "00064640",
+        'V' => "002640",
+        'W' => "0006244640",
+        'X' => "0046 4006",
+        'Y' => "002340 2326",
+        'Z' => "00400646",
+        '0' => "0040460600 4006",
+        '1' => "112026 0646",
+        '2' => "004043030646",
+        '3' => "00404606 0343",
+        '4' => "000343 4046",
+        '6' => "400006464303",
+        '7' => "004016",
+        '8' => "0040460600 0343",
+        '9' => "430300404606",
+        ' ' => "",
+        '.' => "2526",
+        ',' => "2516",
+        ':' => "2122 2526",
+        ';' => "2122 2516",
+        '-' => "0343",
+        '+' => "0343 2125",
+        '=' => "0242 0444",
+        '*' => "0145 4105 2125",
+        '/' => "0640",
+        '\\' => "0046",
+        '(' => "30212536",
+        ')' => "10212516",
+        '[' => "30202636",
+        ']' => "10202616",
+        '<' => "400346",
+        '>' => "004306",
+        '!' => "2024 2526",
+        '%' => "0640 0001 4546",
+        '#' => "1016 3036 0242 0444",
+        '_' => "0646",
+        '|' => "2026",
+        '\'' => "2021",
+        '"' => "1011 3031",
+        _ => "0040432324 2526",
+    }
+}
@@ -0,0 +1,490 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::math::rect::Rect;
+
+// Carácter de la fuente. Las medidas van en píxeles de la página
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Glyph {
+    pub id: u32,
+    // Rectángulo dentro de la textura de la página
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    // Desplazamiento del rectángulo respecto al cursor (y desde la parte de arriba de la línea)
+    pub x_offset: f32,
+    pub y_offset: f32,
+    // Cuánto avanza el cursor después del carácter
+    pub x_advance: f32,
+    pub page: usize,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum TextAlign {
+    Left,
+    Center,
+    Right,
+}
+
+// Carácter ya colocado por Font::layout
+#[derive(Copy, Clone)]
+pub struct PlacedGlyph {
+    // Posición del carácter en el texto (en caracteres, no en bytes)
+    pub index: usize,
+    // Quad en píxeles, con (0, 0) en la esquina superior izquierda del texto
+    pub rect: Rect,
+    pub uv: Rect,
+    pub page: usize,
+}
+
+// Fuente de mapa de bits en formato AngelCode BMFont (texto o binario).
+// Las páginas son rutas a las imágenes, ya resueltas respecto al .fnt; las texturas
+// se cargan aparte (una por página) y se pasan a Text
+pub struct Font {
+    pub face: String,
+    pub size: f32,
+    // Distancia entre líneas
+    pub line_height: f32,
+    // Distancia de la parte de arriba de la línea a la línea base
+    pub base: f32,
+    pub page_width: f32,
+    pub page_height: f32,
+    pub pages: Vec<String>,
+
+    glyphs: HashMap<u32, Glyph>,
+    kerning: HashMap<(u32, u32), f32>,
+}
+
+impl Font {
+    // Lee un .fnt en texto o binario (los binarios empiezan por "BMF")
+    pub fn load(path: &str) -> Result<Font, String> {
+        let bytes = std::fs::read(path).map_err(|e| format!("No se puede leer {}: {}", path, e))?;
+        let base_dir = Path::new(path).parent().unwrap_or_else(|| Path::new(""));
+
+        if bytes.starts_with(b"BMF") {
+            Font::parse_binary(&bytes, base_dir)
+        } else {
+            let text = String::from_utf8(bytes).map_err(|_| format!("{} no es texto UTF-8", path))?;
+            Font::parse_text(&text, base_dir)
+        }
+    }
+
+    fn empty() -> Font {
+        Font {
+            face: String::new(),
+            size: 0.0,
+            line_height: 0.0,
+            base: 0.0,
+            page_width: 1.0,
+            page_height: 1.0,
+            pages: Vec::new(),
+
+            glyphs: HashMap::new(),
+            kerning: HashMap::new(),
+        }
+    }
+
+    // Formato de texto: una etiqueta por línea seguida de pares clave=valor
+    pub fn parse_text(text: &str, base_dir: &Path) -> Result<Font, String> {
+        let mut font = Font::empty();
+
+        for line in text.lines() {
+            let (tag, values) = parse_text_line(line);
+            let number = |key: &str| -> Result<f32, String> {
+                values
+                    .get(key)
+                    .ok_or_else(|| format!("Falta {} en la línea \"{}\" de la fuente", key, line.trim()))?
+                    .parse::<f32>()
+                    .map_err(|_| format!("Valor de {} no válido en la línea \"{}\"", key, line.trim()))
+            };
+
+            match tag {
+                "info" => {
+                    font.face = values.get("face").cloned().unwrap_or_default();
+                    font.size = number("size")?.abs();
+                }
+                "common" => {
+                    font.line_height = number("lineHeight")?;
+                    font.base = number("base")?;
+                    font.page_width = number("scaleW")?;
+                    font.page_height = number("scaleH")?;
+                }
+                "page" => {
+                    let id = number("id")? as usize;
+                    let file = values.get("file").ok_or("Página de la fuente sin \"file\"")?;
+                    if font.pages.len() <= id {
+                        font.pages.resize(id + 1, String::new());
+                    }
+                    font.pages[id] = base_dir.join(file).to_string_lossy().into_owned();
+                }
+                "char" => {
+                    let glyph = Glyph {
+                        id: number("id")? as u32,
+                        x: number("x")?,
+                        y: number("y")?,
+                        width: number("width")?,
+                        height: number("height")?,
+                        x_offset: number("xoffset")?,
+                        y_offset: number("yoffset")?,
+                        x_advance: number("xadvance")?,
+                        page: number("page").unwrap_or(0.0) as usize,
+                    };
+                    font.glyphs.insert(glyph.id, glyph);
+                }
+                "kerning" => {
+                    font.kerning.insert(
+                        (number("first")? as u32, number("second")? as u32),
+                        number("amount")?,
+                    );
+                }
+                _ => {}
+            }
+        }
+
+        font.check()?;
+        Ok(font)
+    }
+
+    // Formato binario (versión 3): "BMF", versión y bloques con tipo y tamaño
+    pub fn parse_binary(bytes: &[u8], base_dir: &Path) -> Result<Font, String> {
+        if bytes.len() < 4 || &bytes[..3] != b"BMF" {
+            return Err(String::from("La fuente binaria no empieza por BMF"));
+        }
+        if bytes[3] != 3 {
+            return Err(format!("Versión de fuente binaria no soportada: {}", bytes[3]));
+        }
+
+        let mut font = Font::empty();
+        let mut reader = Reader { bytes, position: 4 };
+
+        while reader.position < bytes.len() {
+            let block_type = reader.u8()?;
+            let size = reader.u32()? as usize;
+            let block = reader.take(size)?;
+            let mut block_reader = Reader { bytes: block, position: 0 };
+            let r = &mut block_reader;
+
+            match block_type {
+                1 => {
+                    font.size = (r.u16()? as i16).abs() as f32;
+                    // bitField, charSet, stretchH, aa, padding (4), spacing (2) y outline
+                    r.take(12)?;
+                    font.face = r.string()?;
+                }
+                2 => {
+                    font.line_height = r.u16()? as f32;
+                    font.base = r.u16()? as f32;
+                    font.page_width = r.u16()? as f32;
+                    font.page_height = r.u16()? as f32;
+                }
+                3 => {
+                    while r.position < block.len() {
+                        let file = r.string()?;
+                        font.pages.push(base_dir.join(file).to_string_lossy().into_owned());
+                    }
+                }
+                4 => {
+                    for _ in 0..size / 20 {
+                        let glyph = Glyph {
+                            id: r.u32()?,
+                            x: r.u16()? as f32,
+                            y: r.u16()? as f32,
+                            width: r.u16()? as f32,
+                            height: r.u16()? as f32,
+                            x_offset: r.u16()? as i16 as f32,
+                            y_offset: r.u16()? as i16 as f32,
+                            x_advance: r.u16()? as i16 as f32,
+                            page: r.u8()? as usize,
+                        };
+                        r.u8()?; // canal
+                        font.glyphs.insert(glyph.id, glyph);
+                    }
+                }
+                5 => {
+                    for _ in 0..size / 10 {
+                        let first = r.u32()?;
+                        let second = r.u32()?;
+                        let amount = r.u16()? as i16 as f32;
+                        font.kerning.insert((first, second), amount);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        font.check()?;
+        Ok(font)
+    }
+
+    fn check(&self) -> Result<(), String> {
+        if self.glyphs.is_empty() {
+            return Err(format!("La fuente {} no tiene caracteres", self.face));
+        }
+        if let Some(glyph) = self.glyphs.values().find(|g| g.page >= self.pages.len()) {
+            return Err(format!("El carácter {} usa la página {}, que no existe", glyph.id, glyph.page));
+        }
+        Ok(())
+    }
+}
+
+impl GlyphSource for Font {
+    // Carácter de la fuente, o '?' si no lo tiene
+    fn glyph(&self, c: char) -> Option<Glyph> {
+        self.glyphs.get(&(c as u32)).or_else(|| self.glyphs.get(&('?' as u32))).copied()
+    }
+
+    fn kerning(&self, first: char, second: char) -> f32 {
+        self.kerning.get(&(first as u32, second as u32)).copied().unwrap_or(0.0)
+    }
+
+    fn line_height(&self) -> f32 {
+        self.line_height
+    }
+
+    fn page_size(&self) -> (f32, f32) {
+        (self.page_width, self.page_height)
+    }
+}
+
+// Lo que necesita la maquetación de texto de una fuente: caracteres ya colocados en
+// páginas del mismo tamaño, kerning y altura de línea. La implementan Font (BMFont) y
+// GlyphAtlas (TrueType rasterizada al vuelo)
+pub trait GlyphSource {
+    fn glyph(&self, c: char) -> Option<Glyph>;
+    fn kerning(&self, first: char, second: char) -> f32;
+    fn line_height(&self) -> f32;
+    fn page_size(&self) -> (f32, f32);
+
+    // Zona de la página que ocupa el carácter, en coordenadas UV
+    fn uv(&self, glyph: &Glyph) -> Rect {
+        let (page_width, page_height) = self.page_size();
+        Rect::new(
+            glyph.x / page_width,
+            glyph.y / page_height,
+            glyph.width / page_width,
+            glyph.height / page_height,
+        )
+    }
+
+    // Ancho de una línea (sin saltos) contando el kerning
+    fn line_width(&self, line: &[char]) -> f32 {
+        let mut width = 0.0;
+        for (i, &c) in line.iter().enumerate() {
+            width += advance(self, c, line.get(i + 1).copied());
+        }
+        width
+    }
+
+    // Tamaño en píxeles que ocupa el texto con esa anchura máxima
+    fn measure(&self, text: &str, max_width: Option<f32>) -> (f32, f32) {
+        let lines = break_lines(self, text, max_width);
+        let width = lines
+            .iter()
+            .map(|line| self.line_width(&line.iter().map(|&(_, c)| c).collect::<Vec<char>>()))
+            .fold(0.0, f32::max);
+
+        (width, lines.len() as f32 * self.line_height())
+    }
+
+    // Coloca cada carácter. Los '\n' fuerzan un salto de línea y, si hay max_width, las
+    // líneas se parten por los espacios (o dentro de la palabra si no cabe sola).
+    // La alineación es respecto a max_width, o a la línea más ancha si no hay
+    fn layout(&self, text: &str, max_width: Option<f32>, align: TextAlign) -> Vec<PlacedGlyph> {
+        let lines = break_lines(self, text, max_width);
+        let chars: Vec<Vec<char>> = lines.iter().map(|line| line.iter().map(|&(_, c)| c).collect()).collect();
+        let widths: Vec<f32> = chars.iter().map(|line| self.line_width(line)).collect();
+        let block_width = max_width.unwrap_or_else(|| widths.iter().copied().fold(0.0, f32::max));
+
+        let mut placed = Vec::new();
+        for (line_index, line) in lines.iter().enumerate() {
+            let mut pen_x = match align {
+                TextAlign::Left => 0.0,
+                TextAlign::Center => ((block_width - widths[line_index]) / 2.0).floor(),
+                TextAlign::Right => block_width - widths[line_index],
+            };
+            let pen_y = line_index as f32 * self.line_height();
+
+            for (i, &(index, c)) in line.iter().enumerate() {
+                let glyph = match self.glyph(c) {
+                    Some(glyph) => glyph,
+                    None => continue,
+                };
+
+                if glyph.width > 0.0 && glyph.height > 0.0 {
+                    placed.push(PlacedGlyph {
+                        index,
+                        rect: Rect::new(pen_x + glyph.x_offset, pen_y + glyph.y_offset, glyph.width, glyph.height),
+                        uv: self.uv(&glyph),
+                        page: glyph.page,
+                    });
+                }
+                pen_x += advance(self, c, chars[line_index].get(i + 1).copied());
+            }
+        }
+
+        placed
+    }
+}
+
+fn advance<S: GlyphSource + ?Sized>(source: &S, c: char, next: Option<char>) -> f32 {
+    let advance = source.glyph(c).map_or(0.0, |g| g.x_advance);
+    advance + next.map_or(0.0, |next| source.kerning(c, next))
+}
+
+// Parte el texto en líneas de (índice, carácter). Los espacios donde se parte una
+// línea y los '\n' se quitan
+fn break_lines<S: GlyphSource + ?Sized>(source: &S, text: &str, max_width: Option<f32>) -> Vec<Vec<(usize, char)>> {
+    let mut lines = Vec::new();
+    let mut paragraph = Vec::new();
+
+    for (index, c) in text.chars().enumerate() {
+        match c {
+            '\n' => lines.extend(wrap(source, std::mem::take(&mut paragraph), max_width)),
+            '\r' => {}
+            _ => paragraph.push((index, c)),
+        }
+    }
+    lines.extend(wrap(source, paragraph, max_width));
+
+    lines
+}
+
+fn wrap<S: GlyphSource + ?Sized>(
+    source: &S,
+    paragraph: Vec<(usize, char)>,
+    max_width: Option<f32>,
+) -> Vec<Vec<(usize, char)>> {
+    let max_width = match max_width {
+        Some(max_width) => max_width,
+        None => return vec![paragraph],
+    };
+
+    let mut lines = Vec::new();
+    let mut line: Vec<(usize, char)> = Vec::new();
+    let mut line_width = 0.0;
+
+    for word in split_words(&paragraph) {
+        let is_space = word[0].1 == ' ';
+        let word_width: f32 = word.iter().map(|&(_, c)| advance(source, c, None)).sum();
+
+        if is_space {
+            // Los espacios al principio de una línea partida no se ven
+            if !line.is_empty() || lines.is_empty() {
+                line.extend_from_slice(word);
+                line_width += word_width;
+            }
+            continue;
+        }
+
+        if line_width + word_width > max_width && !line.is_empty() {
+            trim_trailing_spaces(&mut line);
+            lines.push(std::mem::take(&mut line));
+            line_width = 0.0;
+        }
+
+        // Palabra más larga que la línea: se parte por caracteres
+        for &(index, c) in word {
+            let char_advance = advance(source, c, None);
+            if line_width + char_advance > max_width && !line.is_empty() && word_width > max_width {
+                lines.push(std::mem::take(&mut line));
+                line_width = 0.0;
+            }
+            line.push((index, c));
+            line_width += char_advance;
+        }
+    }
+
+    trim_trailing_spaces(&mut line);
+    lines.push(line);
+    lines
+}
+
+// Trozos de espacios y de no espacios
+fn split_words(paragraph: &[(usize, char)]) -> Vec<&[(usize, char)]> {
+    let mut words = Vec::new();
+    let mut start = 0;
+
+    for i in 1..=paragraph.len() {
+        if i == paragraph.len() || (paragraph[i].1 == ' ') != (paragraph[start].1 == ' ') {
+            words.push(&paragraph[start..i]);
+            start = i;
+        }
+    }
+
+    words
+}
+
+fn trim_trailing_spaces(line: &mut Vec<(usize, char)>) {
+    while line.last().is_some_and(|&(_, c)| c == ' ') {
+        line.pop();
+    }
+}
+
+// Separa "char id=32 x=0 file="a b.png"" en la etiqueta y sus valores
+fn parse_text_line(line: &str) -> (&str, HashMap<String, String>) {
+    let line = line.trim();
+    let (tag, rest) = line.split_once(' ').unwrap_or((line, ""));
+    let mut values = HashMap::new();
+
+    let mut chars = rest.chars().peekable();
+    loop {
+        while chars.peek() == Some(&' ') {
+            chars.next();
+        }
+        let key: String = chars.by_ref().take_while(|&c| c != '=').collect();
+        if key.is_empty() {
+            break;
+        }
+
+        let value: String = if chars.peek() == Some(&'"') {
+            chars.next();
+            chars.by_ref().take_while(|&c| c != '"').collect()
+        } else {
+            chars.by_ref().take_while(|&c| c != ' ').collect()
+        };
+        values.insert(key.trim().to_string(), value);
+    }
+
+    (tag, values)
+}
+
+// Lectura de enteros little endian del formato binario
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, count: usize) -> Result<&'a [u8], String> {
+        if self.position + count > self.bytes.len() {
+            return Err(String::from("La fuente binaria está cortada"));
+        }
+        let slice = &self.bytes[self.position..self.position + count];
+        self.position += count;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, String> {
+        let b = self.take(2)?;
+        Ok(u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        let b = self.take(4)?;
+        Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    // Cadena terminada en 0
+    fn string(&mut self) -> Result<String, String> {
+        let rest = &self.bytes[self.position..];
+        let end = rest.iter().position(|&b| b == 0).ok_or("Cadena sin terminar en la fuente binaria")?;
+        let text = String::from_utf8_lossy(&rest[..end]).into_owned();
+        self.position += end + 1;
+        Ok(text)
+    }
+}
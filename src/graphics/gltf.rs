@@ -0,0 +1,598 @@
+use std::path::Path;
+
+use base64::Engine;
+use serde_json::Value;
+
+use crate::graphics::color::Color;
+use crate::graphics::mesh::MeshData;
+use crate::math::matrix4x4::Matrix4x4;
+use crate::math::vector2::Vector2;
+use crate::math::vector3::Vector3;
+
+// Importador de glTF 2.0 (https://www.khronos.org/gltf), en .gltf (JSON) y .glb
+// (binario). Los buffers pueden ir incrustados (base64 o el trozo BIN del .glb) o en
+// archivos aparte respecto a base_dir. Sólo triángulos y sin accessors dispersos
+// (sparse); las animaciones y las pieles se ignoran
+
+// Cabecera y tipos de trozo del .glb
+const GLB_MAGIC: u32 = 0x4654_6C67;
+const CHUNK_JSON: u32 = 0x4E4F_534A;
+const CHUNK_BIN: u32 = 0x004E_4942;
+// Máximo de valores de un accessor sin bufferView (todo ceros), que no tiene datos con
+// los que comparar su count
+const MAX_ZERO_ACCESSOR_VALUES: usize = 1 << 26;
+
+// Material PBR metallic-roughness. Las texturas son índices en GltfModel::images
+#[derive(Clone, Debug)]
+pub struct GltfMaterial {
+    pub name: String,
+    pub base_color: Color,
+    pub base_color_texture: Option<usize>,
+    pub metallic: f32,
+    pub roughness: f32,
+    pub normal_texture: Option<usize>,
+    pub double_sided: bool,
+}
+
+// Imagen de una textura: o una ruta a un archivo o los bytes del PNG/JPEG incrustado
+pub struct GltfImage {
+    pub name: String,
+    pub path: Option<String>,
+    pub bytes: Option<Vec<u8>>,
+    pub mime_type: Option<String>,
+}
+
+pub struct GltfPrimitive {
+    pub data: MeshData,
+    pub material: Option<usize>,
+}
+
+pub struct GltfMesh {
+    pub name: String,
+    pub primitives: Vec<GltfPrimitive>,
+}
+
+pub struct GltfNode {
+    pub name: String,
+    pub mesh: Option<usize>,
+    pub children: Vec<usize>,
+    // Transformación respecto al nodo padre
+    pub local: Matrix4x4,
+    // Transformación en el mundo (la del padre por la local)
+    pub world: Matrix4x4,
+}
+
+pub struct GltfModel {
+    pub meshes: Vec<GltfMesh>,
+    pub materials: Vec<GltfMaterial>,
+    pub images: Vec<GltfImage>,
+    pub nodes: Vec<GltfNode>,
+    // Nodos raíz de la escena por defecto (o de la primera)
+    pub roots: Vec<usize>,
+}
+
+// Lee un .gltf o un .glb según la extensión
+pub fn load(path: &str) -> Result<GltfModel, String> {
+    let base_dir = Path::new(path).parent().unwrap_or_else(|| Path::new(""));
+
+    if path.to_lowercase().ends_with(".glb") {
+        let bytes = std::fs::read(path).map_err(|e| format!("No se puede leer {}: {}", path, e))?;
+        parse_glb(&bytes, base_dir)
+    } else {
+        let json = std::fs::read_to_string(path).map_err(|e| format!("No se puede leer {}: {}", path, e))?;
+        parse_json(&json, base_dir)
+    }
+}
+
+pub fn parse_json(json: &str, base_dir: &Path) -> Result<GltfModel, String> {
+    let root: Value = serde_json::from_str(json).map_err(|e| format!("JSON de glTF no válido: {}", e))?;
+    parse_root(&root, None, base_dir)
+}
+
+pub fn parse_glb(bytes: &[u8], base_dir: &Path) -> Result<GltfModel, String> {
+    let read_u32 = |offset: usize| -> Result<u32, String> {
+        bytes
+            .get(offset..offset + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .ok_or_else(|| String::from("El .glb está cortado"))
+    };
+
+    if read_u32(0)? != GLB_MAGIC {
+        return Err(String::from("No es un archivo .glb"));
+    }
+    if read_u32(4)? != 2 {
+        return Err(format!("Versión de .glb no soportada: {}", read_u32(4)?));
+    }
+
+    let mut json = None;
+    let mut bin = None;
+    let mut offset = 12;
+    let length = (read_u32(8)? as usize).min(bytes.len());
+    while offset + 8 <= length {
+        let chunk_length = read_u32(offset)? as usize;
+        let chunk_type = read_u32(offset + 4)?;
+        let chunk = bytes
+            .get(offset + 8..offset + 8 + chunk_length)
+            .ok_or_else(|| String::from("El .glb está cortado"))?;
+
+        match chunk_type {
+            CHUNK_JSON => json = Some(chunk),
+            CHUNK_BIN => bin = Some(chunk),
+            _ => {}
+        }
+        // Los trozos están alineados a 4 bytes
+        offset += 8 + chunk_length.div_ceil(4) * 4;
+    }
+
+    let json = json.ok_or_else(|| String::from("El .glb no tiene JSON"))?;
+    let root: Value = serde_json::from_slice(json).map_err(|e| format!("JSON de glTF no válido: {}", e))?;
+    parse_root(&root, bin, base_dir)
+}
+
+fn parse_root(root: &Value, bin: Option<&[u8]>, base_dir: &Path) -> Result<GltfModel, String> {
+    let version = root.pointer("/asset/version").and_then(Value::as_str).unwrap_or("");
+    if !version.starts_with('2') {
+        return Err(format!("Versión de glTF no soportada: {}", version));
+    }
+
+    let buffers = array(root, "buffers")
+        .iter()
+        .enumerate()
+        .map(|(i, buffer)| load_buffer(buffer, i, bin, base_dir))
+        .collect::<Result<Vec<_>, String>>()?;
+    let reader = Reader { root, buffers };
+
+    let images = array(root, "images")
+        .iter()
+        .map(|image| reader.image(image, base_dir))
+        .collect::<Result<Vec<_>, String>>()?;
+    let materials = array(root, "materials").iter().map(|material| reader.material(material)).collect();
+    let meshes = array(root, "meshes")
+        .iter()
+        .enumerate()
+        .map(|(i, mesh)| reader.mesh(mesh).map_err(|e| format!("Malla {}: {}", i, e)))
+        .collect::<Result<Vec<_>, String>>()?;
+    let mut nodes = array(root, "nodes").iter().map(parse_node).collect::<Vec<_>>();
+
+    let scene = root.get("scene").and_then(Value::as_u64).unwrap_or(0) as usize;
+    let roots: Vec<usize> = match array(root, "scenes").get(scene) {
+        Some(scene) => indices(scene, "nodes"),
+        // Sin escenas, son raíz los nodos que no son hijos de nadie
+        None => (0..nodes.len()).filter(|&i| !nodes.iter().any(|node| node.children.contains(&i))).collect(),
+    };
+
+    for &node in &roots {
+        update_world(&mut nodes, node, &Matrix4x4::identity(), 0)?;
+    }
+
+    Ok(GltfModel {
+        meshes,
+        materials,
+        images,
+        nodes,
+        roots,
+    })
+}
+
+fn array<'a>(value: &'a Value, key: &str) -> &'a [Value] {
+    value.get(key).and_then(Value::as_array).map_or(&[], |values| values.as_slice())
+}
+
+fn indices(value: &Value, key: &str) -> Vec<usize> {
+    array(value, key).iter().filter_map(Value::as_u64).map(|index| index as usize).collect()
+}
+
+fn floats(value: &Value, key: &str) -> Option<Vec<f32>> {
+    value.get(key).and_then(Value::as_array).map(|values| values.iter().map(|v| v.as_f64().unwrap_or(0.0) as f32).collect())
+}
+
+fn load_buffer(buffer: &Value, index: usize, bin: Option<&[u8]>, base_dir: &Path) -> Result<Vec<u8>, String> {
+    match buffer.get("uri").and_then(Value::as_str) {
+        Some(uri) if uri.starts_with("data:") => decode_data_uri(uri),
+        Some(uri) => {
+            let path = base_dir.join(uri);
+            std::fs::read(&path).map_err(|e| format!("No se puede leer {}: {}", path.display(), e))
+        }
+        // Sin uri sólo puede ser el primer buffer de un .glb
+        None if index == 0 => bin.map(|bin| bin.to_vec()).ok_or_else(|| String::from("El buffer 0 no tiene datos")),
+        None => Err(format!("El buffer {} no tiene uri", index)),
+    }
+}
+
+fn decode_data_uri(uri: &str) -> Result<Vec<u8>, String> {
+    let (_, data) = uri
+        .split_once(";base64,")
+        .ok_or_else(|| String::from("Sólo se admiten data URIs en base64"))?;
+    base64::engine::general_purpose::STANDARD
+        .decode(data)
+        .map_err(|e| format!("base64 no válido: {}", e))
+}
+
+// Si count elementos de size bytes, separados stride bytes a partir de offset, caben en
+// length bytes
+fn fits(length: usize, offset: usize, count: usize, stride: usize, size: usize) -> bool {
+    if count == 0 {
+        return true;
+    }
+    (count - 1)
+        .checked_mul(stride)
+        .and_then(|last| last.checked_add(offset))
+        .and_then(|last| last.checked_add(size))
+        .is_some_and(|end| end <= length)
+}
+
+// Lee los datos de los accessors
+struct Reader<'a> {
+    root: &'a Value,
+    buffers: Vec<Vec<u8>>,
+}
+
+impl<'a> Reader<'a> {
+    // Bytes de una bufferView
+    fn view(&self, index: usize) -> Result<(&[u8], Option<usize>), String> {
+        let view = array(self.root, "bufferViews")
+            .get(index)
+            .ok_or_else(|| format!("No existe la bufferView {}", index))?;
+        let buffer = view.get("buffer").and_then(Value::as_u64).unwrap_or(0) as usize;
+        let offset = view.get("byteOffset").and_then(Value::as_u64).unwrap_or(0) as usize;
+        let length = view.get("byteLength").and_then(Value::as_u64).unwrap_or(0) as usize;
+        let stride = view.get("byteStride").and_then(Value::as_u64).map(|stride| stride as usize);
+
+        let bytes = self
+            .buffers
+            .get(buffer)
+            .and_then(|bytes| bytes.get(offset..offset.checked_add(length)?))
+            .ok_or_else(|| format!("La bufferView {} se sale de su buffer", index))?;
+        Ok((bytes, stride))
+    }
+
+    // Valores de un accessor como f32 (count * componentes), normalizando los enteros
+    // si lo pide el accessor
+    fn accessor(&self, index: usize) -> Result<(Vec<f32>, usize), String> {
+        let accessor = array(self.root, "accessors")
+            .get(index)
+            .ok_or_else(|| format!("No existe el accessor {}", index))?;
+        if accessor.get("sparse").is_some() {
+            return Err(String::from("Los accessors sparse no están soportados"));
+        }
+
+        let count = accessor.get("count").and_then(Value::as_u64).unwrap_or(0) as usize;
+        let components = match accessor.get("type").and_then(Value::as_str).unwrap_or("") {
+            "SCALAR" => 1,
+            "VEC2" => 2,
+            "VEC3" => 3,
+            "VEC4" => 4,
+            "MAT4" => 16,
+            other => return Err(format!("Tipo de accessor no soportado: {}", other)),
+        };
+        let component_type = accessor.get("componentType").and_then(Value::as_u64).unwrap_or(0);
+        let normalized = accessor.get("normalized").and_then(Value::as_bool).unwrap_or(false);
+        let component_size = match component_type {
+            5120 | 5121 => 1,
+            5122 | 5123 => 2,
+            5125 | 5126 => 4,
+            other => return Err(format!("Tipo de componente no soportado: {}", other)),
+        };
+
+        let too_big = || format!("El accessor {} tiene demasiados elementos ({})", index, count);
+        let value_count = count.checked_mul(components).ok_or_else(too_big)?;
+
+        // Sin bufferView el accessor es todo ceros
+        let view = match accessor.get("bufferView").and_then(Value::as_u64) {
+            Some(view) => view as usize,
+            None if value_count <= MAX_ZERO_ACCESSOR_VALUES => return Ok((vec![0.0; value_count], components)),
+            None => return Err(too_big()),
+        };
+        let (bytes, stride) = self.view(view)?;
+        let offset = accessor.get("byteOffset").and_then(Value::as_u64).unwrap_or(0) as usize;
+        let stride = stride.unwrap_or(component_size * components);
+        // Antes de reservar nada: el count del archivo puede ser cualquier cosa
+        if !fits(bytes.len(), offset, count, stride, component_size * components) {
+            return Err(format!("El accessor {} se sale de su bufferView", index));
+        }
+
+        let mut values = Vec::with_capacity(value_count);
+        for element in 0..count {
+            for component in 0..components {
+                let start = offset + element * stride + component * component_size;
+                let b = bytes
+                    .get(start..start + component_size)
+                    .ok_or_else(|| format!("El accessor {} se sale de su bufferView", index))?;
+
+                let value = match (component_type, normalized) {
+                    (5126, _) => f32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+                    (5120, true) => (b[0] as i8 as f32 / 127.0).max(-1.0),
+                    (5120, false) => b[0] as i8 as f32,
+                    (5121, true) => b[0] as f32 / 255.0,
+                    (5121, false) => b[0] as f32,
+                    (5122, true) => (i16::from_le_bytes([b[0], b[1]]) as f32 / 32767.0).max(-1.0),
+                    (5122, false) => i16::from_le_bytes([b[0], b[1]]) as f32,
+                    (5123, true) => u16::from_le_bytes([b[0], b[1]]) as f32 / 65535.0,
+                    (5123, false) => u16::from_le_bytes([b[0], b[1]]) as f32,
+                    _ => u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f32,
+                };
+                values.push(value);
+            }
+        }
+
+        Ok((values, components))
+    }
+
+    // Los índices se leen aparte: un u32 no cabe entero en un f32
+    fn indices(&self, index: usize) -> Result<Vec<u32>, String> {
+        let accessor = array(self.root, "accessors")
+            .get(index)
+            .ok_or_else(|| format!("No existe el accessor {}", index))?;
+        let count = accessor.get("count").and_then(Value::as_u64).unwrap_or(0) as usize;
+        let component_type = accessor.get("componentType").and_then(Value::as_u64).unwrap_or(0);
+        let size = match component_type {
+            5121 => 1,
+            5123 => 2,
+            5125 => 4,
+            other => return Err(format!("Tipo de índice no soportado: {}", other)),
+        };
+
+        let view = accessor
+            .get("bufferView")
+            .and_then(Value::as_u64)
+            .ok_or_else(|| String::from("Los índices no tienen bufferView"))?;
+        let (bytes, stride) = self.view(view as usize)?;
+        let offset = accessor.get("byteOffset").and_then(Value::as_u64).unwrap_or(0) as usize;
+        let stride = stride.unwrap_or(size);
+        if !fits(bytes.len(), offset, count, stride, size) {
+            return Err(format!("El accessor {} se sale de su bufferView", index));
+        }
+
+        (0..count)
+            .map(|i| {
+                let start = offset + i * stride;
+                let b = bytes
+                    .get(start..start + size)
+                    .ok_or_else(|| format!("El accessor {} se sale de su bufferView", index))?;
+                Ok(match size {
+                    1 => b[0] as u32,
+                    2 => u16::from_le_bytes([b[0], b[1]]) as u32,
+                    _ => u32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+                })
+            })
+            .collect()
+    }
+
+    fn mesh(&self, mesh: &Value) -> Result<GltfMesh, String> {
+        let mut primitives = Vec::new();
+        for primitive in array(mesh, "primitives") {
+            // 4 = TRIANGLES, el valor por defecto
+            let mode = primitive.get("mode").and_then(Value::as_u64).unwrap_or(4);
+            if mode != 4 {
+                return Err(format!("Sólo se admiten triángulos (mode {})", mode));
+            }
+
+            let attributes = primitive.get("attributes").cloned().unwrap_or(Value::Null);
+            let attribute = |name: &str| attributes.get(name).and_then(Value::as_u64).map(|index| index as usize);
+
+            let mut data = MeshData::new();
+            let position = attribute("POSITION").ok_or_else(|| String::from("Primitiva sin POSITION"))?;
+            let (positions, _) = self.accessor(position)?;
+            data.positions = positions.chunks_exact(3).map(|p| Vector3::new(p[0], p[1], p[2])).collect();
+
+            if let Some(normal) = attribute("NORMAL") {
+                let (normals, _) = self.accessor(normal)?;
+                data.normals = normals.chunks_exact(3).map(|n| Vector3::new(n[0], n[1], n[2])).collect();
+            }
+            // glTF ya tiene v = 0 arriba, como el motor
+            if let Some(uv) = attribute("TEXCOORD_0") {
+                let (uvs, _) = self.accessor(uv)?;
+                data.uvs = uvs.chunks_exact(2).map(|uv| Vector2::new(uv[0], uv[1])).collect();
+            }
+            if let Some(tangent) = attribute("TANGENT") {
+                let (tangents, _) = self.accessor(tangent)?;
+                data.tangents = tangents.chunks_exact(4).map(|t| [t[0], t[1], t[2], t[3]]).collect();
+            }
+
+            data.indices = match primitive.get("indices").and_then(Value::as_u64) {
+                Some(indices) => self.indices(indices as usize)?,
+                None => (0..data.positions.len() as u32).collect(),
+            };
+
+            // Antes de calcular nada: con un índice fuera de rango los cálculos harían panic
+            data.validate()?;
+            if data.normals.is_empty() {
+                data.compute_normals();
+            }
+            if data.tangents.is_empty() {
+                data.compute_tangents();
+            }
+
+            primitives.push(GltfPrimitive {
+                data,
+                material: primitive.get("material").and_then(Value::as_u64).map(|index| index as usize),
+            });
+        }
+
+        Ok(GltfMesh {
+            name: String::from(mesh.get("name").and_then(Value::as_str).unwrap_or("")),
+            primitives,
+        })
+    }
+
+    // Índice de la imagen de una textura ({"index": textura})
+    fn texture_image(&self, texture_info: Option<&Value>) -> Option<usize> {
+        let texture = texture_info?.get("index")?.as_u64()? as usize;
+        let source = array(self.root, "textures").get(texture)?.get("source")?.as_u64()?;
+        Some(source as usize)
+    }
+
+    fn material(&self, material: &Value) -> GltfMaterial {
+        let pbr = material.get("pbrMetallicRoughness");
+        let pbr_value = |key: &str| pbr.and_then(|pbr| pbr.get(key));
+        let base_color = pbr
+            .and_then(|pbr| floats(pbr, "baseColorFactor"))
+            .filter(|factor| factor.len() == 4)
+            .map_or_else(Color::white, |f| Color::from_f32(f[0], f[1], f[2], f[3]));
+
+        GltfMaterial {
+            name: String::from(material.get("name").and_then(Value::as_str).unwrap_or("")),
+            base_color,
+            base_color_texture: self.texture_image(pbr_value("baseColorTexture")),
+            metallic: pbr_value("metallicFactor").and_then(Value::as_f64).unwrap_or(1.0) as f32,
+            roughness: pbr_value("roughnessFactor").and_then(Value::as_f64).unwrap_or(1.0) as f32,
+            normal_texture: self.texture_image(material.get("normalTexture")),
+            double_sided: material.get("doubleSided").and_then(Value::as_bool).unwrap_or(false),
+        }
+    }
+
+    fn image(&self, image: &Value, base_dir: &Path) -> Result<GltfImage, String> {
+        let mut result = GltfImage {
+            name: String::from(image.get("name").and_then(Value::as_str).unwrap_or("")),
+            path: None,
+            bytes: None,
+            mime_type: image.get("mimeType").and_then(Value::as_str).map(String::from),
+        };
+
+        match (image.get("uri").and_then(Value::as_str), image.get("bufferView").and_then(Value::as_u64)) {
+            (Some(uri), _) if uri.starts_with("data:") => result.bytes = Some(decode_data_uri(uri)?),
+            (Some(uri), _) => result.path = Some(base_dir.join(uri).to_string_lossy().into_owned()),
+            (None, Some(view)) => result.bytes = Some(self.view(view as usize)?.0.to_vec()),
+            (None, None) => return Err(String::from("Imagen sin uri ni bufferView")),
+        }
+
+        Ok(result)
+    }
+}
+
+fn parse_node(node: &Value) -> GltfNode {
+    let local = match floats(node, "matrix").filter(|matrix| matrix.len() == 16) {
+        Some(matrix) => {
+            let mut data = [0.0; 16];
+            data.copy_from_slice(&matrix);
+            Matrix4x4 { data }
+        }
+        None => {
+            let translation = floats(node, "translation").filter(|t| t.len() == 3).unwrap_or_else(|| vec![0.0; 3]);
+            let rotation = floats(node, "rotation").filter(|r| r.len() == 4).unwrap_or_else(|| vec![0.0, 0.0, 0.0, 1.0]);
+            let scale = floats(node, "scale").filter(|s| s.len() == 3).unwrap_or_else(|| vec![1.0; 3]);
+
+            Matrix4x4::translation(Vector3::new(translation[0], translation[1], translation[2]))
+                * quaternion_matrix(rotation[0], rotation[1], rotation[2], rotation[3])
+                * Matrix4x4::scale(Vector3::new(scale[0], scale[1], scale[2]))
+        }
+    };
+
+    GltfNode {
+        name: String::from(node.get("name").and_then(Value::as_str).unwrap_or("")),
+        mesh: node.get("mesh").and_then(Value::as_u64).map(|index| index as usize),
+        children: indices(node, "children"),
+        world: Matrix4x4 { data: local.data },
+        local,
+    }
+}
+
+// Matriz de rotación de un cuaternio unitario (x, y, z, w)
+fn quaternion_matrix(x: f32, y: f32, z: f32, w: f32) -> Matrix4x4 {
+    let mut m = Matrix4x4::identity();
+
+    m.data[0] = 1.0 - 2.0 * (y * y + z * z);
+    m.data[1] = 2.0 * (x * y + z * w);
+    m.data[2] = 2.0 * (x * z - y * w);
+    m.data[4] = 2.0 * (x * y - z * w);
+    m.data[5] = 1.0 - 2.0 * (x * x + z * z);
+    m.data[6] = 2.0 * (y * z + x * w);
+    m.data[8] = 2.0 * (x * z + y * w);
+    m.data[9] = 2.0 * (y * z - x * w);
+    m.data[10] = 1.0 - 2.0 * (x * x + y * y);
+
+    m
+}
+
+fn update_world(nodes: &mut [GltfNode], index: usize, parent: &Matrix4x4, depth: usize) -> Result<(), String> {
+    // Un glTF válido es un árbol; esto evita colgarse con uno con ciclos
+    if depth > nodes.len() {
+        return Err(String::from("La jerarquía de nodos tiene un ciclo"));
+    }
+    let node = nodes.get_mut(index).ok_or_else(|| format!("No existe el nodo {}", index))?;
+    node.world = parent * &node.local;
+
+    let world = Matrix4x4 { data: node.world.data };
+    for child in node.children.clone() {
+        update_world(nodes, child, &world, depth + 1)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Un triángulo: tres posiciones (36 bytes) y sus índices u16 (6 bytes)
+    fn triangle(position_accessor: &str, index_count: u64) -> String {
+        triangle_with_indices(position_accessor, index_count, [0, 1, 2])
+    }
+
+    fn triangle_with_indices(position_accessor: &str, index_count: u64, indices: [u16; 3]) -> String {
+        let mut bytes = Vec::new();
+        for value in [0.0f32, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0].iter() {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        for index in indices.iter() {
+            bytes.extend_from_slice(&index.to_le_bytes());
+        }
+        let data = base64::engine::general_purpose::STANDARD.encode(&bytes);
+
+        format!(
+            r#"{{
+                "asset": {{"version": "2.0"}},
+                "buffers": [{{"uri": "data:application/octet-stream;base64,{}", "byteLength": 42}}],
+                "bufferViews": [
+                    {{"buffer": 0, "byteOffset": 0, "byteLength": 36}},
+                    {{"buffer": 0, "byteOffset": 36, "byteLength": 6}}
+                ],
+                "accessors": [
+                    {},
+                    {{"bufferView": 1, "componentType": 5123, "count": {}, "type": "SCALAR"}}
+                ],
+                "meshes": [{{"primitives": [{{"attributes": {{"POSITION": 0}}, "indices": 1}}]}}]
+            }}"#,
+            data, position_accessor, index_count
+        )
+    }
+
+    fn positions(count: u64) -> String {
+        format!(r#"{{"bufferView": 0, "componentType": 5126, "count": {}, "type": "VEC3"}}"#, count)
+    }
+
+    #[test]
+    fn reads_a_triangle() {
+        let model = parse_json(&triangle(&positions(3), 3), Path::new("")).unwrap();
+        let data = &model.meshes[0].primitives[0].data;
+        assert_eq!(data.positions.len(), 3);
+        assert_eq!(data.indices, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn accessor_counts_are_checked_before_allocating() {
+        // Más elementos de los que caben en la bufferView
+        assert!(parse_json(&triangle(&positions(4), 3), Path::new("")).is_err());
+        assert!(parse_json(&triangle(&positions(1 << 40), 3), Path::new("")).is_err());
+        // count * componentes no cabe en un usize
+        assert!(parse_json(&triangle(&positions(u64::MAX / 2), 3), Path::new("")).is_err());
+        assert!(parse_json(&triangle(&positions(3), 1 << 40), Path::new("")).is_err());
+
+        // Sin bufferView son ceros, pero con un límite
+        let zeros = |count: u64| format!(r#"{{"componentType": 5126, "count": {}, "type": "VEC3"}}"#, count);
+        assert!(parse_json(&triangle(&zeros(3), 3), Path::new("")).is_ok());
+        assert!(parse_json(&triangle(&zeros(1 << 40), 3), Path::new("")).is_err());
+    }
+
+    // Sin normales en el archivo se calculan, y un índice que se sale de los vértices
+    // haría panic al calcularlas: tiene que ser un error
+    #[test]
+    fn out_of_range_indices_are_an_error() {
+        let error = parse_json(&triangle_with_indices(&positions(3), 3, [0, 1, 7]), Path::new("")).err().unwrap();
+        assert!(error.contains('7'), "{}", error);
+
+        let data = &parse_json(&triangle(&positions(3), 3), Path::new("")).unwrap().meshes[0].primitives[0].data;
+        assert_eq!(data.normals.len(), 3);
+        // Sin UV no hay tangentes
+        assert!(data.tangents.is_empty());
+    }
+}
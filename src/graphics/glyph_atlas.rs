@@ -0,0 +1,473 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use crate::gl_utility::texture::Texture;
+use crate::graphics::font::{Glyph, GlyphSource};
+use crate::graphics::truetype::{RasterGlyph, TrueTypeFont};
+use crate::math::rect_packer::MaxRectsPacker;
+
+// Separación entre caracteres dentro de la página, para que el filtrado no mezcle vecinos
+const GLYPH_PADDING: u32 = 1;
+
+struct Page {
+    // RGBA: blanco con la cobertura (o la distancia) en el alfa
+    pixels: Vec<u8>,
+    packer: MaxRectsPacker,
+    texture: Option<Texture>,
+    // Hay caracteres nuevos que todavía no se han subido a la textura
+    dirty: bool,
+}
+
+struct AtlasState {
+    // None si ni la fuente ni '?' tienen el carácter
+    glyphs: HashMap<char, Option<Glyph>>,
+    // Los que no caben en una página, para no rasterizarlos cada vez
+    too_big: HashMap<char, String>,
+    pages: Vec<Page>,
+}
+
+// Atlas de caracteres de una fuente TrueType a un tamaño fijo. Los caracteres se
+// rasterizan la primera vez que se piden y se van colocando en páginas; cuando una
+// se llena se abre otra. Las texturas se crean o actualizan al dibujar (bind_page),
+// así que rasterizar no necesita contexto de OpenGL.
+// Con sdf las páginas guardan campos de distancia en lugar de cobertura y hay que
+// dibujarlas con el shader de sdf_text.frag
+pub struct GlyphAtlas {
+    pub size: f32,
+
+    font: TrueTypeFont,
+    sdf_spread: Option<u32>,
+    page_width: u32,
+    page_height: u32,
+    ascent: f32,
+    line_height: f32,
+
+    state: RefCell<AtlasState>,
+}
+
+impl GlyphAtlas {
+    pub fn new(font: TrueTypeFont, size: f32) -> GlyphAtlas {
+        let (ascent, line_height) = font.line_metrics(size);
+
+        GlyphAtlas {
+            size,
+
+            font,
+            sdf_spread: None,
+            page_width: 512,
+            page_height: 512,
+            ascent,
+            line_height,
+
+            state: RefCell::new(AtlasState {
+                glyphs: HashMap::new(),
+                too_big: HashMap::new(),
+                pages: Vec::new(),
+            }),
+        }
+    }
+
+    // Genera campos de distancia con signo que llegan a spread píxeles del borde
+    pub fn with_sdf(mut self, spread: u32) -> GlyphAtlas {
+        self.sdf_spread = Some(spread);
+        self.clear();
+        self
+    }
+
+    pub fn with_page_size(mut self, width: u32, height: u32) -> GlyphAtlas {
+        self.page_width = width;
+        self.page_height = height;
+        self.clear();
+        self
+    }
+
+    pub fn font(&self) -> &TrueTypeFont {
+        &self.font
+    }
+
+    pub fn is_sdf(&self) -> bool {
+        self.sdf_spread.is_some()
+    }
+
+    // Distancia de la parte de arriba de la línea a la línea base
+    pub fn ascent(&self) -> f32 {
+        self.ascent
+    }
+
+    // Rasteriza de antemano los caracteres del texto (por ejemplo "áéíóúüñÁÉÍÓÚÜÑ¿¡")
+    // para no hacerlo en mitad de una partida. Falla si alguno no cabe en una página
+    pub fn prepare(&self, text: &str) -> Result<(), String> {
+        for c in text.chars() {
+            self.try_glyph(c)?;
+        }
+        Ok(())
+    }
+
+    // Como glyph, pero con el error si el carácter no cabe en una página (glyph lo
+    // trata como si la fuente no lo tuviera). Ok(None) si ni la fuente ni '?' lo tienen
+    pub fn try_glyph(&self, c: char) -> Result<Option<Glyph>, String> {
+        {
+            let state = self.state.borrow();
+            if let Some(glyph) = state.glyphs.get(&c) {
+                return Ok(*glyph);
+            }
+            if let Some(error) = state.too_big.get(&c) {
+                return Err(error.clone());
+            }
+        }
+
+        match self.rasterize(c) {
+            Ok(glyph) => {
+                self.state.borrow_mut().glyphs.insert(c, glyph);
+                Ok(glyph)
+            }
+            Err(error) => {
+                self.state.borrow_mut().too_big.insert(c, error.clone());
+                Err(error)
+            }
+        }
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.state.borrow().pages.len()
+    }
+
+    // Píxeles RGBA de una página, tal y como se suben a la textura
+    pub fn page_pixels(&self, page: usize) -> Vec<u8> {
+        self.state.borrow().pages[page].pixels.clone()
+    }
+
+    // Activa la textura de la página, creándola o subiendo los caracteres nuevos antes
+    pub fn bind_page(&self, page: usize, unit: u32) {
+        let mut state = self.state.borrow_mut();
+        let (width, height) = (self.page_width, self.page_height);
+        let page = &mut state.pages[page];
+
+        // La página tiene el tamaño de su textura, pero si no se pudiera actualizar se crea de nuevo
+        let create = match &page.texture {
+            None => true,
+            Some(texture) => page.dirty && texture.update_region(0, 0, width, height, &page.pixels).is_err(),
+        };
+        if create {
+            page.texture = Some(Texture::from_rgba(&format!("{}_{}", self.font.name(), self.size), width, height, &page.pixels));
+        }
+        page.dirty = false;
+
+        if let Some(texture) = &page.texture {
+            texture.bind(unit);
+        }
+    }
+
+    // Olvida todos los caracteres (por ejemplo tras cambiar de modo)
+    fn clear(&mut self) {
+        let state = self.state.get_mut();
+        state.glyphs.clear();
+        state.too_big.clear();
+        state.pages.clear();
+    }
+
+    fn rasterize(&self, c: char) -> Result<Option<Glyph>, String> {
+        let c = if self.font.has_glyph(c) {
+            c
+        } else if self.font.has_glyph('?') {
+            '?'
+        } else {
+            return Ok(None);
+        };
+
+        let raster = match self.sdf_spread {
+            Some(spread) => self.font.rasterize_sdf(c, self.size, spread),
+            None => self.font.rasterize(c, self.size),
+        };
+
+        let mut glyph = Glyph {
+            id: c as u32,
+            x: 0.0,
+            y: 0.0,
+            width: raster.width as f32,
+            height: raster.height as f32,
+            x_offset: raster.x_offset,
+            y_offset: raster.y_offset,
+            x_advance: raster.advance,
+            page: 0,
+        };
+
+        if raster.width > 0 && raster.height > 0 {
+            let (page, x, y) = self.place(c, &raster)?;
+            glyph.x = x as f32;
+            glyph.y = y as f32;
+            glyph.page = page;
+        }
+
+        Ok(Some(glyph))
+    }
+
+    // Busca sitio en alguna página (o abre una nueva) y copia allí el carácter
+    fn place(&self, c: char, raster: &RasterGlyph) -> Result<(usize, u32, u32), String> {
+        let cell_width = raster.width + GLYPH_PADDING;
+        let cell_height = raster.height + GLYPH_PADDING;
+        let too_big = || {
+            format!(
+                "El carácter '{}' de {}x{} no cabe en las páginas de {}x{} del atlas de {} (usa with_page_size)",
+                c, raster.width, raster.height, self.page_width, self.page_height, self.font.name()
+            )
+        };
+        if cell_width > self.page_width || cell_height > self.page_height {
+            return Err(too_big());
+        }
+
+        let mut state = self.state.borrow_mut();
+        let found = state
+            .pages
+            .iter_mut()
+            .enumerate()
+            .find_map(|(i, page)| page.packer.insert(cell_width, cell_height).map(|rect| (i, rect)));
+
+        let (index, rect) = match found {
+            Some(found) => found,
+            None => {
+                let mut packer = MaxRectsPacker::new(self.page_width, self.page_height);
+                let rect = packer.insert(cell_width, cell_height).ok_or_else(too_big)?;
+                state.pages.push(Page {
+                    pixels: vec![0; (self.page_width * self.page_height * 4) as usize],
+                    packer,
+                    texture: None,
+                    dirty: true,
+                });
+                (state.pages.len() - 1, rect)
+            }
+        };
+
+        let page = &mut state.pages[index];
+        for row in 0..raster.height {
+            for column in 0..raster.width {
+                let alpha = raster.pixels[(row * raster.width + column) as usize];
+                let offset = (((rect.y + row) * self.page_width + rect.x + column) * 4) as usize;
+                page.pixels[offset..offset + 4].copy_from_slice(&[255, 255, 255, alpha]);
+            }
+        }
+        page.dirty = true;
+
+        Ok((index, rect.x, rect.y))
+    }
+}
+
+impl GlyphSource for GlyphAtlas {
+    // Los caracteres que no caben en una página no se dibujan; prepare y try_glyph dan el error
+    fn glyph(&self, c: char) -> Option<Glyph> {
+        self.try_glyph(c).unwrap_or(None)
+    }
+
+    fn kerning(&self, first: char, second: char) -> f32 {
+        self.font.kerning(first, second, self.size)
+    }
+
+    fn line_height(&self) -> f32 {
+        self.line_height
+    }
+
+    fn page_size(&self) -> (f32, f32) {
+        (self.page_width as f32, self.page_height as f32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::font::{Font, TextAlign};
+    use std::path::Path;
+
+    const UNITS_PER_EM: u16 = 1000;
+    const ASCENDER: i16 = 800;
+    const DESCENDER: i16 = -200;
+    // Avance en unidades de la fuente y caja del contorno (sin contorno si está vacía)
+    const GLYPHS: [(u16, [i16; 4]); 5] = [
+        (500, [0, 0, 0, 0]),       // .notdef
+        (600, [100, 0, 500, 500]), // 'a'
+        (700, [100, 0, 600, 700]), // 'b'
+        (300, [0, 0, 0, 0]),       // ' '
+        (600, [100, 0, 500, 700]), // '?'
+    ];
+    const CHARS: [(char, u16); 4] = [(' ', 3), ('?', 4), ('a', 1), ('b', 2)];
+    const KERNING_AB: i16 = -100;
+
+    fn u16s(values: &[u16]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_be_bytes()).collect()
+    }
+
+    fn i16s(values: &[i16]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_be_bytes()).collect()
+    }
+
+    fn u32s(values: &[u32]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_be_bytes()).collect()
+    }
+
+    // TrueType mínima con cajas por caracteres, para no depender de un fichero de fuente
+    fn test_font() -> TrueTypeFont {
+        let count = GLYPHS.len() as u16;
+
+        let mut head = u32s(&[0x0001_0000, 0x0001_0000, 0, 0x5F0F_3CF5]);
+        head.extend(u16s(&[0, UNITS_PER_EM]));
+        head.extend(vec![0; 16]); // fechas
+        head.extend(i16s(&[0, DESCENDER, 700, ASCENDER, 0, 8, 2, 0, 0]));
+
+        let mut hhea = u32s(&[0x0001_0000]);
+        hhea.extend(i16s(&[ASCENDER, DESCENDER, 0, 700, 0, 0, 600, 1, 0, 0, 0, 0, 0, 0, 0]));
+        hhea.extend(u16s(&[count]));
+
+        let mut maxp = u32s(&[0x0000_5000]);
+        maxp.extend(u16s(&[count]));
+
+        let mut cmap = u16s(&[0, 1, 3, 10]);
+        cmap.extend(u32s(&[12]));
+        cmap.extend(u16s(&[12, 0]));
+        cmap.extend(u32s(&[16 + 12 * CHARS.len() as u32, 0, CHARS.len() as u32]));
+        for &(c, glyph) in CHARS.iter() {
+            cmap.extend(u32s(&[c as u32, c as u32, glyph as u32]));
+        }
+
+        let mut hmtx = Vec::new();
+        let mut glyf = Vec::new();
+        let mut loca = u16s(&[0]);
+        for &(advance, [x_min, y_min, x_max, y_max]) in GLYPHS.iter() {
+            hmtx.extend(u16s(&[advance]));
+            hmtx.extend(i16s(&[x_min]));
+            if x_max > x_min {
+                // Un contorno de cuatro puntos en la curva, con coordenadas relativas de 16 bits
+                glyf.extend(i16s(&[1, x_min, y_min, x_max, y_max]));
+                glyf.extend(u16s(&[3, 0]));
+                glyf.extend(&[1, 1, 1, 1]);
+                glyf.extend(i16s(&[x_min, 0, x_max - x_min, 0]));
+                glyf.extend(i16s(&[y_min, y_max - y_min, 0, y_min - y_max]));
+            }
+            loca.extend(u16s(&[(glyf.len() / 2) as u16]));
+        }
+
+        let mut kern = u16s(&[0, 1, 0, 6 + 8 + 6, 0x0001, 1, 6, 0, 0, 1, 2]);
+        kern.extend(i16s(&[KERNING_AB]));
+
+        let tables: [(&[u8; 4], Vec<u8>); 8] = [
+            (b"cmap", cmap),
+            (b"glyf", glyf),
+            (b"head", head),
+            (b"hhea", hhea),
+            (b"hmtx", hmtx),
+            (b"kern", kern),
+            (b"loca", loca),
+            (b"maxp", maxp),
+        ];
+
+        let mut bytes = u32s(&[0x0001_0000]);
+        bytes.extend(u16s(&[tables.len() as u16, 128, 3, 0]));
+        let mut offset = 12 + 16 * tables.len();
+        let mut data = Vec::new();
+        for (tag, table) in tables.iter() {
+            bytes.extend(tag.iter());
+            bytes.extend(u32s(&[0, offset as u32, table.len() as u32]));
+            let padded = table.len().div_ceil(4) * 4;
+            data.extend(table);
+            data.extend(vec![0; padded - table.len()]);
+            offset += padded;
+        }
+        bytes.extend(data);
+
+        TrueTypeFont::from_bytes(&bytes).unwrap()
+    }
+
+    // BMFont con los mismos caracteres que el atlas. Los avances, el kerning y la altura
+    // de línea salen de las medidas de diseño, no de lo que calcula la TrueType
+    fn equivalent_bmfont(atlas: &GlyphAtlas) -> Font {
+        let scale = atlas.size / UNITS_PER_EM as f32;
+        let line_height = (ASCENDER - DESCENDER) as f32 * scale;
+        let (page_width, page_height) = atlas.page_size();
+
+        let mut fnt = format!(
+            "common lineHeight={} base={} scaleW={} scaleH={} pages=1\npage id=0 file=\"atlas.png\"\n",
+            line_height, ASCENDER as f32 * scale, page_width, page_height
+        );
+        for &(c, index) in CHARS.iter() {
+            let glyph = atlas.glyph(c).unwrap();
+            fnt += &format!(
+                "char id={} x={} y={} width={} height={} xoffset={} yoffset={} xadvance={} page=0\n",
+                c as u32, glyph.x, glyph.y, glyph.width, glyph.height,
+                glyph.x_offset, glyph.y_offset, GLYPHS[index as usize].0 as f32 * scale
+            );
+        }
+        fnt += &format!("kerning first={} second={} amount={}\n", 'a' as u32, 'b' as u32, KERNING_AB as f32 * scale);
+
+        Font::parse_text(&fnt, Path::new("")).unwrap()
+    }
+
+    fn assert_same_layout(atlas: &GlyphAtlas, font: &Font, text: &str, max_width: Option<f32>, align: TextAlign) {
+        let truetype = atlas.layout(text, max_width, align);
+        let bitmap = font.layout(text, max_width, align);
+        assert_eq!(truetype.len(), bitmap.len(), "{:?}", text);
+
+        for (t, b) in truetype.iter().zip(&bitmap) {
+            assert_eq!(t.index, b.index, "{:?}", text);
+            assert!((t.rect.x - b.rect.x).abs() < 0.001, "{:?}: x {} != {}", text, t.rect.x, b.rect.x);
+            assert!((t.rect.y - b.rect.y).abs() < 0.001, "{:?}: y {} != {}", text, t.rect.y, b.rect.y);
+        }
+
+        let (tw, th) = atlas.measure(text, max_width);
+        let (bw, bh) = font.measure(text, max_width);
+        assert!((tw - bw).abs() < 0.001 && (th - bh).abs() < 0.001, "{:?}", text);
+    }
+
+    #[test]
+    fn truetype_metrics_follow_the_font_units() {
+        let atlas = GlyphAtlas::new(test_font(), 20.0);
+
+        assert_eq!(atlas.line_height(), 20.0);
+        assert_eq!(atlas.ascent(), 16.0);
+        assert_eq!(atlas.glyph('a').unwrap().x_advance, 12.0);
+        assert_eq!(atlas.glyph(' ').unwrap().x_advance, 6.0);
+        assert_eq!(atlas.kerning('a', 'b'), -2.0);
+        assert_eq!(atlas.kerning('b', 'a'), 0.0);
+        // Los que no tiene la fuente salen como '?'
+        assert_eq!(atlas.glyph('z').unwrap().x_advance, atlas.glyph('?').unwrap().x_advance);
+    }
+
+    #[test]
+    fn truetype_layout_matches_bmfont() {
+        let atlas = GlyphAtlas::new(test_font(), 20.0);
+        let font = equivalent_bmfont(&atlas);
+
+        assert_same_layout(&atlas, &font, "abba ab", None, TextAlign::Left);
+        assert_same_layout(&atlas, &font, "ab\nba\n\nb", None, TextAlign::Center);
+        assert_same_layout(&atlas, &font, "ab zb", None, TextAlign::Right);
+    }
+
+    #[test]
+    fn truetype_line_breaks_match_bmfont() {
+        let atlas = GlyphAtlas::new(test_font(), 20.0);
+        let font = equivalent_bmfont(&atlas);
+
+        // "ab" mide 24 y "ab ab" 54: cabe una palabra por línea
+        let placed = atlas.layout("ab ab ab", Some(30.0), TextAlign::Left);
+        let lines: Vec<f32> = placed.iter().map(|p| (p.rect.y / atlas.line_height()).floor()).collect();
+        assert_eq!(lines, vec![0.0, 0.0, 1.0, 1.0, 2.0, 2.0]);
+        assert_eq!(atlas.measure("ab ab ab", Some(30.0)).1, 60.0);
+
+        assert_same_layout(&atlas, &font, "ab ab ab", Some(30.0), TextAlign::Left);
+        assert_same_layout(&atlas, &font, "ab ab ab", Some(60.0), TextAlign::Center);
+        // Una palabra más ancha que la línea se parte por dentro
+        assert_same_layout(&atlas, &font, "abababab", Some(30.0), TextAlign::Left);
+    }
+
+    #[test]
+    fn glyphs_larger_than_the_page_are_an_error() {
+        let atlas = GlyphAtlas::new(test_font(), 200.0).with_page_size(64, 64);
+
+        assert!(atlas.prepare("b").is_err());
+        assert!(atlas.try_glyph('b').is_err());
+        assert!(atlas.glyph('b').is_none());
+        // Los caracteres sin contorno no ocupan sitio en la página
+        assert!(atlas.prepare(" ").is_ok());
+
+        let atlas = GlyphAtlas::new(test_font(), 200.0).with_page_size(256, 256);
+        assert!(atlas.prepare("ab ?").is_ok());
+        assert_eq!(atlas.page_count(), 1);
+    }
+}
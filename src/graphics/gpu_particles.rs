@@ -0,0 +1,394 @@
+use std::ops::Range;
+
+use crate::gl_utility::shader::{Shader, ShaderManager};
+use crate::gl_utility::texture::Texture;
+use crate::graphics::color::Color;
+use crate::graphics::particles::{EmissionTimer, Particle, ParticleConfig};
+use crate::graphics::render_queue::Drawable;
+use crate::math::matrix4x4::Matrix4x4;
+use crate::math::random::Random;
+use crate::math::vector2::Vector2;
+
+pub const UPDATE_SHADER: &str = "gpu_particles_update";
+pub const RENDER_SHADER: &str = "gpu_particles";
+
+// Floats por partícula en el buffer (tres vec4, ver gpu_particles.comp)
+const PARTICLE_SIZE: usize = 12;
+// Tamaño del grupo de trabajo del compute shader (local_size_x)
+const WORK_GROUP_SIZE: u32 = 256;
+// Muestras de las curvas que se mandan al shader (CURVE_SAMPLES)
+const CURVE_SAMPLES: usize = 16;
+
+// Partículas simuladas en la GPU, para efectos con cientos de miles. El estado vive en
+// un shader storage buffer que actualiza un compute shader y del que lee directamente
+// el vertex shader al dibujar. La CPU sólo decide cuándo y dónde nacen, con la misma
+// ParticleConfig que ParticleEmitter y, como en él, si las max_particles están vivas
+// no nacen más. La CPU sabe qué huecos están libres por cuándo muere cada partícula
+pub struct GpuParticles<'a> {
+    // Cambiar max_particles no tiene efecto: el buffer se crea en new
+    pub config: ParticleConfig,
+    pub position: Vector2,
+
+    // Tinte de todas las partículas
+    color: Color,
+    texture: Option<&'a Texture>,
+
+    slots: Slots,
+    emitting: bool,
+    random: Random,
+    timer: EmissionTimer,
+
+    // Partículas nuevas del frame, listas para subir, y el hueco de cada una
+    spawned: Vec<f32>,
+    spawned_slots: Vec<usize>,
+    // Curvas muestreadas para el shader
+    sizes: [f32; CURVE_SAMPLES],
+    colors: [f32; CURVE_SAMPLES * 4],
+
+    ssbo: u32,
+    // El perfil core necesita un VAO para dibujar aunque no haya atributos
+    vao: u32,
+
+    update_shader: &'a Shader,
+    render_shader: &'a Shader,
+}
+
+impl<'a> Drop for GpuParticles<'a> {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteBuffers(1, &self.ssbo);
+            gl::DeleteVertexArrays(1, &self.vao);
+        }
+    }
+}
+
+impl<'a> GpuParticles<'a> {
+    // Registra los shaders de simulación y dibujo (UPDATE_SHADER y RENDER_SHADER)
+    pub fn register_shaders(shaders: &mut ShaderManager) {
+        shaders.register_compute(UPDATE_SHADER, include_str!("../gpu_particles.comp"));
+        shaders.register(RENDER_SHADER, include_str!("../gpu_particles.vert"), include_str!("../gpu_particles.frag"));
+    }
+
+    pub fn new(config: ParticleConfig, position: Vector2, seed: u64, update_shader: &'a Shader, render_shader: &'a Shader) -> GpuParticles<'a> {
+        let capacity = config.max_particles;
+        let mut particles = GpuParticles {
+            config,
+            position,
+
+            color: Color::white(),
+            texture: None,
+
+            slots: Slots::new(capacity),
+            emitting: true,
+            random: Random::new(seed),
+            timer: EmissionTimer::new(),
+
+            spawned: Vec::new(),
+            spawned_slots: Vec::new(),
+            sizes: [1.0; CURVE_SAMPLES],
+            colors: [1.0; CURVE_SAMPLES * 4],
+
+            ssbo: 0,
+            vao: 0,
+
+            update_shader,
+            render_shader,
+        };
+
+        // Todo a 0: vida 0 es una partícula muerta
+        let zeros = vec![0.0f32; capacity * PARTICLE_SIZE];
+        unsafe {
+            gl::GenVertexArrays(1, &mut particles.vao);
+            gl::GenBuffers(1, &mut particles.ssbo);
+            gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, particles.ssbo);
+            gl::BufferData(
+                gl::SHADER_STORAGE_BUFFER,
+                std::mem::size_of_val(zeros.as_slice()) as gl::types::GLsizeiptr,
+                zeros.as_ptr() as *const gl::types::GLvoid,
+                gl::DYNAMIC_DRAW,
+            );
+            gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, 0);
+        }
+
+        particles.sample_curves();
+        particles
+    }
+
+    // Vuelve a muestrear size_over_life y color_over_life (hay que llamarlo si se
+    // cambian en config)
+    pub fn sample_curves(&mut self) {
+        for i in 0..CURVE_SAMPLES {
+            let t = i as f32 / (CURVE_SAMPLES - 1) as f32;
+            let color = self.config.color_over_life.evaluate(t);
+
+            self.sizes[i] = self.config.size_over_life.evaluate(t);
+            self.colors[i * 4..i * 4 + 4].copy_from_slice(&[color.r, color.g, color.b, color.a]);
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.slots.capacity()
+    }
+
+    // Partículas vivas, contando las que nacen en el siguiente update
+    pub fn len(&self) -> usize {
+        self.slots.alive()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn set_color(&mut self, color: Color) {
+        self.color = color;
+    }
+
+    pub fn color(&self) -> Color {
+        self.color
+    }
+
+    pub fn set_texture(&mut self, texture: Option<&'a Texture>) {
+        self.texture = texture;
+    }
+
+    pub fn start(&mut self) {
+        self.emitting = true;
+    }
+
+    // Deja de crear partículas; las que hay terminan su vida
+    pub fn stop(&mut self) {
+        self.emitting = false;
+        self.timer.stop();
+    }
+
+    pub fn is_emitting(&self) -> bool {
+        self.emitting
+    }
+
+    // Crea count partículas en el siguiente update (hasta llenar el buffer)
+    pub fn burst(&mut self, count: u32) {
+        for _ in 0..count {
+            if self.slots.is_full() {
+                break;
+            }
+            let particle = self.config.spawn(&mut self.random, self.position);
+            let slot = match self.slots.allocate(particle.lifetime - particle.age) {
+                Some(slot) => slot,
+                None => break,
+            };
+            write_particle(&mut self.spawned, &particle);
+            self.spawned_slots.push(slot);
+        }
+    }
+
+    // Sube las partículas nuevas y avanza la simulación delta segundos
+    pub fn update(&mut self, delta: f32) {
+        if self.emitting {
+            let count = self.timer.advance(self.config.emission, delta);
+            self.burst(count);
+        }
+        self.upload_spawned();
+
+        let damping = (1.0 - self.config.drag * delta).max(0.0);
+        let capacity = self.slots.capacity() as u32;
+        let shader = self.update_shader;
+        shader.use_shader();
+        unsafe {
+            gl::Uniform1f(shader.get_uniform_location("u_delta"), delta);
+            gl::Uniform2f(shader.get_uniform_location("u_gravity"), self.config.gravity.x, self.config.gravity.y);
+            gl::Uniform1f(shader.get_uniform_location("u_damping"), damping);
+            gl::Uniform1ui(shader.get_uniform_location("u_count"), capacity);
+
+            gl::BindBufferBase(gl::SHADER_STORAGE_BUFFER, 0, self.ssbo);
+        }
+
+        shader.dispatch(capacity.div_ceil(WORK_GROUP_SIZE), 1, 1);
+        self.slots.advance(delta);
+
+        unsafe {
+            // Lo escrito tiene que verse en el siguiente dispatch y al dibujar
+            gl::MemoryBarrier(gl::SHADER_STORAGE_BARRIER_BIT);
+        }
+    }
+
+    // Copia las nuevas en sus huecos, con una llamada por cada tramo de huecos seguidos
+    fn upload_spawned(&mut self) {
+        if self.spawned_slots.is_empty() {
+            return;
+        }
+
+        let float_size = std::mem::size_of::<f32>();
+        unsafe {
+            gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, self.ssbo);
+            for (first, range) in contiguous_runs(&self.spawned_slots) {
+                let chunk = &self.spawned[range.start * PARTICLE_SIZE..range.end * PARTICLE_SIZE];
+                gl::BufferSubData(
+                    gl::SHADER_STORAGE_BUFFER,
+                    (first * PARTICLE_SIZE * float_size) as gl::types::GLintptr,
+                    std::mem::size_of_val(chunk) as gl::types::GLsizeiptr,
+                    chunk.as_ptr() as *const gl::types::GLvoid,
+                );
+            }
+            gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, 0);
+        }
+
+        self.spawned.clear();
+        self.spawned_slots.clear();
+    }
+
+    // Dibuja todas con una llamada (las muertas no producen fragmentos). Activa su
+    // propio shader; u_projection hay que ponerlo en él como en los demás
+    pub fn draw(&self, model: &Matrix4x4) {
+        let shader = self.render_shader;
+        shader.use_shader();
+
+        unsafe {
+            gl::UniformMatrix4fv(shader.get_uniform_location("u_model"), 1, gl::FALSE, model.data.as_ptr());
+            gl::Uniform4f(shader.get_uniform_location("u_color"), self.color.r, self.color.g, self.color.b, self.color.a);
+            gl::Uniform1fv(shader.get_uniform_location("u_sizes"), CURVE_SAMPLES as i32, self.sizes.as_ptr());
+            gl::Uniform4fv(shader.get_uniform_location("u_colors"), CURVE_SAMPLES as i32, self.colors.as_ptr());
+
+            match self.texture {
+                Some(texture) => {
+                    texture.bind(0);
+                    gl::Uniform1i(shader.get_uniform_location("u_diffuse"), 0);
+                    gl::Uniform1i(shader.get_uniform_location("u_textured"), 1);
+                }
+                None => gl::Uniform1i(shader.get_uniform_location("u_textured"), 0),
+            }
+
+            gl::BindBufferBase(gl::SHADER_STORAGE_BUFFER, 0, self.ssbo);
+            gl::BindVertexArray(self.vao);
+            gl::DrawArraysInstanced(gl::TRIANGLES, 0, 6, self.slots.capacity() as i32);
+            gl::BindVertexArray(0);
+        }
+    }
+}
+
+impl<'a> Drawable for GpuParticles<'a> {
+    fn draw(&self, model: &Matrix4x4) {
+        GpuParticles::draw(self, model);
+    }
+}
+
+// Mismo formato que el struct Particle de gpu_particles.comp
+fn write_particle(data: &mut Vec<f32>, particle: &Particle) {
+    data.extend_from_slice(&[
+        particle.position.x, particle.position.y, particle.velocity.x, particle.velocity.y,
+        particle.rotation, particle.angular_velocity, particle.size, particle.age,
+        particle.lifetime, 0.0, 0.0, 0.0,
+    ]);
+}
+
+// Huecos del buffer de la GPU y cuándo muere la partícula de cada uno, en segundos
+// de simulación. Un hueco está libre cuando su partícula ya ha muerto, igual que en el
+// compute shader (edad >= vida)
+struct Slots {
+    deaths: Vec<f64>,
+    time: f64,
+    alive: usize,
+    // Por donde se empieza a buscar hueco: así se reparten en orden y suelen quedar seguidas
+    next: usize,
+}
+
+impl Slots {
+    fn new(capacity: usize) -> Slots {
+        Slots {
+            deaths: vec![0.0; capacity],
+            time: 0.0,
+            alive: 0,
+            next: 0,
+        }
+    }
+
+    fn capacity(&self) -> usize {
+        self.deaths.len()
+    }
+
+    fn alive(&self) -> usize {
+        self.alive
+    }
+
+    fn is_full(&self) -> bool {
+        self.alive >= self.capacity()
+    }
+
+    // Ocupa un hueco libre para una partícula que vive life segundos más
+    fn allocate(&mut self, life: f32) -> Option<usize> {
+        let capacity = self.capacity();
+        if self.is_full() {
+            return None;
+        }
+
+        let time = self.time;
+        let slot = (0..capacity).map(|i| (self.next + i) % capacity).find(|&slot| self.deaths[slot] <= time)?;
+        self.deaths[slot] = time + life as f64;
+        self.next = (slot + 1) % capacity;
+        // Con vida 0 la GPU ya la trata como muerta
+        if life > 0.0 {
+            self.alive += 1;
+        }
+        Some(slot)
+    }
+
+    fn advance(&mut self, delta: f32) {
+        self.time += delta as f64;
+        let time = self.time;
+        self.alive = self.deaths.iter().filter(|&&death| death > time).count();
+    }
+}
+
+// Tramos de huecos seguidos: (primer hueco, posiciones en slots)
+fn contiguous_runs(slots: &[usize]) -> Vec<(usize, Range<usize>)> {
+    let mut runs: Vec<(usize, Range<usize>)> = Vec::new();
+    for (i, &slot) in slots.iter().enumerate() {
+        match runs.last_mut() {
+            Some((first, range)) if *first + range.len() == slot => range.end = i + 1,
+            _ => runs.push((slot, i..i + 1)),
+        }
+    }
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn full_pool_drops_new_particles() {
+        let mut slots = Slots::new(3);
+        assert_eq!(slots.allocate(1.0), Some(0));
+        assert_eq!(slots.allocate(2.0), Some(1));
+        assert_eq!(slots.allocate(3.0), Some(2));
+        // Como ParticleEmitter: lleno no nace ninguna y las vivas no se pisan
+        assert!(slots.is_full());
+        assert_eq!(slots.allocate(1.0), None);
+        assert_eq!(slots.alive(), 3);
+    }
+
+    #[test]
+    fn dead_particles_free_their_slot() {
+        let mut slots = Slots::new(3);
+        slots.allocate(1.0);
+        slots.allocate(0.25);
+        slots.allocate(1.0);
+
+        slots.advance(0.5);
+        assert_eq!(slots.alive(), 2);
+        assert_eq!(slots.allocate(1.0), Some(1));
+        assert_eq!(slots.allocate(1.0), None);
+
+        // Mueren a la vez la 0 y la 2 (edad >= vida, como en el compute shader)
+        slots.advance(0.5);
+        assert_eq!(slots.alive(), 1);
+        assert_eq!(slots.allocate(1.0), Some(2));
+        assert_eq!(slots.allocate(1.0), Some(0));
+    }
+
+    #[test]
+    fn runs_group_consecutive_slots() {
+        assert_eq!(contiguous_runs(&[]), vec![]);
+        assert_eq!(contiguous_runs(&[4, 5, 6]), vec![(4, 0..3)]);
+        assert_eq!(contiguous_runs(&[7, 8, 0, 1, 5]), vec![(7, 0..2), (0, 2..4), (5, 4..5)]);
+    }
+}
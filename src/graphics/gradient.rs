@@ -0,0 +1,117 @@
+use crate::graphics::color::Color;
+
+// Degradado de colores con paradas en posiciones de [0, 1]
+#[derive(Clone)]
+pub struct Gradient {
+    // Ordenadas por posición
+    stops: Vec<(f32, Color)>,
+}
+
+impl Default for Gradient {
+    fn default() -> Gradient {
+        Gradient::new()
+    }
+}
+
+impl Gradient {
+    pub fn new() -> Gradient {
+        Gradient {
+            stops: Vec::new(),
+        }
+    }
+
+    // Degradado de dos colores, de from (0) a to (1)
+    pub fn linear(from: Color, to: Color) -> Gradient {
+        let mut gradient = Gradient::new();
+        gradient.add_stop(0.0, from);
+        gradient.add_stop(1.0, to);
+        gradient
+    }
+
+    // Añade una parada manteniendo el orden. Si ya hay otra en la misma
+    // posición la nueva va detrás, lo que permite cambios bruscos de color
+    pub fn add_stop(&mut self, position: f32, color: Color) {
+        let index = self.stops
+            .iter()
+            .position(|(p, _)| *p > position)
+            .unwrap_or(self.stops.len());
+        self.stops.insert(index, (position, color));
+    }
+
+    pub fn stops(&self) -> &[(f32, Color)] {
+        &self.stops
+    }
+
+    // Color en la posición t. Fuera de las paradas se usa la más cercana.
+    // Sin paradas devuelve blanco
+    pub fn evaluate(&self, t: f32) -> Color {
+        let (first, last) = match (self.stops.first(), self.stops.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return Color::white(),
+        };
+
+        if t <= first.0 {
+            return first.1;
+        }
+        if t >= last.0 {
+            return last.1;
+        }
+
+        for pair in self.stops.windows(2) {
+            let (start, start_color) = pair[0];
+            let (end, end_color) = pair[1];
+
+            if t <= end {
+                let span = end - start;
+                if span <= 0.0 {
+                    return end_color;
+                }
+                return start_color.lerp(&end_color, (t - start) / span);
+            }
+        }
+
+        last.1
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_gradient_is_white() {
+        assert_eq!(Gradient::new().evaluate(0.5), Color::white());
+    }
+
+    #[test]
+    fn linear_gradient_interpolates() {
+        let gradient = Gradient::linear(Color::black(), Color::from_f32(1.0, 0.5, 0.0, 1.0));
+
+        assert_eq!(gradient.evaluate(0.0), Color::black());
+        assert_eq!(gradient.evaluate(0.5), Color::from_f32(0.5, 0.25, 0.0, 1.0));
+        assert_eq!(gradient.evaluate(1.0), Color::from_f32(1.0, 0.5, 0.0, 1.0));
+    }
+
+    #[test]
+    fn outside_the_stops_uses_the_nearest() {
+        let mut gradient = Gradient::new();
+        gradient.add_stop(0.75, Color::red());
+        gradient.add_stop(0.25, Color::blue());
+
+        assert_eq!(gradient.stops()[0].0, 0.25);
+        assert_eq!(gradient.evaluate(-1.0), Color::blue());
+        assert_eq!(gradient.evaluate(0.1), Color::blue());
+        assert_eq!(gradient.evaluate(0.9), Color::red());
+        assert_eq!(gradient.evaluate(0.5), Color::blue().lerp(&Color::red(), 0.5));
+    }
+
+    #[test]
+    fn stops_at_the_same_position_make_a_hard_edge() {
+        let mut gradient = Gradient::linear(Color::black(), Color::white());
+        gradient.add_stop(0.5, Color::red());
+        gradient.add_stop(0.5, Color::green());
+
+        assert_eq!(gradient.evaluate(0.5), Color::red());
+        assert_eq!(gradient.evaluate(0.75), Color::green().lerp(&Color::white(), 0.5));
+        assert_eq!(gradient.evaluate(0.25), Color::black().lerp(&Color::red(), 0.5));
+    }
+}
@@ -0,0 +1,267 @@
+use crate::gl_utility::gl_buffer::{GLBuffer, AttributeInfo};
+use crate::gl_utility::shader::Shader;
+use crate::gl_utility::texture::Texture;
+use crate::graphics::color::Color;
+use crate::graphics::render_queue::Drawable;
+use crate::math::matrix4x4::Matrix4x4;
+use crate::math::rect::Rect;
+use crate::math::vector2::Vector2;
+use crate::math::vector3::Vector3;
+
+// Floats por instancia: posición (3), rotación (1), escala (2), color (4) y región (4)
+const INSTANCE_SIZE: usize = 14;
+
+// Una copia del sprite: dónde está, cómo está girada y escalada, su color y qué zona
+// de la textura muestra
+#[derive(Copy, Clone)]
+pub struct SpriteInstance {
+    pub position: Vector3,
+    // Radianes, alrededor del origen del sprite
+    pub rotation: f32,
+    pub scale: Vector2,
+    pub color: Color,
+    pub region: Rect,
+}
+
+impl SpriteInstance {
+    pub fn new(position: Vector3) -> SpriteInstance {
+        SpriteInstance {
+            position,
+            rotation: 0.0,
+            scale: Vector2::one(),
+            color: Color::white(),
+            region: Rect::new(0.0, 0.0, 1.0, 1.0),
+        }
+    }
+
+    pub fn with_rotation(mut self, rotation: f32) -> SpriteInstance {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn with_scale(mut self, scale: Vector2) -> SpriteInstance {
+        self.scale = scale;
+        self
+    }
+
+    pub fn with_color(mut self, color: Color) -> SpriteInstance {
+        self.color = color;
+        self
+    }
+
+    pub fn with_region(mut self, region: Rect) -> SpriteInstance {
+        self.region = region;
+        self
+    }
+
+    fn write(&self, data: &mut Vec<f32>) {
+        data.extend_from_slice(&[
+            self.position.x, self.position.y, self.position.z,
+            self.rotation,
+            self.scale.x, self.scale.y,
+            self.color.r, self.color.g, self.color.b, self.color.a,
+            self.region.x, self.region.y, self.region.width, self.region.height,
+        ]);
+    }
+}
+
+// Muchas copias del mismo sprite dibujadas con una sola llamada (campos de partículas,
+// multitudes, hierba...). Cada copia lleva sus datos en un buffer de instancias, que se
+// sube con upload_instances después de cambiarlas. Necesita el shader de instanced.vert
+// e instanced.frag
+pub struct InstancedSprite<'a> {
+    pub name: String,
+
+    pub width: f32,
+    pub height: f32,
+    pub origin: Vector3,
+
+    // Tinte de todas las copias, se multiplica por el color de cada una
+    color: Color,
+    u_color_location: i32,
+    u_model_location: i32,
+    u_diffuse_location: i32,
+    u_textured_location: i32,
+
+    texture: Option<&'a Texture>,
+
+    instances: Vec<SpriteInstance>,
+    // Copias que hay en la GPU (las de la última llamada a upload_instances)
+    uploaded: usize,
+    // Se reutiliza en cada subida para no reservar memoria cada frame
+    data: Vec<f32>,
+
+    quad: GLBuffer,
+    instance_buffer: GLBuffer,
+
+    shader: &'a Shader,
+}
+
+impl<'a> InstancedSprite<'a> {
+    pub fn new(name: &str, shader: &'a Shader, width: f32, height: f32) -> InstancedSprite<'a> {
+        let mut instance_buffer = GLBuffer::new();
+        // Se vuelve a subir casi cada frame
+        instance_buffer.set_usage(gl::DYNAMIC_DRAW);
+
+        InstancedSprite {
+            name: String::from(name),
+
+            width,
+            height,
+            origin: Vector3::new(0.5, 0.5, 0.0),
+
+            color: Color::white(),
+            u_color_location: shader.get_uniform_location("u_color"),
+            u_model_location: shader.get_uniform_location("u_model"),
+            u_diffuse_location: shader.get_uniform_location("u_diffuse"),
+            u_textured_location: shader.get_uniform_location("u_textured"),
+
+            texture: None,
+
+            instances: Vec::new(),
+            uploaded: 0,
+            data: Vec::new(),
+
+            quad: GLBuffer::new(),
+            instance_buffer,
+
+            shader,
+        }
+    }
+
+    pub fn load(&mut self) {
+        let attribute = |name: &str, component_size: i32| AttributeInfo {
+            location: self.shader.get_attribute_location(name),
+            component_size,
+        };
+
+        let vertex_attributes = vec![attribute("a_position", 3), attribute("a_tex_coord", 2)];
+        let instance_attributes = vec![
+            attribute("a_offset", 3),
+            attribute("a_rotation", 1),
+            attribute("a_scale", 2),
+            attribute("a_color", 4),
+            attribute("a_region", 4),
+        ];
+
+        self.quad.configure(vertex_attributes, false);
+        self.quad.configure_instances(&mut self.instance_buffer, instance_attributes, 1);
+
+        self.calculate_vertices();
+        self.upload_instances();
+    }
+
+    // Sube el quad (hay que llamarlo al cambiar el tamaño o el origen)
+    pub fn calculate_vertices(&mut self) {
+        let min_x = -(self.width * self.origin.x);
+        let max_x = self.width * (1.0 - self.origin.x);
+        let min_y = -(self.height * self.origin.y);
+        let max_y = self.height * (1.0 - self.origin.y);
+
+        // Las UV van de 0 a 1 y el shader las lleva a la región de cada instancia
+        self.quad.upload(&[
+            min_x, min_y, 0.0, 0.0, 0.0,
+            min_x, max_y, 0.0, 0.0, 1.0,
+            max_x, max_y, 0.0, 1.0, 1.0,
+            max_x, max_y, 0.0, 1.0, 1.0,
+            max_x, min_y, 0.0, 1.0, 0.0,
+            min_x, min_y, 0.0, 0.0, 0.0,
+        ]);
+    }
+
+    // Manda a la GPU las copias tal y como están ahora
+    pub fn upload_instances(&mut self) {
+        self.data.clear();
+        self.data.reserve(self.instances.len() * INSTANCE_SIZE);
+        for instance in &self.instances {
+            instance.write(&mut self.data);
+        }
+
+        self.instance_buffer.upload(&self.data);
+        self.uploaded = self.instances.len();
+    }
+
+    pub fn set_color(&mut self, color: Color) {
+        self.color = color;
+    }
+
+    pub fn color(&self) -> Color {
+        self.color
+    }
+
+    pub fn set_texture(&mut self, texture: Option<&'a Texture>) {
+        self.texture = texture;
+    }
+
+    pub fn texture(&self) -> Option<&'a Texture> {
+        self.texture
+    }
+
+    // Añade una copia y devuelve su índice
+    pub fn push(&mut self, instance: SpriteInstance) -> usize {
+        self.instances.push(instance);
+        self.instances.len() - 1
+    }
+
+    pub fn set(&mut self, index: usize, instance: SpriteInstance) {
+        self.instances[index] = instance;
+    }
+
+    pub fn get(&self, index: usize) -> Option<&SpriteInstance> {
+        self.instances.get(index)
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut SpriteInstance> {
+        self.instances.get_mut(index)
+    }
+
+    // Quita la copia cambiándola por la última (no conserva el orden)
+    pub fn swap_remove(&mut self, index: usize) -> SpriteInstance {
+        self.instances.swap_remove(index)
+    }
+
+    pub fn instances(&self) -> &[SpriteInstance] {
+        &self.instances
+    }
+
+    pub fn instances_mut(&mut self) -> &mut Vec<SpriteInstance> {
+        &mut self.instances
+    }
+
+    pub fn clear(&mut self) {
+        self.instances.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.instances.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.instances.is_empty()
+    }
+
+    // Dibuja las copias subidas; model transforma el grupo entero
+    pub fn draw(&self, model: &Matrix4x4) {
+        unsafe {
+            gl::UniformMatrix4fv(self.u_model_location, 1, gl::FALSE, model.data.as_ptr());
+            gl::Uniform4f(self.u_color_location, self.color.r, self.color.g, self.color.b, self.color.a);
+
+            match self.texture {
+                Some(texture) => {
+                    texture.bind(0);
+                    gl::Uniform1i(self.u_diffuse_location, 0);
+                    gl::Uniform1i(self.u_textured_location, 1);
+                }
+                None => gl::Uniform1i(self.u_textured_location, 0),
+            }
+        }
+
+        self.quad.draw_instanced(self.uploaded);
+    }
+}
+
+impl<'a> Drawable for InstancedSprite<'a> {
+    fn draw(&self, model: &Matrix4x4) {
+        InstancedSprite::draw(self, model);
+    }
+}
@@ -0,0 +1,333 @@
+use crate::gl_utility::shader::{Shader, ShaderManager};
+use crate::graphics::color::Color;
+use crate::graphics::material::Material;
+use crate::graphics::mesh::Mesh;
+use crate::math::matrix4x4::Matrix4x4;
+use crate::math::vector3::Vector3;
+
+pub const LIT_SHADER: &str = "lit";
+
+// Atenuación de las luces puntuales y focos: 1 / (constant + linear * d + quadratic * d²)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Attenuation {
+    pub constant: f32,
+    pub linear: f32,
+    pub quadratic: f32,
+}
+
+impl Attenuation {
+    pub fn new(constant: f32, linear: f32, quadratic: f32) -> Attenuation {
+        Attenuation { constant, linear, quadratic }
+    }
+
+    // Sin atenuación
+    pub fn none() -> Attenuation {
+        Attenuation::new(1.0, 0.0, 0.0)
+    }
+
+    // Valores aproximados para que la luz sea casi imperceptible a range unidades
+    pub fn from_range(range: f32) -> Attenuation {
+        let range = range.max(0.0001);
+        Attenuation::new(1.0, 4.5 / range, 75.0 / (range * range))
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum LightKind {
+    // Como el sol: misma dirección en toda la escena y sin atenuación
+    Directional { direction: Vector3 },
+    Point { position: Vector3, attenuation: Attenuation },
+    // Los ángulos, en radianes, son el semiángulo del cono: hasta inner_angle la luz es
+    // completa y se apaga suavemente hasta outer_angle
+    Spot { position: Vector3, direction: Vector3, attenuation: Attenuation, inner_angle: f32, outer_angle: f32 },
+}
+
+// Las posiciones y direcciones están en coordenadas del mundo
+#[derive(Clone, Copy)]
+pub struct Light {
+    pub kind: LightKind,
+    pub color: Color,
+    pub intensity: f32,
+}
+
+impl Light {
+    pub fn directional(direction: Vector3, color: Color) -> Light {
+        Light { kind: LightKind::Directional { direction }, color, intensity: 1.0 }
+    }
+
+    pub fn point(position: Vector3, color: Color, range: f32) -> Light {
+        let attenuation = Attenuation::from_range(range);
+        Light { kind: LightKind::Point { position, attenuation }, color, intensity: 1.0 }
+    }
+
+    pub fn spot(position: Vector3, direction: Vector3, color: Color, range: f32, inner_angle: f32, outer_angle: f32) -> Light {
+        let attenuation = Attenuation::from_range(range);
+        Light {
+            kind: LightKind::Spot { position, direction, attenuation, inner_angle, outer_angle },
+            color,
+            intensity: 1.0,
+        }
+    }
+
+    pub fn with_intensity(mut self, intensity: f32) -> Light {
+        self.intensity = intensity;
+        self
+    }
+
+    // Sin efecto en las direccionales
+    pub fn with_attenuation(mut self, value: Attenuation) -> Light {
+        match &mut self.kind {
+            LightKind::Directional { .. } => {}
+            LightKind::Point { attenuation, .. } | LightKind::Spot { attenuation, .. } => *attenuation = value,
+        }
+        self
+    }
+}
+
+// Material para LIT_SHADER (o cualquiera con los mismos uniforms): u_color es el color
+// difuso y shininess el exponente especular
+pub fn phong_material<'a>(name: &str, shader: &'a Shader, diffuse: Color, specular: Color, shininess: f32) -> Result<Material<'a>, String> {
+    let mut material = Material::new(name, shader);
+    material.set("u_color", diffuse)?;
+    material.set("u_specular", Vector3::new(specular.r, specular.g, specular.b))?;
+    material.set("u_shininess", shininess)?;
+    Ok(material)
+}
+
+// Posiciones de los campos de u_lights[i]
+struct LightLocations {
+    kind: i32,
+    position: i32,
+    direction: i32,
+    color: i32,
+    attenuation: i32,
+    inner_cos: i32,
+    outer_cos: i32,
+}
+
+// Renderizador hacia delante: cada malla se dibuja una vez con todas las luces de la
+// escena en arrays de uniforms. El máximo de luces se fija al compilar el shader
+// (MAX_LIGHTS); si hay más, sólo cuentan las primeras
+pub struct ForwardRenderer<'a> {
+    pub ambient: Color,
+    // false para el especular de Phong clásico
+    pub blinn: bool,
+
+    lights: Vec<Light>,
+    max_lights: usize,
+    locations: Vec<LightLocations>,
+
+    shader: &'a Shader,
+}
+
+impl<'a> ForwardRenderer<'a> {
+    // Registra LIT_SHADER con sitio para max_lights luces
+    pub fn register_shader(shaders: &mut ShaderManager, max_lights: usize) -> &Shader {
+        let define = format!("#define MAX_LIGHTS {}\n", max_lights.max(1));
+        let insert_define = |source: &str| match source.find('\n') {
+            // Tiene que ir detrás de #version
+            Some(end) => format!("{}{}{}", &source[..=end], define, &source[end + 1..]),
+            None => format!("{}\n{}", source, define),
+        };
+
+        shaders.register(
+            LIT_SHADER,
+            &insert_define(include_str!("../lit.vert")),
+            &insert_define(include_str!("../lit.frag")),
+        )
+    }
+
+    // max_lights tiene que coincidir con el de register_shader
+    pub fn new(shader: &'a Shader, max_lights: usize) -> Result<ForwardRenderer<'a>, String> {
+        let location = |name: String| shader.uniform(&name).map_or(-1, |uniform| uniform.location);
+
+        let mut locations = Vec::with_capacity(max_lights);
+        for i in 0..max_lights {
+            let field = |field: &str| location(format!("u_lights[{}].{}", i, field));
+            locations.push(LightLocations {
+                kind: field("kind"),
+                position: field("position"),
+                direction: field("direction"),
+                color: field("color"),
+                attenuation: field("attenuation"),
+                inner_cos: field("inner_cos"),
+                outer_cos: field("outer_cos"),
+            });
+        }
+
+        // Si el último no existe, el shader se compiló con menos luces
+        if locations.last().is_some_and(|last| last.color == -1) {
+            return Err(format!("El shader {} no tiene sitio para {} luces", shader.name, max_lights));
+        }
+
+        Ok(ForwardRenderer {
+            ambient: Color::from_f32(0.1, 0.1, 0.1, 1.0),
+            blinn: true,
+
+            lights: Vec::new(),
+            max_lights,
+            locations,
+
+            shader,
+        })
+    }
+
+    pub fn shader(&self) -> &'a Shader {
+        self.shader
+    }
+
+    pub fn max_lights(&self) -> usize {
+        self.max_lights
+    }
+
+    // Devuelve el índice de la luz
+    pub fn add_light(&mut self, light: Light) -> usize {
+        self.lights.push(light);
+        self.lights.len() - 1
+    }
+
+    pub fn light(&self, index: usize) -> Option<&Light> {
+        self.lights.get(index)
+    }
+
+    pub fn light_mut(&mut self, index: usize) -> Option<&mut Light> {
+        self.lights.get_mut(index)
+    }
+
+    // Los índices de las siguientes bajan en uno
+    pub fn remove_light(&mut self, index: usize) -> Light {
+        self.lights.remove(index)
+    }
+
+    pub fn lights(&self) -> &[Light] {
+        &self.lights
+    }
+
+    pub fn clear_lights(&mut self) {
+        self.lights.clear();
+    }
+
+    // Activa el shader y sube la cámara y las luces. Hay que llamarlo antes de dibujar
+    // y cada vez que cambien; view_projection es proyección * vista
+    pub fn begin(&self, view_projection: &Matrix4x4, camera_position: Vector3) {
+        let shader = self.shader;
+        let location = |name: &str| shader.uniform(name).map_or(-1, |uniform| uniform.location);
+        let count = self.lights.len().min(self.max_lights);
+
+        shader.use_shader();
+
+        unsafe {
+            gl::UniformMatrix4fv(location("u_projection"), 1, gl::FALSE, view_projection.data.as_ptr());
+            gl::Uniform3f(location("u_camera_position"), camera_position.x, camera_position.y, camera_position.z);
+            gl::Uniform3f(location("u_ambient"), self.ambient.r, self.ambient.g, self.ambient.b);
+            gl::Uniform1i(location("u_blinn"), self.blinn as i32);
+            gl::Uniform1i(location("u_light_count"), count as i32);
+
+            for (light, locations) in self.lights.iter().zip(&self.locations) {
+                upload_light(light, locations);
+            }
+        }
+    }
+
+    // Dibuja una malla con las luces de begin. Si tiene material tiene que usar este
+    // mismo shader
+    pub fn draw(&self, mesh: &Mesh, model: &Matrix4x4) {
+        if mesh.material().is_none() {
+            self.shader.use_shader();
+        }
+        mesh.draw(model);
+    }
+}
+
+// Valores de los campos de u_lights[i] para una luz
+struct LightUniforms {
+    kind: i32,
+    position: Vector3,
+    direction: Vector3,
+    color: Vector3,
+    attenuation: Attenuation,
+    inner_cos: f32,
+    outer_cos: f32,
+}
+
+impl LightUniforms {
+    fn new(light: &Light) -> LightUniforms {
+        let (kind, position, direction, attenuation, inner_cos, outer_cos) = match light.kind {
+            LightKind::Directional { direction } => (0, Vector3::zero(), direction, Attenuation::none(), 1.0, 1.0),
+            LightKind::Point { position, attenuation } => (1, position, Vector3::zero(), attenuation, 1.0, 1.0),
+            LightKind::Spot { position, direction, attenuation, inner_angle, outer_angle } => {
+                // El cono interior tiene que ser algo menor que el exterior para el smoothstep
+                let inner_cos = inner_angle.min(outer_angle).cos();
+                (2, position, direction, attenuation, inner_cos, outer_angle.cos().min(inner_cos - 0.0001))
+            }
+        };
+        let (color, intensity) = (light.color, light.intensity);
+
+        LightUniforms {
+            kind,
+            position,
+            direction: if direction.length_squared() > 0.0 { direction.normalized() } else { direction },
+            color: Vector3::new(color.r * intensity, color.g * intensity, color.b * intensity),
+            attenuation,
+            inner_cos,
+            outer_cos,
+        }
+    }
+}
+
+unsafe fn upload_light(light: &Light, locations: &LightLocations) {
+    let LightUniforms { kind, position, direction, color, attenuation, inner_cos, outer_cos } = LightUniforms::new(light);
+
+    gl::Uniform1i(locations.kind, kind);
+    gl::Uniform3f(locations.position, position.x, position.y, position.z);
+    gl::Uniform3f(locations.direction, direction.x, direction.y, direction.z);
+    gl::Uniform3f(locations.color, color.x, color.y, color.z);
+    gl::Uniform3f(locations.attenuation, attenuation.constant, attenuation.linear, attenuation.quadratic);
+    gl::Uniform1f(locations.inner_cos, inner_cos);
+    gl::Uniform1f(locations.outer_cos, outer_cos);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spot(inner_angle: f32, outer_angle: f32) -> LightUniforms {
+        let light = Light::spot(Vector3::zero(), Vector3::new(0.0, -2.0, 0.0), Color::white(), 10.0, inner_angle, outer_angle);
+        LightUniforms::new(&light)
+    }
+
+    // Lo que necesita el smoothstep del shader: el borde exterior por fuera del interior
+    fn assert_valid_cone(uniforms: &LightUniforms) {
+        assert_eq!(uniforms.kind, 2);
+        assert!(uniforms.outer_cos < uniforms.inner_cos, "{} >= {}", uniforms.outer_cos, uniforms.inner_cos);
+        assert!(uniforms.inner_cos <= 1.0 && uniforms.outer_cos >= -1.0);
+    }
+
+    #[test]
+    fn spot_light_uploads_its_cone() {
+        let uniforms = spot(0.2, 0.5);
+        assert_valid_cone(&uniforms);
+        assert!((uniforms.inner_cos - 0.2f32.cos()).abs() < 1e-6);
+        assert!((uniforms.outer_cos - 0.5f32.cos()).abs() < 1e-6);
+        assert!((uniforms.direction.y + 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn spot_light_with_inner_wider_than_outer_is_still_a_valid_cone() {
+        let uniforms = spot(0.8, 0.3);
+        assert_valid_cone(&uniforms);
+        // Se queda con el cono exterior
+        assert!((uniforms.inner_cos - 0.3f32.cos()).abs() < 1e-6);
+
+        assert_valid_cone(&spot(0.4, 0.4));
+        assert_valid_cone(&spot(0.0, 0.0));
+    }
+
+    #[test]
+    fn intensity_scales_the_color() {
+        let light = Light::point(Vector3::zero(), Color::from_f32(1.0, 0.5, 0.0, 1.0), 5.0).with_intensity(2.0);
+        let uniforms = LightUniforms::new(&light);
+        assert_eq!(uniforms.kind, 1);
+        assert_eq!((uniforms.color.x, uniforms.color.y, uniforms.color.z), (2.0, 1.0, 0.0));
+    }
+}
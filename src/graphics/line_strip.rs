@@ -0,0 +1,107 @@
+use crate::gl_utility::gl_buffer::{GLBuffer, AttributeInfo};
+use crate::gl_utility::shader::Shader;
+use crate::graphics::color::Color;
+use crate::graphics::render_queue::Drawable;
+use crate::math::vector3::Vector3;
+use crate::math::matrix4x4::Matrix4x4;
+use crate::math::curve::Curve;
+
+// Línea poligonal, pensada para depurar curvas y caminos.
+// Usa el mismo shader que Sprite (a_position, u_color y u_model)
+pub struct LineStrip<'a> {
+    pub name: String,
+
+    pub color: Color,
+    u_color_location: i32,
+    u_model_location: i32,
+    u_textured_location: i32,
+
+    buffer: GLBuffer,
+    points: Vec<Vector3>,
+
+    shader: &'a Shader,
+}
+
+impl<'a> LineStrip<'a> {
+    pub fn new(name: &str, shader: &'a Shader, points: Vec<Vector3>) -> LineStrip<'a> {
+        let mut buffer = GLBuffer::new();
+        buffer.set_mode(gl::LINE_STRIP);
+
+        LineStrip {
+            name: String::from(name),
+
+            color: Color::white(),
+            u_color_location: shader.get_uniform_location("u_color"),
+            u_model_location: shader.get_uniform_location("u_model"),
+            u_textured_location: shader.get_uniform_location("u_textured"),
+
+            buffer,
+            points,
+
+            shader,
+        }
+    }
+
+    // Crea la línea muestreando la curva en segments tramos
+    pub fn from_curve(name: &str, shader: &'a Shader, curve: &dyn Curve, segments: usize) -> LineStrip<'a> {
+        LineStrip::new(name, shader, curve.sample(segments))
+    }
+
+    pub fn load(&mut self) {
+        let a_position_location = self.shader.get_attribute_location("a_position");
+        self.buffer.configure(
+            vec![
+                AttributeInfo {
+                    location: a_position_location,
+                    component_size: 3,
+                }
+            ],
+            false,
+        );
+
+        self.upload();
+    }
+
+    // Cambia los puntos y los vuelve a subir a la GPU
+    pub fn set_points(&mut self, points: Vec<Vector3>) {
+        self.points = points;
+        self.upload();
+    }
+
+    fn upload(&mut self) {
+        self.buffer.upload(
+            &self.points
+                .iter()
+                .flat_map(|p| vec![p.x, p.y, p.z])
+                .collect::<Vec<f32>>()
+        );
+    }
+
+    pub fn draw(&self, model: &Matrix4x4) {
+        unsafe {
+            gl::UniformMatrix4fv(
+                self.u_model_location,
+                1,
+                gl::FALSE,
+                model.data.as_ptr(),
+            );
+            gl::Uniform4f(
+                self.u_color_location,
+                self.color.r,
+                self.color.g,
+                self.color.b,
+                self.color.a,
+            );
+            // Las líneas no llevan textura
+            gl::Uniform1i(self.u_textured_location, 0);
+        }
+
+        self.buffer.draw();
+    }
+}
+
+impl<'a> Drawable for LineStrip<'a> {
+    fn draw(&self, model: &Matrix4x4) {
+        LineStrip::draw(self, model);
+    }
+}
@@ -0,0 +1,448 @@
+use std::collections::BTreeMap;
+
+use crate::gl_utility::render_state::{BlendMode, RenderState};
+use crate::gl_utility::shader::{Shader, UniformType};
+use crate::gl_utility::texture::Texture;
+use crate::graphics::color::Color;
+use crate::math::matrix4x4::Matrix4x4;
+use crate::math::vector2::Vector2;
+use crate::math::vector3::Vector3;
+
+// Valor de un parámetro del material
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum UniformValue {
+    Float(f32),
+    Int(i32),
+    Vec2([f32; 2]),
+    Vec3([f32; 3]),
+    Vec4([f32; 4]),
+    Mat4([f32; 16]),
+}
+
+impl UniformValue {
+    // Si el valor se puede mandar a un uniform de ese tipo
+    fn fits(&self, uniform_type: UniformType) -> bool {
+        matches!(
+            (self, uniform_type),
+            (UniformValue::Float(_), UniformType::Float)
+                | (UniformValue::Int(_), UniformType::Int)
+                | (UniformValue::Int(_), UniformType::Bool)
+                | (UniformValue::Vec2(_), UniformType::Vec2)
+                | (UniformValue::Vec3(_), UniformType::Vec3)
+                | (UniformValue::Vec4(_), UniformType::Vec4)
+                | (UniformValue::Mat4(_), UniformType::Mat4)
+        )
+    }
+
+    fn upload(&self, location: i32) {
+        unsafe {
+            match self {
+                UniformValue::Float(value) => gl::Uniform1f(location, *value),
+                UniformValue::Int(value) => gl::Uniform1i(location, *value),
+                UniformValue::Vec2(value) => gl::Uniform2fv(location, 1, value.as_ptr()),
+                UniformValue::Vec3(value) => gl::Uniform3fv(location, 1, value.as_ptr()),
+                UniformValue::Vec4(value) => gl::Uniform4fv(location, 1, value.as_ptr()),
+                UniformValue::Mat4(value) => gl::UniformMatrix4fv(location, 1, gl::FALSE, value.as_ptr()),
+            }
+        }
+    }
+
+    // Tipo y componentes tal y como se escriben en el formato de texto
+    fn to_text(self) -> (&'static str, Vec<String>) {
+        let floats = |values: &[f32]| values.iter().map(|value| value.to_string()).collect();
+        match self {
+            UniformValue::Float(value) => ("float", floats(&[value])),
+            UniformValue::Int(value) => ("int", vec![value.to_string()]),
+            UniformValue::Vec2(value) => ("vec2", floats(&value)),
+            UniformValue::Vec3(value) => ("vec3", floats(&value)),
+            UniformValue::Vec4(value) => ("vec4", floats(&value)),
+            UniformValue::Mat4(value) => ("mat4", floats(&value)),
+        }
+    }
+
+    fn from_text(kind: &str, values: &[&str]) -> Result<UniformValue, String> {
+        if kind == "color" {
+            let hex = values.first().ok_or("Falta el color")?;
+            let color = Color::from_hex(hex).ok_or_else(|| format!("Color no válido: {}", hex))?;
+            return Ok(UniformValue::from(color));
+        }
+        if kind == "int" {
+            let value = values.first().ok_or("Falta el valor")?;
+            return value.parse().map(UniformValue::Int).map_err(|_| format!("Entero no válido: {}", value));
+        }
+
+        let count = match kind {
+            "float" => 1,
+            "vec2" => 2,
+            "vec3" => 3,
+            "vec4" => 4,
+            "mat4" => 16,
+            _ => return Err(format!("Tipo de parámetro desconocido: {}", kind)),
+        };
+        if values.len() != count {
+            return Err(format!("Un {} necesita {} números y tiene {}", kind, count, values.len()));
+        }
+
+        let mut floats = [0.0; 16];
+        for (float, value) in floats.iter_mut().zip(values) {
+            *float = value.parse().map_err(|_| format!("Número no válido: {}", value))?;
+        }
+
+        Ok(match count {
+            1 => UniformValue::Float(floats[0]),
+            2 => UniformValue::Vec2([floats[0], floats[1]]),
+            3 => UniformValue::Vec3([floats[0], floats[1], floats[2]]),
+            4 => UniformValue::Vec4([floats[0], floats[1], floats[2], floats[3]]),
+            _ => UniformValue::Mat4(floats),
+        })
+    }
+}
+
+impl From<f32> for UniformValue {
+    fn from(value: f32) -> UniformValue {
+        UniformValue::Float(value)
+    }
+}
+
+impl From<i32> for UniformValue {
+    fn from(value: i32) -> UniformValue {
+        UniformValue::Int(value)
+    }
+}
+
+impl From<bool> for UniformValue {
+    fn from(value: bool) -> UniformValue {
+        UniformValue::Int(value as i32)
+    }
+}
+
+impl From<Vector2> for UniformValue {
+    fn from(value: Vector2) -> UniformValue {
+        UniformValue::Vec2([value.x, value.y])
+    }
+}
+
+impl From<Vector3> for UniformValue {
+    fn from(value: Vector3) -> UniformValue {
+        UniformValue::Vec3([value.x, value.y, value.z])
+    }
+}
+
+impl From<Color> for UniformValue {
+    fn from(value: Color) -> UniformValue {
+        UniformValue::Vec4([value.r, value.g, value.b, value.a])
+    }
+}
+
+impl From<&Matrix4x4> for UniformValue {
+    fn from(value: &Matrix4x4) -> UniformValue {
+        UniformValue::Mat4(value.data)
+    }
+}
+
+// Shader más los valores de sus uniforms y sus texturas. Lo pueden compartir muchos
+// sprites y mallas: cada uno sólo pone después lo suyo (u_model y compañía).
+// Los parámetros se comprueban contra los uniforms del shader al asignarlos.
+// Formato de texto (una línea por dato, las que empiezan por # son comentarios):
+//     shader basic
+//     blend alpha
+//     color u_color #FF8000FF
+//     float u_strength 0.5
+//     texture u_diffuse player
+pub struct Material<'a> {
+    pub name: String,
+    // Mezcla con la que se dibuja. La aplica la RenderQueue: RenderItem::new la toma
+    // del sprite o la malla que use el material. Fuera de la cola hay que aplicarla
+    // con un StateCache (render_state)
+    pub blend: BlendMode,
+
+    shader: &'a Shader,
+    // BTreeMap para que se apliquen y se guarden siempre en el mismo orden
+    values: BTreeMap<String, UniformValue>,
+    textures: BTreeMap<String, &'a Texture>,
+}
+
+impl<'a> Material<'a> {
+    pub fn new(name: &str, shader: &'a Shader) -> Material<'a> {
+        Material {
+            name: String::from(name),
+            blend: BlendMode::Alpha,
+
+            shader,
+            values: BTreeMap::new(),
+            textures: BTreeMap::new(),
+        }
+    }
+
+    pub fn shader(&self) -> &'a Shader {
+        self.shader
+    }
+
+    // Estado por defecto con la mezcla del material
+    pub fn render_state(&self) -> RenderState {
+        RenderState::new().with_blend(self.blend)
+    }
+
+    pub fn set(&mut self, name: &str, value: impl Into<UniformValue>) -> Result<(), String> {
+        let value = value.into();
+        let uniform = self.shader.uniform(name).ok_or_else(|| {
+            format!("El shader {} del material {} no tiene el uniform {}", self.shader.name, self.name, name)
+        })?;
+        if !value.fits(uniform.uniform_type) {
+            return Err(format!(
+                "El uniform {} del material {} es {:?} y no admite {:?}",
+                name, self.name, uniform.uniform_type, value
+            ));
+        }
+
+        self.values.insert(String::from(name), value);
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<UniformValue> {
+        self.values.get(name).copied()
+    }
+
+    pub fn set_texture(&mut self, name: &str, texture: &'a Texture) -> Result<(), String> {
+        match self.shader.uniform(name) {
+            Some(uniform) if uniform.uniform_type == UniformType::Sampler2D => {
+                self.textures.insert(String::from(name), texture);
+                Ok(())
+            }
+            Some(_) => Err(format!("El uniform {} del material {} no es una textura", name, self.name)),
+            None => Err(format!("El shader {} del material {} no tiene el uniform {}", self.shader.name, self.name, name)),
+        }
+    }
+
+    pub fn texture(&self, name: &str) -> Option<&'a Texture> {
+        self.textures.get(name).copied()
+    }
+
+    // Unidades de textura que ocupa el material (de la 0 a texture_count - 1)
+    pub fn texture_count(&self) -> u32 {
+        self.textures.len() as u32
+    }
+
+    // Activa el shader y le pasa todos los valores y texturas
+    pub fn bind(&self) {
+        self.shader.use_shader();
+
+        for (name, value) in &self.values {
+            value.upload(self.shader.get_uniform_location(name));
+        }
+        for (unit, (name, texture)) in self.textures.iter().enumerate() {
+            texture.bind(unit as u32);
+            unsafe {
+                gl::Uniform1i(self.shader.get_uniform_location(name), unit as i32);
+            }
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = format!("shader {}\nblend {}\n", self.shader.name, blend_name(self.blend));
+        for (name, value) in &self.values {
+            let (kind, components) = value.to_text();
+            text += &format!("{} {} {}\n", kind, name, components.join(" "));
+        }
+        for (name, texture) in &self.textures {
+            text += &format!("texture {} {}\n", name, texture.name);
+        }
+        text
+    }
+
+    // Lee un material del formato de texto. shaders y textures buscan por nombre
+    // (por ejemplo con ShaderManager::find)
+    pub fn parse(
+        name: &str,
+        text: &str,
+        shaders: impl Fn(&str) -> Option<&'a Shader>,
+        textures: impl Fn(&str) -> Option<&'a Texture>,
+    ) -> Result<Material<'a>, String> {
+        let mut material: Option<Material<'a>> = None;
+
+        for (number, line) in text.lines().enumerate() {
+            // Los comentarios ocupan la línea entera: los colores también llevan #
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let error = |message: String| format!("Material {}, línea {}: {}", name, number + 1, message);
+            let words: Vec<&str> = line.split_whitespace().collect();
+
+            if words[0] == "shader" {
+                // Un segundo shader tiraría los valores ya leídos, que eran para el primero
+                if material.is_some() {
+                    return Err(error(String::from("el material ya tiene shader")));
+                }
+                let shader_name = words.get(1).ok_or_else(|| error(String::from("falta el nombre del shader")))?;
+                let shader = shaders(shader_name).ok_or_else(|| error(format!("no existe el shader {}", shader_name)))?;
+                material = Some(Material::new(name, shader));
+                continue;
+            }
+
+            let material = material.as_mut().ok_or_else(|| error(String::from("la primera línea tiene que ser shader")))?;
+            match words[0] {
+                "blend" => {
+                    let mode = words.get(1).copied().unwrap_or("");
+                    material.blend = parse_blend(mode).ok_or_else(|| error(format!("modo de mezcla desconocido: {}", mode)))?;
+                }
+                "texture" => {
+                    if words.len() != 3 {
+                        return Err(error(String::from("se esperaba texture <uniform> <textura>")));
+                    }
+                    let texture = textures(words[2]).ok_or_else(|| error(format!("no existe la textura {}", words[2])))?;
+                    material.set_texture(words[1], texture).map_err(error)?;
+                }
+                kind => {
+                    let uniform = words.get(1).ok_or_else(|| error(String::from("falta el nombre del uniform")))?;
+                    let value = UniformValue::from_text(kind, &words[2..]).map_err(error)?;
+                    material.set(uniform, value).map_err(error)?;
+                }
+            }
+        }
+
+        material.ok_or_else(|| format!("El material {} no indica su shader", name))
+    }
+}
+
+fn blend_name(blend: BlendMode) -> &'static str {
+    match blend {
+        BlendMode::Opaque => "opaque",
+        BlendMode::Alpha => "alpha",
+        BlendMode::Additive => "additive",
+        BlendMode::Multiply => "multiply",
+        BlendMode::Premultiplied => "premultiplied",
+    }
+}
+
+fn parse_blend(name: &str) -> Option<BlendMode> {
+    match name {
+        "opaque" => Some(BlendMode::Opaque),
+        "alpha" => Some(BlendMode::Alpha),
+        "additive" => Some(BlendMode::Additive),
+        "multiply" => Some(BlendMode::Multiply),
+        "premultiplied" => Some(BlendMode::Premultiplied),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shader() -> &'static Shader {
+        Shader::headless(
+            "basic",
+            &[
+                ("u_color", UniformType::Vec4),
+                ("u_strength", UniformType::Float),
+                ("u_count", UniformType::Int),
+                ("u_lit", UniformType::Bool),
+                ("u_offset", UniformType::Vec2),
+                ("u_diffuse", UniformType::Sampler2D),
+            ],
+        )
+    }
+
+    fn parse(text: &str) -> Result<Material<'static>, String> {
+        let shader = shader();
+        let texture = Texture::headless("player", 16, 16);
+        Material::parse(
+            "test",
+            text,
+            |name| if name == "basic" { Some(shader) } else { None },
+            |name| if name == "player" { Some(texture) } else { None },
+        )
+    }
+
+    const TEXT: &str = "# Material de prueba
+shader basic
+blend additive
+color u_color #FF8000FF
+float u_strength 0.5
+int u_count 3
+int u_lit 1
+vec2 u_offset -1.5 2
+texture u_diffuse player
+";
+
+    #[test]
+    fn parses_values_textures_and_blend() {
+        let material = parse(TEXT).unwrap();
+
+        assert_eq!(material.shader().name, "basic");
+        assert_eq!(material.blend, BlendMode::Additive);
+        assert_eq!(material.get("u_color"), Some(UniformValue::Vec4([1.0, 128.0 / 255.0, 0.0, 1.0])));
+        assert_eq!(material.get("u_strength"), Some(UniformValue::Float(0.5)));
+        assert_eq!(material.get("u_count"), Some(UniformValue::Int(3)));
+        assert_eq!(material.get("u_offset"), Some(UniformValue::Vec2([-1.5, 2.0])));
+        assert_eq!(material.texture("u_diffuse").map(|t| t.name.as_str()), Some("player"));
+        assert_eq!(material.texture_count(), 1);
+    }
+
+    // Guardar y volver a leer da el mismo material
+    #[test]
+    fn to_text_round_trips() {
+        let material = parse(TEXT).unwrap();
+        let text = material.to_text();
+        let again = parse(&text).unwrap();
+
+        assert_eq!(again.to_text(), text);
+        assert_eq!(again.blend, material.blend);
+        for name in ["u_color", "u_strength", "u_count", "u_lit", "u_offset"] {
+            assert_eq!(again.get(name), material.get(name), "{}", name);
+        }
+        assert_eq!(again.texture("u_diffuse").map(|t| t.name.as_str()), Some("player"));
+    }
+
+    #[test]
+    fn every_blend_mode_round_trips() {
+        for blend in [BlendMode::Opaque, BlendMode::Alpha, BlendMode::Additive, BlendMode::Multiply, BlendMode::Premultiplied] {
+            let material = parse(&format!("shader basic\nblend {}\n", blend_name(blend))).unwrap();
+            assert_eq!(material.blend, blend);
+            assert_eq!(material.render_state().blend, blend);
+        }
+
+        // Sin línea blend se queda la mezcla alfa
+        assert_eq!(parse("shader basic").unwrap().blend, BlendMode::Alpha);
+    }
+
+    fn parse_error(text: &str) -> String {
+        parse(text).err().unwrap_or_else(|| panic!("se esperaba un error con:\n{}", text))
+    }
+
+    #[test]
+    fn rejects_unknown_names() {
+        assert!(parse_error("shader missing").contains("missing"));
+        assert!(parse_error("shader basic\nfloat u_missing 1").contains("u_missing"));
+        assert!(parse_error("shader basic\ntexture u_diffuse missing").contains("missing"));
+        assert!(parse_error("shader basic\nblend screen").contains("screen"));
+        assert!(parse_error("shader basic\nquaternion u_color 1 2 3 4").contains("quaternion"));
+    }
+
+    #[test]
+    fn rejects_values_of_the_wrong_type() {
+        // El tipo no encaja con el del uniform
+        parse_error("shader basic\nfloat u_color 1");
+        parse_error("shader basic\nvec4 u_strength 1 2 3 4");
+        parse_error("shader basic\ntexture u_color player");
+        // Número de componentes o números no válidos
+        parse_error("shader basic\nvec2 u_offset 1");
+        parse_error("shader basic\nfloat u_strength mucho");
+        parse_error("shader basic\nint u_count 1.5");
+        parse_error("shader basic\ncolor u_color #GG0000");
+    }
+
+    #[test]
+    fn rejects_a_second_shader_line() {
+        let error = parse_error("shader basic\nfloat u_strength 0.5\nshader basic\n");
+        assert!(error.contains("línea 3"), "{}", error);
+    }
+
+    #[test]
+    fn shader_has_to_come_first() {
+        assert!(parse_error("blend opaque\nshader basic").contains("línea 1"));
+        assert!(parse_error("# vacío\n").contains("no indica su shader"));
+    }
+}
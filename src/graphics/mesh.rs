@@ -0,0 +1,493 @@
+use std::f32::consts::{PI, TAU};
+
+use crate::gl_utility::gl_buffer::{GLBuffer, AttributeInfo};
+use crate::gl_utility::render_state::BlendMode;
+use crate::gl_utility::shader::Shader;
+use crate::gl_utility::texture::Texture;
+use crate::graphics::color::Color;
+use crate::graphics::material::Material;
+use crate::graphics::render_queue::Drawable;
+use crate::math::aabb3::Aabb3;
+use crate::math::matrix4x4::Matrix4x4;
+use crate::math::vector2::Vector2;
+use crate::math::vector3::Vector3;
+
+// Geometría 3D en memoria: listas paralelas de atributos (una entrada por vértice) y
+// triángulos como índices. Los triángulos van en sentido antihorario vistos desde
+// fuera y las UV con v = 0 arriba, como en el resto del motor. Los tangentes llevan en
+// w el signo de la bitangente (bitangente = cross(normal, tangente) * w)
+#[derive(Clone)]
+pub struct MeshData {
+    pub positions: Vec<Vector3>,
+    pub normals: Vec<Vector3>,
+    pub uvs: Vec<Vector2>,
+    pub tangents: Vec<[f32; 4]>,
+    pub indices: Vec<u32>,
+}
+
+impl Default for MeshData {
+    fn default() -> MeshData {
+        MeshData::new()
+    }
+}
+
+impl MeshData {
+    pub fn new() -> MeshData {
+        MeshData {
+            positions: Vec::new(),
+            normals: Vec::new(),
+            uvs: Vec::new(),
+            tangents: Vec::new(),
+            indices: Vec::new(),
+        }
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    // Añade un vértice y devuelve su índice
+    pub fn add_vertex(&mut self, position: Vector3, normal: Vector3, uv: Vector2) -> u32 {
+        self.positions.push(position);
+        self.normals.push(normal);
+        self.uvs.push(uv);
+        self.positions.len() as u32 - 1
+    }
+
+    pub fn add_triangle(&mut self, a: u32, b: u32, c: u32) {
+        self.indices.extend_from_slice(&[a, b, c]);
+    }
+
+    // Añade los vértices y triángulos de otra malla
+    pub fn append(&mut self, other: &MeshData) {
+        let offset = self.positions.len() as u32;
+        self.positions.extend_from_slice(&other.positions);
+        self.normals.extend_from_slice(&other.normals);
+        self.uvs.extend_from_slice(&other.uvs);
+        self.tangents.extend_from_slice(&other.tangents);
+        self.indices.extend(other.indices.iter().map(|index| index + offset));
+    }
+
+    pub fn bounds(&self) -> Option<Aabb3> {
+        Aabb3::from_points(&self.positions)
+    }
+
+    // Comprueba que los atributos tienen todos el mismo número de vértices (o están
+    // vacíos) y que los índices no se salen
+    pub fn validate(&self) -> Result<(), String> {
+        let count = self.positions.len();
+        let check = |name: &str, len: usize| {
+            if len != 0 && len != count {
+                Err(format!("La malla tiene {} posiciones y {} {}", count, len, name))
+            } else {
+                Ok(())
+            }
+        };
+        check("normales", self.normals.len())?;
+        check("UV", self.uvs.len())?;
+        check("tangentes", self.tangents.len())?;
+
+        if self.indices.len() % 3 != 0 {
+            return Err(format!("La malla tiene {} índices, que no es múltiplo de 3", self.indices.len()));
+        }
+        if let Some(&index) = self.indices.iter().find(|&&index| index as usize >= count) {
+            return Err(format!("El índice {} se sale de los {} vértices de la malla", index, count));
+        }
+        Ok(())
+    }
+
+    // Normales suaves: la media de las de los triángulos de cada vértice, ponderada
+    // por su área. Los vértices repetidos (costuras de UV) no se comparten
+    pub fn compute_normals(&mut self) {
+        let mut normals = vec![Vector3::zero(); self.positions.len()];
+        for triangle in self.indices.chunks_exact(3) {
+            let [a, b, c] = [triangle[0] as usize, triangle[1] as usize, triangle[2] as usize];
+            // Sin normalizar: su longitud es el doble del área
+            let normal = (self.positions[b] - self.positions[a]).cross(self.positions[c] - self.positions[a]);
+            for &vertex in &[a, b, c] {
+                normals[vertex] += normal;
+            }
+        }
+
+        self.normals = normals.iter().map(|normal| normal.normalized()).collect();
+    }
+
+    // Tangentes a partir de las UV, para los mapas de normales. Necesita normales y UV
+    pub fn compute_tangents(&mut self) {
+        let count = self.positions.len();
+        if self.uvs.len() != count || self.normals.len() != count {
+            self.tangents.clear();
+            return;
+        }
+
+        let mut tangents = vec![Vector3::zero(); count];
+        let mut bitangents = vec![Vector3::zero(); count];
+        for triangle in self.indices.chunks_exact(3) {
+            let [a, b, c] = [triangle[0] as usize, triangle[1] as usize, triangle[2] as usize];
+            let edge1 = self.positions[b] - self.positions[a];
+            let edge2 = self.positions[c] - self.positions[a];
+            let delta1 = self.uvs[b] - self.uvs[a];
+            let delta2 = self.uvs[c] - self.uvs[a];
+
+            let determinant = delta1.x * delta2.y - delta2.x * delta1.y;
+            if determinant.abs() < 1e-12 {
+                continue;
+            }
+            let r = 1.0 / determinant;
+            let tangent = (edge1 * delta2.y - edge2 * delta1.y) * r;
+            let bitangent = (edge2 * delta1.x - edge1 * delta2.x) * r;
+
+            for &vertex in &[a, b, c] {
+                tangents[vertex] += tangent;
+                bitangents[vertex] += bitangent;
+            }
+        }
+
+        self.tangents = (0..count)
+            .map(|i| {
+                let normal = self.normals[i];
+                // Gram-Schmidt: se quita la parte paralela a la normal
+                let mut tangent = (tangents[i] - normal * normal.dot(tangents[i])).normalized();
+                if tangent.length_squared() < 0.5 {
+                    tangent = any_perpendicular(normal);
+                }
+                let handedness = if normal.cross(tangent).dot(bitangents[i]) < 0.0 { -1.0 } else { 1.0 };
+                [tangent.x, tangent.y, tangent.z, handedness]
+            })
+            .collect();
+    }
+
+    // Rejilla de segments x segments cuadrados centrada en center, en el plano de los
+    // ejes right y up (normal = cross(right, up)). La UV (0, 0) es la esquina de arriba a
+    // la izquierda
+    fn add_grid(&mut self, center: Vector3, right: Vector3, up: Vector3, width: f32, height: f32, segments: u32) {
+        let segments = segments.max(1);
+        let normal = right.cross(up);
+        let first = self.positions.len() as u32;
+        let row = segments + 1;
+
+        for j in 0..=segments {
+            for i in 0..=segments {
+                let s = i as f32 / segments as f32;
+                let t = j as f32 / segments as f32;
+                let position = center + right * ((s - 0.5) * width) + up * ((0.5 - t) * height);
+                self.add_vertex(position, normal, Vector2::new(s, t));
+            }
+        }
+
+        self.add_cells(first, row, segments, segments);
+    }
+
+    // Triángulos de una rejilla de vértices (columns + 1 por fila, fila 0 arriba)
+    fn add_cells(&mut self, first: u32, row: u32, columns: u32, rows: u32) {
+        for j in 0..rows {
+            for i in 0..columns {
+                let top_left = first + j * row + i;
+                let top_right = top_left + 1;
+                let bottom_left = top_left + row;
+                let bottom_right = bottom_left + 1;
+
+                self.add_triangle(bottom_left, bottom_right, top_right);
+                self.add_triangle(bottom_left, top_right, top_left);
+            }
+        }
+    }
+
+    // Plano horizontal (normal hacia +y) centrado en el origen, de width en x y depth en z
+    pub fn plane(width: f32, depth: f32, segments: u32) -> MeshData {
+        let mut mesh = MeshData::new();
+        mesh.add_grid(Vector3::zero(), Vector3::new(1.0, 0.0, 0.0), Vector3::new(0.0, 0.0, -1.0), width, depth, segments);
+        mesh.compute_tangents();
+        mesh
+    }
+
+    // Cubo centrado en el origen. Cada cara tiene sus vértices (normales planas) y la
+    // textura entera
+    pub fn cube(size: f32) -> MeshData {
+        let half = size / 2.0;
+        let faces = [
+            // (normal, derecha, arriba)
+            (Vector3::new(1.0, 0.0, 0.0), Vector3::new(0.0, 0.0, -1.0), Vector3::new(0.0, 1.0, 0.0)),
+            (Vector3::new(-1.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 1.0), Vector3::new(0.0, 1.0, 0.0)),
+            (Vector3::new(0.0, 1.0, 0.0), Vector3::new(1.0, 0.0, 0.0), Vector3::new(0.0, 0.0, -1.0)),
+            (Vector3::new(0.0, -1.0, 0.0), Vector3::new(1.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 1.0)),
+            (Vector3::new(0.0, 0.0, 1.0), Vector3::new(1.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0)),
+            (Vector3::new(0.0, 0.0, -1.0), Vector3::new(-1.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0)),
+        ];
+
+        let mut mesh = MeshData::new();
+        for &(normal, right, up) in &faces {
+            mesh.add_grid(normal * half, right, up, size, size, 1);
+        }
+        mesh.compute_tangents();
+        mesh
+    }
+
+    // Esfera UV centrada en el origen: segments divisiones alrededor del eje y y rings
+    // de polo a polo. La costura de la textura queda en +x
+    pub fn sphere(radius: f32, segments: u32, rings: u32) -> MeshData {
+        let segments = segments.max(3);
+        let rings = rings.max(2);
+        let mut mesh = MeshData::new();
+
+        for j in 0..=rings {
+            let t = j as f32 / rings as f32;
+            let phi = t * PI;
+            for i in 0..=segments {
+                let s = i as f32 / segments as f32;
+                let theta = s * TAU;
+                let normal = Vector3::new(phi.sin() * theta.cos(), phi.cos(), -phi.sin() * theta.sin());
+                mesh.add_vertex(normal * radius, normal, Vector2::new(s, t));
+            }
+        }
+
+        // Como add_cells pero sin los triángulos degenerados de los polos
+        let row = segments + 1;
+        for j in 0..rings {
+            for i in 0..segments {
+                let top_left = j * row + i;
+                let top_right = top_left + 1;
+                let bottom_left = top_left + row;
+                let bottom_right = bottom_left + 1;
+
+                if j != rings - 1 {
+                    mesh.add_triangle(bottom_left, bottom_right, top_right);
+                }
+                if j != 0 {
+                    mesh.add_triangle(bottom_left, top_right, top_left);
+                }
+            }
+        }
+
+        mesh.compute_tangents();
+        mesh
+    }
+
+    // Cilindro centrado en el origen a lo largo del eje y, con tapas
+    pub fn cylinder(radius: f32, height: f32, segments: u32) -> MeshData {
+        let segments = segments.max(3);
+        let half = height / 2.0;
+        let mut mesh = MeshData::new();
+
+        let direction = |i: u32| {
+            let theta = i as f32 / segments as f32 * TAU;
+            Vector3::new(theta.cos(), 0.0, -theta.sin())
+        };
+
+        // Lateral: una fila arriba y otra abajo, con la costura repetida
+        for (j, y) in [half, -half].iter().enumerate() {
+            for i in 0..=segments {
+                let normal = direction(i);
+                let position = normal * radius + Vector3::new(0.0, *y, 0.0);
+                mesh.add_vertex(position, normal, Vector2::new(i as f32 / segments as f32, j as f32));
+            }
+        }
+        mesh.add_cells(0, segments + 1, segments, 1);
+
+        // Tapas en abanico, con la textura proyectada desde arriba
+        for &(y, normal) in &[(half, Vector3::new(0.0, 1.0, 0.0)), (-half, Vector3::new(0.0, -1.0, 0.0))] {
+            let center = mesh.add_vertex(Vector3::new(0.0, y, 0.0), normal, Vector2::new(0.5, 0.5));
+            for i in 0..=segments {
+                let d = direction(i);
+                mesh.add_vertex(d * radius + Vector3::new(0.0, y, 0.0), normal, Vector2::new(0.5 + d.x / 2.0, 0.5 + d.z / 2.0));
+            }
+            for i in 0..segments {
+                let (current, next) = (center + 1 + i, center + 2 + i);
+                if y > 0.0 {
+                    mesh.add_triangle(center, current, next);
+                } else {
+                    mesh.add_triangle(center, next, current);
+                }
+            }
+        }
+
+        mesh.compute_tangents();
+        mesh
+    }
+}
+
+// Un vector cualquiera de longitud 1 perpendicular a normal
+fn any_perpendicular(normal: Vector3) -> Vector3 {
+    let axis = if normal.x.abs() < 0.9 { Vector3::new(1.0, 0.0, 0.0) } else { Vector3::new(0.0, 1.0, 0.0) };
+    axis.cross(normal).normalized()
+}
+
+// Malla subida a la GPU, con vértices intercalados e índices. Sólo configura los
+// atributos que usa el shader (a_position, a_normal, a_tex_coord y a_tangent), así el
+// mismo MeshData vale para shaders con y sin luces
+pub struct Mesh<'a> {
+    pub name: String,
+
+    color: Color,
+    texture: Option<&'a Texture>,
+    // Si tiene material se dibuja con su shader, que debe usar los mismos atributos
+    material: Option<&'a Material<'a>>,
+
+    bounds: Option<Aabb3>,
+    triangle_count: usize,
+
+    buffer: GLBuffer,
+
+    shader: &'a Shader,
+}
+
+impl<'a> Mesh<'a> {
+    pub fn new(name: &str, shader: &'a Shader) -> Mesh<'a> {
+        Mesh {
+            name: String::from(name),
+
+            color: Color::white(),
+            texture: None,
+            material: None,
+
+            bounds: None,
+            triangle_count: 0,
+
+            buffer: GLBuffer::new(),
+
+            shader,
+        }
+    }
+
+    // Atributos que tiene el shader, en el orden en que van en cada vértice
+    fn layout(&self, data: &MeshData) -> Vec<(&'static str, i32)> {
+        let has_normals = data.normals.len() == data.positions.len();
+        let has_uvs = data.uvs.len() == data.positions.len();
+        let has_tangents = data.tangents.len() == data.positions.len();
+
+        [("a_position", 3, true), ("a_normal", 3, has_normals), ("a_tex_coord", 2, has_uvs), ("a_tangent", 4, has_tangents)]
+            .iter()
+            .filter(|(name, _, available)| *available && self.shader.attribute(name).is_some())
+            .map(|&(name, size, _)| (name, size))
+            .collect()
+    }
+
+    pub fn upload(&mut self, data: &MeshData) -> Result<(), String> {
+        data.validate()?;
+
+        let layout = self.layout(data);
+        self.buffer.configure(
+            layout
+                .iter()
+                .map(|&(name, component_size)| AttributeInfo {
+                    location: self.shader.get_attribute_location(name),
+                    component_size,
+                })
+                .collect(),
+            false,
+        );
+
+        let stride: i32 = layout.iter().map(|&(_, size)| size).sum();
+        let mut vertices = Vec::with_capacity(data.vertex_count() * stride as usize);
+        for i in 0..data.vertex_count() {
+            for &(name, _) in &layout {
+                match name {
+                    "a_position" => vertices.extend_from_slice(&[data.positions[i].x, data.positions[i].y, data.positions[i].z]),
+                    "a_normal" => vertices.extend_from_slice(&[data.normals[i].x, data.normals[i].y, data.normals[i].z]),
+                    "a_tex_coord" => vertices.extend_from_slice(&[data.uvs[i].x, data.uvs[i].y]),
+                    _ => vertices.extend_from_slice(&data.tangents[i]),
+                }
+            }
+        }
+
+        self.buffer.upload(&vertices);
+        self.buffer.upload_indices(&data.indices);
+        self.bounds = data.bounds();
+        self.triangle_count = data.triangle_count();
+        Ok(())
+    }
+
+    // Caja que contiene la malla en espacio local (None si está vacía)
+    pub fn bounds(&self) -> Option<Aabb3> {
+        self.bounds
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.triangle_count
+    }
+
+    pub fn set_color(&mut self, color: Color) {
+        self.color = color;
+    }
+
+    pub fn color(&self) -> Color {
+        self.color
+    }
+
+    pub fn set_texture(&mut self, texture: Option<&'a Texture>) {
+        self.texture = texture;
+    }
+
+    pub fn set_material(&mut self, material: Option<&'a Material<'a>>) {
+        self.material = material;
+    }
+
+    pub fn material(&self) -> Option<&'a Material<'a>> {
+        self.material
+    }
+
+    pub fn shader(&self) -> &'a Shader {
+        self.material.map_or(self.shader, |material| material.shader())
+    }
+
+    // Con material lo activa; si no, el shader de la malla tiene que estar activo. Lo
+    // que no ponga el material (color, textura) sale de la malla
+    pub fn draw(&self, model: &Matrix4x4) {
+        let shader = self.shader();
+        let location = |name: &str| shader.uniform(name).map_or(-1, |uniform| uniform.location);
+        let mut unit = 0;
+
+        if let Some(material) = self.material {
+            material.bind();
+            unit = material.texture_count();
+        }
+        let material_has = |name: &str| self.material.is_some_and(|material| material.get(name).is_some() || material.texture(name).is_some());
+
+        unsafe {
+            gl::UniformMatrix4fv(location("u_model"), 1, gl::FALSE, model.data.as_ptr());
+
+            // Sólo los shaders con iluminación lo usan
+            let normal_location = location("u_normal_matrix");
+            if normal_location != -1 {
+                gl::UniformMatrix3fv(normal_location, 1, gl::FALSE, model.normal_matrix().as_ptr());
+            }
+
+            if !material_has("u_color") {
+                gl::Uniform4f(location("u_color"), self.color.r, self.color.g, self.color.b, self.color.a);
+            }
+
+            if material_has("u_diffuse") {
+                gl::Uniform1i(location("u_textured"), 1);
+            } else {
+                match self.texture {
+                    Some(texture) => {
+                        texture.bind(unit);
+                        gl::Uniform1i(location("u_diffuse"), unit as i32);
+                        gl::Uniform1i(location("u_textured"), 1);
+                    }
+                    None => gl::Uniform1i(location("u_textured"), 0),
+                }
+            }
+        }
+
+        self.buffer.draw();
+    }
+}
+
+impl<'a> Drawable for Mesh<'a> {
+    fn draw(&self, model: &Matrix4x4) {
+        Mesh::draw(self, model);
+    }
+
+    fn blend(&self) -> Option<BlendMode> {
+        self.material.map(|material| material.blend)
+    }
+}
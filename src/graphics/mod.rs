@@ -1,3 +1,32 @@
 pub mod color;
 pub mod sprite;
-pub mod vertex;
\ No newline at end of file
+pub mod vertex;
+pub mod line_strip;
+pub mod gradient;
+pub mod sprite_sheet;
+pub mod animation;
+pub mod animated_sprite;
+pub mod atlas;
+pub mod aseprite;
+pub mod texture_packer;
+pub mod atlas_builder;
+pub mod tilemap;
+pub mod tilemap_renderer;
+pub mod tiled;
+pub mod font;
+pub mod text;
+pub mod truetype;
+pub mod glyph_atlas;
+pub mod post_process;
+pub mod debug_draw;
+pub mod shape;
+pub mod nine_slice;
+pub mod render_queue;
+pub mod material;
+pub mod instanced_sprite;
+pub mod particles;
+pub mod gpu_particles;
+pub mod mesh;
+pub mod obj;
+pub mod gltf;
+pub mod lighting;
\ No newline at end of file
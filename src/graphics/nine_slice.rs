@@ -0,0 +1,395 @@
+use crate::gl_utility::gl_buffer::{GLBuffer, AttributeInfo};
+use crate::gl_utility::shader::Shader;
+use crate::gl_utility::texture::Texture;
+use crate::graphics::color::Color;
+use crate::graphics::vertex::Vertex;
+use crate::graphics::render_queue::Drawable;
+use crate::math::matrix4x4::Matrix4x4;
+use crate::math::rect::Rect;
+use crate::math::vector3::Vector3;
+
+// Bordes que no se deforman, en píxeles de la textura
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Insets {
+    pub left: f32,
+    pub right: f32,
+    pub top: f32,
+    pub bottom: f32,
+}
+
+impl Insets {
+    pub fn new(left: f32, right: f32, top: f32, bottom: f32) -> Insets {
+        Insets { left, right, top, bottom }
+    }
+
+    pub fn uniform(inset: f32) -> Insets {
+        Insets::new(inset, inset, inset, inset)
+    }
+}
+
+// Cómo se rellenan el centro y los lados al crecer
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum SliceFill {
+    Stretch,
+    // Repite el trozo de textura a su tamaño original (el último se recorta)
+    Tile,
+}
+
+// Tramo de una fila o columna: posición y tamaño en pantalla y en UV
+#[derive(Copy, Clone)]
+struct Span {
+    start: f32,
+    size: f32,
+    uv_start: f32,
+    uv_size: f32,
+    // Tamaño en píxeles del trozo de textura (para repetirlo)
+    source_size: f32,
+    stretched: bool,
+}
+
+impl Span {
+    // Divide el tramo en repeticiones del trozo de textura si hace falta
+    fn pieces(&self, fill: SliceFill) -> Vec<Span> {
+        if fill == SliceFill::Stretch || !self.stretched || self.source_size <= 0.0 {
+            return vec![*self];
+        }
+
+        let mut pieces = Vec::new();
+        let mut offset = 0.0;
+        while offset < self.size - 1e-3 {
+            let size = self.source_size.min(self.size - offset);
+            pieces.push(Span {
+                start: self.start + offset,
+                size,
+                uv_start: self.uv_start,
+                uv_size: self.uv_size * size / self.source_size,
+                ..*self
+            });
+            offset += size;
+        }
+        pieces
+    }
+}
+
+// Sprite para paneles de interfaz: las esquinas se quedan a su tamaño, los lados
+// crecen en una dirección y el centro en las dos. Con insets a 0 arriba y abajo (o a
+// los lados) es un three-slice. Usa el mismo shader y formato de vértice que Sprite
+pub struct NineSlice<'a> {
+    pub name: String,
+
+    pub origin: Vector3,
+
+    width: f32,
+    height: f32,
+    insets: Insets,
+    fill: SliceFill,
+
+    color: Color,
+    u_color_location: i32,
+    u_model_location: i32,
+    u_diffuse_location: i32,
+    u_textured_location: i32,
+
+    texture: &'a Texture,
+    // Zona de la textura con la imagen del panel, en UV (para texturas atlas)
+    region: Rect,
+
+    buffer: GLBuffer,
+    loaded: bool,
+
+    shader: &'a Shader,
+}
+
+impl<'a> NineSlice<'a> {
+    pub fn new(name: &str, shader: &'a Shader, texture: &'a Texture, insets: Insets, width: f32, height: f32) -> NineSlice<'a> {
+        NineSlice {
+            name: String::from(name),
+
+            origin: Vector3::zero(),
+
+            width,
+            height,
+            insets,
+            fill: SliceFill::Stretch,
+
+            color: Color::white(),
+            u_color_location: shader.get_uniform_location("u_color"),
+            u_model_location: shader.get_uniform_location("u_model"),
+            u_diffuse_location: shader.get_uniform_location("u_diffuse"),
+            u_textured_location: shader.get_uniform_location("u_textured"),
+
+            texture,
+            region: Rect::new(0.0, 0.0, 1.0, 1.0),
+
+            buffer: GLBuffer::new(),
+            loaded: false,
+
+            shader,
+        }
+    }
+
+    // Barra que sólo crece a lo ancho (botones, barras de vida)
+    pub fn horizontal(name: &str, shader: &'a Shader, texture: &'a Texture, left: f32, right: f32, width: f32, height: f32) -> NineSlice<'a> {
+        NineSlice::new(name, shader, texture, Insets::new(left, right, 0.0, 0.0), width, height)
+    }
+
+    // Barra que sólo crece a lo alto
+    pub fn vertical(name: &str, shader: &'a Shader, texture: &'a Texture, top: f32, bottom: f32, width: f32, height: f32) -> NineSlice<'a> {
+        NineSlice::new(name, shader, texture, Insets::new(0.0, 0.0, top, bottom), width, height)
+    }
+
+    pub fn with_fill(mut self, fill: SliceFill) -> NineSlice<'a> {
+        self.fill = fill;
+        self
+    }
+
+    pub fn load(&mut self) {
+        let a_position_location = self.shader.get_attribute_location("a_position");
+        let a_tex_coord_location = self.shader.get_attribute_location("a_tex_coord");
+        self.buffer.configure(
+            vec![
+                AttributeInfo {
+                    location: a_position_location,
+                    component_size: 3,
+                },
+                AttributeInfo {
+                    location: a_tex_coord_location,
+                    component_size: 2,
+                },
+            ],
+            false,
+        );
+
+        self.loaded = true;
+        self.calculate_vertices();
+    }
+
+    pub fn set_size(&mut self, width: f32, height: f32) {
+        self.width = width;
+        self.height = height;
+        self.calculate_vertices();
+    }
+
+    pub fn width(&self) -> f32 {
+        self.width
+    }
+
+    pub fn height(&self) -> f32 {
+        self.height
+    }
+
+    pub fn set_insets(&mut self, insets: Insets) {
+        self.insets = insets;
+        self.calculate_vertices();
+    }
+
+    pub fn insets(&self) -> Insets {
+        self.insets
+    }
+
+    pub fn set_fill(&mut self, fill: SliceFill) {
+        self.fill = fill;
+        self.calculate_vertices();
+    }
+
+    pub fn fill(&self) -> SliceFill {
+        self.fill
+    }
+
+    pub fn set_region(&mut self, region: Rect) {
+        self.region = region;
+        self.calculate_vertices();
+    }
+
+    pub fn region(&self) -> Rect {
+        self.region
+    }
+
+    pub fn set_color(&mut self, color: Color) {
+        self.color = color;
+    }
+
+    pub fn color(&self) -> Color {
+        self.color
+    }
+
+    // Tramos de una dirección: borde inicial, centro y borde final. Si no caben los
+    // bordes se encogen en proporción. Los insets se recortan al tamaño de la imagen
+    fn spans(start: f32, size: f32, first: f32, last: f32, uv_start: f32, uv_size: f32, source_size: f32) -> [Span; 3] {
+        let border = |start: f32| Span {
+            start,
+            size: 0.0,
+            uv_start,
+            uv_size: 0.0,
+            source_size: 0.0,
+            stretched: false,
+        };
+        // Una región vacía no tiene bordes que conservar: todo es centro
+        if source_size <= 0.0 {
+            let center = Span {
+                start,
+                size,
+                uv_start,
+                uv_size,
+                source_size: 0.0,
+                stretched: true,
+            };
+            return [border(start), center, border(start + size)];
+        }
+
+        let first = first.max(0.0).min(source_size);
+        let last = last.max(0.0).min(source_size - first);
+        let scale = if first + last > size && first + last > 0.0 { size / (first + last) } else { 1.0 };
+        let (first_size, last_size) = (first * scale, last * scale);
+        let (first_uv, last_uv) = (uv_size * first / source_size, uv_size * last / source_size);
+        let center_source = source_size - first - last;
+
+        [
+            Span {
+                start,
+                size: first_size,
+                uv_start,
+                uv_size: first_uv,
+                source_size: first,
+                stretched: false,
+            },
+            Span {
+                start: start + first_size,
+                size: size - first_size - last_size,
+                uv_start: uv_start + first_uv,
+                uv_size: (uv_size - first_uv - last_uv).max(0.0),
+                source_size: center_source,
+                stretched: true,
+            },
+            Span {
+                start: start + size - last_size,
+                size: last_size,
+                uv_start: uv_start + uv_size - last_uv,
+                uv_size: last_uv,
+                source_size: last,
+                stretched: false,
+            },
+        ]
+    }
+
+    // Vértices de todos los quads (dos triángulos cada uno), sin los de tamaño 0
+    fn vertices(&self) -> Vec<Vertex> {
+        let source_width = self.texture.width as f32 * self.region.width;
+        let source_height = self.texture.height as f32 * self.region.height;
+
+        let columns = NineSlice::spans(
+            -(self.width * self.origin.x), self.width,
+            self.insets.left, self.insets.right,
+            self.region.x, self.region.width, source_width,
+        );
+        let rows = NineSlice::spans(
+            -(self.height * self.origin.y), self.height,
+            self.insets.top, self.insets.bottom,
+            self.region.y, self.region.height, source_height,
+        );
+
+        let mut vertices = Vec::new();
+        for row in rows.iter().flat_map(|row| row.pieces(self.fill)) {
+            for column in columns.iter().flat_map(|column| column.pieces(self.fill)) {
+                if row.size <= 0.0 || column.size <= 0.0 {
+                    continue;
+                }
+
+                let (min_x, max_x) = (column.start, column.start + column.size);
+                let (min_y, max_y) = (row.start, row.start + row.size);
+                let (min_u, max_u) = (column.uv_start, column.uv_start + column.uv_size);
+                let (min_v, max_v) = (row.uv_start, row.uv_start + row.uv_size);
+
+                vertices.extend_from_slice(&[
+                    Vertex::new(min_x, min_y, 0.0, min_u, min_v),
+                    Vertex::new(min_x, max_y, 0.0, min_u, max_v),
+                    Vertex::new(max_x, max_y, 0.0, max_u, max_v),
+                    Vertex::new(max_x, max_y, 0.0, max_u, max_v),
+                    Vertex::new(max_x, min_y, 0.0, max_u, min_v),
+                    Vertex::new(min_x, min_y, 0.0, min_u, min_v),
+                ]);
+            }
+        }
+
+        vertices
+    }
+
+    pub fn calculate_vertices(&mut self) {
+        if !self.loaded {
+            return;
+        }
+
+        self.buffer.upload(
+            &self.vertices()
+                .iter()
+                .flat_map(|v| vec![
+                    v.position.x, v.position.y, v.position.z,
+                    v.tex_coords.x, v.tex_coords.y,
+                ])
+                .collect::<Vec<f32>>()
+        );
+    }
+
+    pub fn draw(&self, model: &Matrix4x4) {
+        unsafe {
+            gl::UniformMatrix4fv(self.u_model_location, 1, gl::FALSE, model.data.as_ptr());
+            gl::Uniform4f(self.u_color_location, self.color.r, self.color.g, self.color.b, self.color.a);
+
+            self.texture.bind(0);
+            gl::Uniform1i(self.u_diffuse_location, 0);
+            gl::Uniform1i(self.u_textured_location, 1);
+        }
+
+        self.buffer.draw();
+    }
+}
+
+impl<'a> Drawable for NineSlice<'a> {
+    fn draw(&self, model: &Matrix4x4) {
+        NineSlice::draw(self, model);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_finite(spans: &[Span; 3]) {
+        for span in spans.iter() {
+            for value in [span.start, span.size, span.uv_start, span.uv_size, span.source_size].iter() {
+                assert!(value.is_finite(), "valor no finito en un tramo");
+            }
+            assert!(span.size >= 0.0 && span.uv_size >= 0.0 && span.source_size >= 0.0);
+        }
+    }
+
+    #[test]
+    fn spans_keep_borders_and_stretch_the_center() {
+        let spans = NineSlice::spans(0.0, 100.0, 10.0, 20.0, 0.0, 1.0, 50.0);
+        assert_finite(&spans);
+        assert_eq!((spans[0].size, spans[1].size, spans[2].size), (10.0, 70.0, 20.0));
+        assert_eq!((spans[0].uv_size, spans[2].uv_size), (0.2, 0.4));
+        assert_eq!(spans[1].source_size, 20.0);
+    }
+
+    #[test]
+    fn spans_with_an_empty_source_are_all_center() {
+        let spans = NineSlice::spans(5.0, 100.0, 10.0, 20.0, 0.25, 0.0, 0.0);
+        assert_finite(&spans);
+        assert_eq!((spans[0].size, spans[1].size, spans[2].size), (0.0, 100.0, 0.0));
+        assert_eq!(spans[1].start, 5.0);
+    }
+
+    #[test]
+    fn spans_clamp_insets_larger_than_the_source() {
+        let spans = NineSlice::spans(0.0, 100.0, 40.0, 40.0, 0.0, 1.0, 50.0);
+        assert_finite(&spans);
+        assert_eq!(spans[1].source_size, 0.0);
+        assert_eq!((spans[0].source_size, spans[2].source_size), (40.0, 10.0));
+        assert!((spans[0].uv_size + spans[1].uv_size + spans[2].uv_size - 1.0).abs() < 0.0001);
+
+        let spans = NineSlice::spans(0.0, 100.0, 80.0, -5.0, 0.0, 1.0, 50.0);
+        assert_finite(&spans);
+        assert_eq!((spans[0].source_size, spans[2].source_size), (50.0, 0.0));
+    }
+}
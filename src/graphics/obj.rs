@@ -0,0 +1,328 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::graphics::color::Color;
+use crate::graphics::mesh::MeshData;
+use crate::math::vector2::Vector2;
+use crate::math::vector3::Vector3;
+
+// Importador de Wavefront OBJ y sus materiales MTL. Cada objeto o grupo (o y g) y cada
+// cambio de material (usemtl) da una malla distinta. Los polígonos se dividen en
+// abanico, así que tienen que ser convexos. Las rutas de texturas y de los .mtl se
+// resuelven respecto a base_dir
+
+// Material de un .mtl, sólo con los datos que usa el motor
+#[derive(Clone, Debug)]
+pub struct ObjMaterial {
+    pub name: String,
+    pub ambient: Color,
+    // El alfa es la opacidad (d o 1 - Tr)
+    pub diffuse: Color,
+    pub specular: Color,
+    // Exponente especular (Ns)
+    pub shininess: f32,
+    pub diffuse_texture: Option<String>,
+    pub normal_texture: Option<String>,
+    pub specular_texture: Option<String>,
+}
+
+impl ObjMaterial {
+    fn new(name: &str) -> ObjMaterial {
+        ObjMaterial {
+            name: String::from(name),
+            ambient: Color::black(),
+            diffuse: Color::white(),
+            specular: Color::black(),
+            shininess: 0.0,
+            diffuse_texture: None,
+            normal_texture: None,
+            specular_texture: None,
+        }
+    }
+}
+
+pub struct ObjMesh {
+    pub name: String,
+    pub material: Option<String>,
+    pub data: MeshData,
+}
+
+pub struct ObjModel {
+    pub meshes: Vec<ObjMesh>,
+    pub materials: Vec<ObjMaterial>,
+}
+
+impl ObjModel {
+    pub fn material(&self, name: &str) -> Option<&ObjMaterial> {
+        self.materials.iter().find(|material| material.name == name)
+    }
+}
+
+pub fn load(path: &str) -> Result<ObjModel, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("No se puede leer {}: {}", path, e))?;
+    let base_dir = Path::new(path).parent().unwrap_or_else(|| Path::new(""));
+    parse(&text, base_dir)
+}
+
+// Malla en construcción: los vértices se comparten si repiten posición, UV y normal
+struct MeshBuilder {
+    name: String,
+    material: Option<String>,
+    data: MeshData,
+    vertices: HashMap<(usize, Option<usize>, Option<usize>), u32>,
+    // Vértices sin vn, a los que se les calcula la normal al terminar
+    without_normal: Vec<u32>,
+    missing_uvs: bool,
+}
+
+impl MeshBuilder {
+    fn new(name: &str, material: Option<String>) -> MeshBuilder {
+        MeshBuilder {
+            name: String::from(name),
+            material,
+            data: MeshData::new(),
+            vertices: HashMap::new(),
+            without_normal: Vec::new(),
+            missing_uvs: false,
+        }
+    }
+
+    fn finish(mut self) -> ObjMesh {
+        // Las normales que trae el archivo se respetan
+        if !self.without_normal.is_empty() {
+            let mut normals = self.data.normals.clone();
+            self.data.compute_normals();
+            for &vertex in &self.without_normal {
+                normals[vertex as usize] = self.data.normals[vertex as usize];
+            }
+            self.data.normals = normals;
+        }
+        if self.missing_uvs {
+            self.data.uvs.clear();
+        }
+        self.data.compute_tangents();
+
+        ObjMesh {
+            name: self.name,
+            material: self.material,
+            data: self.data,
+        }
+    }
+}
+
+pub fn parse(text: &str, base_dir: &Path) -> Result<ObjModel, String> {
+    let mut positions: Vec<Vector3> = Vec::new();
+    let mut uvs: Vec<Vector2> = Vec::new();
+    let mut normals: Vec<Vector3> = Vec::new();
+
+    let mut meshes = Vec::new();
+    let mut materials = Vec::new();
+    let mut current = MeshBuilder::new("", None);
+
+    for (number, line) in text.lines().enumerate() {
+        let error = |message: String| format!("OBJ, línea {}: {}", number + 1, message);
+        let mut words = line.split_whitespace();
+        let keyword = match words.next() {
+            Some(keyword) if !keyword.starts_with('#') => keyword,
+            _ => continue,
+        };
+        let rest: Vec<&str> = words.collect();
+
+        match keyword {
+            "v" => positions.push(parse_vector3(&rest).map_err(error)?),
+            "vn" => normals.push(parse_vector3(&rest).map_err(error)?),
+            // OBJ tiene v = 0 abajo
+            "vt" => {
+                let values = parse_floats(&rest, 2).map_err(error)?;
+                uvs.push(Vector2::new(values[0], 1.0 - values[1]));
+            }
+            "o" | "g" | "usemtl" => {
+                let (name, material) = if keyword == "usemtl" {
+                    (current.name.clone(), rest.first().map(|name| String::from(*name)))
+                } else {
+                    (rest.join(" "), current.material.clone())
+                };
+                let previous = std::mem::replace(&mut current, MeshBuilder::new(&name, material));
+                if !previous.data.is_empty() {
+                    meshes.push(previous.finish());
+                }
+            }
+            "mtllib" => {
+                for file in &rest {
+                    let path = base_dir.join(file);
+                    let mtl = std::fs::read_to_string(&path)
+                        .map_err(|e| error(format!("No se puede leer {}: {}", path.display(), e)))?;
+                    let mtl_dir = path.parent().unwrap_or(base_dir);
+                    materials.extend(parse_mtl(&mtl, mtl_dir)?);
+                }
+            }
+            "f" => {
+                if rest.len() < 3 {
+                    return Err(error(String::from("una cara necesita al menos 3 vértices")));
+                }
+
+                let mut face = Vec::with_capacity(rest.len());
+                for corner in &rest {
+                    let key = parse_corner(corner, positions.len(), uvs.len(), normals.len()).map_err(error)?;
+                    let index = match current.vertices.get(&key) {
+                        Some(&index) => index,
+                        None => {
+                            let (position, uv, normal) = key;
+                            current.missing_uvs |= uv.is_none();
+                            let index = current.data.add_vertex(
+                                positions[position],
+                                normal.map_or_else(Vector3::zero, |normal| normals[normal]),
+                                uv.map_or_else(Vector2::zero, |uv| uvs[uv]),
+                            );
+                            if normal.is_none() {
+                                current.without_normal.push(index);
+                            }
+                            current.vertices.insert(key, index);
+                            index
+                        }
+                    };
+                    face.push(index);
+                }
+
+                for i in 1..face.len() - 1 {
+                    current.data.add_triangle(face[0], face[i], face[i + 1]);
+                }
+            }
+            // Grupos de suavizado, líneas, curvas...
+            _ => {}
+        }
+    }
+
+    if !current.data.is_empty() {
+        meshes.push(current.finish());
+    }
+
+    Ok(ObjModel { meshes, materials })
+}
+
+pub fn parse_mtl(text: &str, base_dir: &Path) -> Result<Vec<ObjMaterial>, String> {
+    let mut materials: Vec<ObjMaterial> = Vec::new();
+
+    for (number, line) in text.lines().enumerate() {
+        let error = |message: String| format!("MTL, línea {}: {}", number + 1, message);
+        let mut words = line.split_whitespace();
+        let keyword = match words.next() {
+            Some(keyword) if !keyword.starts_with('#') => keyword,
+            _ => continue,
+        };
+        let rest: Vec<&str> = words.collect();
+
+        if keyword == "newmtl" {
+            materials.push(ObjMaterial::new(&rest.join(" ")));
+            continue;
+        }
+
+        let material = materials
+            .last_mut()
+            .ok_or_else(|| error(format!("{} antes de newmtl", keyword)))?;
+        // Las opciones de los mapas (-bm 1, -o ...) van antes del nombre del archivo
+        let texture = || rest.last().map(|file| base_dir.join(file).to_string_lossy().into_owned());
+
+        match keyword {
+            "Ka" => material.ambient = parse_color(&rest).map_err(error)?,
+            "Kd" => material.diffuse = parse_color(&rest).map_err(error)?.with_alpha(material.diffuse.a),
+            "Ks" => material.specular = parse_color(&rest).map_err(error)?,
+            "Ns" => material.shininess = parse_floats(&rest, 1).map_err(error)?[0],
+            "d" => material.diffuse.a = parse_floats(&rest, 1).map_err(error)?[0],
+            "Tr" => material.diffuse.a = 1.0 - parse_floats(&rest, 1).map_err(error)?[0],
+            "map_Kd" => material.diffuse_texture = texture(),
+            "map_Bump" | "map_bump" | "bump" | "norm" => material.normal_texture = texture(),
+            "map_Ks" => material.specular_texture = texture(),
+            _ => {}
+        }
+    }
+
+    Ok(materials)
+}
+
+fn parse_floats(values: &[&str], count: usize) -> Result<Vec<f32>, String> {
+    if values.len() < count {
+        return Err(format!("se esperaban {} números y hay {}", count, values.len()));
+    }
+    values[..count]
+        .iter()
+        .map(|value| value.parse::<f32>().map_err(|_| format!("número no válido: {}", value)))
+        .collect()
+}
+
+fn parse_vector3(values: &[&str]) -> Result<Vector3, String> {
+    let values = parse_floats(values, 3)?;
+    Ok(Vector3::new(values[0], values[1], values[2]))
+}
+
+fn parse_color(values: &[&str]) -> Result<Color, String> {
+    let values = parse_floats(values, 3)?;
+    Ok(Color::from_f32(values[0], values[1], values[2], 1.0))
+}
+
+// Un vértice de cara: v, v/vt, v//vn o v/vt/vn. Los índices empiezan en 1 y los
+// negativos cuentan desde el final
+fn parse_corner(corner: &str, positions: usize, uvs: usize, normals: usize) -> Result<(usize, Option<usize>, Option<usize>), String> {
+    let mut parts = corner.split('/');
+    let mut index = |count: usize, required: bool| -> Result<Option<usize>, String> {
+        match parts.next() {
+            Some(part) if !part.is_empty() => {
+                let value: i64 = part.parse().map_err(|_| format!("índice no válido: {}", corner))?;
+                let resolved = if value < 0 { count as i64 + value } else { value - 1 };
+                if resolved < 0 || resolved >= count as i64 {
+                    return Err(format!("el índice {} no existe", value));
+                }
+                Ok(Some(resolved as usize))
+            }
+            _ if required => Err(format!("vértice sin posición: {}", corner)),
+            _ => Ok(None),
+        }
+    };
+
+    let position = index(positions, true)?.unwrap_or(0);
+    let uv = index(uvs, false)?;
+    let normal = index(normals, false)?;
+    Ok((position, uv, normal))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_normal(actual: Vector3, expected: Vector3) {
+        assert!(
+            (actual - expected).length() < 0.0001,
+            "normal ({}, {}, {}) en lugar de ({}, {}, {})",
+            actual.x, actual.y, actual.z, expected.x, expected.y, expected.z
+        );
+    }
+
+    #[test]
+    fn computes_normals_when_the_file_has_none() {
+        let model = parse("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n", Path::new("")).unwrap();
+        let data = &model.meshes[0].data;
+        for &normal in &data.normals {
+            assert_normal(normal, Vector3::new(0.0, 0.0, 1.0));
+        }
+    }
+
+    #[test]
+    fn keeps_file_normals_when_some_corners_lack_them() {
+        // Un triángulo con normales inclinadas y otro, en el mismo plano, sin ellas
+        let text = "v 0 0 0\nv 1 0 0\nv 0 1 0\nv 1 1 0\n\
+                    vn 1 0 0\nvn 0 1 0\n\
+                    f 1//1 2//2 3//1\n\
+                    f 2 4 3\n";
+        let model = parse(text, Path::new("")).unwrap();
+        let data = &model.meshes[0].data;
+        assert_eq!(data.positions.len(), 6);
+
+        assert_normal(data.normals[0], Vector3::new(1.0, 0.0, 0.0));
+        assert_normal(data.normals[1], Vector3::new(0.0, 1.0, 0.0));
+        assert_normal(data.normals[2], Vector3::new(1.0, 0.0, 0.0));
+        for &normal in &data.normals[3..] {
+            assert_normal(normal, Vector3::new(0.0, 0.0, 1.0));
+        }
+    }
+}
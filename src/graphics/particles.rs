@@ -0,0 +1,467 @@
+use std::f32::consts::TAU;
+
+use crate::graphics::color::Color;
+use crate::graphics::gradient::Gradient;
+use crate::graphics::instanced_sprite::{InstancedSprite, SpriteInstance};
+use crate::math::circle::Circle;
+use crate::math::random::Random;
+use crate::math::rect::Rect;
+use crate::math::vector2::Vector2;
+use crate::math::vector3::Vector3;
+
+// Valor que cambia a lo largo de la vida de la partícula, con claves en posiciones de
+// [0, 1] e interpolación lineal entre ellas (como Gradient pero con números)
+#[derive(Clone)]
+pub struct ValueCurve {
+    // Ordenadas por posición
+    keys: Vec<(f32, f32)>,
+}
+
+impl Default for ValueCurve {
+    fn default() -> ValueCurve {
+        ValueCurve::new()
+    }
+}
+
+impl ValueCurve {
+    pub fn new() -> ValueCurve {
+        ValueCurve {
+            keys: Vec::new(),
+        }
+    }
+
+    pub fn constant(value: f32) -> ValueCurve {
+        let mut curve = ValueCurve::new();
+        curve.add_key(0.0, value);
+        curve
+    }
+
+    pub fn linear(from: f32, to: f32) -> ValueCurve {
+        let mut curve = ValueCurve::new();
+        curve.add_key(0.0, from);
+        curve.add_key(1.0, to);
+        curve
+    }
+
+    pub fn add_key(&mut self, position: f32, value: f32) {
+        let index = self.keys
+            .iter()
+            .position(|(p, _)| *p > position)
+            .unwrap_or(self.keys.len());
+        self.keys.insert(index, (position, value));
+    }
+
+    pub fn keys(&self) -> &[(f32, f32)] {
+        &self.keys
+    }
+
+    // Fuera de las claves se usa la más cercana. Sin claves devuelve 1
+    pub fn evaluate(&self, t: f32) -> f32 {
+        let (first, last) = match (self.keys.first(), self.keys.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return 1.0,
+        };
+
+        if t <= first.0 {
+            return first.1;
+        }
+        if t >= last.0 {
+            return last.1;
+        }
+
+        for pair in self.keys.windows(2) {
+            let (start, start_value) = pair[0];
+            let (end, end_value) = pair[1];
+
+            if t <= end {
+                let span = end - start;
+                if span <= 0.0 {
+                    return end_value;
+                }
+                return start_value + (end_value - start_value) * (t - start) / span;
+            }
+        }
+
+        last.1
+    }
+}
+
+// Zona donde nacen las partículas, relativa a la posición del emisor
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum EmitterShape {
+    Point,
+    Circle { radius: f32 },
+    // Centrado en el emisor
+    Rect { width: f32, height: f32 },
+    // Desde el emisor, hacia angle (radianes) con una apertura total de spread.
+    // Ignora la dirección de la configuración
+    Cone { angle: f32, spread: f32 },
+}
+
+// Cuándo nacen las partículas
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Emission {
+    // rate partículas por segundo
+    Continuous { rate: f32 },
+    // count partículas de golpe cada interval segundos (con interval 0, una sola vez)
+    Burst { count: u32, interval: f32 },
+}
+
+// Cómo es un sistema de partículas. Los rangos (mínimo, máximo) se sortean al nacer
+// cada partícula. La usan tanto ParticleEmitter como la versión en GPU
+#[derive(Clone)]
+pub struct ParticleConfig {
+    // Tamaño del pool: si está lleno no nacen más
+    pub max_particles: usize,
+    pub shape: EmitterShape,
+    pub emission: Emission,
+
+    // Dirección de salida en radianes (0 hacia la derecha, PI / 2 hacia abajo con y
+    // hacia abajo) y apertura total alrededor de ella (TAU = en todas direcciones)
+    pub direction: f32,
+    pub spread: f32,
+
+    pub lifetime: (f32, f32),
+    pub speed: (f32, f32),
+    pub size: (f32, f32),
+    pub rotation: (f32, f32),
+    pub angular_velocity: (f32, f32),
+
+    // Multiplican al tamaño y dan el color según la vida transcurrida (de 0 a 1)
+    pub size_over_life: ValueCurve,
+    pub color_over_life: Gradient,
+
+    // Aceleración constante, en unidades por segundo al cuadrado
+    pub gravity: Vector2,
+    // Fracción de la velocidad que se pierde por segundo
+    pub drag: f32,
+}
+
+impl ParticleConfig {
+    pub fn new(max_particles: usize) -> ParticleConfig {
+        ParticleConfig {
+            max_particles,
+            shape: EmitterShape::Point,
+            emission: Emission::Continuous { rate: 10.0 },
+
+            direction: 0.0,
+            spread: TAU,
+
+            lifetime: (1.0, 1.0),
+            speed: (50.0, 50.0),
+            size: (8.0, 8.0),
+            rotation: (0.0, 0.0),
+            angular_velocity: (0.0, 0.0),
+
+            size_over_life: ValueCurve::constant(1.0),
+            color_over_life: Gradient::linear(Color::white(), Color::white().with_alpha(0.0)),
+
+            gravity: Vector2::zero(),
+            drag: 0.0,
+        }
+    }
+
+    pub fn with_shape(mut self, shape: EmitterShape) -> ParticleConfig {
+        self.shape = shape;
+        self
+    }
+
+    pub fn with_emission(mut self, emission: Emission) -> ParticleConfig {
+        self.emission = emission;
+        self
+    }
+
+    pub fn with_direction(mut self, direction: f32, spread: f32) -> ParticleConfig {
+        self.direction = direction;
+        self.spread = spread;
+        self
+    }
+
+    pub fn with_lifetime(mut self, min: f32, max: f32) -> ParticleConfig {
+        self.lifetime = (min, max);
+        self
+    }
+
+    pub fn with_speed(mut self, min: f32, max: f32) -> ParticleConfig {
+        self.speed = (min, max);
+        self
+    }
+
+    pub fn with_size(mut self, min: f32, max: f32) -> ParticleConfig {
+        self.size = (min, max);
+        self
+    }
+
+    pub fn with_rotation(mut self, min: f32, max: f32) -> ParticleConfig {
+        self.rotation = (min, max);
+        self
+    }
+
+    pub fn with_angular_velocity(mut self, min: f32, max: f32) -> ParticleConfig {
+        self.angular_velocity = (min, max);
+        self
+    }
+
+    pub fn with_size_over_life(mut self, curve: ValueCurve) -> ParticleConfig {
+        self.size_over_life = curve;
+        self
+    }
+
+    pub fn with_color_over_life(mut self, gradient: Gradient) -> ParticleConfig {
+        self.color_over_life = gradient;
+        self
+    }
+
+    pub fn with_gravity(mut self, gravity: Vector2) -> ParticleConfig {
+        self.gravity = gravity;
+        self
+    }
+
+    pub fn with_drag(mut self, drag: f32) -> ParticleConfig {
+        self.drag = drag;
+        self
+    }
+
+    // Partícula recién nacida en un emisor que está en position
+    pub fn spawn(&self, random: &mut Random, position: Vector2) -> Particle {
+        let (direction, spread) = match self.shape {
+            EmitterShape::Cone { angle, spread } => (angle, spread),
+            _ => (self.direction, self.spread),
+        };
+
+        // Siempre en el mismo orden, para que la simulación sea repetible
+        let offset = spawn_point(random, self.shape);
+        let angle = direction + random.range_f32(-0.5, 0.5) * spread;
+        let speed = range(random, self.speed);
+
+        Particle {
+            position: position + offset,
+            velocity: Vector2::new(angle.cos(), angle.sin()) * speed,
+            rotation: range(random, self.rotation),
+            angular_velocity: range(random, self.angular_velocity),
+            size: range(random, self.size),
+            age: 0.0,
+            lifetime: range(random, self.lifetime),
+        }
+    }
+}
+
+// Cuenta cuántas partículas tocan en cada frame según el modo de emisión
+pub(crate) struct EmissionTimer {
+    // Partículas pendientes del modo continuo (la parte fraccionaria de rate * delta)
+    pending: f32,
+    // Tiempo hasta la siguiente ráfaga
+    burst_timer: f32,
+    bursts_done: u32,
+}
+
+impl EmissionTimer {
+    pub(crate) fn new() -> EmissionTimer {
+        EmissionTimer {
+            pending: 0.0,
+            burst_timer: 0.0,
+            bursts_done: 0,
+        }
+    }
+
+    // Partículas que hay que crear tras pasar delta segundos
+    pub(crate) fn advance(&mut self, emission: Emission, delta: f32) -> u32 {
+        match emission {
+            Emission::Continuous { rate } => {
+                self.pending += rate * delta;
+                let count = self.pending.floor();
+                self.pending -= count;
+                count as u32
+            }
+            Emission::Burst { count, interval } => {
+                if interval <= 0.0 {
+                    if self.bursts_done > 0 {
+                        return 0;
+                    }
+                    self.bursts_done = 1;
+                    return count;
+                }
+
+                let mut total = 0;
+                self.burst_timer -= delta;
+                while self.burst_timer <= 0.0 {
+                    total += count;
+                    self.bursts_done += 1;
+                    self.burst_timer += interval;
+                }
+                total
+            }
+        }
+    }
+
+    // Al parar no se guarda la fracción pendiente
+    pub(crate) fn stop(&mut self) {
+        self.pending = 0.0;
+    }
+
+    // Una ráfaga única que ya ha salido no vuelve a emitir
+    pub(crate) fn is_over(&self, emission: Emission) -> bool {
+        matches!(emission, Emission::Burst { interval, .. } if interval <= 0.0 && self.bursts_done > 0)
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct Particle {
+    pub position: Vector2,
+    pub velocity: Vector2,
+    pub rotation: f32,
+    pub angular_velocity: f32,
+    // Tamaño al nacer, antes de aplicar size_over_life
+    pub size: f32,
+    pub age: f32,
+    pub lifetime: f32,
+}
+
+impl Particle {
+    // Vida transcurrida, de 0 a 1
+    pub fn progress(&self) -> f32 {
+        if self.lifetime > 0.0 { (self.age / self.lifetime).min(1.0) } else { 1.0 }
+    }
+}
+
+// Emisor de partículas simulado en CPU. Reserva sitio para max_particles al crearse y
+// después no vuelve a reservar memoria. Con la misma semilla y los mismos delta la
+// simulación es siempre igual
+pub struct ParticleEmitter {
+    pub config: ParticleConfig,
+    // Las partículas ya nacidas no se mueven con el emisor
+    pub position: Vector2,
+
+    emitting: bool,
+    // Las vivas, sin orden: las que mueren se cambian por la última
+    particles: Vec<Particle>,
+    random: Random,
+    timer: EmissionTimer,
+}
+
+impl ParticleEmitter {
+    pub fn new(config: ParticleConfig, position: Vector2, seed: u64) -> ParticleEmitter {
+        ParticleEmitter {
+            particles: Vec::with_capacity(config.max_particles),
+            config,
+            position,
+
+            emitting: true,
+            random: Random::new(seed),
+            timer: EmissionTimer::new(),
+        }
+    }
+
+    pub fn start(&mut self) {
+        self.emitting = true;
+    }
+
+    // Deja de crear partículas; las que hay terminan su vida
+    pub fn stop(&mut self) {
+        self.emitting = false;
+        self.timer.stop();
+    }
+
+    pub fn is_emitting(&self) -> bool {
+        self.emitting
+    }
+
+    // Vuelve al principio con otra semilla, sin partículas
+    pub fn reset(&mut self, seed: u64) {
+        self.particles.clear();
+        self.random = Random::new(seed);
+        self.timer = EmissionTimer::new();
+        self.emitting = true;
+    }
+
+    // true si ya no emite y no queda ninguna partícula (para quitar el emisor)
+    pub fn is_finished(&self) -> bool {
+        (!self.emitting || self.timer.is_over(self.config.emission)) && self.particles.is_empty()
+    }
+
+    pub fn particles(&self) -> &[Particle] {
+        &self.particles
+    }
+
+    pub fn len(&self) -> usize {
+        self.particles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.particles.is_empty()
+    }
+
+    // Crea count partículas ya mismo (hasta llenar el pool)
+    pub fn burst(&mut self, count: u32) {
+        for _ in 0..count {
+            if self.particles.len() >= self.config.max_particles {
+                break;
+            }
+            let particle = self.config.spawn(&mut self.random, self.position);
+            self.particles.push(particle);
+        }
+    }
+
+    pub fn update(&mut self, delta: f32) {
+        if self.emitting {
+            let count = self.timer.advance(self.config.emission, delta);
+            self.burst(count);
+        }
+
+        let gravity = self.config.gravity;
+        let damping = (1.0 - self.config.drag * delta).max(0.0);
+
+        let mut i = 0;
+        while i < self.particles.len() {
+            let particle = &mut self.particles[i];
+            particle.age += delta;
+            if particle.age >= particle.lifetime {
+                self.particles.swap_remove(i);
+                continue;
+            }
+
+            particle.velocity += gravity * delta;
+            particle.velocity *= damping;
+            particle.position += particle.velocity * delta;
+            particle.rotation += particle.angular_velocity * delta;
+            i += 1;
+        }
+    }
+
+    // Copia las partículas vivas como instancias del sprite y las sube a la GPU,
+    // así todo el emisor se dibuja con una llamada
+    pub fn write_instances(&self, sprite: &mut InstancedSprite) {
+        let (width, height) = (sprite.width, sprite.height);
+        let instances = sprite.instances_mut();
+        instances.clear();
+
+        for particle in &self.particles {
+            let t = particle.progress();
+            let size = particle.size * self.config.size_over_life.evaluate(t);
+            // La escala lleva el quad del sprite (width x height) al tamaño de la partícula
+            instances.push(
+                SpriteInstance::new(Vector3::new(particle.position.x, particle.position.y, 0.0))
+                    .with_rotation(particle.rotation)
+                    .with_scale(Vector2::new(size / width, size / height))
+                    .with_color(self.config.color_over_life.evaluate(t)),
+            );
+        }
+
+        sprite.upload_instances();
+    }
+}
+
+fn range(random: &mut Random, (min, max): (f32, f32)) -> f32 {
+    if max > min { random.range_f32(min, max) } else { min }
+}
+
+// Punto de salida de una partícula nueva, relativo al emisor
+fn spawn_point(random: &mut Random, shape: EmitterShape) -> Vector2 {
+    match shape {
+        EmitterShape::Point | EmitterShape::Cone { .. } => Vector2::zero(),
+        EmitterShape::Circle { radius } => random.point_in_circle(&Circle::new(Vector2::zero(), radius)),
+        EmitterShape::Rect { width, height } => {
+            random.point_in_rect(&Rect::new(-width / 2.0, -height / 2.0, width, height))
+        }
+    }
+}
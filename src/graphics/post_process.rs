@@ -0,0 +1,440 @@
+use crate::gl_utility::framebuffer::{DepthAttachment, Framebuffer};
+use crate::gl_utility::render_state::{BlendMode, RenderState, StateCache};
+use crate::gl_utility::shader::{Shader, ShaderManager};
+use crate::gl_utility::texture::{Texture, TextureFormat};
+use crate::graphics::color::Color;
+
+// Programas de los efectos, registrados en el ShaderManager con estos nombres
+const COPY_SHADER: &str = "post_copy";
+const BLUR_SHADER: &str = "post_blur";
+const BRIGHT_SHADER: &str = "post_bright";
+const BLOOM_SHADER: &str = "post_bloom";
+const LUT_SHADER: &str = "post_lut";
+const VIGNETTE_SHADER: &str = "post_vignette";
+const CRT_SHADER: &str = "post_crt";
+const FXAA_SHADER: &str = "post_fxaa";
+
+// Las zonas brillantes se desenfocan a mitad de resolución: es más barato y se extienden más
+const BLOOM_DOWNSCALE: u32 = 2;
+
+pub struct Bloom {
+    // Brillo (canal más alto, 0-1 o más en HDR) a partir del que un píxel brilla
+    pub threshold: f32,
+    pub intensity: f32,
+    // Pasadas de desenfoque: más pasadas, halo más ancho
+    pub iterations: u32,
+    pub spread: f32,
+}
+
+impl Bloom {
+    pub fn new(threshold: f32, intensity: f32) -> Bloom {
+        Bloom {
+            threshold,
+            intensity,
+            iterations: 3,
+            spread: 1.0,
+        }
+    }
+}
+
+pub struct Blur {
+    pub iterations: u32,
+    // Separación entre muestras en texels
+    pub spread: f32,
+}
+
+impl Blur {
+    pub fn new(iterations: u32, spread: f32) -> Blur {
+        Blur { iterations, spread }
+    }
+}
+
+// Corrección de color con una tabla (LUT) en forma de tira de size cuadros de
+// size x size píxeles. ColorGrading::neutral_lut genera la tabla que no cambia
+// nada, para retocarla en un programa de dibujo junto a una captura del juego
+pub struct ColorGrading<'a> {
+    pub lut: &'a Texture,
+    pub strength: f32,
+}
+
+impl<'a> ColorGrading<'a> {
+    pub fn new(lut: &'a Texture) -> ColorGrading<'a> {
+        if lut.width != lut.height * lut.height {
+            panic!(
+                "La LUT {} mide {}x{} y debería medir {}x{}",
+                lut.name, lut.width, lut.height, lut.height * lut.height, lut.height
+            );
+        }
+
+        ColorGrading { lut, strength: 1.0 }
+    }
+
+    // Píxeles RGBA de la LUT neutra de (size * size) x size
+    pub fn neutral_lut(size: u32) -> Vec<u8> {
+        let max = (size.max(2) - 1) as f32;
+        let width = size * size;
+        let mut pixels = Vec::with_capacity((width * size * 4) as usize);
+
+        for y in 0..size {
+            for x in 0..width {
+                let red = (x % size) as f32 / max;
+                let green = y as f32 / max;
+                let blue = (x / size) as f32 / max;
+                pixels.extend_from_slice(&[
+                    (red * 255.0).round() as u8,
+                    (green * 255.0).round() as u8,
+                    (blue * 255.0).round() as u8,
+                    255,
+                ]);
+            }
+        }
+
+        pixels
+    }
+}
+
+pub struct Vignette {
+    pub intensity: f32,
+    // Distancia al centro (1 = esquina) a partir de la que todo queda oscurecido
+    pub radius: f32,
+    pub softness: f32,
+}
+
+impl Vignette {
+    pub fn new(intensity: f32) -> Vignette {
+        Vignette {
+            intensity,
+            radius: 1.0,
+            softness: 0.6,
+        }
+    }
+}
+
+pub struct Crt {
+    pub curvature: f32,
+    pub scanlines: f32,
+}
+
+impl Crt {
+    pub fn new(curvature: f32, scanlines: f32) -> Crt {
+        Crt { curvature, scanlines }
+    }
+}
+
+pub enum Effect<'a> {
+    Bloom(Bloom),
+    Blur(Blur),
+    ColorGrading(ColorGrading<'a>),
+    Vignette(Vignette),
+    Crt(Crt),
+    Fxaa,
+}
+
+pub struct PostEffect<'a> {
+    pub name: String,
+    pub enabled: bool,
+    pub effect: Effect<'a>,
+}
+
+// Cadena de efectos a pantalla completa. La escena se dibuja entre begin() y end();
+// end() pasa la imagen por los efectos activos, en orden, alternando entre dos
+// framebuffers, y la última la copia a la ventana
+pub struct PostProcess<'a> {
+    effects: Vec<PostEffect<'a>>,
+
+    scene: Framebuffer,
+    ping_pong: [Framebuffer; 2],
+    bloom: [Framebuffer; 2],
+
+    // Los pasos no tienen vértices (salen de gl_VertexID) pero OpenGL pide un VAO activo
+    vao: u32,
+}
+
+impl<'a> Drop for PostProcess<'a> {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteVertexArrays(1, &self.vao);
+        }
+    }
+}
+
+impl<'a> PostProcess<'a> {
+    // samples > 1 activa el multisample en la escena. Los buffers intermedios son de
+    // coma flotante para que el bloom pueda trabajar con brillos por encima de 1
+    pub fn new(shaders: &mut ShaderManager, width: u32, height: u32, samples: u32) -> Result<PostProcess<'a>, String> {
+        let vertex = include_str!("../post.vert");
+        shaders.register(COPY_SHADER, vertex, include_str!("../post_copy.frag"));
+        shaders.register(BLUR_SHADER, vertex, include_str!("../post_blur.frag"));
+        shaders.register(BRIGHT_SHADER, vertex, include_str!("../post_bright.frag"));
+        shaders.register(BLOOM_SHADER, vertex, include_str!("../post_bloom.frag"));
+        shaders.register(LUT_SHADER, vertex, include_str!("../post_lut.frag"));
+        shaders.register(VIGNETTE_SHADER, vertex, include_str!("../post_vignette.frag"));
+        shaders.register(CRT_SHADER, vertex, include_str!("../post_crt.frag"));
+        shaders.register(FXAA_SHADER, vertex, include_str!("../post_fxaa.frag"));
+
+        let buffer = |name: &str, width: u32, height: u32| {
+            Framebuffer::builder(name, width, height)
+                .with_color(TextureFormat::Rgba16F)
+                .build()
+        };
+        let (bloom_width, bloom_height) = PostProcess::bloom_size(width, height);
+
+        let mut post_process = PostProcess {
+            effects: Vec::new(),
+
+            scene: Framebuffer::builder("post_scene", width, height)
+                .with_color(TextureFormat::Rgba16F)
+                .with_depth(DepthAttachment::DepthStencil)
+                .with_samples(samples)
+                .build()?,
+            ping_pong: [buffer("post_ping", width, height)?, buffer("post_pong", width, height)?],
+            bloom: [
+                buffer("post_bloom_a", bloom_width, bloom_height)?,
+                buffer("post_bloom_b", bloom_width, bloom_height)?,
+            ],
+
+            vao: 0,
+        };
+
+        unsafe {
+            gl::GenVertexArrays(1, &mut post_process.vao);
+        }
+
+        Ok(post_process)
+    }
+
+    fn bloom_size(width: u32, height: u32) -> (u32, u32) {
+        ((width / BLOOM_DOWNSCALE).max(1), (height / BLOOM_DOWNSCALE).max(1))
+    }
+
+    pub fn width(&self) -> u32 {
+        self.scene.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.scene.height
+    }
+
+    // Cambia el tamaño de todos los buffers (por ejemplo al redimensionar la ventana)
+    pub fn resize(&mut self, width: u32, height: u32) -> Result<(), String> {
+        let (bloom_width, bloom_height) = PostProcess::bloom_size(width, height);
+
+        self.scene.resize(width, height)?;
+        for buffer in self.ping_pong.iter_mut() {
+            buffer.resize(width, height)?;
+        }
+        for buffer in self.bloom.iter_mut() {
+            buffer.resize(bloom_width, bloom_height)?;
+        }
+        Ok(())
+    }
+
+    // Añade el efecto al final de la cadena
+    pub fn push(&mut self, name: &str, effect: Effect<'a>) {
+        let index = self.effects.len();
+        self.insert(index, name, effect);
+    }
+
+    pub fn insert(&mut self, index: usize, name: &str, effect: Effect<'a>) {
+        self.effects.insert(
+            index,
+            PostEffect {
+                name: String::from(name),
+                enabled: true,
+                effect,
+            },
+        );
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<PostEffect<'a>> {
+        let index = self.index_of(name)?;
+        Some(self.effects.remove(index))
+    }
+
+    // Mueve el efecto a la posición index de la cadena
+    pub fn move_to(&mut self, name: &str, index: usize) -> bool {
+        match self.remove(name) {
+            Some(effect) => {
+                self.effects.insert(index.min(self.effects.len()), effect);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.effects.iter().position(|effect| effect.name == name)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&PostEffect<'a>> {
+        self.effects.iter().find(|effect| effect.name == name)
+    }
+
+    // Para cambiar los parámetros de un efecto mientras el juego corre
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut PostEffect<'a>> {
+        self.effects.iter_mut().find(|effect| effect.name == name)
+    }
+
+    pub fn set_enabled(&mut self, name: &str, enabled: bool) {
+        if let Some(effect) = self.get_mut(name) {
+            effect.enabled = enabled;
+        }
+    }
+
+    pub fn effects(&self) -> &[PostEffect<'a>] {
+        &self.effects
+    }
+
+    // A partir de aquí se dibuja la escena fuera de pantalla
+    pub fn begin(&self, clear_color: Color) {
+        self.scene.bind();
+        self.scene.clear(clear_color);
+    }
+
+    // Aplica los efectos y dibuja el resultado en la ventana. Deja activo el programa de
+    // post_copy, así que después hay que volver a llamar a use_shader() para dibujar.
+    // El estado de OpenGL se cambia a través de cache y al terminar se deja como estaba
+    pub fn end(&self, shaders: &mut ShaderManager, cache: &mut StateCache, window_width: u32, window_height: u32) {
+        self.scene.resolve();
+
+        // Los pasos pisan todos los píxeles: ni mezcla ni profundidad
+        let previous = cache.current().copied();
+        cache.apply(&RenderState::new().with_blend(BlendMode::Opaque));
+        unsafe {
+            gl::BindVertexArray(self.vao);
+        }
+
+        // Índice en ping_pong con la imagen actual (None mientras siga en la escena)
+        let mut current: Option<usize> = None;
+        for post_effect in self.effects.iter().filter(|effect| effect.enabled) {
+            current = Some(self.apply(shaders, &post_effect.effect, current));
+        }
+
+        Framebuffer::bind_default(window_width, window_height);
+        self.pass(shaders, COPY_SHADER, self.image(current), |_| {});
+
+        unsafe {
+            gl::BindVertexArray(0);
+        }
+        if let Some(previous) = previous {
+            cache.apply(&previous);
+        }
+    }
+
+    fn image(&self, current: Option<usize>) -> &Texture {
+        match current {
+            Some(index) => self.ping_pong[index].texture(0),
+            None => self.scene.texture(0),
+        }
+    }
+
+    // Aplica un efecto a la imagen actual y devuelve en qué buffer ha quedado el resultado
+    fn apply(&self, shaders: &mut ShaderManager, effect: &Effect<'a>, current: Option<usize>) -> usize {
+        let target = current.map_or(0, |index| 1 - index);
+        let source = self.image(current);
+        let (width, height) = (self.scene.width as f32, self.scene.height as f32);
+
+        match effect {
+            Effect::Bloom(bloom) => {
+                self.bloom[0].bind();
+                self.pass(shaders, BRIGHT_SHADER, source, |shader| unsafe {
+                    gl::Uniform1f(shader.get_uniform_location("u_threshold"), bloom.threshold);
+                });
+
+                let bloom_size = (self.bloom[0].width as f32, self.bloom[0].height as f32);
+                for _ in 0..bloom.iterations {
+                    self.blur(shaders, &self.bloom[0], &self.bloom[1], bloom_size, bloom.spread);
+                }
+
+                self.ping_pong[target].bind();
+                self.bloom[0].texture(0).bind(1);
+                self.pass(shaders, BLOOM_SHADER, source, |shader| unsafe {
+                    gl::Uniform1i(shader.get_uniform_location("u_bloom"), 1);
+                    gl::Uniform1f(shader.get_uniform_location("u_intensity"), bloom.intensity);
+                });
+                target
+            }
+            Effect::Blur(blur) => {
+                let target = match current {
+                    Some(index) => index,
+                    // Primero se copia la escena para desenfocar entre los dos buffers
+                    None => {
+                        self.ping_pong[0].bind();
+                        self.pass(shaders, COPY_SHADER, source, |_| {});
+                        0
+                    }
+                };
+
+                for _ in 0..blur.iterations {
+                    self.blur(shaders, &self.ping_pong[target], &self.ping_pong[1 - target], (width, height), blur.spread);
+                }
+                target
+            }
+            Effect::ColorGrading(grading) => {
+                self.ping_pong[target].bind();
+                grading.lut.bind(1);
+                self.pass(shaders, LUT_SHADER, source, |shader| unsafe {
+                    gl::Uniform1i(shader.get_uniform_location("u_lut"), 1);
+                    gl::Uniform1f(shader.get_uniform_location("u_lut_size"), grading.lut.height as f32);
+                    gl::Uniform1f(shader.get_uniform_location("u_strength"), grading.strength);
+                });
+                target
+            }
+            Effect::Vignette(vignette) => {
+                self.ping_pong[target].bind();
+                self.pass(shaders, VIGNETTE_SHADER, source, |shader| unsafe {
+                    gl::Uniform1f(shader.get_uniform_location("u_intensity"), vignette.intensity);
+                    gl::Uniform1f(shader.get_uniform_location("u_radius"), vignette.radius);
+                    gl::Uniform1f(shader.get_uniform_location("u_softness"), vignette.softness);
+                });
+                target
+            }
+            Effect::Crt(crt) => {
+                self.ping_pong[target].bind();
+                self.pass(shaders, CRT_SHADER, source, |shader| unsafe {
+                    gl::Uniform2f(shader.get_uniform_location("u_resolution"), width, height);
+                    gl::Uniform1f(shader.get_uniform_location("u_curvature"), crt.curvature);
+                    gl::Uniform1f(shader.get_uniform_location("u_scanlines"), crt.scanlines);
+                });
+                target
+            }
+            Effect::Fxaa => {
+                self.ping_pong[target].bind();
+                self.pass(shaders, FXAA_SHADER, source, |shader| unsafe {
+                    gl::Uniform2f(shader.get_uniform_location("u_texel_size"), 1.0 / width, 1.0 / height);
+                });
+                target
+            }
+        }
+    }
+
+    // Desenfoque gaussiano separable: horizontal de image a temporary y vertical de vuelta
+    fn blur(&self, shaders: &mut ShaderManager, image: &Framebuffer, temporary: &Framebuffer, size: (f32, f32), spread: f32) {
+        let directions = [(spread / size.0, 0.0), (0.0, spread / size.1)];
+        let steps = [(image, temporary), (temporary, image)];
+
+        for (&(source, target), &(x, y)) in steps.iter().zip(directions.iter()) {
+            target.bind();
+            self.pass(shaders, BLUR_SHADER, source.texture(0), |shader| unsafe {
+                gl::Uniform2f(shader.get_uniform_location("u_direction"), x, y);
+            });
+        }
+    }
+
+    // Dibuja el triángulo de pantalla completa en el framebuffer activo leyendo source
+    fn pass(&self, shaders: &mut ShaderManager, name: &str, source: &Texture, set_uniforms: impl Fn(&Shader)) {
+        let shader = shaders.get(name);
+        shader.use_shader();
+        source.bind(0);
+
+        unsafe {
+            gl::Uniform1i(shader.get_uniform_location("u_source"), 0);
+        }
+        set_uniforms(shader);
+
+        unsafe {
+            gl::DrawArrays(gl::TRIANGLES, 0, 3);
+            gl::ActiveTexture(gl::TEXTURE0);
+        }
+    }
+}
@@ -0,0 +1,409 @@
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+use crate::gl_utility::render_state::{BlendMode, RenderState, StateCache};
+use crate::gl_utility::shader::Shader;
+use crate::math::matrix4x4::Matrix4x4;
+
+// Cualquier cosa que se dibuja con una matriz de transformación
+pub trait Drawable {
+    fn draw(&self, model: &Matrix4x4);
+
+    // Mezcla que pide lo que se dibuja (la de su material, si tiene). RenderItem::new
+    // la pone en su estado
+    fn blend(&self) -> Option<BlendMode> {
+        None
+    }
+}
+
+// Petición de dibujo para la RenderQueue
+pub struct RenderItem<'a> {
+    pub layer: i32,
+    // Dentro de la capa, más z se dibuja más encima
+    pub z: f32,
+    // Clave para agrupar lo que comparte shader y texturas (sólo ordena lo opaco)
+    pub material: u32,
+    // Estado de OpenGL con el que se dibuja. Con blend Opaque va en la pasada de opacos
+    pub state: RenderState,
+
+    drawable: &'a dyn Drawable,
+    model: Matrix4x4,
+    // Programa que se activa antes de dibujar
+    shader: Option<&'a Shader>,
+    // Orden de llegada, para que el orden sea estable entre frames
+    sequence: usize,
+}
+
+impl<'a> RenderItem<'a> {
+    pub fn new(drawable: &'a dyn Drawable, model: Matrix4x4) -> RenderItem<'a> {
+        let state = RenderState::new();
+
+        RenderItem {
+            layer: 0,
+            z: 0.0,
+            material: 0,
+            state: drawable.blend().map_or(state, |blend| state.with_blend(blend)),
+
+            drawable,
+            model,
+            shader: None,
+            sequence: 0,
+        }
+    }
+
+    pub fn with_layer(mut self, layer: i32) -> RenderItem<'a> {
+        self.layer = layer;
+        self
+    }
+
+    pub fn with_z(mut self, z: f32) -> RenderItem<'a> {
+        self.z = z;
+        self
+    }
+
+    pub fn with_material(mut self, material: u32) -> RenderItem<'a> {
+        self.material = material;
+        self
+    }
+
+    pub fn with_blend(mut self, blend: BlendMode) -> RenderItem<'a> {
+        self.state.blend = blend;
+        self
+    }
+
+    pub fn with_state(mut self, state: RenderState) -> RenderItem<'a> {
+        self.state = state;
+        self
+    }
+
+    pub fn with_shader(mut self, shader: &'a Shader) -> RenderItem<'a> {
+        self.shader = Some(shader);
+        self
+    }
+
+    // Posición y de la matriz (la traslación), para ordenar por y
+    fn y(&self) -> f32 {
+        self.model.data[13]
+    }
+}
+
+// Cola de dibujo del frame: se le envían RenderItems en cualquier orden y flush los
+// ordena y los dibuja. Primero van los opacos y después los transparentes de atrás
+// hacia delante (capa, z y, en las capas con y-sort, y: con y hacia abajo lo que está
+// más abajo en pantalla tapa lo de arriba). Con depth_test los opacos se dibujan de
+// delante hacia atrás y agrupados por material, y el test de profundidad descarta lo
+// tapado; para eso su z tiene que ir también en la matriz
+pub struct RenderQueue<'a> {
+    pub depth_test: bool,
+
+    items: Vec<RenderItem<'a>>,
+    y_sorted_layers: HashSet<i32>,
+}
+
+impl<'a> Default for RenderQueue<'a> {
+    fn default() -> RenderQueue<'a> {
+        RenderQueue::new()
+    }
+}
+
+impl<'a> RenderQueue<'a> {
+    pub fn new() -> RenderQueue<'a> {
+        RenderQueue {
+            depth_test: false,
+
+            items: Vec::new(),
+            y_sorted_layers: HashSet::new(),
+        }
+    }
+
+    pub fn with_depth_test(mut self, depth_test: bool) -> RenderQueue<'a> {
+        self.depth_test = depth_test;
+        self
+    }
+
+    // En las capas con y-sort lo que tiene la misma z se ordena por la y de su posición
+    // (juegos con vista cenital)
+    pub fn set_y_sort(&mut self, layer: i32, enabled: bool) {
+        if enabled {
+            self.y_sorted_layers.insert(layer);
+        } else {
+            self.y_sorted_layers.remove(&layer);
+        }
+    }
+
+    pub fn submit(&mut self, mut item: RenderItem<'a>) {
+        item.sequence = self.items.len();
+        self.items.push(item);
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+
+    // Capa, z y (en las capas con y-sort) y: de menos a más es de atrás hacia delante
+    fn depth_order(&self, a: &RenderItem, b: &RenderItem) -> Ordering {
+        let y_sort = |item: &RenderItem| {
+            if self.y_sorted_layers.contains(&item.layer) { item.y() } else { 0.0 }
+        };
+
+        a.layer
+            .cmp(&b.layer)
+            .then(a.z.partial_cmp(&b.z).unwrap_or(Ordering::Equal))
+            .then(y_sort(a).partial_cmp(&y_sort(b)).unwrap_or(Ordering::Equal))
+    }
+
+    // Orden en que se dibujarán los elementos (índices en el orden de envío)
+    pub fn sorted_order(&self) -> Vec<usize> {
+        let mut opaque: Vec<&RenderItem> = self.items.iter().filter(|item| item.state.blend.is_opaque()).collect();
+        let mut transparent: Vec<&RenderItem> = self.items.iter().filter(|item| !item.state.blend.is_opaque()).collect();
+
+        if self.depth_test {
+            // Con igual profundidad gana el último enviado, como sin depth_test
+            opaque.sort_by(|a, b| {
+                b.layer
+                    .cmp(&a.layer)
+                    .then(a.material.cmp(&b.material))
+                    .then(self.depth_order(b, a))
+                    .then(a.sequence.cmp(&b.sequence))
+            });
+        } else {
+            opaque.sort_by(|a, b| self.depth_order(a, b).then(a.sequence.cmp(&b.sequence)));
+        }
+        transparent.sort_by(|a, b| self.depth_order(a, b).then(a.sequence.cmp(&b.sequence)));
+
+        opaque.iter().chain(transparent.iter()).map(|item| item.sequence).collect()
+    }
+
+    // Ordena y dibuja todo lo enviado y vacía la cola, aplicando el estado de cada
+    // elemento a través de la caché. Devuelve cuántos elementos ha dibujado.
+    // Al terminar deja activa la escritura de profundidad y todos los canales, sin
+    // scissor: glClear respeta esas máscaras, y si el último elemento era transparente
+    // el borrado del frame siguiente no limpiaría la profundidad
+    pub fn flush(&mut self, cache: &mut StateCache) -> usize {
+        let order = self.sorted_order();
+
+        for &index in &order {
+            let item = &self.items[index];
+
+            let mut state = item.state;
+            if self.depth_test {
+                // Los transparentes respetan la profundidad de los opacos pero no la escriben
+                state.depth_test = true;
+                state.depth_write = state.blend.is_opaque();
+            }
+            cache.apply(&state);
+
+            // Se activa siempre: un drawable puede activar su propio programa al dibujar
+            // (Sprite con material lo hace), así que no sabemos cuál quedó activo
+            if let Some(shader) = item.shader {
+                shader.use_shader();
+            }
+
+            item.drawable.draw(&item.model);
+        }
+
+        if let Some(&last) = cache.current() {
+            cache.apply(
+                &last
+                    .with_depth(last.depth_test, true)
+                    .with_scissor(None)
+                    .with_color_mask(true, true, true, true),
+            );
+        }
+
+        self.items.clear();
+        order.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gl_utility::render_state::Scissor;
+    use crate::math::vector3::Vector3;
+
+    struct Quad(Option<BlendMode>);
+
+    impl Drawable for Quad {
+        fn draw(&self, _model: &Matrix4x4) {}
+
+        fn blend(&self) -> Option<BlendMode> {
+            self.0
+        }
+    }
+
+    #[test]
+    fn items_take_the_blend_of_their_drawable() {
+        let plain = Quad(None);
+        let opaque = Quad(Some(BlendMode::Opaque));
+        let additive = Quad(Some(BlendMode::Additive));
+
+        assert_eq!(RenderItem::new(&plain, Matrix4x4::identity()).state, RenderState::new());
+        assert_eq!(RenderItem::new(&opaque, Matrix4x4::identity()).state.blend, BlendMode::Opaque);
+        assert_eq!(RenderItem::new(&additive, Matrix4x4::identity()).state.blend, BlendMode::Additive);
+        // Lo que se pida después manda sobre el material
+        let item = RenderItem::new(&opaque, Matrix4x4::identity()).with_blend(BlendMode::Alpha);
+        assert_eq!(item.state.blend, BlendMode::Alpha);
+    }
+
+    #[test]
+    fn material_blend_decides_the_pass() {
+        let transparent = Quad(None);
+        let opaque = Quad(Some(BlendMode::Opaque));
+
+        let mut queue = RenderQueue::new();
+        queue.submit(RenderItem::new(&transparent, Matrix4x4::identity()));
+        queue.submit(RenderItem::new(&opaque, Matrix4x4::identity()));
+        assert_eq!(queue.sorted_order(), vec![1, 0]);
+
+        let mut cache = StateCache::headless();
+        assert_eq!(queue.flush(&mut cache), 2);
+        assert_eq!(cache.current().map(|state| state.blend), Some(BlendMode::Alpha));
+    }
+
+    fn at(x: f32, y: f32) -> Matrix4x4 {
+        Matrix4x4::translation(Vector3::new(x, y, 0.0))
+    }
+
+    #[test]
+    fn layers_then_z_back_to_front() {
+        let quad = Quad(Some(BlendMode::Alpha));
+
+        let mut queue = RenderQueue::new();
+        queue.submit(RenderItem::new(&quad, at(0.0, 0.0)).with_layer(1).with_z(0.0));
+        queue.submit(RenderItem::new(&quad, at(0.0, 0.0)).with_layer(0).with_z(5.0));
+        queue.submit(RenderItem::new(&quad, at(0.0, 0.0)).with_layer(0).with_z(-1.0));
+        queue.submit(RenderItem::new(&quad, at(0.0, 0.0)).with_layer(-2).with_z(9.0));
+
+        assert_eq!(queue.sorted_order(), vec![3, 2, 1, 0]);
+    }
+
+    // Con todo igual se respeta el orden de envío, en las dos pasadas
+    #[test]
+    fn equal_keys_keep_submission_order() {
+        let transparent = Quad(Some(BlendMode::Alpha));
+        let opaque = Quad(Some(BlendMode::Opaque));
+
+        let mut queue = RenderQueue::new();
+        for i in 0..6 {
+            let quad: &dyn Drawable = if i % 2 == 0 { &transparent } else { &opaque };
+            queue.submit(RenderItem::new(quad, at(0.0, 0.0)));
+        }
+
+        assert_eq!(queue.sorted_order(), vec![1, 3, 5, 0, 2, 4]);
+    }
+
+    // y hacia abajo: lo que tiene más y está más abajo en pantalla y tapa lo de arriba
+    #[test]
+    fn y_sort_only_in_enabled_layers() {
+        let quad = Quad(Some(BlendMode::Alpha));
+
+        let mut queue = RenderQueue::new();
+        queue.set_y_sort(1, true);
+        queue.submit(RenderItem::new(&quad, at(0.0, 30.0)).with_layer(1));
+        queue.submit(RenderItem::new(&quad, at(0.0, 10.0)).with_layer(1));
+        queue.submit(RenderItem::new(&quad, at(0.0, 20.0)).with_layer(1));
+        // Sin y-sort en la capa 0 manda el orden de envío
+        queue.submit(RenderItem::new(&quad, at(0.0, 50.0)));
+        queue.submit(RenderItem::new(&quad, at(0.0, 40.0)));
+        // La z sigue mandando sobre la y
+        queue.submit(RenderItem::new(&quad, at(0.0, 0.0)).with_layer(1).with_z(1.0));
+
+        assert_eq!(queue.sorted_order(), vec![3, 4, 1, 2, 0, 5]);
+
+        queue.set_y_sort(1, false);
+        assert_eq!(queue.sorted_order(), vec![3, 4, 0, 1, 2, 5]);
+    }
+
+    // Con depth_test los opacos van por capa de delante hacia atrás, agrupados por material
+    // y dentro del material de delante hacia atrás; los transparentes siguen al final
+    #[test]
+    fn depth_test_groups_opaque_items_by_material() {
+        let opaque = Quad(Some(BlendMode::Opaque));
+        let transparent = Quad(Some(BlendMode::Alpha));
+
+        let mut queue = RenderQueue::new().with_depth_test(true);
+        queue.submit(RenderItem::new(&opaque, at(0.0, 0.0)).with_material(2).with_z(1.0));
+        queue.submit(RenderItem::new(&opaque, at(0.0, 0.0)).with_material(1).with_z(0.0));
+        queue.submit(RenderItem::new(&transparent, at(0.0, 0.0)).with_material(1).with_z(5.0));
+        queue.submit(RenderItem::new(&opaque, at(0.0, 0.0)).with_material(2).with_z(3.0));
+        queue.submit(RenderItem::new(&opaque, at(0.0, 0.0)).with_material(1).with_z(2.0));
+        queue.submit(RenderItem::new(&opaque, at(0.0, 0.0)).with_material(9).with_layer(1));
+        queue.submit(RenderItem::new(&transparent, at(0.0, 0.0)).with_z(-5.0));
+
+        assert_eq!(queue.sorted_order(), vec![5, 4, 1, 3, 0, 6, 2]);
+    }
+
+    #[test]
+    fn without_depth_test_opaque_items_ignore_the_material() {
+        let opaque = Quad(Some(BlendMode::Opaque));
+
+        let mut queue = RenderQueue::new();
+        queue.submit(RenderItem::new(&opaque, at(0.0, 0.0)).with_material(2).with_z(1.0));
+        queue.submit(RenderItem::new(&opaque, at(0.0, 0.0)).with_material(1).with_z(0.0));
+        queue.submit(RenderItem::new(&opaque, at(0.0, 0.0)).with_material(2).with_z(-1.0));
+
+        assert_eq!(queue.sorted_order(), vec![2, 1, 0]);
+    }
+
+    #[test]
+    fn flush_empties_the_queue() {
+        let quad = Quad(None);
+
+        let mut queue = RenderQueue::new();
+        queue.submit(RenderItem::new(&quad, at(0.0, 0.0)));
+        queue.submit(RenderItem::new(&quad, at(0.0, 0.0)));
+
+        assert_eq!(queue.flush(&mut StateCache::headless()), 2);
+        assert!(queue.is_empty());
+        assert_eq!(queue.flush(&mut StateCache::headless()), 0);
+    }
+
+    // El último en dibujarse es transparente (no escribe profundidad), pero la caché
+    // tiene que acabar con la escritura activa para que el glClear siguiente funcione
+    #[test]
+    fn flush_leaves_depth_writes_enabled() {
+        let opaque = Quad(Some(BlendMode::Opaque));
+        let transparent = Quad(Some(BlendMode::Alpha));
+
+        let mut queue = RenderQueue::new().with_depth_test(true);
+        queue.submit(RenderItem::new(&opaque, at(0.0, 0.0)));
+        queue.submit(RenderItem::new(&transparent, at(0.0, 0.0)));
+
+        let mut cache = StateCache::headless();
+        queue.flush(&mut cache);
+
+        let state = cache.current().unwrap();
+        assert!(state.depth_write);
+        assert!(state.depth_test);
+        assert_eq!(state.blend, BlendMode::Alpha);
+    }
+
+    #[test]
+    fn flush_clears_scissor_and_color_mask() {
+        let quad = Quad(None);
+        let state = RenderState::new()
+            .with_depth(false, false)
+            .with_scissor(Some(Scissor::new(0, 0, 8, 8)))
+            .with_color_mask(true, false, true, false);
+
+        let mut queue = RenderQueue::new();
+        queue.submit(RenderItem::new(&quad, at(0.0, 0.0)).with_state(state));
+
+        let mut cache = StateCache::headless();
+        queue.flush(&mut cache);
+
+        let state = cache.current().unwrap();
+        assert!(state.depth_write);
+        assert_eq!(state.scissor, None);
+        assert_eq!(state.color_mask, [true; 4]);
+    }
+}
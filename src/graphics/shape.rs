@@ -0,0 +1,573 @@
+use std::f32::consts::{PI, TAU};
+
+use crate::gl_utility::gl_buffer::{GLBuffer, AttributeInfo};
+use crate::gl_utility::shader::Shader;
+use crate::graphics::color::Color;
+use crate::graphics::render_queue::Drawable;
+use crate::math::matrix4x4::Matrix4x4;
+use crate::math::polygon::Polygon;
+use crate::math::rect::Rect;
+use crate::math::vector2::Vector2;
+
+// Cómo se unen dos tramos de una línea gruesa
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum LineJoin {
+    Miter,
+    Bevel,
+    Round,
+}
+
+// Cómo terminan los extremos de una línea gruesa abierta
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum LineCap {
+    Butt,
+    Square,
+    Round,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct StrokeStyle {
+    pub width: f32,
+    pub join: LineJoin,
+    pub cap: LineCap,
+    // Con Miter, a partir de esta proporción entre la punta y la anchura se une con Bevel
+    pub miter_limit: f32,
+}
+
+impl StrokeStyle {
+    pub fn new(width: f32) -> StrokeStyle {
+        StrokeStyle {
+            width,
+            join: LineJoin::Miter,
+            cap: LineCap::Butt,
+            miter_limit: 4.0,
+        }
+    }
+
+    pub fn with_join(mut self, join: LineJoin) -> StrokeStyle {
+        self.join = join;
+        self
+    }
+
+    pub fn with_cap(mut self, cap: LineCap) -> StrokeStyle {
+        self.cap = cap;
+        self
+    }
+
+    pub fn with_miter_limit(mut self, miter_limit: f32) -> StrokeStyle {
+        self.miter_limit = miter_limit;
+        self
+    }
+}
+
+// Malla de triángulos con gráficos vectoriales (polígonos rellenos, curvas y líneas
+// gruesas), con posición y color por vértice. Los bordes se suavizan con una franja de
+// antialias píxeles que pasa del color a transparente. Se dibuja con ShapeRenderer
+pub struct ShapeMesh {
+    // Error máximo en píxeles al aproximar curvas con segmentos
+    pub tolerance: f32,
+    pub antialias: f32,
+
+    vertices: Vec<f32>,
+}
+
+impl Default for ShapeMesh {
+    fn default() -> ShapeMesh {
+        ShapeMesh::new()
+    }
+}
+
+impl ShapeMesh {
+    pub fn new() -> ShapeMesh {
+        ShapeMesh {
+            tolerance: 0.25,
+            antialias: 1.0,
+
+            vertices: Vec::new(),
+        }
+    }
+
+    // 0 para bordes duros (pixel art)
+    pub fn with_antialias(mut self, antialias: f32) -> ShapeMesh {
+        self.antialias = antialias;
+        self
+    }
+
+    pub fn with_tolerance(mut self, tolerance: f32) -> ShapeMesh {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn clear(&mut self) {
+        self.vertices.clear();
+    }
+
+    // Posición (3) y color (4) de cada vértice, tres vértices por triángulo
+    pub fn vertices(&self) -> &[f32] {
+        &self.vertices
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.vertices.len() / (7 * 3)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty()
+    }
+
+    // Polígono simple relleno, convexo o cóncavo
+    pub fn fill_polygon(&mut self, points: &[Vector2], color: Color) {
+        let points = remove_duplicates(points, true);
+        if points.len() < 3 {
+            return;
+        }
+
+        let polygon = Polygon::new(points);
+        // Las normales de los lados (girados a la izquierda) apuntan hacia dentro en
+        // sentido antihorario, así que en ese caso hay que darles la vuelta
+        let outward = if polygon.signed_area() > 0.0 { -1.0 } else { 1.0 };
+        let (inner, outer) = self.fringe_offsets(&polygon.points, outward);
+
+        for [a, b, c] in polygon.triangulate() {
+            self.triangle([(inner[a], color), (inner[b], color), (inner[c], color)]);
+        }
+        self.fringe(&inner, &outer, color);
+    }
+
+    pub fn fill_rect(&mut self, rect: &Rect, color: Color) {
+        self.fill_polygon(&rect.corners(), color);
+    }
+
+    pub fn fill_rounded_rect(&mut self, rect: &Rect, radius: f32, color: Color) {
+        let points = self.rounded_rect_points(rect, radius);
+        self.fill_polygon(&points, color);
+    }
+
+    pub fn fill_circle(&mut self, center: Vector2, radius: f32, color: Color) {
+        let points = self.circle_points(center, radius);
+        self.fill_polygon(&points, color);
+    }
+
+    // Sector circular (porción de tarta) entre dos ángulos en radianes
+    pub fn fill_sector(&mut self, center: Vector2, radius: f32, start_angle: f32, end_angle: f32, color: Color) {
+        if (end_angle - start_angle).abs() >= TAU {
+            self.fill_circle(center, radius, color);
+            return;
+        }
+
+        let mut points = vec![center];
+        points.extend(self.arc_points(center, radius, start_angle, end_angle - start_angle));
+        self.fill_polygon(&points, color);
+    }
+
+    pub fn stroke_rect(&mut self, rect: &Rect, style: &StrokeStyle, color: Color) {
+        self.stroke_polyline(&rect.corners(), true, style, color);
+    }
+
+    pub fn stroke_rounded_rect(&mut self, rect: &Rect, radius: f32, style: &StrokeStyle, color: Color) {
+        let points = self.rounded_rect_points(rect, radius);
+        self.stroke_polyline(&points, true, style, color);
+    }
+
+    pub fn stroke_circle(&mut self, center: Vector2, radius: f32, style: &StrokeStyle, color: Color) {
+        let points = self.circle_points(center, radius);
+        self.stroke_polyline(&points, true, style, color);
+    }
+
+    pub fn stroke_arc(&mut self, center: Vector2, radius: f32, start_angle: f32, end_angle: f32, style: &StrokeStyle, color: Color) {
+        let points = self.arc_points(center, radius, start_angle, end_angle - start_angle);
+        self.stroke_polyline(&points, false, style, color);
+    }
+
+    // Línea gruesa que pasa por los puntos. Con closed también une el último con el primero
+    pub fn stroke_polyline(&mut self, points: &[Vector2], closed: bool, style: &StrokeStyle, color: Color) {
+        let points = remove_duplicates(points, closed);
+        let closed = closed && points.len() > 2;
+        if points.len() < 2 || style.width <= 0.0 {
+            return;
+        }
+
+        let half = style.width * 0.5;
+        let count = points.len();
+
+        // Puntos del borde izquierdo y derecho de cada vértice, en el sentido de la línea
+        let mut left = Vec::with_capacity(count);
+        let mut right = Vec::with_capacity(count);
+        for i in 0..count {
+            let point = points[i];
+            let previous = if i > 0 || closed { Some(points[(i + count - 1) % count]) } else { None };
+            let next = if i + 1 < count || closed { Some(points[(i + 1) % count]) } else { None };
+
+            let (left_points, right_points) = match (previous, next) {
+                (Some(previous), Some(next)) => self.join(previous, point, next, half, style),
+                (None, Some(next)) => ShapeMesh::cap_sides(point, (next - point).normalized(), -half, half, style.cap),
+                (Some(previous), None) => ShapeMesh::cap_sides(point, (point - previous).normalized(), half, half, style.cap),
+                (None, None) => unreachable!(),
+            };
+            left.push(left_points);
+            right.push(right_points);
+        }
+
+        if closed {
+            self.stroke_closed(&left, &right, color);
+        } else {
+            self.stroke_open(&points, &left, &right, half, style.cap, color);
+        }
+    }
+
+    // Contorno: lado izquierdo hacia delante, remate final, lado derecho hacia atrás y
+    // remate inicial. Es un solo polígono con la franja de antialias alrededor
+    fn stroke_open(&mut self, points: &[Vector2], left: &[Vec<Vector2>], right: &[Vec<Vector2>], half: f32, cap: LineCap, color: Color) {
+        let last = points.len() - 1;
+        let mut outline = Vec::new();
+        let mut left_indices = Vec::with_capacity(left.len());
+        let mut right_indices = vec![Vec::new(); right.len()];
+
+        for side in left {
+            left_indices.push((outline.len()..outline.len() + side.len()).collect::<Vec<usize>>());
+            outline.extend_from_slice(side);
+        }
+
+        // Puntos intermedios de los remates redondos (el semicírculo va de un lado al otro)
+        let round_cap = |mesh: &ShapeMesh, center: Vector2, side: Vector2| -> Vec<Vector2> {
+            if cap != LineCap::Round {
+                return Vec::new();
+            }
+            let mut arc = mesh.arc_points(center, half, side.y.atan2(side.x), -PI);
+            arc.pop();
+            arc.remove(0);
+            arc
+        };
+
+        let end_direction = (points[last] - points[last - 1]).normalized();
+        let end_cap = round_cap(self, points[last], end_direction.perpendicular());
+        let end_cap_indices: Vec<usize> = (outline.len()..outline.len() + end_cap.len()).collect();
+        outline.extend(end_cap);
+
+        for (i, side) in right.iter().enumerate().rev() {
+            let start = outline.len();
+            outline.extend(side.iter().rev());
+            // En el contorno van al revés; los índices se guardan en el sentido de la línea
+            right_indices[i] = (start..outline.len()).rev().collect();
+        }
+
+        let start_direction = (points[1] - points[0]).normalized();
+        let start_cap = round_cap(self, points[0], -start_direction.perpendicular());
+        let start_cap_indices: Vec<usize> = (outline.len()..outline.len() + start_cap.len()).collect();
+        outline.extend(start_cap);
+
+        let (inner, outer) = self.fringe_offsets(&outline, 1.0);
+        let mut triangles = ShapeMesh::stroke_triangles(&left_indices, &right_indices, false);
+
+        let mut end_fan = vec![*left_indices[last].last().unwrap()];
+        end_fan.extend(end_cap_indices);
+        end_fan.push(right_indices[last][0]);
+        let mut start_fan = vec![right_indices[0][0]];
+        start_fan.extend(start_cap_indices);
+        start_fan.push(left_indices[0][0]);
+        for fan in [end_fan, start_fan].iter() {
+            for j in 1..fan.len() - 1 {
+                triangles.push([fan[0], fan[j], fan[j + 1]]);
+            }
+        }
+
+        for [a, b, c] in triangles {
+            self.triangle([(inner[a], color), (inner[b], color), (inner[c], color)]);
+        }
+        self.fringe(&inner, &outer, color);
+    }
+
+    // Una línea cerrada tiene dos contornos: el del lado izquierdo y el del derecho
+    fn stroke_closed(&mut self, left: &[Vec<Vector2>], right: &[Vec<Vector2>], color: Color) {
+        let left_outline: Vec<Vector2> = left.iter().flatten().copied().collect();
+        // El derecho se recorre al revés para que la franja también quede hacia fuera
+        let right_outline: Vec<Vector2> = right.iter().flatten().rev().copied().collect();
+
+        let (left_inner, left_outer) = self.fringe_offsets(&left_outline, 1.0);
+        let (right_inner, right_outer) = self.fringe_offsets(&right_outline, 1.0);
+
+        let mut positions = left_inner.clone();
+        positions.extend(right_inner.iter().rev());
+
+        let mut next = 0;
+        let mut indices = |sides: &[Vec<Vector2>]| -> Vec<Vec<usize>> {
+            sides
+                .iter()
+                .map(|side| {
+                    next += side.len();
+                    (next - side.len()..next).collect()
+                })
+                .collect()
+        };
+        let left_indices = indices(left);
+        let right_indices = indices(right);
+
+        for [a, b, c] in ShapeMesh::stroke_triangles(&left_indices, &right_indices, true) {
+            self.triangle([(positions[a], color), (positions[b], color), (positions[c], color)]);
+        }
+        self.fringe(&left_inner, &left_outer, color);
+        self.fringe(&right_inner, &right_outer, color);
+    }
+
+    // Triángulos del cuerpo de la línea: abanicos en las uniones y un cuadrilátero por tramo
+    fn stroke_triangles(left: &[Vec<usize>], right: &[Vec<usize>], closed: bool) -> Vec<[usize; 3]> {
+        let count = left.len();
+        let mut triangles = Vec::new();
+
+        for i in 0..count {
+            for j in 1..left[i].len() {
+                triangles.push([right[i][0], left[i][j - 1], left[i][j]]);
+            }
+            for j in 1..right[i].len() {
+                triangles.push([left[i][0], right[i][j - 1], right[i][j]]);
+            }
+
+            if i + 1 < count || closed {
+                let next = (i + 1) % count;
+                let (a, b) = (*left[i].last().unwrap(), left[next][0]);
+                let (c, d) = (right[next][0], *right[i].last().unwrap());
+                triangles.push([a, b, c]);
+                triangles.push([a, c, d]);
+            }
+        }
+
+        triangles
+    }
+
+    // Lados de un extremo. forward es lo que avanza el remate cuadrado (-half al principio)
+    fn cap_sides(point: Vector2, direction: Vector2, forward: f32, half: f32, cap: LineCap) -> (Vec<Vector2>, Vec<Vector2>) {
+        let normal = direction.perpendicular() * half;
+        let extension = if cap == LineCap::Square { direction * forward } else { Vector2::zero() };
+        (vec![point + normal + extension], vec![point - normal + extension])
+    }
+
+    // Lados de una unión entre dos tramos. En la parte de dentro del giro se cortan en
+    // un punto; en la de fuera depende del tipo de unión
+    fn join(&self, previous: Vector2, point: Vector2, next: Vector2, half: f32, style: &StrokeStyle) -> (Vec<Vector2>, Vec<Vector2>) {
+        let (incoming, outgoing) = (point - previous, next - point);
+        let (d0, d1) = (incoming.normalized(), outgoing.normalized());
+        let (n0, n1) = (d0.perpendicular(), d1.perpendicular());
+        let turn = d0.cross(d1);
+
+        if turn.abs() < 1e-4 && d0.dot(d1) > 0.0 {
+            return (vec![point + n0 * half], vec![point - n0 * half]);
+        }
+
+        // 1 si gira hacia la izquierda (la izquierda es la parte de dentro)
+        let side = if turn > 0.0 { 1.0 } else { -1.0 };
+        let middle = (n0 + n1).normalized();
+        let cos = middle.dot(n0);
+
+        // La punta de dentro no puede pasar del final de los tramos
+        let inner = if cos > 1e-3 {
+            let shortest = incoming.length().min(outgoing.length());
+            let length = (half / cos).min((half * half + shortest * shortest).sqrt());
+            point + middle * (length * side)
+        } else {
+            point
+        };
+
+        let (out0, out1) = (n0 * -side, n1 * -side);
+        let outer = match style.join {
+            LineJoin::Miter if cos > 1e-3 && 1.0 / cos <= style.miter_limit => vec![point + middle * (-side * half / cos)],
+            LineJoin::Round => self.arc_points(point, half, out0.y.atan2(out0.x), out0.cross(out1).atan2(out0.dot(out1))),
+            _ => vec![point + out0 * half, point + out1 * half],
+        };
+
+        if side > 0.0 {
+            (vec![inner], outer)
+        } else {
+            (outer, vec![inner])
+        }
+    }
+
+    // Desplaza cada punto del contorno media franja hacia dentro y media hacia fuera.
+    // outward es 1 si las normales a la izquierda de los lados apuntan hacia fuera y -1 si no
+    fn fringe_offsets(&self, outline: &[Vector2], outward: f32) -> (Vec<Vector2>, Vec<Vector2>) {
+        if self.antialias <= 0.0 {
+            return (outline.to_vec(), Vec::new());
+        }
+
+        let half = self.antialias * 0.5;
+        let count = outline.len();
+        let mut inner = Vec::with_capacity(count);
+        let mut outer = Vec::with_capacity(count);
+
+        for i in 0..count {
+            let point = outline[i];
+            let previous = outline[(i + count - 1) % count];
+            let next = outline[(i + 1) % count];
+            let n0 = (point - previous).normalized().perpendicular() * outward;
+            let n1 = (next - point).normalized().perpendicular() * outward;
+
+            let sum = n0 + n1;
+            let normal = if sum.length_squared() < 1e-6 {
+                n0
+            } else {
+                // Como la unión en inglete, limitada para que las esquinas muy agudas no piquen
+                let middle = sum.normalized();
+                middle / middle.dot(n0).max(0.25)
+            };
+
+            inner.push(point - normal * half);
+            outer.push(point + normal * half);
+        }
+
+        (inner, outer)
+    }
+
+    fn fringe(&mut self, inner: &[Vector2], outer: &[Vector2], color: Color) {
+        if outer.is_empty() {
+            return;
+        }
+
+        let transparent = color.with_alpha(0.0);
+        let count = inner.len();
+        for i in 0..count {
+            let j = (i + 1) % count;
+            self.triangle([(inner[i], color), (inner[j], color), (outer[j], transparent)]);
+            self.triangle([(inner[i], color), (outer[j], transparent), (outer[i], transparent)]);
+        }
+    }
+
+    fn triangle(&mut self, vertices: [(Vector2, Color); 3]) {
+        for (position, color) in vertices.iter() {
+            self.vertices.extend_from_slice(&[position.x, position.y, 0.0, color.r, color.g, color.b, color.a]);
+        }
+    }
+
+    // Segmentos para que un arco de ese radio y ángulo no se separe más de tolerance de la curva
+    fn segments(&self, radius: f32, angle: f32) -> usize {
+        let radius = radius.abs();
+        if radius <= self.tolerance {
+            return 1;
+        }
+
+        let step = 2.0 * (1.0 - self.tolerance / radius).acos();
+        ((angle.abs() / step).ceil() as usize).clamp(1, 256)
+    }
+
+    // Puntos del arco, incluidos los dos extremos. sweep negativo va en el otro sentido
+    fn arc_points(&self, center: Vector2, radius: f32, start_angle: f32, sweep: f32) -> Vec<Vector2> {
+        let segments = self.segments(radius, sweep);
+        (0..=segments)
+            .map(|i| {
+                let angle = start_angle + sweep * i as f32 / segments as f32;
+                center + Vector2::new(angle.cos(), angle.sin()) * radius
+            })
+            .collect()
+    }
+
+    fn circle_points(&self, center: Vector2, radius: f32) -> Vec<Vector2> {
+        let segments = self.segments(radius, TAU).max(6);
+        (0..segments)
+            .map(|i| {
+                let angle = TAU * i as f32 / segments as f32;
+                center + Vector2::new(angle.cos(), angle.sin()) * radius
+            })
+            .collect()
+    }
+
+    fn rounded_rect_points(&self, rect: &Rect, radius: f32) -> Vec<Vector2> {
+        let radius = radius.clamp(0.0, rect.width.min(rect.height) * 0.5);
+        if radius <= 0.0 {
+            return rect.corners().to_vec();
+        }
+
+        let (left, top) = (rect.x + radius, rect.y + radius);
+        let (right, bottom) = (rect.x + rect.width - radius, rect.y + rect.height - radius);
+        let corners = [
+            (Vector2::new(left, top), PI),
+            (Vector2::new(right, top), PI * 1.5),
+            (Vector2::new(right, bottom), 0.0),
+            (Vector2::new(left, bottom), PI * 0.5),
+        ];
+
+        corners
+            .iter()
+            .flat_map(|&(center, start)| self.arc_points(center, radius, start, PI * 0.5))
+            .collect()
+    }
+}
+
+// Quita los puntos repetidos seguidos (y el último si closed y coincide con el primero)
+fn remove_duplicates(points: &[Vector2], closed: bool) -> Vec<Vector2> {
+    let mut result: Vec<Vector2> = Vec::with_capacity(points.len());
+    for &point in points {
+        if result.last().map_or(true, |last| last.distance(point) > 1e-4) {
+            result.push(point);
+        }
+    }
+
+    if closed && result.len() > 1 && result[0].distance(result[result.len() - 1]) <= 1e-4 {
+        result.pop();
+    }
+    result
+}
+
+// Dibuja una ShapeMesh con el shader de shape.vert/shape.frag
+// (a_position, a_color, u_projection, u_model y u_color como tinte)
+pub struct ShapeRenderer<'a> {
+    pub name: String,
+    pub color: Color,
+
+    buffer: GLBuffer,
+    u_color_location: i32,
+    u_model_location: i32,
+
+    shader: &'a Shader,
+}
+
+impl<'a> ShapeRenderer<'a> {
+    pub fn new(name: &str, shader: &'a Shader) -> ShapeRenderer<'a> {
+        let mut buffer = GLBuffer::new();
+        buffer.configure(
+            vec![
+                AttributeInfo {
+                    location: shader.get_attribute_location("a_position"),
+                    component_size: 3,
+                },
+                AttributeInfo {
+                    location: shader.get_attribute_location("a_color"),
+                    component_size: 4,
+                },
+            ],
+            false,
+        );
+
+        ShapeRenderer {
+            name: String::from(name),
+            color: Color::white(),
+
+            buffer,
+            u_color_location: shader.get_uniform_location("u_color"),
+            u_model_location: shader.get_uniform_location("u_model"),
+
+            shader,
+        }
+    }
+
+    // Sube la malla. Si cambia cada frame conviene llamar antes a set_dynamic
+    pub fn upload(&mut self, mesh: &ShapeMesh) {
+        self.buffer.upload(mesh.vertices());
+    }
+
+    pub fn set_dynamic(&mut self, dynamic: bool) {
+        self.buffer.set_usage(if dynamic { gl::STREAM_DRAW } else { gl::STATIC_DRAW });
+    }
+
+    pub fn draw(&self, model: &Matrix4x4) {
+        self.shader.use_shader();
+        unsafe {
+            gl::UniformMatrix4fv(self.u_model_location, 1, gl::FALSE, model.data.as_ptr());
+            gl::Uniform4f(self.u_color_location, self.color.r, self.color.g, self.color.b, self.color.a);
+        }
+        self.buffer.draw();
+    }
+}
+
+impl<'a> Drawable for ShapeRenderer<'a> {
+    fn draw(&self, model: &Matrix4x4) {
+        ShapeRenderer::draw(self, model);
+    }
+}
@@ -1,9 +1,16 @@
 use crate::gl_utility::gl_buffer::{GLBuffer, AttributeInfo};
+use crate::gl_utility::render_state::BlendMode;
 use crate::gl_utility::shader::Shader;
+use crate::gl_utility::texture::Texture;
 use crate::graphics::color::Color;
 use crate::graphics::vertex::Vertex;
+use crate::graphics::render_queue::Drawable;
+use crate::graphics::material::Material;
 use crate::math::vector3::Vector3;
 use crate::math::matrix4x4::Matrix4x4;
+use crate::math::transform::Transform;
+use crate::math::rect::Rect;
+use crate::math::vector2::Vector2;
 
 pub struct Sprite<'a> {
     pub name: String,
@@ -16,6 +23,16 @@ pub struct Sprite<'a> {
     color: Color,
     u_color_position: i32,
     u_model_location: i32,
+    u_diffuse_location: i32,
+    u_textured_location: i32,
+
+    // Textura opcional y zona de la textura que se dibuja, en coordenadas UV [0, 1]
+    texture: Option<&'a Texture>,
+    region: Rect,
+
+    // Si tiene material se dibuja con su shader y sus valores, que deben usar los
+    // mismos atributos que el shader del sprite (a_position y a_tex_coord)
+    material: Option<&'a Material<'a>>,
 
     buffer: GLBuffer,
     vertices: [Vertex; 6],
@@ -43,22 +60,34 @@ impl<'a> Sprite<'a> {
             color: Color::red(),
             u_color_position: shader.get_uniform_location("u_color"),   // uniform position
             u_model_location: shader.get_uniform_location("u_model"), // uniform matriz transf
+            u_diffuse_location: shader.get_uniform_location("u_diffuse"), // uniform textura
+            u_textured_location: shader.get_uniform_location("u_textured"),
+
+            texture: None,
+            region: Rect::new(0.0, 0.0, 1.0, 1.0),
+
+            material: None,
 
             buffer: GLBuffer::new(),
 
-            vertices: [Vertex::new(0.0, 0.0, 0.0); 6],
+            vertices: [Vertex::new(0.0, 0.0, 0.0, 0.0, 0.0); 6],
             shader: shader,
         }
     }
 
     pub fn load(&mut self) {
         let a_position_location = self.shader.get_attribute_location("a_position");
+        let a_tex_coord_location = self.shader.get_attribute_location("a_tex_coord");
         self.buffer.configure(
             vec![
                 AttributeInfo {
                     location: a_position_location,
                     component_size: 3,
-                }
+                },
+                AttributeInfo {
+                    location: a_tex_coord_location,
+                    component_size: 2,
+                },
             ],
             false,
         );
@@ -66,32 +95,86 @@ impl<'a> Sprite<'a> {
         self.calculate_vertices();
     }
 
+    pub fn set_color(&mut self, color: Color) {
+        self.color = color;
+    }
+
+    pub fn color(&self) -> Color {
+        self.color
+    }
+
+    // El color se multiplica por la textura, así que con blanco se ve la textura tal cual
+    pub fn set_texture(&mut self, texture: &'a Texture) {
+        self.texture = Some(texture);
+    }
+
+    pub fn texture(&self) -> Option<&'a Texture> {
+        self.texture
+    }
+
+    // Lo que ponga el material (color, textura) tiene prioridad sobre lo del sprite
+    pub fn set_material(&mut self, material: Option<&'a Material<'a>>) {
+        self.material = material;
+    }
+
+    pub fn material(&self) -> Option<&'a Material<'a>> {
+        self.material
+    }
+
+    // Cambia la zona de la textura que se dibuja (coordenadas UV) y recalcula los vértices
+    pub fn set_region(&mut self, region: Rect) {
+        self.region = region;
+        self.calculate_vertices();
+    }
+
+    pub fn region(&self) -> Rect {
+        self.region
+    }
+
+    // Devuelve las esquinas del quad en espacio local (teniendo en cuenta el origen)
+    fn local_extents(&self) -> (f32, f32, f32, f32) {
+        local_extents(self.width, self.height, self.origin)
+    }
+
     pub fn calculate_vertices(&mut self) {
-        let min_x = -(self.width * self.origin.x);
-        let max_x = self.width * (1.0 - self.origin.x);
+        let (min_x, max_x, min_y, max_y) = self.local_extents();
 
-        let min_y = -(self.height * self.origin.y);
-        let max_y = self.height * (1.0 - self.origin.y);
+        let min_u = self.region.x;
+        let max_u = self.region.x + self.region.width;
+        let min_v = self.region.y;
+        let max_v = self.region.y + self.region.height;
 
-        self.vertices[0] = Vertex::new(min_x, min_y, 0.0);
-        self.vertices[1] = Vertex::new(min_x, max_y, 0.0);
-        self.vertices[2] = Vertex::new(max_x, max_y, 0.0);
+        self.vertices[0] = Vertex::new(min_x, min_y, 0.0, min_u, min_v);
+        self.vertices[1] = Vertex::new(min_x, max_y, 0.0, min_u, max_v);
+        self.vertices[2] = Vertex::new(max_x, max_y, 0.0, max_u, max_v);
 
-        self.vertices[3] = Vertex::new(max_x, max_y, 0.0);
-        self.vertices[4] = Vertex::new(max_x, min_y, 0.0);
-        self.vertices[5] = Vertex::new(min_x, min_y, 0.0);
+        self.vertices[3] = Vertex::new(max_x, max_y, 0.0, max_u, max_v);
+        self.vertices[4] = Vertex::new(max_x, min_y, 0.0, max_u, min_v);
+        self.vertices[5] = Vertex::new(min_x, min_y, 0.0, min_u, min_v);
 
 
         self.buffer.upload(
             &self.vertices
                 .iter()
-                .flat_map(|v| vec![v.position.x, v.position.y, v.position.z])
+                .flat_map(|v| vec![
+                    v.position.x, v.position.y, v.position.z,
+                    v.tex_coords.x, v.tex_coords.y,
+                ])
                 .collect::<Vec<f32>>()
         );
     }
 
+    // Rectángulo en espacio de mundo que contiene el sprite transformado.
+    // Sirve para picking con el ratón y para culling
+    pub fn bounds(&self, transform: &Transform) -> Rect {
+        world_bounds(self.local_extents(), transform)
+    }
+
     pub fn draw(&self, model: &Matrix4x4) {
-        let u_color_position = self.shader.get_uniform_location("u_color");   // uniform position
+        if let Some(material) = self.material {
+            self.draw_with_material(material, model);
+            return;
+        }
 
         unsafe {
             // Enviamos a OpenGL uniforms
@@ -108,8 +191,143 @@ impl<'a> Sprite<'a> {
                 self.color.b,
                 self.color.a,
             );
+
+            match self.texture {
+                Some(texture) => {
+                    texture.bind(0);
+                    gl::Uniform1i(self.u_diffuse_location, 0);
+                    gl::Uniform1i(self.u_textured_location, 1);
+                }
+                None => gl::Uniform1i(self.u_textured_location, 0),
+            }
+        }
+
+        self.buffer.draw();
+    }
+
+    // Activa el material y completa lo que no tenga con lo del sprite. Los uniforms
+    // que no existen en su shader tienen posición -1 y OpenGL los ignora
+    fn draw_with_material(&self, material: &Material, model: &Matrix4x4) {
+        material.bind();
+        let shader = material.shader();
+        let location = |name: &str| shader.uniform(name).map_or(-1, |uniform| uniform.location);
+
+        unsafe {
+            gl::UniformMatrix4fv(location("u_model"), 1, gl::FALSE, model.data.as_ptr());
+
+            if material.get("u_color").is_none() {
+                let color = self.color;
+                gl::Uniform4f(location("u_color"), color.r, color.g, color.b, color.a);
+            }
+
+            if material.texture("u_diffuse").is_none() {
+                match self.texture {
+                    Some(texture) => {
+                        // Detrás de las unidades que ocupa el material
+                        let unit = material.texture_count();
+                        texture.bind(unit);
+                        gl::Uniform1i(location("u_diffuse"), unit as i32);
+                        gl::Uniform1i(location("u_textured"), 1);
+                    }
+                    None => gl::Uniform1i(location("u_textured"), 0),
+                }
+            } else {
+                gl::Uniform1i(location("u_textured"), 1);
+            }
         }
 
         self.buffer.draw();
     }
-}
\ No newline at end of file
+}
+
+impl<'a> Drawable for Sprite<'a> {
+    fn draw(&self, model: &Matrix4x4) {
+        Sprite::draw(self, model);
+    }
+
+    fn blend(&self) -> Option<BlendMode> {
+        self.material.map(|material| material.blend)
+    }
+}
+
+// Esquinas del quad en espacio local: (min_x, max_x, min_y, max_y).
+// origin va de 0 a 1 en cada eje y marca el punto del quad que queda en la posición
+fn local_extents(width: f32, height: f32, origin: Vector3) -> (f32, f32, f32, f32) {
+    let min_x = -(width * origin.x);
+    let max_x = width * (1.0 - origin.x);
+
+    let min_y = -(height * origin.y);
+    let max_y = height * (1.0 - origin.y);
+
+    (min_x, max_x, min_y, max_y)
+}
+
+// Transforma las 4 esquinas y devuelve el rectángulo que las contiene
+fn world_bounds(extents: (f32, f32, f32, f32), transform: &Transform) -> Rect {
+    let (min_x, max_x, min_y, max_y) = extents;
+    let matrix = transform.get_transformation_matrix();
+
+    let corners: Vec<Vector2> = [(min_x, min_y), (max_x, min_y), (max_x, max_y), (min_x, max_y)]
+        .iter()
+        .map(|&(x, y)| {
+            let point = matrix.transform_point(Vector3::new(x, y, 0.0));
+            Vector2::new(point.x, point.y)
+        })
+        .collect();
+
+    Rect::from_points(&corners).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOLERANCE: f32 = 1e-4;
+
+    fn assert_rect_close(rect: Rect, x: f32, y: f32, width: f32, height: f32) {
+        assert!((rect.x - x).abs() < TOLERANCE, "x: {} != {}", rect.x, x);
+        assert!((rect.y - y).abs() < TOLERANCE, "y: {} != {}", rect.y, y);
+        assert!((rect.width - width).abs() < TOLERANCE, "width: {} != {}", rect.width, width);
+        assert!((rect.height - height).abs() < TOLERANCE, "height: {} != {}", rect.height, height);
+    }
+
+    #[test]
+    fn bounds_follow_position_and_scale() {
+        let mut transform = Transform::new();
+        transform.position = Vector3::new(10.0, 5.0, 0.0);
+        transform.scale = Vector3::new(2.0, 3.0, 1.0);
+
+        let bounds = world_bounds(local_extents(4.0, 2.0, Vector3::zero()), &transform);
+        assert_rect_close(bounds, 10.0, 5.0, 8.0, 6.0);
+    }
+
+    #[test]
+    fn bounds_are_centered_on_a_centered_origin() {
+        let bounds = world_bounds(local_extents(4.0, 2.0, Vector3::new(0.5, 0.5, 0.0)), &Transform::new());
+
+        assert_rect_close(bounds, -2.0, -1.0, 4.0, 2.0);
+    }
+
+    // Al girar el quad los límites crecen para seguir conteniendo todas las esquinas
+    #[test]
+    fn bounds_contain_rotated_corners() {
+        let mut transform = Transform::new();
+        transform.rotation = Vector3::new(0.0, 0.0, std::f32::consts::FRAC_PI_2);
+
+        let quarter = world_bounds(local_extents(2.0, 1.0, Vector3::zero()), &transform);
+        assert_rect_close(quarter, -1.0, 0.0, 1.0, 2.0);
+
+        transform.rotation = Vector3::new(0.0, 0.0, std::f32::consts::FRAC_PI_4);
+        let diagonal = world_bounds(local_extents(2.0, 2.0, Vector3::new(0.5, 0.5, 0.0)), &transform);
+        let half = 2.0f32.sqrt();
+        assert_rect_close(diagonal, -half, -half, half * 2.0, half * 2.0);
+    }
+
+    #[test]
+    fn empty_sprite_has_empty_bounds() {
+        let mut transform = Transform::new();
+        transform.position = Vector3::new(3.0, 4.0, 0.0);
+
+        assert_rect_close(world_bounds(local_extents(0.0, 0.0, Vector3::zero()), &transform), 3.0, 4.0, 0.0, 0.0);
+    }
+}
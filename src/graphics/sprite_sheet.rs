@@ -0,0 +1,83 @@
+use crate::gl_utility::texture::Texture;
+use crate::graphics::atlas::AtlasRegion;
+use crate::math::rect::Rect;
+use crate::math::vector3::Vector3;
+
+// Textura dividida en fotogramas. Los rectángulos van en píxeles de la textura
+pub struct SpriteSheet<'a> {
+    pub texture: &'a Texture,
+    frames: Vec<Rect>,
+    // Origen para Sprite::origin de cada fotograma, si lo tiene (fotogramas recortados de un atlas)
+    origins: Vec<Option<Vector3>>,
+}
+
+impl<'a> SpriteSheet<'a> {
+    // Recorre la textura por filas, de izquierda a derecha y de arriba a abajo.
+    // margin es el borde alrededor de la rejilla y spacing la separación entre fotogramas
+    pub fn from_grid(
+        texture: &'a Texture,
+        frame_width: u32, frame_height: u32,
+        margin: u32, spacing: u32,
+    ) -> SpriteSheet<'a> {
+        if frame_width == 0 || frame_height == 0 {
+            panic!("Los fotogramas de la hoja {} no pueden medir 0", texture.name);
+        }
+
+        let columns = (texture.width.saturating_sub(margin * 2) + spacing) / (frame_width + spacing);
+        let rows = (texture.height.saturating_sub(margin * 2) + spacing) / (frame_height + spacing);
+
+        let mut frames = Vec::with_capacity((columns * rows) as usize);
+        for row in 0..rows {
+            for column in 0..columns {
+                frames.push(Rect::new(
+                    (margin + column * (frame_width + spacing)) as f32,
+                    (margin + row * (frame_height + spacing)) as f32,
+                    frame_width as f32,
+                    frame_height as f32,
+                ));
+            }
+        }
+
+        let origins = vec![None; frames.len()];
+        SpriteSheet { texture, frames, origins }
+    }
+
+    pub fn from_rects(texture: &'a Texture, frames: Vec<Rect>) -> SpriteSheet<'a> {
+        let origins = vec![None; frames.len()];
+        SpriteSheet { texture, frames, origins }
+    }
+
+    // Un fotograma por región, con el origen que compensa el recorte
+    pub fn from_regions(texture: &'a Texture, regions: &[AtlasRegion]) -> SpriteSheet<'a> {
+        SpriteSheet {
+            texture,
+            frames: regions.iter().map(|r| r.frame).collect(),
+            origins: regions.iter().map(|r| Some(r.origin())).collect(),
+        }
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    // Rectángulo del fotograma en píxeles
+    pub fn frame(&self, index: usize) -> Rect {
+        match self.frames.get(index) {
+            Some(&frame) => frame,
+            _ => panic!("La hoja {} no tiene el fotograma {}", self.texture.name, index),
+        }
+    }
+
+    pub fn origin(&self, index: usize) -> Option<Vector3> {
+        self.origins.get(index).copied().flatten()
+    }
+
+    // Rectángulo del fotograma en coordenadas UV, listo para Sprite::set_region
+    pub fn uv(&self, index: usize) -> Rect {
+        let frame = self.frame(index);
+        let width = self.texture.width as f32;
+        let height = self.texture.height as f32;
+
+        Rect::new(frame.x / width, frame.y / height, frame.width / width, frame.height / height)
+    }
+}
\ No newline at end of file
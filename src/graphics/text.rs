@@ -0,0 +1,257 @@
+use std::ops::Range;
+
+use crate::gl_utility::gl_buffer::{GLBuffer, AttributeInfo};
+use crate::gl_utility::shader::Shader;
+use crate::gl_utility::texture::Texture;
+use crate::graphics::color::Color;
+use crate::graphics::font::{Font, GlyphSource, TextAlign};
+use crate::graphics::glyph_atlas::GlyphAtlas;
+use crate::graphics::render_queue::Drawable;
+use crate::math::matrix4x4::Matrix4x4;
+use crate::math::vector2::Vector2;
+
+// De dónde salen los caracteres y las texturas de sus páginas
+enum TextFont<'a> {
+    Bitmap(&'a Font, Vec<&'a Texture>),
+    Atlas(&'a GlyphAtlas),
+}
+
+impl<'a> TextFont<'a> {
+    fn source(&self) -> &dyn GlyphSource {
+        match self {
+            TextFont::Bitmap(font, _) => *font,
+            TextFont::Atlas(atlas) => *atlas,
+        }
+    }
+
+    fn page_count(&self) -> usize {
+        match self {
+            TextFont::Bitmap(_, pages) => pages.len(),
+            TextFont::Atlas(atlas) => atlas.page_count(),
+        }
+    }
+
+    fn bind_page(&self, page: usize) {
+        match self {
+            TextFont::Bitmap(_, pages) => pages[page].bind(0),
+            TextFont::Atlas(atlas) => atlas.bind_page(page, 0),
+        }
+    }
+}
+
+// Texto dibujado con una fuente BMFont o con un GlyphAtlas de una TrueType. Necesita el
+// shader de text.vert/text.frag (o text.vert/sdf_text.frag si el atlas es SDF), que además
+// de a_position y a_tex_coord tiene a_color para el color de cada carácter.
+// (0, 0) es la esquina superior izquierda del texto. Se usa un GLBuffer por página de
+// la fuente, así que una fuente de una página es una sola llamada de dibujo
+pub struct Text<'a> {
+    pub name: String,
+
+    text: String,
+    align: TextAlign,
+    max_width: Option<f32>,
+    // Tinte de todo el texto, se multiplica por el color de cada carácter
+    color: Color,
+    char_colors: Vec<Color>,
+    size: Vector2,
+
+    font: TextFont<'a>,
+    buffers: Vec<GLBuffer>,
+    loaded: bool,
+
+    u_color_location: i32,
+    u_model_location: i32,
+    u_diffuse_location: i32,
+
+    shader: &'a Shader,
+}
+
+impl<'a> Text<'a> {
+    // pages son las texturas de las páginas de la fuente, en el mismo orden
+    pub fn new(name: &str, shader: &'a Shader, font: &'a Font, pages: Vec<&'a Texture>) -> Text<'a> {
+        if pages.len() != font.pages.len() {
+            panic!(
+                "La fuente {} tiene {} páginas y se han pasado {} texturas",
+                font.face, font.pages.len(), pages.len()
+            );
+        }
+
+        Text::with_font(name, shader, TextFont::Bitmap(font, pages))
+    }
+
+    // Texto con una fuente TrueType: los caracteres que falten se rasterizan al cambiar el texto
+    pub fn from_atlas(name: &str, shader: &'a Shader, atlas: &'a GlyphAtlas) -> Text<'a> {
+        Text::with_font(name, shader, TextFont::Atlas(atlas))
+    }
+
+    fn with_font(name: &str, shader: &'a Shader, font: TextFont<'a>) -> Text<'a> {
+        Text {
+            name: String::from(name),
+
+            text: String::new(),
+            align: TextAlign::Left,
+            max_width: None,
+            color: Color::white(),
+            char_colors: Vec::new(),
+            size: Vector2::zero(),
+
+            font,
+            buffers: Vec::new(),
+            loaded: false,
+
+            u_color_location: shader.get_uniform_location("u_color"),
+            u_model_location: shader.get_uniform_location("u_model"),
+            u_diffuse_location: shader.get_uniform_location("u_diffuse"),
+
+            shader,
+        }
+    }
+
+    pub fn load(&mut self) {
+        self.loaded = true;
+        self.rebuild();
+    }
+
+    // Un GLBuffer configurado por página (las de un GlyphAtlas pueden ir apareciendo)
+    fn ensure_buffers(&mut self, count: usize) {
+        if self.buffers.len() >= count {
+            return;
+        }
+
+        let a_position_location = self.shader.get_attribute_location("a_position");
+        let a_tex_coord_location = self.shader.get_attribute_location("a_tex_coord");
+        let a_color_location = self.shader.get_attribute_location("a_color");
+
+        while self.buffers.len() < count {
+            let mut buffer = GLBuffer::new();
+            buffer.configure(
+                vec![
+                    AttributeInfo {
+                        location: a_position_location,
+                        component_size: 3,
+                    },
+                    AttributeInfo {
+                        location: a_tex_coord_location,
+                        component_size: 2,
+                    },
+                    AttributeInfo {
+                        location: a_color_location,
+                        component_size: 4,
+                    },
+                ],
+                false,
+            );
+            self.buffers.push(buffer);
+        }
+    }
+
+    // Cambia el texto. Los colores por carácter vuelven a blanco
+    pub fn set_text(&mut self, text: &str) {
+        self.text = String::from(text);
+        self.char_colors = vec![Color::white(); text.chars().count()];
+        self.rebuild();
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn set_align(&mut self, align: TextAlign) {
+        self.align = align;
+        self.rebuild();
+    }
+
+    pub fn align(&self) -> TextAlign {
+        self.align
+    }
+
+    // Anchura a partir de la que se parten las líneas por palabras (None para no partir)
+    pub fn set_max_width(&mut self, max_width: Option<f32>) {
+        self.max_width = max_width;
+        self.rebuild();
+    }
+
+    pub fn max_width(&self) -> Option<f32> {
+        self.max_width
+    }
+
+    pub fn set_color(&mut self, color: Color) {
+        self.color = color;
+    }
+
+    pub fn color(&self) -> Color {
+        self.color
+    }
+
+    // Colorea los caracteres del rango (índices en caracteres, no en bytes)
+    pub fn set_range_color(&mut self, range: Range<usize>, color: Color) {
+        let end = range.end.min(self.char_colors.len());
+        for char_color in &mut self.char_colors[range.start.min(end)..end] {
+            *char_color = color;
+        }
+        self.rebuild();
+    }
+
+    // Tamaño del texto en píxeles (la anchura es max_width si lo hay)
+    pub fn size(&self) -> Vector2 {
+        self.size
+    }
+
+    fn rebuild(&mut self) {
+        let (width, height) = self.font.source().measure(&self.text, self.max_width);
+        self.size = Vector2::new(self.max_width.unwrap_or(width), height);
+
+        if !self.loaded {
+            return;
+        }
+
+        // Con un atlas, maquetar puede rasterizar caracteres y abrir páginas nuevas
+        let layout = self.font.source().layout(&self.text, self.max_width, self.align);
+        let page_count = self.font.page_count();
+        self.ensure_buffers(page_count);
+
+        let mut data: Vec<Vec<f32>> = vec![Vec::new(); page_count];
+        for placed in layout {
+            let color = self.char_colors.get(placed.index).copied().unwrap_or_else(Color::white);
+            let (min_x, min_y) = (placed.rect.x, placed.rect.y);
+            let (max_x, max_y) = (min_x + placed.rect.width, min_y + placed.rect.height);
+            let (min_u, min_v) = (placed.uv.x, placed.uv.y);
+            let (max_u, max_v) = (min_u + placed.uv.width, min_v + placed.uv.height);
+
+            let quad = [
+                (min_x, min_y, min_u, min_v),
+                (min_x, max_y, min_u, max_v),
+                (max_x, max_y, max_u, max_v),
+                (max_x, max_y, max_u, max_v),
+                (max_x, min_y, max_u, min_v),
+                (min_x, min_y, min_u, min_v),
+            ];
+            for &(x, y, u, v) in quad.iter() {
+                data[placed.page].extend_from_slice(&[x, y, 0.0, u, v, color.r, color.g, color.b, color.a]);
+            }
+        }
+
+        for (buffer, data) in self.buffers.iter_mut().zip(data) {
+            buffer.upload(&data);
+        }
+    }
+
+    pub fn draw(&self, model: &Matrix4x4) {
+        unsafe {
+            gl::UniformMatrix4fv(self.u_model_location, 1, gl::FALSE, model.data.as_ptr());
+            gl::Uniform4f(self.u_color_location, self.color.r, self.color.g, self.color.b, self.color.a);
+            gl::Uniform1i(self.u_diffuse_location, 0);
+        }
+
+        for (page, buffer) in self.buffers.iter().enumerate().take(self.font.page_count()) {
+            self.font.bind_page(page);
+            buffer.draw();
+        }
+    }
+}
+
+impl<'a> Drawable for Text<'a> {
+    fn draw(&self, model: &Matrix4x4) {
+        Text::draw(self, model);
+    }
+}
@@ -0,0 +1,147 @@
+use serde_json::Value;
+
+use crate::graphics::atlas::{TextureAtlas, parse_frames, parse_meta};
+use crate::graphics::animation::{Animation, PlayMode};
+use crate::math::vector2::Vector2;
+
+// Importa el formato "JSON (Hash)" o "JSON (Array)" de TexturePacker. El pivote de
+// cada fotograma viene ya normalizado. TexturePacker no guarda duraciones, así que
+// las animaciones (la clave "animations" que añade el exportador de Pixi) usan
+// frame_duration segundos por fotograma
+pub fn parse(json: &str, frame_duration: f32) -> Result<TextureAtlas, String> {
+    let root: Value = serde_json::from_str(json)
+        .map_err(|e| format!("JSON de TexturePacker no válido: {}", e))?;
+
+    let (image, width, height) = parse_meta(&root)?;
+    let frames = parse_frames(&root)?;
+
+    let regions: Vec<_> = frames
+        .iter()
+        .map(|frame| frame.to_region(width as f32, height as f32, frame.pivot.unwrap_or_else(Vector2::zero)))
+        .collect();
+
+    let mut clips = Vec::new();
+    if let Some(animations) = root.get("animations").and_then(Value::as_object) {
+        for (name, frame_names) in animations {
+            let frame_names = frame_names
+                .as_array()
+                .ok_or_else(|| format!("La animación {} no es una lista de fotogramas", name))?;
+
+            let mut animation = Animation::new(name, PlayMode::Loop);
+            for frame_name in frame_names {
+                let frame_name = frame_name.as_str().unwrap_or("");
+                let index = regions
+                    .iter()
+                    .position(|r| r.name == frame_name)
+                    .ok_or_else(|| format!("La animación {} usa el fotograma {} que no existe", name, frame_name))?;
+                animation.add_frame(index, frame_duration);
+            }
+            clips.push(animation);
+        }
+    }
+
+    Ok(TextureAtlas {
+        image,
+        width,
+        height,
+
+        regions,
+        clips,
+    })
+}
+
+pub fn load(path: &str, frame_duration: f32) -> Result<TextureAtlas, String> {
+    let json = std::fs::read_to_string(path)
+        .map_err(|e| format!("No se puede leer {}: {}", path, e))?;
+    parse(&json, frame_duration)
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH: &str = r#"{
+        "frames": {
+            "run_0.png": {
+                "frame": {"x": 0, "y": 0, "w": 20, "h": 30},
+                "rotated": false, "trimmed": true,
+                "spriteSourceSize": {"x": 6, "y": 2, "w": 20, "h": 30},
+                "sourceSize": {"w": 32, "h": 32},
+                "pivot": {"x": 0.5, "y": 1}
+            },
+            "run_1.png": {
+                "frame": {"x": 20, "y": 0, "w": 32, "h": 32},
+                "rotated": false, "trimmed": false,
+                "spriteSourceSize": {"x": 0, "y": 0, "w": 32, "h": 32},
+                "sourceSize": {"w": 32, "h": 32}
+            }
+        },
+        "animations": {
+            "run": ["run_0.png", "run_1.png", "run_0.png"]
+        },
+        "meta": {"image": "run.png", "size": {"w": 64, "h": 32}}
+    }"#;
+
+    const ARRAY: &str = r#"{
+        "frames": [
+            {"filename": "idle.png", "frame": {"x": 0, "y": 0, "w": 16, "h": 16}, "pivot": {"x": 0.25, "y": 0.75}}
+        ],
+        "meta": {"image": "idle.png", "size": {"w": 16, "h": 16}}
+    }"#;
+
+    #[test]
+    fn parses_hash_layout_with_pivots() {
+        let atlas = parse(HASH, 0.1).unwrap();
+
+        assert_eq!(atlas.regions.len(), 2);
+        let first = atlas.region("run_0.png").unwrap();
+        assert_eq!((first.pivot.x, first.pivot.y), (0.5, 1.0));
+        assert_eq!((first.trim_offset.x, first.trim_offset.y), (6.0, 2.0));
+
+        // Sin pivote en el JSON se usa la esquina superior izquierda
+        let second = atlas.region("run_1.png").unwrap();
+        assert_eq!((second.pivot.x, second.pivot.y), (0.0, 0.0));
+    }
+
+    // Pivote (16, 32) en la imagen de 32x32, que en el quad recortado es (10, 30)
+    #[test]
+    fn trimmed_origin_keeps_the_pivot() {
+        let atlas = parse(HASH, 0.1).unwrap();
+
+        let origin = atlas.region("run_0.png").unwrap().origin();
+        assert_eq!((origin.x, origin.y), (0.5, 1.0));
+    }
+
+    #[test]
+    fn parses_array_layout() {
+        let atlas = parse(ARRAY, 0.1).unwrap();
+
+        let region = atlas.region("idle.png").unwrap();
+        assert_eq!((region.pivot.x, region.pivot.y), (0.25, 0.75));
+        assert_eq!((region.uv.width, region.uv.height), (1.0, 1.0));
+    }
+
+    #[test]
+    fn animations_use_the_fixed_frame_duration() {
+        let atlas = parse(HASH, 0.125).unwrap();
+
+        let run = atlas.clip("run").unwrap();
+        assert_eq!(run.mode, PlayMode::Loop);
+        let frames: Vec<(usize, f32)> = run.frames.iter().map(|f| (f.frame, f.duration)).collect();
+        assert_eq!(frames, vec![(0, 0.125), (1, 0.125), (0, 0.125)]);
+    }
+
+    #[test]
+    fn rejects_animations_with_unknown_frames() {
+        let json = HASH.replace(r#""run_1.png", "run_0.png"]"#, r#""run_9.png"]"#);
+
+        let error = parse(&json, 0.1).err().unwrap();
+        assert!(error.contains("run_9.png"), "{}", error);
+    }
+
+    #[test]
+    fn rejects_rotated_frames() {
+        let json = HASH.replace(r#""rotated": false, "trimmed": true"#, r#""rotated": true, "trimmed": true"#);
+
+        assert!(parse(&json, 0.1).is_err());
+    }
+}
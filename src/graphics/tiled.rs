@@ -0,0 +1,909 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::io::Read;
+use std::path::Path;
+
+use base64::Engine;
+use roxmltree::Node;
+use serde_json::Value;
+
+use crate::graphics::color::Color;
+use crate::graphics::tilemap::{
+    Layer, MapObject, ObjectLayer, ObjectShape, Properties, PropertyValue, Tile, TileData, TileFrame,
+    TileLayer, Tilemap, Tileset,
+};
+use crate::math::vector2::Vector2;
+
+// Importador de mapas de Tiled (https://www.mapeditor.org) en TMX (XML) y JSON.
+// Sólo mapas ortogonales y no infinitos, con tilesets de una sola imagen. Los tilesets
+// externos (.tsx o .tsj/.json) se leen de disco respecto a base_dir
+
+// Lee un .tmx o un .json según la extensión
+pub fn load(path: &str) -> Result<Tilemap, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("No se puede leer {}: {}", path, e))?;
+    let base_dir = Path::new(path).parent().unwrap_or_else(|| Path::new(""));
+
+    if path.to_lowercase().ends_with(".tmx") {
+        parse_tmx(&text, base_dir)
+    } else {
+        parse_json(&text, base_dir)
+    }
+}
+
+// Desplazamiento y visibilidad que los grupos pasan a sus capas
+#[derive(Copy, Clone)]
+struct Inherited {
+    offset: Vector2,
+    opacity: f32,
+    visible: bool,
+}
+
+impl Inherited {
+    fn root() -> Inherited {
+        Inherited {
+            offset: Vector2::zero(),
+            opacity: 1.0,
+            visible: true,
+        }
+    }
+
+    fn child(&self, offset: Vector2, opacity: f32, visible: bool) -> Inherited {
+        Inherited {
+            offset: self.offset + offset,
+            opacity: self.opacity * opacity,
+            visible: self.visible && visible,
+        }
+    }
+}
+
+fn check_map(orientation: &str, infinite: bool) -> Result<(), String> {
+    if orientation != "orthogonal" {
+        return Err(format!("Sólo se admiten mapas ortogonales (el mapa es {})", orientation));
+    }
+    if infinite {
+        return Err(String::from("Los mapas infinitos no están soportados"));
+    }
+    Ok(())
+}
+
+// Máximo de tiles por capa, para que un archivo corrupto no reserve memoria sin límite
+const MAX_LAYER_TILES: u32 = 1 << 26;
+
+fn tile_area(what: &str, width: u32, height: u32) -> Result<usize, String> {
+    match width.checked_mul(height) {
+        Some(area) if area <= MAX_LAYER_TILES => Ok(area as usize),
+        _ => Err(format!("{} de {}x{} tiles es demasiado grande", what, width, height)),
+    }
+}
+
+fn check_map_size(map: &Tilemap) -> Result<(), String> {
+    tile_area("El mapa", map.width, map.height)?;
+    // El tamaño en píxeles también tiene que caber en un u32
+    if map.width.checked_mul(map.tile_width).is_none() || map.height.checked_mul(map.tile_height).is_none() {
+        return Err(format!(
+            "El mapa de {}x{} tiles de {}x{} es demasiado grande",
+            map.width, map.height, map.tile_width, map.tile_height
+        ));
+    }
+    Ok(())
+}
+
+fn resolve(base_dir: &Path, relative: &str) -> String {
+    base_dir.join(relative).to_string_lossy().into_owned()
+}
+
+// Tiled guarda los colores como "#AARRGGBB" o "#RRGGBB"
+fn parse_color(text: &str) -> Option<Color> {
+    let text = text.trim();
+    let hex = text.strip_prefix('#').unwrap_or(text);
+    if !hex.is_ascii() {
+        return None;
+    }
+    if hex.len() == 8 {
+        Color::from_hex(&format!("{}{}", &hex[2..], &hex[..2]))
+    } else {
+        Color::from_hex(hex)
+    }
+}
+
+fn property_value(kind: &str, text: &str) -> Result<PropertyValue, String> {
+    let invalid = || format!("Valor de propiedad {} no válido: {}", kind, text);
+
+    Ok(match kind {
+        "int" => PropertyValue::Int(text.parse().map_err(|_| invalid())?),
+        "float" => PropertyValue::Float(text.parse().map_err(|_| invalid())?),
+        "bool" => PropertyValue::Bool(text == "true"),
+        // Un color vacío es "sin color", lo dejamos transparente
+        "color" if text.is_empty() => PropertyValue::Color(Color::transparent()),
+        "color" => PropertyValue::Color(parse_color(text).ok_or_else(invalid)?),
+        "file" => PropertyValue::File(String::from(text)),
+        "object" => PropertyValue::Object(text.parse().map_err(|_| invalid())?),
+        _ => PropertyValue::String(String::from(text)),
+    })
+}
+
+// Convierte los datos de una capa (CSV o base64, con o sin compresión) en tiles.
+// count es el número de tiles que tiene que tener la capa
+fn decode_tiles(text: &str, encoding: Option<&str>, compression: Option<&str>, count: usize) -> Result<Vec<Tile>, String> {
+    let raw: Vec<u32> = match encoding {
+        None | Some("csv") => text
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse::<u32>().map_err(|_| format!("Tile no válido en la capa: {}", s)))
+            .collect::<Result<_, _>>()?,
+        Some("base64") => {
+            let bytes = base64::engine::general_purpose::STANDARD
+                .decode(text.trim())
+                .map_err(|e| format!("Base64 no válido en la capa: {}", e))?;
+            let bytes = decompress(bytes, compression, count * 4)?;
+            bytes
+                .chunks_exact(4)
+                .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .collect()
+        }
+        Some(other) => return Err(format!("Codificación de capa no soportada: {}", other)),
+    };
+
+    Ok(raw.into_iter().map(Tile::from_raw).collect())
+}
+
+// Se lee como mucho un byte más de lo esperado: unos pocos bytes comprimidos pueden
+// ocupar gigas al descomprimirlos, y así un archivo malicioso no agota la memoria
+fn decompress(bytes: Vec<u8>, compression: Option<&str>, expected: usize) -> Result<Vec<u8>, String> {
+    let limit = expected as u64 + 1;
+    let mut out = Vec::new();
+    let result = match compression {
+        None | Some("") => return Ok(bytes),
+        Some("zlib") => flate2::read::ZlibDecoder::new(&bytes[..]).take(limit).read_to_end(&mut out),
+        Some("gzip") => flate2::read::GzDecoder::new(&bytes[..]).take(limit).read_to_end(&mut out),
+        Some(other) => return Err(format!("Compresión de capa no soportada: {}", other)),
+    };
+
+    result.map_err(|e| format!("No se puede descomprimir la capa: {}", e))?;
+    if out.len() > expected {
+        return Err(format!("La capa descomprimida ocupa más de los {} bytes esperados", expected));
+    }
+    if out.len() < expected {
+        return Err(format!("La capa descomprimida ocupa {} bytes en vez de {}", out.len(), expected));
+    }
+    Ok(out)
+}
+
+fn check_layer_size(name: &str, tiles: &[Tile], width: u32, height: u32) -> Result<(), String> {
+    if tiles.len() != tile_area(&format!("La capa {}", name), width, height)? {
+        return Err(format!(
+            "La capa {} de {}x{} tiene {} tiles",
+            name, width, height, tiles.len()
+        ));
+    }
+    Ok(())
+}
+
+// Además completa columns y tile_count si el archivo no los trae, a partir del tamaño
+// de la imagen; con tile_count a 0 ningún tile del tileset se vería
+fn check_tileset(tileset: &mut Tileset) -> Result<(), String> {
+    if tileset.image.is_empty() {
+        return Err(format!(
+            "El tileset {} no tiene imagen (las colecciones de imágenes no están soportadas)",
+            tileset.name
+        ));
+    }
+    if tileset.tile_width == 0 || tileset.tile_height == 0 {
+        return Err(format!("El tileset {} tiene tiles de tamaño 0", tileset.name));
+    }
+
+    // Tiles que caben en size píxeles, descontando los márgenes y la separación
+    let (margin, spacing) = (tileset.margin, tileset.spacing);
+    let fit = |size: u32, tile: u32| {
+        size.saturating_sub(margin.saturating_mul(2)).saturating_add(spacing) / tile.saturating_add(spacing)
+    };
+
+    if tileset.columns == 0 {
+        tileset.columns = fit(tileset.image_width, tileset.tile_width);
+    }
+    if tileset.tile_count == 0 {
+        tileset.tile_count = tileset.columns.saturating_mul(fit(tileset.image_height, tileset.tile_height));
+    }
+    if tileset.tile_count == 0 {
+        return Err(format!(
+            "El tileset {} no tiene tilecount y no se puede calcular sin el tamaño de la imagen",
+            tileset.name
+        ));
+    }
+    if tileset.first_gid.checked_add(tileset.tile_count).is_none() {
+        return Err(format!("El tileset {} tiene demasiados tiles", tileset.name));
+    }
+    Ok(())
+}
+
+// ------------------------------------------------------------------ TMX
+
+pub fn parse_tmx(xml: &str, base_dir: &Path) -> Result<Tilemap, String> {
+    let document = roxmltree::Document::parse(xml).map_err(|e| format!("TMX no válido: {}", e))?;
+    let root = document.root_element();
+    if !root.has_tag_name("map") {
+        return Err(String::from("El TMX no tiene <map>"));
+    }
+
+    check_map(root.attribute("orientation").unwrap_or("orthogonal"), attribute(root, "infinite", 0)? != 0)?;
+
+    let mut map = Tilemap::new(
+        required(root, "width")?,
+        required(root, "height")?,
+        required(root, "tilewidth")?,
+        required(root, "tileheight")?,
+    );
+    check_map_size(&map)?;
+    map.background = root.attribute("backgroundcolor").and_then(parse_color);
+    map.properties = xml_properties(root)?;
+
+    for tileset in root.children().filter(|n| n.has_tag_name("tileset")) {
+        let first_gid = required(tileset, "firstgid")?;
+        map.tilesets.push(match tileset.attribute("source") {
+            Some(source) => load_external_tileset(&base_dir.join(source), first_gid)?,
+            None => xml_tileset(tileset, first_gid, base_dir)?,
+        });
+    }
+
+    xml_layers(root, Inherited::root(), &mut map.layers)?;
+    Ok(map)
+}
+
+fn required<T: std::str::FromStr>(node: Node, name: &str) -> Result<T, String> {
+    let text = node
+        .attribute(name)
+        .ok_or_else(|| format!("Falta el atributo {} en <{}>", name, node.tag_name().name()))?;
+    text.parse().map_err(|_| format!("Atributo {} no válido en <{}>: {}", name, node.tag_name().name(), text))
+}
+
+fn attribute<T: std::str::FromStr>(node: Node, name: &str, default: T) -> Result<T, String> {
+    match node.attribute(name) {
+        Some(_) => required(node, name),
+        None => Ok(default),
+    }
+}
+
+fn xml_properties(node: Node) -> Result<Properties, String> {
+    let mut properties = Properties::new();
+    let list = match node.children().find(|n| n.has_tag_name("properties")) {
+        Some(list) => list,
+        None => return Ok(properties),
+    };
+
+    for property in list.children().filter(|n| n.has_tag_name("property")) {
+        let name = property.attribute("name").ok_or("Propiedad sin nombre")?;
+        let kind = property.attribute("type").unwrap_or("string");
+
+        let value = if kind == "class" {
+            PropertyValue::Class(xml_properties(property)?)
+        } else {
+            // Los textos de varias líneas van como contenido en lugar de en value
+            let text = property.attribute("value").or_else(|| property.text()).unwrap_or("");
+            property_value(kind, text)?
+        };
+        properties.insert(String::from(name), value);
+    }
+
+    Ok(properties)
+}
+
+fn xml_tileset(node: Node, first_gid: u32, base_dir: &Path) -> Result<Tileset, String> {
+    let image = node.children().find(|n| n.has_tag_name("image"));
+
+    let mut tileset = Tileset {
+        name: node.attribute("name").unwrap_or("").to_string(),
+        first_gid,
+
+        tile_width: required(node, "tilewidth")?,
+        tile_height: required(node, "tileheight")?,
+        margin: attribute(node, "margin", 0)?,
+        spacing: attribute(node, "spacing", 0)?,
+        columns: attribute(node, "columns", 0)?,
+        tile_count: attribute(node, "tilecount", 0)?,
+
+        image: image
+            .and_then(|i| i.attribute("source"))
+            .map(|source| resolve(base_dir, source))
+            .unwrap_or_default(),
+        image_width: match image {
+            Some(image) => attribute(image, "width", 0)?,
+            None => 0,
+        },
+        image_height: match image {
+            Some(image) => attribute(image, "height", 0)?,
+            None => 0,
+        },
+
+        tiles: HashMap::new(),
+        properties: xml_properties(node)?,
+    };
+    check_tileset(&mut tileset)?;
+
+    for tile in node.children().filter(|n| n.has_tag_name("tile")) {
+        let id: u32 = required(tile, "id")?;
+        let mut animation = Vec::new();
+        if let Some(frames) = tile.children().find(|n| n.has_tag_name("animation")) {
+            for frame in frames.children().filter(|n| n.has_tag_name("frame")) {
+                animation.push(TileFrame {
+                    tile: required(frame, "tileid")?,
+                    duration: required::<f32>(frame, "duration")? / 1000.0,
+                });
+            }
+        }
+
+        tileset.tiles.insert(id, TileData {
+            // Desde Tiled 1.9 "type" se llama "class"
+            kind: tile.attribute("class").or_else(|| tile.attribute("type")).unwrap_or("").to_string(),
+            properties: xml_properties(tile)?,
+            animation,
+        });
+    }
+
+    Ok(tileset)
+}
+
+fn load_external_tileset(path: &Path, first_gid: u32) -> Result<Tileset, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("No se puede leer el tileset {}: {}", path.display(), e))?;
+    let base_dir = path.parent().unwrap_or_else(|| Path::new(""));
+
+    let is_tsx = path.extension().is_some_and(|e| e.eq_ignore_ascii_case("tsx"));
+    if is_tsx {
+        let document = roxmltree::Document::parse(&text)
+            .map_err(|e| format!("TSX no válido ({}): {}", path.display(), e))?;
+        xml_tileset(document.root_element(), first_gid, base_dir)
+    } else {
+        let root: Value = serde_json::from_str(&text)
+            .map_err(|e| format!("Tileset JSON no válido ({}): {}", path.display(), e))?;
+        json_tileset(&root, first_gid, base_dir)
+    }
+}
+
+fn xml_common(node: Node, inherited: Inherited) -> Result<Inherited, String> {
+    Ok(inherited.child(
+        Vector2::new(attribute(node, "offsetx", 0.0)?, attribute(node, "offsety", 0.0)?),
+        attribute(node, "opacity", 1.0)?,
+        attribute::<u32>(node, "visible", 1)? != 0,
+    ))
+}
+
+fn xml_layers(parent: Node, inherited: Inherited, layers: &mut Vec<Layer>) -> Result<(), String> {
+    for node in parent.children().filter(Node::is_element) {
+        match node.tag_name().name() {
+            "layer" => layers.push(Layer::Tiles(xml_tile_layer(node, inherited)?)),
+            "objectgroup" => layers.push(Layer::Objects(xml_object_layer(node, inherited)?)),
+            "group" => xml_layers(node, xml_common(node, inherited)?, layers)?,
+            // Las capas de imagen no se importan
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn xml_tile_layer(node: Node, inherited: Inherited) -> Result<TileLayer, String> {
+    let common = xml_common(node, inherited)?;
+    let name = node.attribute("name").unwrap_or("");
+    let width = required(node, "width")?;
+    let height = required(node, "height")?;
+
+    let area = tile_area(&format!("La capa {}", name), width, height)?;
+
+    let data = node.children().find(|n| n.has_tag_name("data"));
+    let tiles = match data {
+        // Formato antiguo: un <tile gid="..."/> por celda
+        Some(data) if data.attribute("encoding").is_none() => data
+            .children()
+            .filter(|n| n.has_tag_name("tile"))
+            .map(|tile| attribute(tile, "gid", 0).map(Tile::from_raw))
+            .collect::<Result<Vec<Tile>, String>>()?,
+        Some(data) => decode_tiles(
+            data.text().unwrap_or(""),
+            data.attribute("encoding"),
+            data.attribute("compression"),
+            area,
+        )?,
+        None => vec![Tile::empty(); area],
+    };
+    check_layer_size(name, &tiles, width, height)?;
+
+    Ok(TileLayer {
+        name: String::from(name),
+        width,
+        height,
+        tiles,
+
+        visible: common.visible,
+        opacity: common.opacity,
+        offset: common.offset,
+        properties: xml_properties(node)?,
+    })
+}
+
+fn xml_object_layer(node: Node, inherited: Inherited) -> Result<ObjectLayer, String> {
+    let common = xml_common(node, inherited)?;
+
+    let objects = node
+        .children()
+        .filter(|n| n.has_tag_name("object"))
+        .map(xml_object)
+        .collect::<Result<Vec<MapObject>, String>>()?;
+
+    Ok(ObjectLayer {
+        name: node.attribute("name").unwrap_or("").to_string(),
+        objects,
+
+        visible: common.visible,
+        opacity: common.opacity,
+        offset: common.offset,
+        properties: xml_properties(node)?,
+    })
+}
+
+fn xml_object(node: Node) -> Result<MapObject, String> {
+    let child = |name: &str| node.children().find(|n| n.has_tag_name(name));
+
+    let shape = if child("ellipse").is_some() {
+        ObjectShape::Ellipse
+    } else if child("point").is_some() {
+        ObjectShape::Point
+    } else if let Some(polygon) = child("polygon") {
+        ObjectShape::Polygon(parse_points(polygon.attribute("points").unwrap_or(""))?)
+    } else if let Some(polyline) = child("polyline") {
+        ObjectShape::Polyline(parse_points(polyline.attribute("points").unwrap_or(""))?)
+    } else {
+        ObjectShape::Rectangle
+    };
+
+    Ok(MapObject {
+        id: attribute(node, "id", 0)?,
+        name: node.attribute("name").unwrap_or("").to_string(),
+        kind: node.attribute("class").or_else(|| node.attribute("type")).unwrap_or("").to_string(),
+
+        position: Vector2::new(attribute(node, "x", 0.0)?, attribute(node, "y", 0.0)?),
+        width: attribute(node, "width", 0.0)?,
+        height: attribute(node, "height", 0.0)?,
+        rotation: attribute(node, "rotation", 0.0)?,
+
+        shape,
+        tile: node.attribute("gid").map(|_| required(node, "gid").map(Tile::from_raw)).transpose()?,
+
+        visible: attribute::<u32>(node, "visible", 1)? != 0,
+        properties: xml_properties(node)?,
+    })
+}
+
+// Puntos de un polígono en TMX: "x1,y1 x2,y2 ..."
+fn parse_points(text: &str) -> Result<Vec<Vector2>, String> {
+    text.split_whitespace()
+        .map(|pair| {
+            let mut parts = pair.split(',').map(str::parse::<f32>);
+            match (parts.next(), parts.next()) {
+                (Some(Ok(x)), Some(Ok(y))) => Ok(Vector2::new(x, y)),
+                _ => Err(format!("Punto de polígono no válido: {}", pair)),
+            }
+        })
+        .collect()
+}
+
+// ------------------------------------------------------------------ JSON
+
+pub fn parse_json(json: &str, base_dir: &Path) -> Result<Tilemap, String> {
+    let root: Value = serde_json::from_str(json).map_err(|e| format!("JSON de Tiled no válido: {}", e))?;
+
+    check_map(str_field(&root, "orientation").unwrap_or("orthogonal"), bool_field(&root, "infinite", false))?;
+
+    let mut map = Tilemap::new(
+        u32_field(&root, "width")?,
+        u32_field(&root, "height")?,
+        u32_field(&root, "tilewidth")?,
+        u32_field(&root, "tileheight")?,
+    );
+    check_map_size(&map)?;
+    map.background = str_field(&root, "backgroundcolor").and_then(parse_color);
+    map.properties = json_properties(&root)?;
+
+    for tileset in root.get("tilesets").and_then(Value::as_array).into_iter().flatten() {
+        let first_gid = u32_field(tileset, "firstgid")?;
+        map.tilesets.push(match str_field(tileset, "source") {
+            Some(source) => load_external_tileset(&base_dir.join(source), first_gid)?,
+            None => json_tileset(tileset, first_gid, base_dir)?,
+        });
+    }
+
+    json_layers(&root, Inherited::root(), &mut map.layers)?;
+    Ok(map)
+}
+
+fn str_field<'v>(value: &'v Value, key: &str) -> Option<&'v str> {
+    value.get(key).and_then(Value::as_str)
+}
+
+fn f32_field(value: &Value, key: &str, default: f32) -> f32 {
+    value.get(key).and_then(Value::as_f64).map(|n| n as f32).unwrap_or(default)
+}
+
+fn bool_field(value: &Value, key: &str, default: bool) -> bool {
+    value.get(key).and_then(Value::as_bool).unwrap_or(default)
+}
+
+fn u32_field(value: &Value, key: &str) -> Result<u32, String> {
+    match value.get(key) {
+        Some(field) => json_u32(key, field),
+        None => Err(format!("Falta el campo \"{}\"", key)),
+    }
+}
+
+fn u32_field_or(value: &Value, key: &str, default: u32) -> Result<u32, String> {
+    value.get(key).map_or(Ok(default), |field| json_u32(key, field))
+}
+
+// Los números negativos, con decimales o mayores que u32::MAX son un error, no se truncan
+fn json_u32(key: &str, field: &Value) -> Result<u32, String> {
+    field
+        .as_u64()
+        .and_then(|n| u32::try_from(n).ok())
+        .ok_or_else(|| format!("El campo \"{}\" no es un entero válido: {}", key, field))
+}
+
+// En JSON las propiedades son un array de {name, type, value}
+fn json_properties(value: &Value) -> Result<Properties, String> {
+    let mut properties = Properties::new();
+
+    for property in value.get("properties").and_then(Value::as_array).into_iter().flatten() {
+        let name = str_field(property, "name").ok_or("Propiedad sin nombre")?;
+        let kind = str_field(property, "type").unwrap_or("string");
+        let value = property.get("value").unwrap_or(&Value::Null);
+
+        let value = match (kind, value) {
+            ("class", _) => PropertyValue::Class(json_class_members(value)),
+            (_, Value::String(text)) => property_value(kind, text)?,
+            ("float", _) => PropertyValue::Float(value.as_f64().unwrap_or(0.0)),
+            ("int", _) => PropertyValue::Int(value.as_i64().unwrap_or(0)),
+            ("object", _) => PropertyValue::Object(value.as_u64().unwrap_or(0) as u32),
+            ("bool", _) => PropertyValue::Bool(value.as_bool().unwrap_or(false)),
+            _ => PropertyValue::String(value.to_string()),
+        };
+        properties.insert(String::from(name), value);
+    }
+
+    Ok(properties)
+}
+
+// Los miembros de una clase van como un objeto sin tipos, así que se deducen del valor
+fn json_class_members(value: &Value) -> Properties {
+    let mut properties = Properties::new();
+
+    for (name, member) in value.as_object().into_iter().flatten() {
+        let member = match member {
+            Value::Bool(b) => PropertyValue::Bool(*b),
+            Value::Number(n) if n.is_i64() => PropertyValue::Int(n.as_i64().unwrap_or(0)),
+            Value::Number(n) => PropertyValue::Float(n.as_f64().unwrap_or(0.0)),
+            Value::String(text) => PropertyValue::String(text.clone()),
+            Value::Object(_) => PropertyValue::Class(json_class_members(member)),
+            other => PropertyValue::String(other.to_string()),
+        };
+        properties.insert(name.clone(), member);
+    }
+
+    properties
+}
+
+fn json_tileset(value: &Value, first_gid: u32, base_dir: &Path) -> Result<Tileset, String> {
+    let mut tileset = Tileset {
+        name: str_field(value, "name").unwrap_or("").to_string(),
+        first_gid,
+
+        tile_width: u32_field(value, "tilewidth")?,
+        tile_height: u32_field(value, "tileheight")?,
+        margin: u32_field_or(value, "margin", 0)?,
+        spacing: u32_field_or(value, "spacing", 0)?,
+        columns: u32_field_or(value, "columns", 0)?,
+        tile_count: u32_field_or(value, "tilecount", 0)?,
+
+        image: str_field(value, "image").map(|source| resolve(base_dir, source)).unwrap_or_default(),
+        image_width: u32_field_or(value, "imagewidth", 0)?,
+        image_height: u32_field_or(value, "imageheight", 0)?,
+
+        tiles: HashMap::new(),
+        properties: json_properties(value)?,
+    };
+    check_tileset(&mut tileset)?;
+
+    for tile in value.get("tiles").and_then(Value::as_array).into_iter().flatten() {
+        let animation = tile
+            .get("animation")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .map(|frame| {
+                Ok(TileFrame {
+                    tile: u32_field(frame, "tileid")?,
+                    duration: f32_field(frame, "duration", 0.0) / 1000.0,
+                })
+            })
+            .collect::<Result<Vec<TileFrame>, String>>()?;
+
+        tileset.tiles.insert(u32_field(tile, "id")?, TileData {
+            kind: str_field(tile, "class").or_else(|| str_field(tile, "type")).unwrap_or("").to_string(),
+            properties: json_properties(tile)?,
+            animation,
+        });
+    }
+
+    Ok(tileset)
+}
+
+fn json_common(value: &Value, inherited: Inherited) -> Inherited {
+    inherited.child(
+        Vector2::new(f32_field(value, "offsetx", 0.0), f32_field(value, "offsety", 0.0)),
+        f32_field(value, "opacity", 1.0),
+        bool_field(value, "visible", true),
+    )
+}
+
+fn json_layers(parent: &Value, inherited: Inherited, layers: &mut Vec<Layer>) -> Result<(), String> {
+    for layer in parent.get("layers").and_then(Value::as_array).into_iter().flatten() {
+        match str_field(layer, "type") {
+            Some("tilelayer") => layers.push(Layer::Tiles(json_tile_layer(layer, inherited)?)),
+            Some("objectgroup") => layers.push(Layer::Objects(json_object_layer(layer, inherited)?)),
+            Some("group") => json_layers(layer, json_common(layer, inherited), layers)?,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn json_tile_layer(value: &Value, inherited: Inherited) -> Result<TileLayer, String> {
+    let common = json_common(value, inherited);
+    let name = str_field(value, "name").unwrap_or("");
+    let width = u32_field(value, "width")?;
+    let height = u32_field(value, "height")?;
+    let area = tile_area(&format!("La capa {}", name), width, height)?;
+
+    let tiles = match value.get("data") {
+        Some(Value::Array(data)) => data
+            .iter()
+            .map(|gid| gid.as_u64().and_then(|gid| u32::try_from(gid).ok()).map(Tile::from_raw))
+            .collect::<Option<Vec<Tile>>>()
+            .ok_or_else(|| format!("Tile no válido en la capa {}", name))?,
+        Some(Value::String(data)) => decode_tiles(
+            data,
+            str_field(value, "encoding"),
+            str_field(value, "compression"),
+            area,
+        )?,
+        _ => return Err(format!("La capa {} no tiene \"data\"", name)),
+    };
+    check_layer_size(name, &tiles, width, height)?;
+
+    Ok(TileLayer {
+        name: String::from(name),
+        width,
+        height,
+        tiles,
+
+        visible: common.visible,
+        opacity: common.opacity,
+        offset: common.offset,
+        properties: json_properties(value)?,
+    })
+}
+
+fn json_object_layer(value: &Value, inherited: Inherited) -> Result<ObjectLayer, String> {
+    let common = json_common(value, inherited);
+
+    let objects = value
+        .get("objects")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .map(json_object)
+        .collect::<Result<Vec<MapObject>, String>>()?;
+
+    Ok(ObjectLayer {
+        name: str_field(value, "name").unwrap_or("").to_string(),
+        objects,
+
+        visible: common.visible,
+        opacity: common.opacity,
+        offset: common.offset,
+        properties: json_properties(value)?,
+    })
+}
+
+fn json_points(value: &Value) -> Vec<Vector2> {
+    value
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|p| Vector2::new(f32_field(p, "x", 0.0), f32_field(p, "y", 0.0)))
+        .collect()
+}
+
+fn json_object(value: &Value) -> Result<MapObject, String> {
+    let shape = if bool_field(value, "ellipse", false) {
+        ObjectShape::Ellipse
+    } else if bool_field(value, "point", false) {
+        ObjectShape::Point
+    } else if let Some(points) = value.get("polygon") {
+        ObjectShape::Polygon(json_points(points))
+    } else if let Some(points) = value.get("polyline") {
+        ObjectShape::Polyline(json_points(points))
+    } else {
+        ObjectShape::Rectangle
+    };
+
+    Ok(MapObject {
+        id: u32_field_or(value, "id", 0)?,
+        name: str_field(value, "name").unwrap_or("").to_string(),
+        kind: str_field(value, "class").or_else(|| str_field(value, "type")).unwrap_or("").to_string(),
+
+        position: Vector2::new(f32_field(value, "x", 0.0), f32_field(value, "y", 0.0)),
+        width: f32_field(value, "width", 0.0),
+        height: f32_field(value, "height", 0.0),
+        rotation: f32_field(value, "rotation", 0.0),
+
+        shape,
+        tile: value.get("gid").and_then(Value::as_u64).map(|gid| Tile::from_raw(gid as u32)),
+
+        visible: bool_field(value, "visible", true),
+        properties: json_properties(value)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tmx(map_size: &str, tileset: &str) -> String {
+        format!(
+            r#"<map orientation="orthogonal" {} tilewidth="16" tileheight="16">
+                 {}
+                 <layer name="suelo" width="2" height="2"><data encoding="csv">1,2,3,4</data></layer>
+               </map>"#,
+            map_size, tileset
+        )
+    }
+
+    #[test]
+    fn parse_color_handles_alpha_and_rejects_non_ascii() {
+        assert_eq!(parse_color("#80ff0000"), Color::from_hex("ff000080"));
+        assert_eq!(parse_color("ff0000"), Color::from_hex("ff0000"));
+        // 8 bytes pero no ASCII: antes hacía panic al cortar el texto
+        assert_eq!(parse_color("#ñff0000"), None);
+        assert_eq!(parse_color("€€ab"), None);
+    }
+
+    #[test]
+    fn missing_tilecount_is_derived_from_image() {
+        let xml = tmx(
+            r#"width="2" height="2""#,
+            r#"<tileset firstgid="1" name="t" tilewidth="16" tileheight="16" margin="1" spacing="2">
+                 <image source="t.png" width="54" height="36"/>
+               </tileset>"#,
+        );
+        let map = parse_tmx(&xml, Path::new("")).unwrap();
+        let tileset = &map.tilesets[0];
+        assert_eq!(tileset.columns, 3);
+        assert_eq!(tileset.tile_count, 6);
+        assert!(tileset.contains(6));
+        assert!(!tileset.contains(7));
+    }
+
+    #[test]
+    fn missing_tilecount_without_image_size_is_an_error() {
+        let xml = tmx(
+            r#"width="2" height="2""#,
+            r#"<tileset firstgid="1" name="t" tilewidth="16" tileheight="16"><image source="t.png"/></tileset>"#,
+        );
+        assert!(parse_tmx(&xml, Path::new("")).is_err());
+    }
+
+    #[test]
+    fn huge_maps_are_rejected() {
+        let tileset = r#"<tileset firstgid="1" name="t" tilewidth="16" tileheight="16" tilecount="4" columns="2">
+                           <image source="t.png" width="32" height="32"/>
+                         </tileset>"#;
+        assert!(parse_tmx(&tmx(r#"width="4294967295" height="2""#, tileset), Path::new("")).is_err());
+        assert!(parse_tmx(&tmx(r#"width="65536" height="65536""#, tileset), Path::new("")).is_err());
+        assert!(parse_tmx(&tmx(r#"width="2" height="2""#, tileset), Path::new("")).is_ok());
+
+        let json = r#"{"orientation": "orthogonal", "width": 4294967295, "height": 4294967295,
+                       "tilewidth": 16, "tileheight": 16, "layers": []}"#;
+        assert!(parse_json(json, Path::new("")).is_err());
+    }
+
+    #[test]
+    fn huge_layers_are_rejected() {
+        let xml = r#"<map orientation="orthogonal" width="2" height="2" tilewidth="16" tileheight="16">
+                       <layer name="vacía" width="100000" height="100000"/>
+                     </map>"#;
+        assert!(parse_tmx(xml, Path::new("")).is_err());
+    }
+
+    fn compressed_layer(compression: &str, raw: &[u8]) -> String {
+        use std::io::Write;
+
+        let bytes = match compression {
+            "zlib" => {
+                let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(raw).unwrap();
+                encoder.finish().unwrap()
+            }
+            _ => {
+                let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(raw).unwrap();
+                encoder.finish().unwrap()
+            }
+        };
+        let data = base64::engine::general_purpose::STANDARD.encode(bytes);
+
+        format!(
+            r#"<map orientation="orthogonal" width="2" height="2" tilewidth="16" tileheight="16">
+                 <layer name="suelo" width="2" height="2"><data encoding="base64" compression="{}">{}</data></layer>
+               </map>"#,
+            compression, data
+        )
+    }
+
+    fn gids(map: &Tilemap) -> Vec<u32> {
+        match &map.layers[0] {
+            Layer::Tiles(layer) => layer.tiles.iter().map(|t| t.gid).collect(),
+            _ => panic!("la primera capa no es de tiles"),
+        }
+    }
+
+    #[test]
+    fn compressed_layers_are_decoded() {
+        let raw: Vec<u8> = [1u32, 2, 3, 4].iter().flat_map(|gid| gid.to_le_bytes()).collect();
+
+        for compression in ["zlib", "gzip"] {
+            let map = parse_tmx(&compressed_layer(compression, &raw), Path::new("")).unwrap();
+            assert_eq!(gids(&map), vec![1, 2, 3, 4]);
+        }
+    }
+
+    // Unos pocos KB comprimidos que ocuparían 8 MB: se deja de leer al pasar de 16 bytes
+    #[test]
+    fn compressed_layers_larger_than_the_map_are_rejected() {
+        let bomb = vec![0u8; 8 << 20];
+
+        for compression in ["zlib", "gzip"] {
+            let xml = compressed_layer(compression, &bomb);
+            assert!(xml.len() < 1 << 20);
+
+            let error = parse_tmx(&xml, Path::new("")).err().unwrap();
+            assert!(error.contains("16 bytes"), "{}", error);
+        }
+    }
+
+    #[test]
+    fn short_compressed_layers_are_rejected() {
+        let raw: Vec<u8> = [1u32, 2, 3].iter().flat_map(|gid| gid.to_le_bytes()).collect();
+
+        assert!(parse_tmx(&compressed_layer("zlib", &raw), Path::new("")).is_err());
+    }
+
+    fn json_map(width: &str, data: &str) -> String {
+        format!(
+            r#"{{"orientation": "orthogonal", "width": {}, "height": 2, "tilewidth": 16, "tileheight": 16,
+                "layers": [{{"type": "tilelayer", "name": "suelo", "width": 2, "height": 2, "data": {}}}]}}"#,
+            width, data
+        )
+    }
+
+    // Antes 4294967298 se truncaba a 2 y el mapa se cargaba como si fuera válido
+    #[test]
+    fn json_numbers_out_of_range_are_rejected() {
+        assert!(parse_json(&json_map("2", "[1, 2, 3, 4]"), Path::new("")).is_ok());
+
+        for width in ["-2", "4294967298", "2.5"] {
+            let error = parse_json(&json_map(width, "[1, 2, 3, 4]"), Path::new("")).err().unwrap();
+            assert!(error.contains("width"), "{}", error);
+        }
+
+        assert!(parse_json(&json_map("2", "[1, 2, 3, 4294967297]"), Path::new("")).is_err());
+        assert!(parse_json(&json_map("2", "[1, 2, 3, -4]"), Path::new("")).is_err());
+    }
+}
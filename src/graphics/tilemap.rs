@@ -0,0 +1,404 @@
+use std::collections::HashMap;
+
+use crate::graphics::color::Color;
+use crate::math::rect::Rect;
+use crate::math::vector2::Vector2;
+
+// Bits altos de los gid de Tiled que indican si el tile está volteado
+pub const FLIPPED_HORIZONTALLY: u32 = 0x8000_0000;
+pub const FLIPPED_VERTICALLY: u32 = 0x4000_0000;
+pub const FLIPPED_DIAGONALLY: u32 = 0x2000_0000;
+// El bit 0x1000_0000 es la rotación de 120º de los mapas hexagonales, que no usamos
+const GID_MASK: u32 = 0x0fff_ffff;
+
+// Propiedad personalizada de Tiled
+#[derive(Clone, PartialEq, Debug)]
+pub enum PropertyValue {
+    String(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Color(Color),
+    // Ruta de fichero, tal y como la guarda Tiled
+    File(String),
+    // Id de otro objeto del mapa
+    Object(u32),
+    // Tipo personalizado (clase) con sus propios miembros
+    Class(Properties),
+}
+
+pub type Properties = HashMap<String, PropertyValue>;
+
+// Celda de una capa. gid 0 es una celda vacía; el resto es el id global del tile
+// (first_gid del tileset + id local)
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Tile {
+    pub gid: u32,
+    pub flip_x: bool,
+    pub flip_y: bool,
+    // Intercambia los ejes X e Y (con los otros dos permite rotar 90º)
+    pub flip_diagonal: bool,
+}
+
+impl Tile {
+    pub fn new(gid: u32) -> Tile {
+        Tile {
+            gid,
+            flip_x: false,
+            flip_y: false,
+            flip_diagonal: false,
+        }
+    }
+
+    pub fn empty() -> Tile {
+        Tile::new(0)
+    }
+
+    // Separa el gid de los bits de volteo tal y como vienen en los ficheros de Tiled
+    pub fn from_raw(raw: u32) -> Tile {
+        Tile {
+            gid: raw & GID_MASK,
+            flip_x: raw & FLIPPED_HORIZONTALLY != 0,
+            flip_y: raw & FLIPPED_VERTICALLY != 0,
+            flip_diagonal: raw & FLIPPED_DIAGONALLY != 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.gid == 0
+    }
+}
+
+// Fotograma de un tile animado
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct TileFrame {
+    // Id local dentro del tileset
+    pub tile: u32,
+    // En segundos
+    pub duration: f32,
+}
+
+// Información extra de un tile concreto del tileset
+#[derive(Clone, Default)]
+pub struct TileData {
+    pub kind: String,
+    pub properties: Properties,
+    pub animation: Vec<TileFrame>,
+}
+
+// Imagen dividida en tiles. Los ids locales van de 0 a tile_count - 1,
+// por filas de izquierda a derecha
+#[derive(Clone)]
+pub struct Tileset {
+    pub name: String,
+    pub first_gid: u32,
+
+    pub tile_width: u32,
+    pub tile_height: u32,
+    pub margin: u32,
+    pub spacing: u32,
+    pub columns: u32,
+    pub tile_count: u32,
+
+    // Ruta de la imagen, ya resuelta respecto al fichero del mapa
+    pub image: String,
+    pub image_width: u32,
+    pub image_height: u32,
+
+    // Sólo los tiles que tienen propiedades o animación, por id local
+    pub tiles: HashMap<u32, TileData>,
+    pub properties: Properties,
+}
+
+impl Tileset {
+    pub fn contains(&self, gid: u32) -> bool {
+        gid >= self.first_gid && gid < self.first_gid + self.tile_count
+    }
+
+    // Rectángulo del tile en píxeles de la imagen
+    pub fn tile_rect(&self, local_id: u32) -> Rect {
+        let columns = self.columns.max(1);
+        let column = local_id % columns;
+        let row = local_id / columns;
+
+        Rect::new(
+            (self.margin + column * (self.tile_width + self.spacing)) as f32,
+            (self.margin + row * (self.tile_height + self.spacing)) as f32,
+            self.tile_width as f32,
+            self.tile_height as f32,
+        )
+    }
+
+    // Rectángulo del tile en coordenadas UV
+    pub fn uv(&self, local_id: u32) -> Rect {
+        let rect = self.tile_rect(local_id);
+        let width = self.image_width.max(1) as f32;
+        let height = self.image_height.max(1) as f32;
+
+        Rect::new(rect.x / width, rect.y / height, rect.width / width, rect.height / height)
+    }
+
+    pub fn is_animated(&self, local_id: u32) -> bool {
+        self.tiles.get(&local_id).is_some_and(|t| !t.animation.is_empty())
+    }
+
+    // Id local que se ve en el instante time (segundos desde el inicio). Las animaciones
+    // de Tiled siempre se repiten; un tile sin animación se devuelve tal cual
+    pub fn animated_tile(&self, local_id: u32, time: f32) -> u32 {
+        let animation = match self.tiles.get(&local_id) {
+            Some(data) if !data.animation.is_empty() => &data.animation,
+            _ => return local_id,
+        };
+
+        let total: f32 = animation.iter().map(|f| f.duration).sum();
+        if total <= 0.0 {
+            return animation[0].tile;
+        }
+
+        let mut remaining = time.rem_euclid(total);
+        for frame in animation {
+            if remaining < frame.duration {
+                return frame.tile;
+            }
+            remaining -= frame.duration;
+        }
+        animation[animation.len() - 1].tile
+    }
+}
+
+pub struct TileLayer {
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+    // Por filas, de arriba a abajo
+    pub tiles: Vec<Tile>,
+
+    pub visible: bool,
+    pub opacity: f32,
+    // Desplazamiento en píxeles (incluye el de los grupos que la contienen)
+    pub offset: Vector2,
+    pub properties: Properties,
+}
+
+impl TileLayer {
+    pub fn new(name: &str, width: u32, height: u32) -> TileLayer {
+        TileLayer {
+            name: String::from(name),
+            width,
+            height,
+            tiles: vec![Tile::empty(); (width * height) as usize],
+
+            visible: true,
+            opacity: 1.0,
+            offset: Vector2::zero(),
+            properties: Properties::new(),
+        }
+    }
+
+    pub fn tile(&self, x: u32, y: u32) -> Option<Tile> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        Some(self.tiles[(y * self.width + x) as usize])
+    }
+
+    pub fn set_tile(&mut self, x: u32, y: u32, tile: Tile) {
+        if x < self.width && y < self.height {
+            self.tiles[(y * self.width + x) as usize] = tile;
+        }
+    }
+}
+
+#[derive(Clone)]
+pub enum ObjectShape {
+    Rectangle,
+    Ellipse,
+    Point,
+    // Puntos relativos a la posición del objeto
+    Polygon(Vec<Vector2>),
+    Polyline(Vec<Vector2>),
+}
+
+// Objeto de una capa de objetos (zonas, puntos de aparición, colisiones...)
+#[derive(Clone)]
+pub struct MapObject {
+    pub id: u32,
+    pub name: String,
+    // Tipo o clase asignado en Tiled
+    pub kind: String,
+
+    // Esquina superior izquierda en píxeles (inferior izquierda si es un tile, como en Tiled)
+    pub position: Vector2,
+    pub width: f32,
+    pub height: f32,
+    // En grados, en sentido horario
+    pub rotation: f32,
+
+    pub shape: ObjectShape,
+    // Objetos que son un tile colocado libremente
+    pub tile: Option<Tile>,
+
+    pub visible: bool,
+    pub properties: Properties,
+}
+
+impl MapObject {
+    // Rectángulo que ocupa sin tener en cuenta la rotación
+    pub fn bounds(&self) -> Rect {
+        match &self.shape {
+            ObjectShape::Polygon(points) | ObjectShape::Polyline(points) => {
+                let absolute: Vec<Vector2> = points.iter().map(|p| self.position + *p).collect();
+                Rect::from_points(&absolute).unwrap_or_else(|| Rect::new(self.position.x, self.position.y, 0.0, 0.0))
+            }
+            _ if self.tile.is_some() => {
+                Rect::new(self.position.x, self.position.y - self.height, self.width, self.height)
+            }
+            _ => Rect::new(self.position.x, self.position.y, self.width, self.height),
+        }
+    }
+}
+
+pub struct ObjectLayer {
+    pub name: String,
+    pub objects: Vec<MapObject>,
+
+    pub visible: bool,
+    pub opacity: f32,
+    pub offset: Vector2,
+    pub properties: Properties,
+}
+
+impl ObjectLayer {
+    pub fn object(&self, name: &str) -> Option<&MapObject> {
+        self.objects.iter().find(|o| o.name == name)
+    }
+
+    pub fn objects_of_kind<'a>(&'a self, kind: &'a str) -> impl Iterator<Item = &'a MapObject> + 'a {
+        self.objects.iter().filter(move |o| o.kind == kind)
+    }
+}
+
+pub enum Layer {
+    Tiles(TileLayer),
+    Objects(ObjectLayer),
+}
+
+impl Layer {
+    pub fn name(&self) -> &str {
+        match self {
+            Layer::Tiles(layer) => &layer.name,
+            Layer::Objects(layer) => &layer.name,
+        }
+    }
+}
+
+// Mapa ortogonal de tiles. Las capas van en orden de dibujo (la primera, al fondo).
+// Las capas de los grupos de Tiled se aplanan en esta lista
+pub struct Tilemap {
+    pub width: u32,
+    pub height: u32,
+    pub tile_width: u32,
+    pub tile_height: u32,
+
+    pub tilesets: Vec<Tileset>,
+    pub layers: Vec<Layer>,
+
+    pub background: Option<Color>,
+    pub properties: Properties,
+}
+
+impl Tilemap {
+    pub fn new(width: u32, height: u32, tile_width: u32, tile_height: u32) -> Tilemap {
+        Tilemap {
+            width,
+            height,
+            tile_width,
+            tile_height,
+
+            tilesets: Vec::new(),
+            layers: Vec::new(),
+
+            background: None,
+            properties: Properties::new(),
+        }
+    }
+
+    // Índice del tileset al que pertenece el gid y el id local dentro de él
+    pub fn tileset_for(&self, gid: u32) -> Option<(usize, u32)> {
+        if gid == 0 {
+            return None;
+        }
+
+        self.tilesets
+            .iter()
+            .enumerate()
+            .filter(|(_, tileset)| tileset.first_gid <= gid)
+            .max_by_key(|(_, tileset)| tileset.first_gid)
+            .filter(|(_, tileset)| tileset.contains(gid))
+            .map(|(i, tileset)| (i, gid - tileset.first_gid))
+    }
+
+    // Datos del tile (propiedades, animación) a partir de su gid
+    pub fn tile_data(&self, gid: u32) -> Option<&TileData> {
+        let (tileset, local_id) = self.tileset_for(gid)?;
+        self.tilesets[tileset].tiles.get(&local_id)
+    }
+
+    pub fn layer(&self, name: &str) -> Option<&Layer> {
+        self.layers.iter().find(|l| l.name() == name)
+    }
+
+    pub fn tile_layer(&self, name: &str) -> Option<&TileLayer> {
+        self.layers.iter().find_map(|layer| match layer {
+            Layer::Tiles(layer) if layer.name == name => Some(layer),
+            _ => None,
+        })
+    }
+
+    pub fn tile_layer_mut(&mut self, name: &str) -> Option<&mut TileLayer> {
+        self.layers.iter_mut().find_map(|layer| match layer {
+            Layer::Tiles(layer) if layer.name == name => Some(layer),
+            _ => None,
+        })
+    }
+
+    pub fn object_layer(&self, name: &str) -> Option<&ObjectLayer> {
+        self.layers.iter().find_map(|layer| match layer {
+            Layer::Objects(layer) if layer.name == name => Some(layer),
+            _ => None,
+        })
+    }
+
+    // Tamaño del mapa en píxeles
+    pub fn bounds(&self) -> Rect {
+        Rect::new(
+            0.0,
+            0.0,
+            (self.width * self.tile_width) as f32,
+            (self.height * self.tile_height) as f32,
+        )
+    }
+
+    // Celda que hay bajo un punto en píxeles del mapa
+    pub fn world_to_tile(&self, point: Vector2) -> Option<(u32, u32)> {
+        if point.x < 0.0 || point.y < 0.0 {
+            return None;
+        }
+
+        let x = (point.x / self.tile_width as f32) as u32;
+        let y = (point.y / self.tile_height as f32) as u32;
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        Some((x, y))
+    }
+
+    pub fn tile_rect(&self, x: u32, y: u32) -> Rect {
+        Rect::new(
+            (x * self.tile_width) as f32,
+            (y * self.tile_height) as f32,
+            self.tile_width as f32,
+            self.tile_height as f32,
+        )
+    }
+}
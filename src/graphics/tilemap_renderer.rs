@@ -0,0 +1,271 @@
+use crate::gl_utility::gl_buffer::{GLBuffer, AttributeInfo};
+use crate::gl_utility::shader::Shader;
+use crate::gl_utility::texture::Texture;
+use crate::graphics::tilemap::{Layer, Tile, TileLayer, Tilemap};
+use crate::math::matrix4x4::Matrix4x4;
+use crate::math::rect::Rect;
+
+// Trozo de una capa dibujado con una sola llamada: todos sus tiles son del mismo tileset
+struct Chunk {
+    layer: usize,
+    tileset: usize,
+    opacity: f32,
+    // En píxeles del mapa, para descartar los trozos que no se ven
+    bounds: Rect,
+
+    // Celda y tile de cada quad, para poder rehacer los vértices de los animados
+    tiles: Vec<(u32, u32, Tile)>,
+    // Id local que se está viendo de cada tile animado (vacío si no hay)
+    animated_frames: Vec<u32>,
+
+    buffer: GLBuffer,
+}
+
+// Dibuja las capas de tiles de un Tilemap. Cada capa se divide en trozos de
+// chunk_size x chunk_size tiles con su propio GLBuffer, y sólo se dibujan los que
+// tocan la vista. Usa el mismo shader que Sprite (a_position, a_tex_coord, u_model,
+// u_color, u_diffuse y u_textured)
+pub struct TilemapRenderer<'a> {
+    pub chunk_size: u32,
+
+    map: &'a Tilemap,
+    // Una textura por tileset, en el mismo orden que map.tilesets
+    textures: Vec<&'a Texture>,
+    chunks: Vec<Chunk>,
+    // Segundos desde el inicio, para las animaciones
+    time: f32,
+
+    u_color_location: i32,
+    u_model_location: i32,
+    u_diffuse_location: i32,
+    u_textured_location: i32,
+
+    shader: &'a Shader,
+}
+
+impl<'a> TilemapRenderer<'a> {
+    pub fn new(map: &'a Tilemap, textures: Vec<&'a Texture>, shader: &'a Shader) -> TilemapRenderer<'a> {
+        if textures.len() != map.tilesets.len() {
+            panic!(
+                "El mapa tiene {} tilesets y se han pasado {} texturas",
+                map.tilesets.len(), textures.len()
+            );
+        }
+
+        TilemapRenderer {
+            chunk_size: 16,
+
+            map,
+            textures,
+            chunks: Vec::new(),
+            time: 0.0,
+
+            u_color_location: shader.get_uniform_location("u_color"),
+            u_model_location: shader.get_uniform_location("u_model"),
+            u_diffuse_location: shader.get_uniform_location("u_diffuse"),
+            u_textured_location: shader.get_uniform_location("u_textured"),
+
+            shader,
+        }
+    }
+
+    // Crea los trozos y sube sus vértices. Hay que volver a llamarlo si cambia chunk_size
+    pub fn load(&mut self) {
+        self.chunks.clear();
+
+        for (layer_index, layer) in self.map.layers.iter().enumerate() {
+            if let Layer::Tiles(layer) = layer {
+                self.build_layer(layer_index, layer);
+            }
+        }
+
+        for i in 0..self.chunks.len() {
+            self.configure_chunk(i);
+            self.upload_chunk(i);
+        }
+    }
+
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+
+    fn build_layer(&mut self, layer_index: usize, layer: &TileLayer) {
+        let chunk_size = self.chunk_size.max(1);
+        let chunk_width = chunk_size * self.map.tile_width;
+        let chunk_height = chunk_size * self.map.tile_height;
+
+        for chunk_y in (0..layer.height).step_by(chunk_size as usize) {
+            for chunk_x in (0..layer.width).step_by(chunk_size as usize) {
+                // Un trozo por tileset que aparezca en esta zona
+                let mut by_tileset: Vec<Vec<(u32, u32, Tile)>> = vec![Vec::new(); self.map.tilesets.len()];
+
+                for y in chunk_y..(chunk_y + chunk_size).min(layer.height) {
+                    for x in chunk_x..(chunk_x + chunk_size).min(layer.width) {
+                        let tile = layer.tiles[(y * layer.width + x) as usize];
+                        if let Some((tileset, _)) = self.map.tileset_for(tile.gid) {
+                            by_tileset[tileset].push((x, y, tile));
+                        }
+                    }
+                }
+
+                for (tileset, tiles) in by_tileset.into_iter().enumerate() {
+                    if tiles.is_empty() {
+                        continue;
+                    }
+
+                    // Los tiles más altos que la rejilla sobresalen por arriba
+                    let overflow = self.map.tilesets[tileset].tile_height.saturating_sub(self.map.tile_height);
+                    let extra_width = self.map.tilesets[tileset].tile_width.saturating_sub(self.map.tile_width);
+
+                    self.chunks.push(Chunk {
+                        layer: layer_index,
+                        tileset,
+                        opacity: layer.opacity,
+                        bounds: Rect::new(
+                            layer.offset.x + (chunk_x * self.map.tile_width) as f32,
+                            layer.offset.y + (chunk_y * self.map.tile_height) as f32 - overflow as f32,
+                            (chunk_width + extra_width) as f32,
+                            (chunk_height + overflow) as f32,
+                        ),
+
+                        tiles,
+                        animated_frames: Vec::new(),
+
+                        buffer: GLBuffer::new(),
+                    });
+                }
+            }
+        }
+    }
+
+    fn configure_chunk(&mut self, index: usize) {
+        let a_position_location = self.shader.get_attribute_location("a_position");
+        let a_tex_coord_location = self.shader.get_attribute_location("a_tex_coord");
+        self.chunks[index].buffer.configure(
+            vec![
+                AttributeInfo {
+                    location: a_position_location,
+                    component_size: 3,
+                },
+                AttributeInfo {
+                    location: a_tex_coord_location,
+                    component_size: 2,
+                },
+            ],
+            false,
+        );
+    }
+
+    // Rehace los vértices del trozo con el fotograma actual de cada tile animado
+    fn upload_chunk(&mut self, index: usize) {
+        let map = self.map;
+        let chunk = &mut self.chunks[index];
+        let tileset = &map.tilesets[chunk.tileset];
+        let offset = match &map.layers[chunk.layer] {
+            Layer::Tiles(layer) => layer.offset,
+            Layer::Objects(layer) => layer.offset,
+        };
+
+        chunk.animated_frames.clear();
+        let mut data = Vec::with_capacity(chunk.tiles.len() * 6 * 5);
+
+        for &(x, y, tile) in &chunk.tiles {
+            let mut local_id = tile.gid - tileset.first_gid;
+            if tileset.is_animated(local_id) {
+                local_id = tileset.animated_tile(local_id, self.time);
+                chunk.animated_frames.push(local_id);
+            }
+
+            // Como en Tiled, el tile se apoya en la esquina inferior izquierda de la celda
+            let min_x = offset.x + (x * map.tile_width) as f32;
+            let max_x = min_x + tileset.tile_width as f32;
+            let max_y = offset.y + ((y + 1) * map.tile_height) as f32;
+            let min_y = max_y - tileset.tile_height as f32;
+
+            let uv = tileset.uv(local_id);
+            let corner = |cx: f32, cy: f32| tile_uv(&tile, cx, cy, &uv);
+
+            let quad = [
+                (min_x, min_y, corner(0.0, 0.0)),
+                (min_x, max_y, corner(0.0, 1.0)),
+                (max_x, max_y, corner(1.0, 1.0)),
+                (max_x, max_y, corner(1.0, 1.0)),
+                (max_x, min_y, corner(1.0, 0.0)),
+                (min_x, min_y, corner(0.0, 0.0)),
+            ];
+            for (px, py, (u, v)) in quad.iter() {
+                data.extend_from_slice(&[*px, *py, 0.0, *u, *v]);
+            }
+        }
+
+        chunk.buffer.upload(&data);
+    }
+
+    // Avanza las animaciones y vuelve a subir sólo los trozos cuyo fotograma ha cambiado
+    pub fn update(&mut self, delta: f32) {
+        self.time += delta;
+
+        for i in 0..self.chunks.len() {
+            let chunk = &self.chunks[i];
+            if chunk.animated_frames.is_empty() {
+                continue;
+            }
+
+            let tileset = &self.map.tilesets[chunk.tileset];
+            let changed = chunk
+                .tiles
+                .iter()
+                .map(|(_, _, tile)| tile.gid - tileset.first_gid)
+                .filter(|&local_id| tileset.is_animated(local_id))
+                .zip(&chunk.animated_frames)
+                .any(|(local_id, &shown)| tileset.animated_tile(local_id, self.time) != shown);
+
+            if changed {
+                self.upload_chunk(i);
+            }
+        }
+    }
+
+    // Dibuja las capas visibles. view es la zona que ve la cámara en píxeles del mapa
+    // (antes de aplicar model); los trozos fuera de ella no se dibujan.
+    // Devuelve cuántos trozos se han dibujado
+    pub fn draw(&self, model: &Matrix4x4, view: &Rect) -> usize {
+        let mut drawn = 0;
+
+        unsafe {
+            gl::UniformMatrix4fv(self.u_model_location, 1, gl::FALSE, model.data.as_ptr());
+            gl::Uniform1i(self.u_diffuse_location, 0);
+            gl::Uniform1i(self.u_textured_location, 1);
+        }
+
+        for chunk in &self.chunks {
+            let visible = match &self.map.layers[chunk.layer] {
+                Layer::Tiles(layer) => layer.visible,
+                Layer::Objects(_) => false,
+            };
+            if !visible || !chunk.bounds.overlaps(view) {
+                continue;
+            }
+
+            self.textures[chunk.tileset].bind(0);
+            unsafe {
+                gl::Uniform4f(self.u_color_location, 1.0, 1.0, 1.0, chunk.opacity);
+            }
+            chunk.buffer.draw();
+            drawn += 1;
+        }
+
+        drawn
+    }
+}
+
+// Coordenada de textura para la esquina (cx, cy) del quad (0 = izquierda/arriba,
+// 1 = derecha/abajo). Tiled aplica primero el volteo diagonal, luego el horizontal
+// y luego el vertical, así que aquí se deshacen en orden inverso
+fn tile_uv(tile: &Tile, cx: f32, cy: f32, uv: &Rect) -> (f32, f32) {
+    let cy = if tile.flip_y { 1.0 - cy } else { cy };
+    let cx = if tile.flip_x { 1.0 - cx } else { cx };
+    let (sx, sy) = if tile.flip_diagonal { (cy, cx) } else { (cx, cy) };
+
+    (uv.x + sx * uv.width, uv.y + sy * uv.height)
+}
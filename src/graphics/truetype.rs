@@ -0,0 +1,138 @@
+// Cuántas veces más grande se rasteriza el carácter para calcular el campo de distancias
+const SDF_UPSCALE: f32 = 4.0;
+
+// Carácter rasterizado: un byte de cobertura (o de distancia) por píxel, por filas
+pub struct RasterGlyph {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+    // Desplazamiento del bitmap respecto al cursor, con y desde la parte de arriba de la línea
+    pub x_offset: f32,
+    pub y_offset: f32,
+    pub advance: f32,
+}
+
+// Fuente TrueType u OpenType cargada en memoria. Rasteriza caracteres a cualquier
+// tamaño en píxeles (fontdue por debajo)
+pub struct TrueTypeFont {
+    font: fontdue::Font,
+}
+
+impl TrueTypeFont {
+    pub fn from_bytes(bytes: &[u8]) -> Result<TrueTypeFont, String> {
+        let font = fontdue::Font::from_bytes(bytes, fontdue::FontSettings::default())
+            .map_err(|e| format!("Fuente TrueType no válida: {}", e))?;
+        Ok(TrueTypeFont { font })
+    }
+
+    pub fn load(path: &str) -> Result<TrueTypeFont, String> {
+        let bytes = std::fs::read(path).map_err(|e| format!("No se puede leer {}: {}", path, e))?;
+        TrueTypeFont::from_bytes(&bytes)
+    }
+
+    pub fn name(&self) -> &str {
+        self.font.name().unwrap_or("")
+    }
+
+    pub fn has_glyph(&self, c: char) -> bool {
+        self.font.lookup_glyph_index(c) != 0
+    }
+
+    // Devuelve (ascendente, distancia entre líneas) para ese tamaño
+    pub fn line_metrics(&self, size: f32) -> (f32, f32) {
+        match self.font.horizontal_line_metrics(size) {
+            Some(metrics) => (metrics.ascent, metrics.new_line_size),
+            None => (size, size),
+        }
+    }
+
+    pub fn kerning(&self, first: char, second: char, size: f32) -> f32 {
+        self.font.horizontal_kern(first, second, size).unwrap_or(0.0)
+    }
+
+    // Rasteriza con antialiasing: cada píxel es la cobertura del carácter (0-255)
+    pub fn rasterize(&self, c: char, size: f32) -> RasterGlyph {
+        let (ascent, _) = self.line_metrics(size);
+        let (metrics, pixels) = self.font.rasterize(c, size);
+
+        RasterGlyph {
+            width: metrics.width as u32,
+            height: metrics.height as u32,
+            pixels,
+            x_offset: metrics.xmin as f32,
+            y_offset: ascent - (metrics.ymin + metrics.height as i32) as f32,
+            advance: metrics.advance_width,
+        }
+    }
+
+    // Rasteriza un campo de distancias con signo: 128 es el borde del carácter, más es
+    // dentro y menos fuera, llegando a 0 y 255 a spread píxeles del borde. Se puede
+    // escalar mucho más que un bitmap normal sin que se vean los píxeles
+    pub fn rasterize_sdf(&self, c: char, size: f32, spread: u32) -> RasterGlyph {
+        let advance = self.font.metrics(c, size).advance_width;
+        let (metrics, coverage) = self.font.rasterize(c, size * SDF_UPSCALE);
+        if metrics.width == 0 || metrics.height == 0 {
+            // Espacios y demás caracteres sin forma
+            return RasterGlyph {
+                width: 0,
+                height: 0,
+                pixels: Vec::new(),
+                x_offset: 0.0,
+                y_offset: 0.0,
+                advance,
+            };
+        }
+
+        let (high_width, high_height) = (metrics.width as i32, metrics.height as i32);
+        let inside = |x: i32, y: i32| {
+            x >= 0 && y >= 0 && x < high_width && y < high_height
+                && coverage[(y * high_width + x) as usize] >= 128
+        };
+
+        // Bordes: píxeles de dentro con algún vecino fuera
+        let mut edges = Vec::new();
+        for y in 0..high_height {
+            for x in 0..high_width {
+                if inside(x, y) && (!inside(x - 1, y) || !inside(x + 1, y) || !inside(x, y - 1) || !inside(x, y + 1)) {
+                    edges.push((x as f32 + 0.5, y as f32 + 0.5));
+                }
+            }
+        }
+
+        let width = (metrics.width as f32 / SDF_UPSCALE).ceil() as u32 + spread * 2;
+        let height = (metrics.height as f32 / SDF_UPSCALE).ceil() as u32 + spread * 2;
+        let mut pixels = vec![0; (width * height) as usize];
+
+        for y in 0..height {
+            for x in 0..width {
+                // Centro del píxel en la imagen grande
+                let high_x = (x as f32 - spread as f32 + 0.5) * SDF_UPSCALE;
+                let high_y = (y as f32 - spread as f32 + 0.5) * SDF_UPSCALE;
+
+                let distance_squared = edges
+                    .iter()
+                    .map(|&(ex, ey)| (ex - high_x) * (ex - high_x) + (ey - high_y) * (ey - high_y))
+                    .fold(f32::MAX, f32::min);
+                let mut distance = distance_squared.sqrt() / SDF_UPSCALE;
+                if !inside(high_x.floor() as i32, high_y.floor() as i32) {
+                    distance = -distance;
+                }
+
+                let value = 0.5 + distance / (2.0 * spread.max(1) as f32);
+                pixels[(y * width + x) as usize] = (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+            }
+        }
+
+        let (ascent, _) = self.line_metrics(size);
+        let top = (metrics.ymin + metrics.height as i32) as f32 / SDF_UPSCALE;
+
+        RasterGlyph {
+            width,
+            height,
+            pixels,
+            x_offset: metrics.xmin as f32 / SDF_UPSCALE - spread as f32,
+            y_offset: ascent - top - spread as f32,
+            advance,
+        }
+    }
+}
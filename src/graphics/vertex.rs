@@ -1,14 +1,18 @@
 use crate::math::vector3::Vector3;
+use crate::math::vector2::Vector2;
 
 #[derive(Copy, Clone)]
 pub struct Vertex {
-    pub position: Vector3
+    pub position: Vector3,
+    // Coordenadas de textura (u, v), con v = 0 en la fila superior de la imagen
+    pub tex_coords: Vector2,
 }
 
 impl Vertex {
-    pub fn new(x: f32, y: f32, z: f32) -> Vertex {
+    pub fn new(x: f32, y: f32, z: f32, u: f32, v: f32) -> Vertex {
         Vertex {
             position: Vector3::new(x, y, z),
+            tex_coords: Vector2::new(u, v),
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,30 @@
+#version 430
+
+// Por vértice: el quad del sprite
+in vec3 a_position;
+in vec2 a_tex_coord;
+
+// Por instancia
+in vec3 a_offset;
+in float a_rotation;
+in vec2 a_scale;
+in vec4 a_color;
+in vec4 a_region;// x, y, ancho y alto en UV
+
+uniform mat4 u_projection;
+uniform mat4 u_model;// transformación de todo el grupo
+
+out vec2 v_tex_coord;
+out vec4 v_color;
+
+void main() {
+    // Escala, rotación y traslación como Transform (rotación en z)
+    vec2 scaled = a_position.xy * a_scale;
+    float c = cos(a_rotation);
+    float s = sin(a_rotation);
+    vec2 rotated = vec2(c * scaled.x - s * scaled.y, s * scaled.x + c * scaled.y);
+
+    v_tex_coord = a_region.xy + a_tex_coord * a_region.zw;
+    v_color = a_color;
+    gl_Position = u_projection * u_model * vec4(vec3(rotated, a_position.z) + a_offset, 1.0);
+}
@@ -0,0 +1,6 @@
+extern crate gl;
+
+pub mod gl_utility;
+pub mod math;
+pub mod graphics;
+pub mod tweening;
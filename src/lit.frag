@@ -0,0 +1,94 @@
+#version 430
+
+precision mediump float;
+
+// ForwardRenderer lo define al registrar el shader
+#ifndef MAX_LIGHTS
+#define MAX_LIGHTS 8
+#endif
+
+const int DIRECTIONAL = 0;
+const int POINT = 1;
+const int SPOT = 2;
+
+// Todo en coordenadas del mundo
+struct Light {
+    int kind;
+    vec3 position;
+    vec3 direction;// normalizada, hacia donde apunta la luz
+    vec3 color;// ya multiplicado por la intensidad
+    vec3 attenuation;// constante, lineal, cuadrática
+    float inner_cos;// coseno del cono sin atenuar
+    float outer_cos;// coseno del borde del cono
+};
+
+uniform Light u_lights[MAX_LIGHTS];
+uniform int u_light_count;
+uniform vec3 u_ambient;
+uniform vec3 u_camera_position;
+uniform int u_blinn;// 0 = Phong, 1 = Blinn-Phong
+
+uniform vec4 u_color;// difuso
+uniform sampler2D u_diffuse;
+uniform int u_textured;// 0 = sólo color, 1 = color * textura
+uniform vec3 u_specular;
+uniform float u_shininess;
+
+in vec3 v_world_position;
+in vec3 v_normal;
+in vec2 v_tex_coord;
+
+out vec4 frag_color;
+
+void main() {
+    vec4 base = u_color;
+    if (u_textured != 0) {
+        base *= texture(u_diffuse, v_tex_coord);
+    }
+
+    vec3 normal = normalize(v_normal);
+    if (!gl_FrontFacing) {
+        normal = -normal;
+    }
+    vec3 to_camera = normalize(u_camera_position - v_world_position);
+
+    vec3 diffuse = vec3(0.0);
+    vec3 specular = vec3(0.0);
+
+    for (int i = 0; i < u_light_count && i < MAX_LIGHTS; i++) {
+        Light light = u_lights[i];
+        vec3 to_light = -light.direction;
+        float attenuation = 1.0;
+
+        if (light.kind != DIRECTIONAL) {
+            vec3 offset = light.position - v_world_position;
+            float light_distance = length(offset);
+            to_light = offset / max(light_distance, 0.0001);
+            attenuation = 1.0 / max(dot(light.attenuation, vec3(1.0, light_distance, light_distance * light_distance)), 0.0001);
+
+            if (light.kind == SPOT) {
+                float angle_cos = dot(-to_light, light.direction);
+                attenuation *= smoothstep(light.outer_cos, light.inner_cos, angle_cos);
+            }
+        }
+
+        float lambert = dot(normal, to_light);
+        if (lambert <= 0.0 || attenuation <= 0.0) {
+            continue;
+        }
+
+        float highlight;
+        if (u_blinn != 0) {
+            vec3 halfway = normalize(to_light + to_camera);
+            highlight = pow(max(dot(normal, halfway), 0.0), u_shininess);
+        } else {
+            vec3 reflected = reflect(-to_light, normal);
+            highlight = pow(max(dot(reflected, to_camera), 0.0), u_shininess);
+        }
+
+        diffuse += light.color * lambert * attenuation;
+        specular += light.color * highlight * attenuation;
+    }
+
+    frag_color = vec4(base.rgb * (u_ambient + diffuse) + u_specular * specular, base.a);
+}
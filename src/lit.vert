@@ -0,0 +1,22 @@
+#version 430
+
+in vec3 a_position;
+in vec3 a_normal;
+in vec2 a_tex_coord;
+
+uniform mat4 u_projection;// proyección * vista
+uniform mat4 u_model;
+uniform mat3 u_normal_matrix;// inversa traspuesta de u_model
+
+out vec3 v_world_position;
+out vec3 v_normal;
+out vec2 v_tex_coord;
+
+void main() {
+    vec4 world_position = u_model * vec4(a_position, 1.0);
+
+    v_world_position = world_position.xyz;
+    v_normal = u_normal_matrix * a_normal;
+    v_tex_coord = a_tex_coord;
+    gl_Position = u_projection * world_position;
+}
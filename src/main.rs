@@ -12,22 +12,15 @@ https://www.youtube.com/watch?v=27VrlPfHdqM&list=PL-88NuvRRCqAPrkxlIH3bFdNiKTYhZ
 extern crate sdl2;
 extern crate gl;
 
-mod gl_utility;
-mod math;
-mod graphics;
-
 use sdl2::video::GLProfile;
 use sdl2::event::Event;
 use sdl2::keyboard::Keycode;
 
-use std::ffi::CString;
-
-use gl_utility::shader::{ShaderManager, Shader};
-use gl_utility::gl_buffer::{GLBuffer, AttributeInfo};
-use math::matrix4x4::Matrix4x4;
-use graphics::color::Color;
-use graphics::sprite::Sprite;
-use math::transform::Transform;
+use zengine::gl_utility::shader::ShaderManager;
+use zengine::gl_utility::render_state::{RenderState, StateCache};
+use zengine::math::matrix4x4::Matrix4x4;
+use zengine::graphics::sprite::Sprite;
+use zengine::math::transform::Transform;
 
 // LLamada de debugging
 extern "system" fn dbg_callback(
@@ -118,6 +111,10 @@ fn main() {
 
     // Usar programa shader
     basic_shader.use_shader();
+
+    // Estado por defecto: transparencia con el alfa de los colores
+    let mut state_cache = StateCache::new();
+    state_cache.apply(&RenderState::new());
     unsafe {
         // Color de fondo
         gl::ClearColor(0.0, 0.0, 0.0, 1.0);
@@ -0,0 +1,149 @@
+use crate::math::vector3::Vector3;
+
+// Caja alineada con los ejes en 3D
+#[derive(Copy, Clone)]
+pub struct Aabb3 {
+    pub min: Vector3,
+    pub max: Vector3,
+}
+
+impl Aabb3 {
+    pub fn new(min: Vector3, max: Vector3) -> Aabb3 {
+        Aabb3 {
+            min,
+            max,
+        }
+    }
+
+    // Devuelve la menor caja que contiene todos los puntos, o None si no hay puntos
+    pub fn from_points(points: &[Vector3]) -> Option<Aabb3> {
+        let first = *points.first()?;
+        let (min, max) = points
+            .iter()
+            .fold((first, first), |(min, max), p| (min.min(*p), max.max(*p)));
+
+        Some(Aabb3::new(min, max))
+    }
+
+    pub fn center(&self) -> Vector3 {
+        (self.min + self.max) * 0.5
+    }
+
+    pub fn size(&self) -> Vector3 {
+        self.max - self.min
+    }
+
+    pub fn contains_point(&self, point: Vector3) -> bool {
+        point.x >= self.min.x && point.x <= self.max.x
+            && point.y >= self.min.y && point.y <= self.max.y
+            && point.z >= self.min.z && point.z <= self.max.z
+    }
+
+    // Indica si other está completamente dentro de self
+    pub fn contains_aabb(&self, other: &Aabb3) -> bool {
+        self.contains_point(other.min) && self.contains_point(other.max)
+    }
+
+    pub fn overlaps(&self, other: &Aabb3) -> bool {
+        self.min.x < other.max.x && other.min.x < self.max.x
+            && self.min.y < other.max.y && other.min.y < self.max.y
+            && self.min.z < other.max.z && other.min.z < self.max.z
+    }
+
+    // Devuelve la zona común de las dos cajas, o None si no se solapan
+    pub fn intersection(&self, other: &Aabb3) -> Option<Aabb3> {
+        if !self.overlaps(other) {
+            return None;
+        }
+
+        Some(Aabb3::new(self.min.max(other.min), self.max.min(other.max)))
+    }
+
+    // Devuelve la menor caja que contiene a las dos
+    pub fn merge(&self, other: &Aabb3) -> Aabb3 {
+        Aabb3::new(self.min.min(other.min), self.max.max(other.max))
+    }
+
+    // Agranda la caja amount unidades por cada lado (encoge si es negativo)
+    pub fn expand(&self, amount: f32) -> Aabb3 {
+        let offset = Vector3::new(amount, amount, amount);
+        Aabb3::new(self.min - offset, self.max + offset)
+    }
+
+    // Agranda la caja lo justo para que contenga el punto
+    pub fn expand_to_include(&self, point: Vector3) -> Aabb3 {
+        Aabb3::new(self.min.min(point), self.max.max(point))
+    }
+
+    pub fn closest_point(&self, point: Vector3) -> Vector3 {
+        point.max(self.min).min(self.max)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aabb(min: (f32, f32, f32), max: (f32, f32, f32)) -> Aabb3 {
+        Aabb3::new(Vector3::new(min.0, min.1, min.2), Vector3::new(max.0, max.1, max.2))
+    }
+
+    #[test]
+    fn from_points_needs_at_least_one_point() {
+        assert!(Aabb3::from_points(&[]).is_none());
+
+        let bounds = Aabb3::from_points(&[
+            Vector3::new(1.0, -2.0, 3.0),
+            Vector3::new(-1.0, 4.0, 0.0),
+        ]).unwrap();
+        assert_eq!((bounds.min.x, bounds.min.y, bounds.min.z), (-1.0, -2.0, 0.0));
+        assert_eq!((bounds.max.x, bounds.max.y, bounds.max.z), (1.0, 4.0, 3.0));
+    }
+
+    // Una caja de tamaño 0 contiene su punto; no se solapa consigo misma ni con
+    // una caja a la que solo toca por una cara
+    #[test]
+    fn empty_aabb_contains_its_point_only() {
+        let point = aabb((1.0, 1.0, 1.0), (1.0, 1.0, 1.0));
+        let unit = aabb((0.0, 0.0, 0.0), (1.0, 2.0, 2.0));
+
+        assert!(point.contains_point(Vector3::one()));
+        assert!(!point.contains_point(Vector3::new(1.0, 1.0, 1.001)));
+        assert!(!point.overlaps(&point));
+        assert!(!point.overlaps(&unit));
+        assert!(point.intersection(&unit).is_none());
+        assert!(unit.contains_aabb(&point));
+    }
+
+    #[test]
+    fn touching_faces_do_not_overlap() {
+        let a = aabb((0.0, 0.0, 0.0), (1.0, 1.0, 1.0));
+        let b = aabb((1.0, 0.0, 0.0), (2.0, 1.0, 1.0));
+
+        assert!(!a.overlaps(&b));
+        assert!(a.intersection(&b).is_none());
+        assert!(a.contains_point(Vector3::new(1.0, 0.5, 0.5)));
+    }
+
+    #[test]
+    fn intersection_and_merge() {
+        let a = aabb((0.0, 0.0, 0.0), (2.0, 2.0, 2.0));
+        let b = aabb((1.0, -1.0, 1.0), (3.0, 1.0, 4.0));
+
+        let common = a.intersection(&b).unwrap();
+        assert_eq!((common.min.x, common.min.y, common.min.z), (1.0, 0.0, 1.0));
+        assert_eq!((common.max.x, common.max.y, common.max.z), (2.0, 1.0, 2.0));
+
+        let merged = a.merge(&b);
+        assert_eq!((merged.min.x, merged.min.y, merged.min.z), (0.0, -1.0, 0.0));
+        assert_eq!((merged.max.x, merged.max.y, merged.max.z), (3.0, 2.0, 4.0));
+        assert!(merged.contains_aabb(&a) && merged.contains_aabb(&b));
+    }
+
+    #[test]
+    fn closest_point_clamps_each_axis() {
+        let a = aabb((0.0, 0.0, 0.0), (1.0, 2.0, 3.0));
+
+        let closest = a.closest_point(Vector3::new(-5.0, 1.0, 10.0));
+        assert_eq!((closest.x, closest.y, closest.z), (0.0, 1.0, 3.0));
+    }
+}
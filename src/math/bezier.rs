@@ -0,0 +1,82 @@
+use crate::math::vector3::Vector3;
+use crate::math::curve::Curve;
+
+#[derive(Copy, Clone)]
+pub struct QuadraticBezier {
+    pub start: Vector3,
+    pub control: Vector3,
+    pub end: Vector3,
+}
+
+impl QuadraticBezier {
+    pub fn new(start: Vector3, control: Vector3, end: Vector3) -> QuadraticBezier {
+        QuadraticBezier {
+            start,
+            control,
+            end,
+        }
+    }
+}
+
+impl Curve for QuadraticBezier {
+    fn point(&self, t: f32) -> Vector3 {
+        let u = 1.0 - t;
+        self.start * (u * u) + self.control * (2.0 * u * t) + self.end * (t * t)
+    }
+
+    fn derivative(&self, t: f32) -> Vector3 {
+        let u = 1.0 - t;
+        (self.control - self.start) * (2.0 * u) + (self.end - self.control) * (2.0 * t)
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct CubicBezier {
+    pub start: Vector3,
+    pub control1: Vector3,
+    pub control2: Vector3,
+    pub end: Vector3,
+}
+
+impl CubicBezier {
+    pub fn new(start: Vector3, control1: Vector3, control2: Vector3, end: Vector3) -> CubicBezier {
+        CubicBezier {
+            start,
+            control1,
+            control2,
+            end,
+        }
+    }
+
+    // Divide la curva en t en dos curvas (algoritmo de De Casteljau)
+    pub fn split(&self, t: f32) -> (CubicBezier, CubicBezier) {
+        let p01 = self.start.lerp(self.control1, t);
+        let p12 = self.control1.lerp(self.control2, t);
+        let p23 = self.control2.lerp(self.end, t);
+        let p012 = p01.lerp(p12, t);
+        let p123 = p12.lerp(p23, t);
+        let middle = p012.lerp(p123, t);
+
+        (
+            CubicBezier::new(self.start, p01, p012, middle),
+            CubicBezier::new(middle, p123, p23, self.end),
+        )
+    }
+}
+
+impl Curve for CubicBezier {
+    fn point(&self, t: f32) -> Vector3 {
+        let u = 1.0 - t;
+        self.start * (u * u * u)
+            + self.control1 * (3.0 * u * u * t)
+            + self.control2 * (3.0 * u * t * t)
+            + self.end * (t * t * t)
+    }
+
+    fn derivative(&self, t: f32) -> Vector3 {
+        let u = 1.0 - t;
+        (self.control1 - self.start) * (3.0 * u * u)
+            + (self.control2 - self.control1) * (6.0 * u * t)
+            + (self.end - self.control2) * (3.0 * t * t)
+    }
+}
\ No newline at end of file
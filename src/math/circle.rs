@@ -0,0 +1,182 @@
+use crate::math::vector2::Vector2;
+use crate::math::rect::Rect;
+
+#[derive(Copy, Clone)]
+pub struct Circle {
+    pub center: Vector2,
+    pub radius: f32,
+}
+
+impl Circle {
+    pub fn new(center: Vector2, radius: f32) -> Circle {
+        Circle {
+            center,
+            radius,
+        }
+    }
+
+    pub fn contains_point(&self, point: Vector2) -> bool {
+        (point - self.center).length_squared() <= self.radius * self.radius
+    }
+
+    // Indica si other está completamente dentro de self
+    pub fn contains_circle(&self, other: &Circle) -> bool {
+        self.center.distance(other.center) + other.radius <= self.radius
+    }
+
+    pub fn overlaps(&self, other: &Circle) -> bool {
+        let radii = self.radius + other.radius;
+        (other.center - self.center).length_squared() < radii * radii
+    }
+
+    pub fn overlaps_rect(&self, rect: &Rect) -> bool {
+        let closest = rect.closest_point(self.center);
+        (closest - self.center).length_squared() < self.radius * self.radius
+    }
+
+    // Devuelve los puntos donde se cortan las circunferencias (0, 1 o 2 puntos)
+    pub fn intersection_points(&self, other: &Circle) -> Vec<Vector2> {
+        let offset = other.center - self.center;
+        let distance = offset.length();
+
+        // Separados, uno dentro del otro o concéntricos
+        if distance > self.radius + other.radius
+            || distance < (self.radius - other.radius).abs()
+            || distance == 0.0 {
+            return Vec::new();
+        }
+
+        // Distancia desde self.center hasta la cuerda que une los dos puntos
+        let a = (self.radius * self.radius - other.radius * other.radius + distance * distance)
+            / (2.0 * distance);
+        let h = (self.radius * self.radius - a * a).max(0.0).sqrt();
+
+        let direction = offset / distance;
+        let middle = self.center + direction * a;
+
+        if h == 0.0 {
+            vec![middle]
+        } else {
+            let perpendicular = direction.perpendicular() * h;
+            vec![middle + perpendicular, middle - perpendicular]
+        }
+    }
+
+    pub fn bounds(&self) -> Rect {
+        Rect::new(
+            self.center.x - self.radius,
+            self.center.y - self.radius,
+            self.radius * 2.0,
+            self.radius * 2.0,
+        )
+    }
+
+    // Devuelve el menor círculo que contiene a los dos
+    pub fn merge(&self, other: &Circle) -> Circle {
+        if self.contains_circle(other) {
+            return *self;
+        }
+        if other.contains_circle(self) {
+            return *other;
+        }
+
+        let offset = other.center - self.center;
+        let distance = offset.length();
+        let radius = (distance + self.radius + other.radius) * 0.5;
+        let center = self.center + offset / distance * (radius - self.radius);
+
+        Circle::new(center, radius)
+    }
+
+    // Agranda el radio amount unidades (encoge si es negativo)
+    pub fn expand(&self, amount: f32) -> Circle {
+        Circle::new(self.center, (self.radius + amount).max(0.0))
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOLERANCE: f32 = 1e-5;
+
+    fn assert_point_close(point: Vector2, x: f32, y: f32) {
+        assert!((point.x - x).abs() < TOLERANCE && (point.y - y).abs() < TOLERANCE,
+                "({}, {}) != ({}, {})", point.x, point.y, x, y);
+    }
+
+    // Dos círculos tangentes por fuera no se solapan y se cortan en un único punto
+    #[test]
+    fn touching_circles_meet_at_one_point() {
+        let a = Circle::new(Vector2::zero(), 1.0);
+        let b = Circle::new(Vector2::new(2.0, 0.0), 1.0);
+
+        assert!(!a.overlaps(&b));
+
+        let points = a.intersection_points(&b);
+        assert_eq!(points.len(), 1);
+        assert_point_close(points[0], 1.0, 0.0);
+    }
+
+    #[test]
+    fn crossing_circles_meet_at_two_points() {
+        let a = Circle::new(Vector2::zero(), 1.0);
+        let b = Circle::new(Vector2::new(1.0, 0.0), 1.0);
+
+        assert!(a.overlaps(&b));
+
+        let mut points = a.intersection_points(&b);
+        points.sort_by(|p, q| p.y.total_cmp(&q.y));
+        assert_eq!(points.len(), 2);
+        assert_point_close(points[0], 0.5, -(0.75f32).sqrt());
+        assert_point_close(points[1], 0.5, (0.75f32).sqrt());
+    }
+
+    #[test]
+    fn nested_and_concentric_circles_have_no_intersection_points() {
+        let outer = Circle::new(Vector2::zero(), 3.0);
+
+        assert!(outer.intersection_points(&Circle::new(Vector2::new(0.5, 0.0), 1.0)).is_empty());
+        assert!(outer.intersection_points(&Circle::new(Vector2::zero(), 3.0)).is_empty());
+        assert!(outer.intersection_points(&Circle::new(Vector2::new(10.0, 0.0), 1.0)).is_empty());
+    }
+
+    // Tocar el borde por dentro todavía cuenta como contenido
+    #[test]
+    fn contains_circle_touching_from_inside() {
+        let outer = Circle::new(Vector2::zero(), 2.0);
+
+        assert!(outer.contains_circle(&Circle::new(Vector2::new(1.0, 0.0), 1.0)));
+        assert!(!outer.contains_circle(&Circle::new(Vector2::new(1.5, 0.0), 1.0)));
+        assert!(outer.contains_point(Vector2::new(0.0, 2.0)));
+        assert!(!outer.contains_point(Vector2::new(0.0, 2.001)));
+    }
+
+    #[test]
+    fn overlaps_rect_with_center_inside_or_touching_edge() {
+        let rect = Rect::new(0.0, 0.0, 4.0, 4.0);
+
+        // Centro dentro del rectángulo: el punto más cercano es el propio centro
+        assert!(Circle::new(Vector2::new(2.0, 2.0), 0.5).overlaps_rect(&rect));
+        // Tangente al lado derecho
+        assert!(!Circle::new(Vector2::new(5.0, 2.0), 1.0).overlaps_rect(&rect));
+        // Cerca de una esquina pero fuera del radio
+        assert!(!Circle::new(Vector2::new(5.0, 5.0), 1.2).overlaps_rect(&rect));
+        assert!(Circle::new(Vector2::new(5.0, 5.0), 1.5).overlaps_rect(&rect));
+    }
+
+    #[test]
+    fn merge_contains_both_circles() {
+        let a = Circle::new(Vector2::zero(), 1.0);
+        let b = Circle::new(Vector2::new(4.0, 0.0), 1.0);
+
+        let merged = a.merge(&b);
+        assert_point_close(merged.center, 2.0, 0.0);
+        assert!((merged.radius - 3.0).abs() < TOLERANCE);
+
+        // Si uno contiene al otro el resultado es el mayor
+        let inner = Circle::new(Vector2::new(0.5, 0.0), 0.25);
+        let merged = a.merge(&inner);
+        assert_point_close(merged.center, 0.0, 0.0);
+        assert_eq!(merged.radius, 1.0);
+    }
+}
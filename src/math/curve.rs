@@ -0,0 +1,141 @@
+use std::cmp::Ordering;
+
+use crate::math::vector3::Vector3;
+
+// Curva paramétrica con t en [0, 1]
+pub trait Curve {
+    fn point(&self, t: f32) -> Vector3;
+
+    // Derivada respecto a t (su longitud es la velocidad al recorrer la curva)
+    fn derivative(&self, t: f32) -> Vector3;
+
+    fn tangent(&self, t: f32) -> Vector3 {
+        self.derivative(t).normalized()
+    }
+
+    // Normal en el plano XY: la tangente girada 90 grados en sentido antihorario
+    fn normal(&self, t: f32) -> Vector3 {
+        let tangent = self.tangent(t);
+        Vector3::new(-tangent.y, tangent.x, 0.0)
+    }
+
+    // Devuelve segments + 1 puntos repartidos uniformemente en t
+    fn sample(&self, segments: usize) -> Vec<Vector3> {
+        let segments = segments.max(1);
+        (0..=segments)
+            .map(|i| self.point(i as f32 / segments as f32))
+            .collect()
+    }
+}
+
+// Tabla de longitud de arco para recorrer una curva a velocidad constante.
+// Repartir t uniformemente no reparte los puntos uniformemente en distancia
+pub struct ArcLengthTable {
+    // Longitud acumulada en cada muestra (t = i / (len - 1))
+    lengths: Vec<f32>,
+}
+
+impl ArcLengthTable {
+    pub fn new(curve: &dyn Curve, samples: usize) -> ArcLengthTable {
+        let points = curve.sample(samples);
+
+        let mut lengths = Vec::with_capacity(points.len());
+        let mut total = 0.0;
+        lengths.push(0.0);
+        for pair in points.windows(2) {
+            total += pair[0].distance(pair[1]);
+            lengths.push(total);
+        }
+
+        ArcLengthTable { lengths }
+    }
+
+    pub fn length(&self) -> f32 {
+        *self.lengths.last().unwrap()
+    }
+
+    // Devuelve el parámetro t que está a la distancia dada del inicio de la curva
+    pub fn t_at_distance(&self, distance: f32) -> f32 {
+        let total = self.length();
+        // Curva de longitud nula, o NaN si algún punto de control es NaN
+        if total.is_nan() || total <= 0.0 {
+            return 0.0;
+        }
+        let distance = if distance.is_nan() { 0.0 } else { distance.clamp(0.0, total) };
+
+        // Primera muestra cuya longitud acumulada es >= distance
+        let index = match self.lengths.binary_search_by(|l| l.partial_cmp(&distance).unwrap_or(Ordering::Less)) {
+            Ok(i) => return i as f32 / (self.lengths.len() - 1) as f32,
+            Err(i) => i,
+        };
+
+        let before = self.lengths[index - 1];
+        let after = self.lengths[index];
+        let fraction = (distance - before) / (after - before);
+
+        (index as f32 - 1.0 + fraction) / (self.lengths.len() - 1) as f32
+    }
+
+    // Igual que t_at_distance pero con la distancia como fracción de la longitud total
+    pub fn t_at_fraction(&self, fraction: f32) -> f32 {
+        self.t_at_distance(fraction * self.length())
+    }
+
+    pub fn point_at_distance(&self, curve: &dyn Curve, distance: f32) -> Vector3 {
+        curve.point(self.t_at_distance(distance))
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOLERANCE: f32 = 1e-4;
+
+    // Recta de start a end, con velocidad constante
+    struct Line {
+        start: Vector3,
+        end: Vector3,
+    }
+
+    impl Curve for Line {
+        fn point(&self, t: f32) -> Vector3 {
+            self.start.lerp(self.end, t)
+        }
+
+        fn derivative(&self, _t: f32) -> Vector3 {
+            self.end - self.start
+        }
+    }
+
+    fn line(end: Vector3) -> Line {
+        Line { start: Vector3::zero(), end }
+    }
+
+    #[test]
+    fn t_at_distance_on_a_line_is_proportional() {
+        let table = ArcLengthTable::new(&line(Vector3::new(10.0, 0.0, 0.0)), 8);
+
+        assert!((table.length() - 10.0).abs() < TOLERANCE);
+        assert!((table.t_at_distance(2.5) - 0.25).abs() < TOLERANCE);
+        assert!((table.t_at_fraction(0.6) - 0.6).abs() < TOLERANCE);
+        assert_eq!(table.t_at_distance(-1.0), 0.0);
+        assert_eq!(table.t_at_distance(50.0), 1.0);
+    }
+
+    #[test]
+    fn zero_length_curve_returns_start() {
+        let table = ArcLengthTable::new(&line(Vector3::zero()), 4);
+
+        assert_eq!(table.t_at_distance(1.0), 0.0);
+    }
+
+    // Un punto de control NaN no puede hacer que la búsqueda entre en pánico
+    #[test]
+    fn nan_curve_does_not_panic() {
+        let table = ArcLengthTable::new(&line(Vector3::new(f32::NAN, 0.0, 0.0)), 4);
+        assert_eq!(table.t_at_distance(1.0), 0.0);
+
+        let table = ArcLengthTable::new(&line(Vector3::new(4.0, 0.0, 0.0)), 4);
+        assert_eq!(table.t_at_distance(f32::NAN), 0.0);
+    }
+}
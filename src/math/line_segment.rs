@@ -0,0 +1,138 @@
+use crate::math::vector2::Vector2;
+use crate::math::rect::Rect;
+
+#[derive(Copy, Clone)]
+pub struct LineSegment {
+    pub start: Vector2,
+    pub end: Vector2,
+}
+
+impl LineSegment {
+    pub fn new(start: Vector2, end: Vector2) -> LineSegment {
+        LineSegment {
+            start,
+            end,
+        }
+    }
+
+    pub fn length(&self) -> f32 {
+        self.start.distance(self.end)
+    }
+
+    pub fn direction(&self) -> Vector2 {
+        self.end - self.start
+    }
+
+    // Punto del segmento en t (0 = start, 1 = end)
+    pub fn point_at(&self, t: f32) -> Vector2 {
+        self.start.lerp(self.end, t)
+    }
+
+    pub fn bounds(&self) -> Rect {
+        Rect::from_min_max(self.start.min(self.end), self.start.max(self.end))
+    }
+
+    // Devuelve el punto del segmento más cercano al punto dado
+    pub fn closest_point(&self, point: Vector2) -> Vector2 {
+        let direction = self.direction();
+        let length_squared = direction.length_squared();
+        if length_squared == 0.0 {
+            return self.start;
+        }
+
+        let t = ((point - self.start).dot(direction) / length_squared).clamp(0.0, 1.0);
+        self.point_at(t)
+    }
+
+    pub fn distance_to_point(&self, point: Vector2) -> f32 {
+        self.closest_point(point).distance(point)
+    }
+
+    // Devuelve el punto donde se cortan los dos segmentos, o None si no se cortan
+    // (los segmentos paralelos o colineales no se consideran cortados)
+    pub fn intersection(&self, other: &LineSegment) -> Option<Vector2> {
+        let (t, u) = segment_parameters(self.start, self.direction(), other.start, other.direction())?;
+
+        if (0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u) {
+            Some(self.point_at(t))
+        } else {
+            None
+        }
+    }
+
+    pub fn intersects(&self, other: &LineSegment) -> bool {
+        self.intersection(other).is_some()
+    }
+}
+
+// Resuelve p + t * r = q + u * s y devuelve (t, u), o None si las rectas son paralelas
+pub(crate) fn segment_parameters(p: Vector2, r: Vector2, q: Vector2, s: Vector2) -> Option<(f32, f32)> {
+    let denominator = r.cross(s);
+    if denominator.abs() < f32::EPSILON {
+        return None;
+    }
+
+    let offset = q - p;
+    Some((offset.cross(s) / denominator, offset.cross(r) / denominator))
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(x1: f32, y1: f32, x2: f32, y2: f32) -> LineSegment {
+        LineSegment::new(Vector2::new(x1, y1), Vector2::new(x2, y2))
+    }
+
+    #[test]
+    fn crossing_segments_intersect() {
+        let point = segment(0.0, 0.0, 2.0, 2.0).intersection(&segment(0.0, 2.0, 2.0, 0.0)).unwrap();
+
+        assert_eq!((point.x, point.y), (1.0, 1.0));
+    }
+
+    // Los extremos cuentan: un segmento que acaba justo encima del otro lo corta
+    #[test]
+    fn touching_endpoints_intersect() {
+        let a = segment(0.0, 0.0, 2.0, 0.0);
+
+        let t = a.intersection(&segment(1.0, 0.0, 1.0, 3.0)).unwrap();
+        assert_eq!((t.x, t.y), (1.0, 0.0));
+
+        let corner = a.intersection(&segment(2.0, 0.0, 2.0, 1.0)).unwrap();
+        assert_eq!((corner.x, corner.y), (2.0, 0.0));
+
+        assert!(!a.intersects(&segment(1.0, 0.1, 1.0, 3.0)));
+    }
+
+    #[test]
+    fn parallel_and_collinear_segments_do_not_intersect() {
+        let a = segment(0.0, 0.0, 2.0, 0.0);
+
+        assert!(a.intersection(&segment(0.0, 1.0, 2.0, 1.0)).is_none());
+        assert!(a.intersection(&segment(1.0, 0.0, 3.0, 0.0)).is_none());
+    }
+
+    #[test]
+    fn closest_point_clamps_to_the_ends() {
+        let a = segment(0.0, 0.0, 4.0, 0.0);
+
+        let middle = a.closest_point(Vector2::new(1.0, 3.0));
+        assert_eq!((middle.x, middle.y), (1.0, 0.0));
+
+        let end = a.closest_point(Vector2::new(6.0, 1.0));
+        assert_eq!((end.x, end.y), (4.0, 0.0));
+
+        assert_eq!(a.distance_to_point(Vector2::new(-3.0, 4.0)), 5.0);
+    }
+
+    // Un segmento de longitud 0 se comporta como un punto
+    #[test]
+    fn degenerate_segment_acts_as_a_point() {
+        let point = segment(1.0, 1.0, 1.0, 1.0);
+
+        let closest = point.closest_point(Vector2::new(5.0, 4.0));
+        assert_eq!((closest.x, closest.y), (1.0, 1.0));
+        assert_eq!(point.distance_to_point(Vector2::new(4.0, 5.0)), 5.0);
+        assert!(!point.intersects(&segment(0.0, 0.0, 2.0, 2.0)));
+    }
+}
@@ -1,4 +1,5 @@
 use crate::math::vector3::Vector3;
+use crate::math::transform::Transform;
 use auto_ops::*; // Tienen macros para hacer sobrecarga de operadores
 
 pub struct Matrix4x4 {
@@ -151,6 +152,45 @@ impl Matrix4x4 {
         m
     }
 
+    // Devuelve una matriz de proyección en perspectiva (cámara mirando hacia -z, y hacia
+    // arriba). fov_y es el ángulo vertical de visión en radianes
+    pub fn perspective(fov_y: f32, aspect: f32, near_clip: f32, far_clip: f32) -> Matrix4x4 {
+        let mut m = Matrix4x4::identity();
+        let f = 1.0 / (fov_y / 2.0).tan();
+
+        m.data[0] = f / aspect;
+        m.data[5] = f;
+        m.data[10] = (far_clip + near_clip) / (near_clip - far_clip);
+        m.data[11] = -1.0;
+        m.data[14] = 2.0 * far_clip * near_clip / (near_clip - far_clip);
+        m.data[15] = 0.0;
+
+        m
+    }
+
+    // Matriz de vista de una cámara en eye que mira hacia target
+    pub fn look_at(eye: Vector3, target: Vector3, up: Vector3) -> Matrix4x4 {
+        let forward = (target - eye).normalized();
+        let right = forward.cross(up).normalized();
+        let up = right.cross(forward);
+
+        let mut m = Matrix4x4::identity();
+
+        m.data[0] = right.x;
+        m.data[4] = right.y;
+        m.data[8] = right.z;
+        m.data[1] = up.x;
+        m.data[5] = up.y;
+        m.data[9] = up.z;
+        m.data[2] = -forward.x;
+        m.data[6] = -forward.y;
+        m.data[10] = -forward.z;
+        m.data[12] = -right.dot(eye);
+        m.data[13] = -up.dot(eye);
+        m.data[14] = forward.dot(eye);
+
+        m
+    }
 
     // Devuelve una matriz de translación
     pub fn translation(position: Vector3) -> Matrix4x4 {
@@ -227,4 +267,368 @@ impl Matrix4x4 {
 
         m
     }
-}
\ No newline at end of file
+    // Multiplica un punto por la matriz (con w = 1)
+    pub fn transform_point(&self, point: Vector3) -> Vector3 {
+        let d = &self.data;
+
+        Vector3::new(
+            d[0] * point.x + d[4] * point.y + d[8] * point.z + d[12],
+            d[1] * point.x + d[5] * point.y + d[9] * point.z + d[13],
+            d[2] * point.x + d[6] * point.y + d[10] * point.z + d[14],
+        )
+    }
+
+    pub fn transpose(&self) -> Matrix4x4 {
+        let mut m = Matrix4x4::identity();
+
+        for column in 0..4 {
+            for row in 0..4 {
+                m.data[column * 4 + row] = self.data[row * 4 + column];
+            }
+        }
+
+        m
+    }
+
+    // Devuelve la matriz inversa, o None si no tiene (determinante 0)
+    pub fn inverse(&self) -> Option<Matrix4x4> {
+        let a = &self.data;
+        let (a00, a01, a02, a03) = (a[0], a[1], a[2], a[3]);
+        let (a10, a11, a12, a13) = (a[4], a[5], a[6], a[7]);
+        let (a20, a21, a22, a23) = (a[8], a[9], a[10], a[11]);
+        let (a30, a31, a32, a33) = (a[12], a[13], a[14], a[15]);
+
+        // Determinantes 2x2 de las dos primeras y las dos últimas columnas
+        let b00 = a00 * a11 - a01 * a10;
+        let b01 = a00 * a12 - a02 * a10;
+        let b02 = a00 * a13 - a03 * a10;
+        let b03 = a01 * a12 - a02 * a11;
+        let b04 = a01 * a13 - a03 * a11;
+        let b05 = a02 * a13 - a03 * a12;
+        let b06 = a20 * a31 - a21 * a30;
+        let b07 = a20 * a32 - a22 * a30;
+        let b08 = a20 * a33 - a23 * a30;
+        let b09 = a21 * a32 - a22 * a31;
+        let b10 = a21 * a33 - a23 * a31;
+        let b11 = a22 * a33 - a23 * a32;
+
+        let determinant = b00 * b11 - b01 * b10 + b02 * b09 + b03 * b08 - b04 * b07 + b05 * b06;
+        if determinant.abs() < f32::EPSILON * f32::EPSILON {
+            return None;
+        }
+        let inv = 1.0 / determinant;
+
+        Some(Matrix4x4 {
+            data: [
+                (a11 * b11 - a12 * b10 + a13 * b09) * inv,
+                (a02 * b10 - a01 * b11 - a03 * b09) * inv,
+                (a31 * b05 - a32 * b04 + a33 * b03) * inv,
+                (a22 * b04 - a21 * b05 - a23 * b03) * inv,
+                (a12 * b08 - a10 * b11 - a13 * b07) * inv,
+                (a00 * b11 - a02 * b08 + a03 * b07) * inv,
+                (a32 * b02 - a30 * b05 - a33 * b01) * inv,
+                (a20 * b05 - a22 * b02 + a23 * b01) * inv,
+                (a10 * b10 - a11 * b08 + a13 * b06) * inv,
+                (a01 * b08 - a00 * b10 - a03 * b06) * inv,
+                (a30 * b04 - a31 * b02 + a33 * b00) * inv,
+                (a21 * b02 - a20 * b04 - a23 * b00) * inv,
+                (a11 * b07 - a10 * b09 - a12 * b06) * inv,
+                (a00 * b09 - a01 * b07 + a02 * b06) * inv,
+                (a31 * b01 - a30 * b03 - a32 * b00) * inv,
+                (a20 * b03 - a21 * b01 + a22 * b00) * inv,
+            ]
+        })
+    }
+
+    // Matriz 3x3 (por columnas) para transformar normales: la inversa traspuesta de la
+    // parte de rotación y escala. Con escala no uniforme la matriz del modelo las
+    // torcería. Si la matriz no tiene inversa devuelve la identidad
+    pub fn normal_matrix(&self) -> [f32; 9] {
+        match self.inverse() {
+            Some(inverse) => {
+                let m = inverse.transpose().data;
+                [m[0], m[1], m[2], m[4], m[5], m[6], m[8], m[9], m[10]]
+            }
+            None => [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0],
+        }
+    }
+
+    // Devuelve el determinante de la submatriz 3x3 (rotación y escala)
+    pub fn determinant_3x3(&self) -> f32 {
+        let d = &self.data;
+
+        d[0] * (d[5] * d[10] - d[9] * d[6])
+            - d[4] * (d[1] * d[10] - d[9] * d[2])
+            + d[8] * (d[1] * d[6] - d[5] * d[2])
+    }
+
+    // Descompone la matriz en posición, rotación y escala (la inversa de
+    // Transform::get_transformation_matrix). Devuelve None si la matriz no se
+    // puede representar con un Transform: tiene proyección, escala nula o
+    // cizalla (shear)
+    pub fn decompose(&self) -> Option<Transform> {
+        const EPSILON: f32 = 1e-5;
+        const SHEAR_EPSILON: f32 = 1e-4;
+
+        let d = &self.data;
+
+        // La última fila debe ser (0, 0, 0, 1), si no hay proyección
+        if d[3].abs() > EPSILON || d[7].abs() > EPSILON || d[11].abs() > EPSILON
+            || (d[15] - 1.0).abs() > EPSILON {
+            return None;
+        }
+
+        // Cada columna de la submatriz 3x3 es un eje de rotación multiplicado por su escala
+        let mut x_axis = [d[0], d[1], d[2]];
+        let mut y_axis = [d[4], d[5], d[6]];
+        let mut z_axis = [d[8], d[9], d[10]];
+
+        let length = |v: &[f32; 3]| (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+        let dot = |a: &[f32; 3], b: &[f32; 3]| a[0] * b[0] + a[1] * b[1] + a[2] * b[2];
+
+        let mut scale = Vector3::new(length(&x_axis), length(&y_axis), length(&z_axis));
+        if scale.x < EPSILON || scale.y < EPSILON || scale.z < EPSILON {
+            return None;
+        }
+
+        // Un determinante negativo indica una reflexión: la asignamos a la escala en x
+        if self.determinant_3x3() < 0.0 {
+            scale.x = -scale.x;
+        }
+
+        for i in 0..3 {
+            x_axis[i] /= scale.x;
+            y_axis[i] /= scale.y;
+            z_axis[i] /= scale.z;
+        }
+
+        // Si los ejes no son perpendiculares entre sí hay cizalla
+        if dot(&x_axis, &y_axis).abs() > SHEAR_EPSILON
+            || dot(&x_axis, &z_axis).abs() > SHEAR_EPSILON
+            || dot(&y_axis, &z_axis).abs() > SHEAR_EPSILON {
+            return None;
+        }
+
+        // Extraemos los ángulos de R = Rz * Ry * Rx (mismo orden que Matrix4x4::rotation)
+        let r00 = x_axis[0];
+        let r10 = x_axis[1];
+        let r20 = x_axis[2];
+        let r11 = y_axis[1];
+        let r21 = y_axis[2];
+        let r12 = z_axis[1];
+        let r22 = z_axis[2];
+
+        let mut rotation = Vector3::zero();
+        rotation.y = (-r20).clamp(-1.0, 1.0).asin();
+
+        if rotation.y.cos() > EPSILON {
+            rotation.x = r21.atan2(r22);
+            rotation.z = r10.atan2(r00);
+        } else {
+            // Bloqueo de cardán (gimbal lock): x y z giran sobre el mismo eje,
+            // así que ponemos z a cero y toda la rotación va a x
+            rotation.x = (-r12).atan2(r11);
+            rotation.z = 0.0;
+        }
+
+        let mut transform = Transform::new();
+        transform.position = Vector3::new(d[12], d[13], d[14]);
+        transform.rotation = rotation;
+        transform.scale = scale;
+
+        Some(transform)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOLERANCE: f32 = 1e-4;
+
+    fn transform(position: Vector3, rotation: Vector3, scale: Vector3) -> Transform {
+        let mut transform = Transform::new();
+        transform.position = position;
+        transform.rotation = rotation;
+        transform.scale = scale;
+        transform
+    }
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a - b).abs() < TOLERANCE, "{} != {}", a, b);
+    }
+
+    fn assert_vector_close(a: Vector3, b: Vector3) {
+        assert_close(a.x, b.x);
+        assert_close(a.y, b.y);
+        assert_close(a.z, b.z);
+    }
+
+    fn assert_matrix_close(a: &Matrix4x4, b: &Matrix4x4) {
+        for (a, b) in a.data.iter().zip(b.data.iter()) {
+            assert_close(*a, *b);
+        }
+    }
+
+    // Transform -> matriz -> decompose tiene que dar los mismos campos
+    fn assert_round_trip(original: Transform) {
+        let matrix = original.get_transformation_matrix();
+        let decomposed = matrix.decompose().expect("la matriz se tendría que poder descomponer");
+
+        assert_vector_close(decomposed.position, original.position);
+        assert_vector_close(decomposed.rotation, original.rotation);
+        assert_vector_close(decomposed.scale, original.scale);
+        assert_matrix_close(&decomposed.get_transformation_matrix(), &matrix);
+    }
+
+    #[test]
+    fn decompose_round_trips_translation() {
+        assert_round_trip(transform(Vector3::new(10.0, -4.5, 3.0), Vector3::zero(), Vector3::one()));
+    }
+
+    #[test]
+    fn decompose_round_trips_rotation() {
+        assert_round_trip(transform(Vector3::zero(), Vector3::new(0.3, -0.7, 1.2), Vector3::one()));
+        assert_round_trip(transform(Vector3::zero(), Vector3::new(0.0, 0.0, 2.5), Vector3::one()));
+    }
+
+    #[test]
+    fn decompose_round_trips_scale() {
+        assert_round_trip(transform(Vector3::zero(), Vector3::zero(), Vector3::new(2.0, 0.5, 3.0)));
+    }
+
+    #[test]
+    fn decompose_round_trips_full_transform() {
+        assert_round_trip(transform(
+            Vector3::new(150.0, 150.0, -2.0),
+            Vector3::new(0.2, 0.4, 0.6),
+            Vector3::new(3.3, 1.0, 0.25),
+        ));
+    }
+
+    #[test]
+    fn decompose_keeps_negative_scale_on_x() {
+        assert_round_trip(transform(Vector3::new(1.0, 2.0, 3.0), Vector3::new(0.1, 0.2, 0.3), Vector3::new(-2.0, 1.5, 1.0)));
+    }
+
+    #[test]
+    fn decompose_moves_other_reflections_to_x() {
+        // Una escala negativa en y se representa con x negativa y otra rotación,
+        // pero la matriz tiene que ser la misma
+        let original = transform(Vector3::new(5.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 0.5), Vector3::new(1.0, -3.0, 2.0));
+        let matrix = original.get_transformation_matrix();
+        let decomposed = Transform::from_matrix(&matrix).unwrap();
+
+        assert!(decomposed.scale.x < 0.0);
+        assert_close(decomposed.scale.y.abs(), 3.0);
+        assert_close(decomposed.scale.z.abs(), 2.0);
+        assert_matrix_close(&decomposed.get_transformation_matrix(), &matrix);
+    }
+
+    #[test]
+    fn decompose_handles_gimbal_lock() {
+        let original = transform(Vector3::zero(), Vector3::new(0.4, std::f32::consts::FRAC_PI_2, 0.0), Vector3::one());
+        let matrix = original.get_transformation_matrix();
+        let decomposed = matrix.decompose().unwrap();
+
+        assert_matrix_close(&decomposed.get_transformation_matrix(), &matrix);
+    }
+
+    #[test]
+    fn decompose_rejects_shear() {
+        let mut shear = Matrix4x4::identity();
+        shear.data[4] = 0.5;
+
+        assert!(shear.decompose().is_none());
+        assert!(Transform::from_matrix(&(Matrix4x4::rotation_z(0.3) * shear)).is_none());
+    }
+
+    #[test]
+    fn decompose_rejects_projection() {
+        assert!(Matrix4x4::perspective(1.0, 4.0 / 3.0, 0.1, 100.0).decompose().is_none());
+
+        let mut projective = Matrix4x4::identity();
+        projective.data[3] = 0.01;
+        assert!(Transform::from_matrix(&projective).is_none());
+    }
+
+    #[test]
+    fn decompose_rejects_zero_scale() {
+        assert!(Matrix4x4::scale(Vector3::new(1.0, 0.0, 1.0)).decompose().is_none());
+    }
+
+    fn dot(a: Vector3, b: Vector3) -> f32 {
+        a.x * b.x + a.y * b.y + a.z * b.z
+    }
+
+    // Dirección por la parte 3x3 de la matriz (sin traslación)
+    fn transform_direction(m: &Matrix4x4, v: Vector3) -> Vector3 {
+        let d = &m.data;
+        Vector3::new(
+            d[0] * v.x + d[4] * v.y + d[8] * v.z,
+            d[1] * v.x + d[5] * v.y + d[9] * v.z,
+            d[2] * v.x + d[6] * v.y + d[10] * v.z,
+        )
+    }
+
+    fn transform_normal(m: &[f32; 9], n: Vector3) -> Vector3 {
+        Vector3::new(
+            m[0] * n.x + m[3] * n.y + m[6] * n.z,
+            m[1] * n.x + m[4] * n.y + m[7] * n.z,
+            m[2] * n.x + m[5] * n.y + m[8] * n.z,
+        )
+    }
+
+    #[test]
+    fn inverse_times_matrix_is_identity() {
+        let matrices = [
+            transform(Vector3::new(3.0, -2.0, 7.5), Vector3::new(0.3, -1.1, 2.0), Vector3::new(2.0, 0.5, -1.5))
+                .get_transformation_matrix(),
+            Matrix4x4::perspective(1.0, 16.0 / 9.0, 0.1, 100.0),
+            Matrix4x4::scale(Vector3::new(0.01, 0.01, 0.01)),
+        ];
+
+        for matrix in matrices.iter() {
+            let inverse = matrix.inverse().expect("la matriz tiene inversa");
+            assert_matrix_close(&(matrix * &inverse), &Matrix4x4::identity());
+            assert_matrix_close(&(&inverse * matrix), &Matrix4x4::identity());
+        }
+    }
+
+    #[test]
+    fn singular_matrices_have_no_inverse() {
+        assert!(Matrix4x4::scale(Vector3::new(2.0, 0.0, 1.0)).inverse().is_none());
+
+        // La tercera columna es el doble de la primera
+        let mut dependent = transform(Vector3::new(1.0, 2.0, 3.0), Vector3::new(0.2, 0.4, 0.6), Vector3::one())
+            .get_transformation_matrix();
+        for row in 0..4 {
+            dependent.data[8 + row] = 2.0 * dependent.data[row];
+        }
+        assert!(dependent.inverse().is_none());
+
+        // Sin inversa, normal_matrix deja las normales como están
+        assert_eq!(dependent.normal_matrix(), [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn normal_matrix_keeps_normals_perpendicular() {
+        let model = transform(Vector3::new(5.0, 1.0, -2.0), Vector3::new(0.5, 0.25, -0.8), Vector3::new(4.0, 0.5, 1.0))
+            .get_transformation_matrix();
+        let normal_matrix = model.normal_matrix();
+
+        // Una superficie inclinada: dos tangentes y su normal
+        let tangent = Vector3::new(1.0, 1.0, 0.0);
+        let bitangent = Vector3::new(0.0, 1.0, -1.0);
+        let normal = Vector3::new(-1.0, 1.0, 1.0);
+        assert_close(dot(tangent, normal), 0.0);
+        assert_close(dot(bitangent, normal), 0.0);
+
+        let normal = transform_normal(&normal_matrix, normal).normalized();
+        assert_close(dot(transform_direction(&model, tangent).normalized(), normal), 0.0);
+        assert_close(dot(transform_direction(&model, bitangent).normalized(), normal), 0.0);
+
+        // Con la matriz del modelo la normal dejaría de ser perpendicular
+        let skewed = transform_direction(&model, Vector3::new(-1.0, 1.0, 1.0)).normalized();
+        assert!(dot(transform_direction(&model, tangent).normalized(), skewed).abs() > 0.1);
+    }
+}
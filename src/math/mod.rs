@@ -1,3 +1,16 @@
 pub mod matrix4x4;
+pub mod vector2;
 pub mod vector3;
-pub mod transform;
\ No newline at end of file
+pub mod transform;
+pub mod rect;
+pub mod circle;
+pub mod aabb3;
+pub mod ray;
+pub mod line_segment;
+pub mod polygon;
+pub mod curve;
+pub mod bezier;
+pub mod spline;
+pub mod random;
+pub mod noise;
+pub mod rect_packer;
\ No newline at end of file
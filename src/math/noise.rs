@@ -0,0 +1,413 @@
+use crate::math::random::Random;
+
+// Gradientes para Perlin y Simplex en 3D: los puntos medios de las aristas de un cubo
+const GRADIENTS_3D: [[f32; 3]; 12] = [
+    [1.0, 1.0, 0.0], [-1.0, 1.0, 0.0], [1.0, -1.0, 0.0], [-1.0, -1.0, 0.0],
+    [1.0, 0.0, 1.0], [-1.0, 0.0, 1.0], [1.0, 0.0, -1.0], [-1.0, 0.0, -1.0],
+    [0.0, 1.0, 1.0], [0.0, -1.0, 1.0], [0.0, 1.0, -1.0], [0.0, -1.0, -1.0],
+];
+
+// Gradientes en 2D: ejes y diagonales
+const GRADIENTS_2D: [[f32; 2]; 8] = [
+    [1.0, 0.0], [-1.0, 0.0], [0.0, 1.0], [0.0, -1.0],
+    [1.0, 1.0], [-1.0, 1.0], [1.0, -1.0], [-1.0, -1.0],
+];
+
+// Factores de sesgo del simplex 2D: (sqrt(3) - 1) / 2 y (3 - sqrt(3)) / 6
+const F2: f32 = 0.366_025_42;
+const G2: f32 = 0.211_324_87;
+const F3: f32 = 1.0 / 3.0;
+const G3: f32 = 1.0 / 6.0;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum NoiseKind {
+    Value,
+    Perlin,
+    Simplex,
+}
+
+// Parámetros del ruido fractal: cada octava multiplica la frecuencia por
+// lacunarity y la amplitud por gain
+#[derive(Copy, Clone)]
+pub struct Fractal {
+    pub kind: NoiseKind,
+    pub octaves: u32,
+    pub lacunarity: f32,
+    pub gain: f32,
+}
+
+impl Fractal {
+    // Valores habituales: el doble de frecuencia y la mitad de amplitud por octava
+    pub fn new(kind: NoiseKind, octaves: u32) -> Fractal {
+        Fractal {
+            kind,
+            octaves,
+            lacunarity: 2.0,
+            gain: 0.5,
+        }
+    }
+
+    fn sum<F: Fn(f32) -> f32>(&self, sample: F) -> f32 {
+        let mut sum = 0.0;
+        let mut amplitude = 1.0;
+        let mut frequency = 1.0;
+        let mut total_amplitude = 0.0;
+
+        for _ in 0..self.octaves.max(1) {
+            sum += sample(frequency) * amplitude;
+            total_amplitude += amplitude;
+            amplitude *= self.gain;
+            frequency *= self.lacunarity;
+        }
+
+        sum / total_amplitude
+    }
+}
+
+// Ruido coherente (value, Perlin y simplex) en 2D y 3D, con resultados en [-1, 1]
+// aproximadamente. La tabla de permutación sale de la semilla con Random, así
+// que la misma semilla da el mismo terreno en cualquier máquina
+pub struct Noise {
+    permutation: [u8; 512],
+}
+
+impl Noise {
+    pub fn new(seed: u64) -> Noise {
+        let mut table: Vec<u8> = (0..=255).collect();
+        Random::new(seed).shuffle(&mut table);
+
+        let mut permutation = [0; 512];
+        for i in 0..512 {
+            permutation[i] = table[i & 255];
+        }
+
+        Noise { permutation }
+    }
+
+    fn hash2(&self, x: i32, y: i32) -> usize {
+        let x = (x & 255) as usize;
+        let y = (y & 255) as usize;
+        self.permutation[self.permutation[x] as usize + y] as usize
+    }
+
+    fn hash3(&self, x: i32, y: i32, z: i32) -> usize {
+        let z = (z & 255) as usize;
+        self.permutation[self.hash2(x, y) + z] as usize
+    }
+
+    pub fn get2(&self, kind: NoiseKind, x: f32, y: f32) -> f32 {
+        match kind {
+            NoiseKind::Value => self.value2(x, y),
+            NoiseKind::Perlin => self.perlin2(x, y),
+            NoiseKind::Simplex => self.simplex2(x, y),
+        }
+    }
+
+    pub fn get3(&self, kind: NoiseKind, x: f32, y: f32, z: f32) -> f32 {
+        match kind {
+            NoiseKind::Value => self.value3(x, y, z),
+            NoiseKind::Perlin => self.perlin3(x, y, z),
+            NoiseKind::Simplex => self.simplex3(x, y, z),
+        }
+    }
+
+    // Ruido fractal (fBm): suma varias octavas, normalizado a [-1, 1]
+    pub fn fractal2(&self, fractal: &Fractal, x: f32, y: f32) -> f32 {
+        fractal.sum(|frequency| self.get2(fractal.kind, x * frequency, y * frequency))
+    }
+
+    pub fn fractal3(&self, fractal: &Fractal, x: f32, y: f32, z: f32) -> f32 {
+        fractal.sum(|frequency| self.get3(fractal.kind, x * frequency, y * frequency, z * frequency))
+    }
+
+    // Ruido de valor: valores aleatorios en los vértices de la rejilla, interpolados
+    pub fn value2(&self, x: f32, y: f32) -> f32 {
+        let (xi, xf) = split(x);
+        let (yi, yf) = split(y);
+        let u = fade(xf);
+        let v = fade(yf);
+
+        let corner = |dx: i32, dy: i32| lattice_value(self.hash2(xi.wrapping_add(dx), yi.wrapping_add(dy)));
+
+        lerp(
+            lerp(corner(0, 0), corner(1, 0), u),
+            lerp(corner(0, 1), corner(1, 1), u),
+            v,
+        )
+    }
+
+    pub fn value3(&self, x: f32, y: f32, z: f32) -> f32 {
+        let (xi, xf) = split(x);
+        let (yi, yf) = split(y);
+        let (zi, zf) = split(z);
+        let u = fade(xf);
+        let v = fade(yf);
+        let w = fade(zf);
+
+        let corner = |dx: i32, dy: i32, dz: i32| lattice_value(self.hash3(xi.wrapping_add(dx), yi.wrapping_add(dy), zi.wrapping_add(dz)));
+
+        lerp(
+            lerp(
+                lerp(corner(0, 0, 0), corner(1, 0, 0), u),
+                lerp(corner(0, 1, 0), corner(1, 1, 0), u),
+                v,
+            ),
+            lerp(
+                lerp(corner(0, 0, 1), corner(1, 0, 1), u),
+                lerp(corner(0, 1, 1), corner(1, 1, 1), u),
+                v,
+            ),
+            w,
+        )
+    }
+
+    // Ruido Perlin mejorado (2002)
+    pub fn perlin2(&self, x: f32, y: f32) -> f32 {
+        let (xi, xf) = split(x);
+        let (yi, yf) = split(y);
+        let u = fade(xf);
+        let v = fade(yf);
+
+        let corner = |dx: i32, dy: i32| {
+            let g = GRADIENTS_2D[self.hash2(xi.wrapping_add(dx), yi.wrapping_add(dy)) & 7];
+            g[0] * (xf - dx as f32) + g[1] * (yf - dy as f32)
+        };
+
+        lerp(
+            lerp(corner(0, 0), corner(1, 0), u),
+            lerp(corner(0, 1), corner(1, 1), u),
+            v,
+        )
+    }
+
+    pub fn perlin3(&self, x: f32, y: f32, z: f32) -> f32 {
+        let (xi, xf) = split(x);
+        let (yi, yf) = split(y);
+        let (zi, zf) = split(z);
+        let u = fade(xf);
+        let v = fade(yf);
+        let w = fade(zf);
+
+        let corner = |dx: i32, dy: i32, dz: i32| {
+            let g = GRADIENTS_3D[self.hash3(xi.wrapping_add(dx), yi.wrapping_add(dy), zi.wrapping_add(dz)) % 12];
+            g[0] * (xf - dx as f32) + g[1] * (yf - dy as f32) + g[2] * (zf - dz as f32)
+        };
+
+        lerp(
+            lerp(
+                lerp(corner(0, 0, 0), corner(1, 0, 0), u),
+                lerp(corner(0, 1, 0), corner(1, 1, 0), u),
+                v,
+            ),
+            lerp(
+                lerp(corner(0, 0, 1), corner(1, 0, 1), u),
+                lerp(corner(0, 1, 1), corner(1, 1, 1), u),
+                v,
+            ),
+            w,
+        )
+    }
+
+    // Ruido simplex 2D (implementación de Stefan Gustavson)
+    pub fn simplex2(&self, x: f32, y: f32) -> f32 {
+        // Pasamos a la rejilla sesgada para saber en qué celda simplex estamos
+        let s = (x + y) * F2;
+        let i = (x + s).floor() as i32;
+        let j = (y + s).floor() as i32;
+        let t = (i as f32 + j as f32) * G2;
+        let x0 = x - (i as f32 - t);
+        let y0 = y - (j as f32 - t);
+
+        // Triángulo inferior o superior de la celda
+        let (i1, j1) = if x0 > y0 { (1, 0) } else { (0, 1) };
+
+        let x1 = x0 - i1 as f32 + G2;
+        let y1 = y0 - j1 as f32 + G2;
+        let x2 = x0 - 1.0 + 2.0 * G2;
+        let y2 = y0 - 1.0 + 2.0 * G2;
+
+        let contribution = |hash: usize, x: f32, y: f32| {
+            let t = 0.5 - x * x - y * y;
+            if t < 0.0 {
+                0.0
+            } else {
+                let g = GRADIENTS_3D[hash % 12];
+                let t2 = t * t;
+                t2 * t2 * (g[0] * x + g[1] * y)
+            }
+        };
+
+        let n0 = contribution(self.hash2(i, j), x0, y0);
+        let n1 = contribution(self.hash2(i.wrapping_add(i1), j.wrapping_add(j1)), x1, y1);
+        let n2 = contribution(self.hash2(i.wrapping_add(1), j.wrapping_add(1)), x2, y2);
+
+        70.0 * (n0 + n1 + n2)
+    }
+
+    // Ruido simplex 3D (implementación de Stefan Gustavson)
+    pub fn simplex3(&self, x: f32, y: f32, z: f32) -> f32 {
+        let s = (x + y + z) * F3;
+        let i = (x + s).floor() as i32;
+        let j = (y + s).floor() as i32;
+        let k = (z + s).floor() as i32;
+        let t = (i as f32 + j as f32 + k as f32) * G3;
+        let x0 = x - (i as f32 - t);
+        let y0 = y - (j as f32 - t);
+        let z0 = z - (k as f32 - t);
+
+        // Cuál de los 6 tetraedros de la celda contiene el punto
+        let (i1, j1, k1, i2, j2, k2) = if x0 >= y0 {
+            if y0 >= z0 {
+                (1, 0, 0, 1, 1, 0)
+            } else if x0 >= z0 {
+                (1, 0, 0, 1, 0, 1)
+            } else {
+                (0, 0, 1, 1, 0, 1)
+            }
+        } else if y0 < z0 {
+            (0, 0, 1, 0, 1, 1)
+        } else if x0 < z0 {
+            (0, 1, 0, 0, 1, 1)
+        } else {
+            (0, 1, 0, 1, 1, 0)
+        };
+
+        let x1 = x0 - i1 as f32 + G3;
+        let y1 = y0 - j1 as f32 + G3;
+        let z1 = z0 - k1 as f32 + G3;
+        let x2 = x0 - i2 as f32 + 2.0 * G3;
+        let y2 = y0 - j2 as f32 + 2.0 * G3;
+        let z2 = z0 - k2 as f32 + 2.0 * G3;
+        let x3 = x0 - 1.0 + 3.0 * G3;
+        let y3 = y0 - 1.0 + 3.0 * G3;
+        let z3 = z0 - 1.0 + 3.0 * G3;
+
+        let contribution = |hash: usize, x: f32, y: f32, z: f32| {
+            let t = 0.6 - x * x - y * y - z * z;
+            if t < 0.0 {
+                0.0
+            } else {
+                let g = GRADIENTS_3D[hash % 12];
+                let t2 = t * t;
+                t2 * t2 * (g[0] * x + g[1] * y + g[2] * z)
+            }
+        };
+
+        let n0 = contribution(self.hash3(i, j, k), x0, y0, z0);
+        let n1 = contribution(self.hash3(i.wrapping_add(i1), j.wrapping_add(j1), k.wrapping_add(k1)), x1, y1, z1);
+        let n2 = contribution(self.hash3(i.wrapping_add(i2), j.wrapping_add(j2), k.wrapping_add(k2)), x2, y2, z2);
+        let n3 = contribution(self.hash3(i.wrapping_add(1), j.wrapping_add(1), k.wrapping_add(1)), x3, y3, z3);
+
+        32.0 * (n0 + n1 + n2 + n3)
+    }
+}
+
+// Separa un número en su celda entera y la parte fraccionaria.
+// Las celdas sólo se usan para el hash (módulo 256), por eso al sumarles
+// los vecinos se usa wrapping_add: con coordenadas enormes la conversión
+// satura en i32::MAX y una suma normal desbordaría
+fn split(value: f32) -> (i32, f32) {
+    let floor = value.floor();
+    (floor as i32, value - floor)
+}
+
+// Curva 6t^5 - 15t^4 + 10t^3 de Perlin, con primera y segunda derivada nulas en los extremos
+fn fade(t: f32) -> f32 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+// Pasa un hash de 0 a 255 a un valor en [-1, 1]
+fn lattice_value(hash: usize) -> f32 {
+    hash as f32 / 127.5 - 1.0
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOLERANCE: f32 = 1e-6;
+
+    const KINDS: [NoiseKind; 3] = [NoiseKind::Value, NoiseKind::Perlin, NoiseKind::Simplex];
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a - b).abs() < TOLERANCE, "{} != {}", a, b);
+    }
+
+    // Valores de referencia con la semilla 1234. Si cambian, los mundos generados
+    // con una semilla guardada dejan de salir iguales
+    #[test]
+    fn seeded_samples_are_stable() {
+        let noise = Noise::new(1234);
+
+        // (tipo, x, y, z, ruido 2D en (x, y), ruido 3D en (x, y, z))
+        let expected = [
+            (NoiseKind::Value, 0.3, 0.7, 0.1, -0.28129864, 0.282169),
+            (NoiseKind::Value, 12.25, -3.5, 7.75, 0.513534, 0.6632204),
+            (NoiseKind::Value, -100.4, 55.9, 0.33, -0.18060482, -0.5592826),
+            (NoiseKind::Perlin, 0.3, 0.7, 0.1, 0.40290022, 0.027192557),
+            (NoiseKind::Perlin, 12.25, -3.5, 7.75, 0.28442383, 0.7433028),
+            (NoiseKind::Perlin, -100.4, 55.9, 0.33, -0.051618516, 0.38814527),
+            (NoiseKind::Simplex, 0.3, 0.7, 0.1, 0.22071536, 0.08579081),
+            (NoiseKind::Simplex, 12.25, -3.5, 7.75, 0.42749676, -0.420922),
+            (NoiseKind::Simplex, -100.4, 55.9, 0.33, -0.40069714, 0.49962577),
+        ];
+
+        for &(kind, x, y, z, value2, value3) in expected.iter() {
+            assert_close(noise.get2(kind, x, y), value2);
+            assert_close(noise.get3(kind, x, y, z), value3);
+        }
+
+        assert_close(noise.fractal2(&Fractal::new(NoiseKind::Perlin, 4), 1.3, 2.7), 0.16819339);
+    }
+
+    #[test]
+    fn same_seed_gives_same_noise() {
+        let a = Noise::new(99);
+        let b = Noise::new(99);
+        let c = Noise::new(100);
+
+        assert_eq!(a.permutation[..], b.permutation[..]);
+        assert_ne!(a.permutation[..], c.permutation[..]);
+    }
+
+    // El gradiente se multiplica por la distancia al vértice, que en la rejilla es 0
+    #[test]
+    fn perlin_is_zero_on_the_lattice() {
+        let noise = Noise::new(1);
+
+        for &(x, y, z) in &[(0.0, 0.0, 0.0), (3.0, -7.0, 12.0), (-1.0, 255.0, 256.0)] {
+            assert_eq!(noise.perlin2(x, y), 0.0);
+            assert_eq!(noise.perlin3(x, y, z), 0.0);
+        }
+    }
+
+    // Con coordenadas enormes la celda satura en i32::MAX; sumarle el vecino no puede desbordar
+    #[test]
+    fn huge_coordinates_do_not_overflow() {
+        let noise = Noise::new(3);
+
+        for &kind in KINDS.iter() {
+            for &value in &[1e20, -1e20, f32::MAX, f32::MIN, 2_147_483_520.0] {
+                assert!(noise.get2(kind, value, value).is_finite());
+                assert!(noise.get3(kind, value, -value, value).is_finite());
+            }
+        }
+    }
+
+    #[test]
+    fn samples_stay_in_range() {
+        let noise = Noise::new(2024);
+        let mut random = Random::new(8);
+
+        for _ in 0..500 {
+            let (x, y, z) = (random.range_f32(-50.0, 50.0), random.range_f32(-50.0, 50.0), random.range_f32(-50.0, 50.0));
+            for &kind in KINDS.iter() {
+                assert!(noise.get2(kind, x, y).abs() <= 1.05);
+                assert!(noise.get3(kind, x, y, z).abs() <= 1.05);
+            }
+        }
+    }
+}
@@ -0,0 +1,301 @@
+use crate::math::vector2::Vector2;
+use crate::math::rect::Rect;
+use crate::math::line_segment::LineSegment;
+
+// Polígono simple (sin autointersecciones) definido por sus vértices en orden
+#[derive(Clone)]
+pub struct Polygon {
+    pub points: Vec<Vector2>,
+}
+
+impl Polygon {
+    pub fn new(points: Vec<Vector2>) -> Polygon {
+        Polygon {
+            points,
+        }
+    }
+
+    // Devuelve los lados del polígono, incluido el que cierra el último vértice con el primero
+    pub fn edges(&self) -> Vec<LineSegment> {
+        let count = self.points.len();
+        (0..count)
+            .map(|i| LineSegment::new(self.points[i], self.points[(i + 1) % count]))
+            .collect()
+    }
+
+    // Área con signo: positiva si los vértices van en sentido antihorario (con y hacia arriba)
+    pub fn signed_area(&self) -> f32 {
+        self.edges()
+            .iter()
+            .map(|edge| edge.start.cross(edge.end))
+            .sum::<f32>() * 0.5
+    }
+
+    pub fn area(&self) -> f32 {
+        self.signed_area().abs()
+    }
+
+    pub fn is_convex(&self) -> bool {
+        let count = self.points.len();
+        if count < 3 {
+            return false;
+        }
+
+        let mut sign = 0.0;
+        for i in 0..count {
+            let a = self.points[i];
+            let b = self.points[(i + 1) % count];
+            let c = self.points[(i + 2) % count];
+
+            let cross = (b - a).cross(c - b);
+            if cross != 0.0 {
+                if sign != 0.0 && cross.signum() != sign {
+                    return false;
+                }
+                sign = cross.signum();
+            }
+        }
+
+        // Si no gira en ningún vértice todos los puntos están alineados
+        sign != 0.0
+    }
+
+    pub fn bounds(&self) -> Option<Rect> {
+        Rect::from_points(&self.points)
+    }
+
+    // Regla par-impar: lanzamos un rayo horizontal y contamos los lados que cruza
+    pub fn contains_point(&self, point: Vector2) -> bool {
+        let mut inside = false;
+        let count = self.points.len();
+        let mut j = count.wrapping_sub(1);
+
+        for i in 0..count {
+            let a = self.points[i];
+            let b = self.points[j];
+
+            if (a.y > point.y) != (b.y > point.y)
+                && point.x < (b.x - a.x) * (point.y - a.y) / (b.y - a.y) + a.x {
+                inside = !inside;
+            }
+            j = i;
+        }
+
+        inside
+    }
+
+    // Devuelve los puntos donde el segmento corta los lados del polígono
+    pub fn segment_intersections(&self, segment: &LineSegment) -> Vec<Vector2> {
+        self.edges()
+            .iter()
+            .filter_map(|edge| edge.intersection(segment))
+            .collect()
+    }
+
+    pub fn overlaps(&self, other: &Polygon) -> bool {
+        if self.points.is_empty() || other.points.is_empty() {
+            return false;
+        }
+
+        // O se cortan algún par de lados, o uno está completamente dentro del otro
+        let other_edges = other.edges();
+        self.edges()
+            .iter()
+            .any(|edge| other_edges.iter().any(|other_edge| edge.intersects(other_edge)))
+            || self.contains_point(other.points[0])
+            || other.contains_point(self.points[0])
+    }
+
+    pub fn overlaps_rect(&self, rect: &Rect) -> bool {
+        self.overlaps(&Polygon::from_rect(rect))
+    }
+
+    pub fn from_rect(rect: &Rect) -> Polygon {
+        Polygon::new(rect.corners().to_vec())
+    }
+
+    pub fn translate(&mut self, offset: Vector2) {
+        for point in self.points.iter_mut() {
+            *point += offset;
+        }
+    }
+
+    // Divide el polígono en triángulos (índices de points) recortando orejas: vértices
+    // convexos cuyo triángulo con sus vecinos no contiene ningún otro vértice.
+    // Vale para polígonos cóncavos; los triángulos salen con el mismo sentido que el polígono
+    pub fn triangulate(&self) -> Vec<[usize; 3]> {
+        let count = self.points.len();
+        if count < 3 {
+            return Vec::new();
+        }
+
+        let orientation = if self.signed_area() >= 0.0 { 1.0 } else { -1.0 };
+        let mut remaining: Vec<usize> = (0..count).collect();
+        let mut triangles = Vec::with_capacity(count - 2);
+
+        while remaining.len() > 3 {
+            let length = remaining.len();
+            let ear = (0..length).find(|&i| {
+                let previous = remaining[(i + length - 1) % length];
+                let next = remaining[(i + 1) % length];
+                self.is_ear(previous, remaining[i], next, &remaining, orientation)
+            });
+
+            // Sin orejas el polígono es degenerado (lados solapados o que se cruzan):
+            // se recorta cualquier vértice para terminar igualmente
+            let i = ear.unwrap_or(0);
+            triangles.push([
+                remaining[(i + length - 1) % length],
+                remaining[i],
+                remaining[(i + 1) % length],
+            ]);
+            remaining.remove(i);
+        }
+
+        triangles.push([remaining[0], remaining[1], remaining[2]]);
+        triangles
+    }
+
+    fn is_ear(&self, previous: usize, current: usize, next: usize, remaining: &[usize], orientation: f32) -> bool {
+        let (a, b, c) = (self.points[previous], self.points[current], self.points[next]);
+        if (b - a).cross(c - b) * orientation <= 0.0 {
+            return false;
+        }
+
+        remaining
+            .iter()
+            .filter(|&&index| index != previous && index != current && index != next)
+            .all(|&index| !Polygon::triangle_contains(a, b, c, self.points[index], orientation))
+    }
+
+    // Incluye los bordes, para no dejar pasar orejas con un vértice encima de un lado
+    fn triangle_contains(a: Vector2, b: Vector2, c: Vector2, point: Vector2, orientation: f32) -> bool {
+        (b - a).cross(point - a) * orientation >= 0.0
+            && (c - b).cross(point - b) * orientation >= 0.0
+            && (a - c).cross(point - c) * orientation >= 0.0
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOLERANCE: f32 = 1e-5;
+
+    fn polygon(points: &[(f32, f32)]) -> Polygon {
+        Polygon::new(points.iter().map(|&(x, y)| Vector2::new(x, y)).collect())
+    }
+
+    fn square() -> Polygon {
+        polygon(&[(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)])
+    }
+
+    // Forma de L: cóncava en el vértice (1, 1)
+    fn l_shape() -> Polygon {
+        polygon(&[(0.0, 0.0), (2.0, 0.0), (2.0, 1.0), (1.0, 1.0), (1.0, 2.0), (0.0, 2.0)])
+    }
+
+    fn triangles_area(polygon: &Polygon, triangles: &[[usize; 3]]) -> f32 {
+        triangles
+            .iter()
+            .map(|t| {
+                let (a, b, c) = (polygon.points[t[0]], polygon.points[t[1]], polygon.points[t[2]]);
+                (b - a).cross(c - a).abs() * 0.5
+            })
+            .sum()
+    }
+
+    #[test]
+    fn signed_area_follows_winding() {
+        assert!((square().signed_area() - 4.0).abs() < TOLERANCE);
+
+        let mut clockwise = square();
+        clockwise.points.reverse();
+        assert!((clockwise.signed_area() + 4.0).abs() < TOLERANCE);
+        assert!((clockwise.area() - 4.0).abs() < TOLERANCE);
+    }
+
+    #[test]
+    fn convexity() {
+        assert!(square().is_convex());
+        assert!(!l_shape().is_convex());
+        // Un vértice en mitad de un lado no rompe la convexidad
+        assert!(polygon(&[(0.0, 0.0), (1.0, 0.0), (2.0, 0.0), (1.0, 1.0)]).is_convex());
+    }
+
+    #[test]
+    fn contains_point_in_concave_polygon() {
+        let shape = l_shape();
+
+        assert!(shape.contains_point(Vector2::new(0.5, 1.5)));
+        assert!(shape.contains_point(Vector2::new(1.5, 0.5)));
+        assert!(!shape.contains_point(Vector2::new(1.5, 1.5)));
+        assert!(!shape.contains_point(Vector2::new(-0.5, 0.5)));
+    }
+
+    // Con menos de 3 vértices o con todos alineados no hay interior
+    #[test]
+    fn degenerate_polygons_are_empty() {
+        let empty = Polygon::new(Vec::new());
+        assert!(empty.edges().is_empty());
+        assert!(empty.bounds().is_none());
+        assert!(!empty.contains_point(Vector2::zero()));
+        assert!(!empty.overlaps(&square()));
+        assert!(empty.triangulate().is_empty());
+
+        let line = polygon(&[(0.0, 0.0), (1.0, 0.0), (2.0, 0.0)]);
+        assert_eq!(line.area(), 0.0);
+        assert!(!line.is_convex());
+        assert!(!line.contains_point(Vector2::new(1.0, 0.0)));
+
+        let segment = polygon(&[(0.0, 0.0), (1.0, 1.0)]);
+        assert!(!segment.is_convex());
+        assert!(segment.triangulate().is_empty());
+    }
+
+    #[test]
+    fn overlaps_when_edges_cross_or_one_is_inside() {
+        let outer = square();
+
+        let mut crossing = square();
+        crossing.translate(Vector2::new(1.0, 1.0));
+        assert!(outer.overlaps(&crossing));
+
+        // Ningún lado se corta pero uno está dentro del otro
+        let inner = polygon(&[(0.5, 0.5), (1.5, 0.5), (1.0, 1.5)]);
+        assert!(outer.overlaps(&inner));
+        assert!(inner.overlaps(&outer));
+
+        let mut apart = square();
+        apart.translate(Vector2::new(5.0, 0.0));
+        assert!(!outer.overlaps(&apart));
+        assert!(!outer.overlaps_rect(&Rect::new(3.0, 3.0, 1.0, 1.0)));
+        assert!(outer.overlaps_rect(&Rect::new(1.0, 1.0, 0.5, 0.5)));
+    }
+
+    #[test]
+    fn segment_intersections_on_each_crossed_edge() {
+        let points = square().segment_intersections(&LineSegment::new(Vector2::new(-1.0, 1.0), Vector2::new(3.0, 1.0)));
+
+        let mut xs: Vec<f32> = points.iter().map(|p| p.x).collect();
+        xs.sort_by(|a, b| a.total_cmp(b));
+        assert_eq!(xs, vec![0.0, 2.0]);
+    }
+
+    // Los triángulos cubren exactamente el área del polígono, también si es cóncavo
+    #[test]
+    fn triangulate_covers_concave_polygon() {
+        let shape = l_shape();
+        let triangles = shape.triangulate();
+
+        assert_eq!(triangles.len(), shape.points.len() - 2);
+        assert!((triangles_area(&shape, &triangles) - shape.area()).abs() < TOLERANCE);
+    }
+
+    // Un polígono con vértices alineados termina igualmente con n - 2 triángulos
+    #[test]
+    fn triangulate_terminates_on_collinear_points() {
+        let shape = polygon(&[(0.0, 0.0), (1.0, 0.0), (2.0, 0.0), (3.0, 0.0)]);
+
+        assert_eq!(shape.triangulate().len(), 2);
+    }
+}
@@ -0,0 +1,301 @@
+use crate::math::vector2::Vector2;
+use crate::math::vector3::Vector3;
+use crate::math::rect::Rect;
+use crate::math::circle::Circle;
+use crate::math::aabb3::Aabb3;
+use crate::math::polygon::Polygon;
+
+// Multiplicador e incremento por defecto de PCG32
+const PCG_MULTIPLIER: u64 = 6364136223846793005;
+const PCG_INCREMENT: u64 = 1442695040888963407;
+
+// Generador pseudoaleatorio PCG32 (XSH RR). Con la misma semilla produce
+// siempre la misma secuencia en cualquier plataforma, así las repeticiones
+// (replays) dan resultados idénticos. Sólo usa aritmética entera y operaciones
+// de coma flotante exactas (nada de sin/cos/ln, que dependen de la libm)
+#[derive(Clone)]
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Random {
+        let mut random = Random { state: 0 };
+        random.next_u32();
+        random.state = random.state.wrapping_add(seed);
+        random.next_u32();
+        random
+    }
+
+    // Estado interno, para guardarlo y restaurarlo con set_state
+    pub fn state(&self) -> u64 {
+        self.state
+    }
+
+    pub fn set_state(&mut self, state: u64) {
+        self.state = state;
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.state = old.wrapping_mul(PCG_MULTIPLIER).wrapping_add(PCG_INCREMENT);
+
+        let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+        let rotation = (old >> 59) as u32;
+        xorshifted.rotate_right(rotation)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        ((self.next_u32() as u64) << 32) | self.next_u32() as u64
+    }
+
+    // Número en [0, 1). Usa 24 bits, la precisión de la mantisa de un f32
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u32() >> 8) as f32 / (1u32 << 24) as f32
+    }
+
+    // Entero uniforme en [0, bound) sin sesgo (por rechazo)
+    pub fn below(&mut self, bound: u32) -> u32 {
+        if bound == 0 {
+            panic!("Random::below necesita un límite mayor que 0");
+        }
+
+        let threshold = bound.wrapping_neg() % bound;
+        loop {
+            let value = self.next_u32();
+            if value >= threshold {
+                return value % bound;
+            }
+        }
+    }
+
+    // Entero uniforme en [min, max] (ambos incluidos)
+    pub fn range_i32(&mut self, min: i32, max: i32) -> i32 {
+        if min > max {
+            panic!("Random::range_i32 con min ({}) mayor que max ({})", min, max);
+        }
+
+        let span = (max as i64 - min as i64 + 1) as u64;
+        if span > u32::MAX as u64 {
+            return self.next_u32() as i32;
+        }
+        (min as i64 + self.below(span as u32) as i64) as i32
+    }
+
+    // Real uniforme en [min, max)
+    pub fn range_f32(&mut self, min: f32, max: f32) -> f32 {
+        min + (max - min) * self.next_f32()
+    }
+
+    // Devuelve true con probabilidad probability
+    pub fn chance(&mut self, probability: f32) -> bool {
+        self.next_f32() < probability
+    }
+
+    pub fn choose<'b, T>(&mut self, items: &'b [T]) -> Option<&'b T> {
+        if items.is_empty() {
+            None
+        } else {
+            Some(&items[self.below(items.len() as u32) as usize])
+        }
+    }
+
+    // Elige un índice con probabilidad proporcional a su peso.
+    // Devuelve None si no hay pesos o todos son 0
+    pub fn weighted_index(&mut self, weights: &[f32]) -> Option<usize> {
+        let total: f32 = weights.iter().map(|w| w.max(0.0)).sum();
+        if total <= 0.0 {
+            return None;
+        }
+
+        let mut target = self.next_f32() * total;
+        for (i, weight) in weights.iter().enumerate() {
+            let weight = weight.max(0.0);
+            if target < weight {
+                return Some(i);
+            }
+            target -= weight;
+        }
+
+        // Por errores de redondeo: el último con peso positivo
+        weights.iter().rposition(|w| *w > 0.0)
+    }
+
+    pub fn weighted_choice<'b, T>(&mut self, items: &'b [(T, f32)]) -> Option<&'b T> {
+        let weights: Vec<f32> = items.iter().map(|(_, weight)| *weight).collect();
+        self.weighted_index(&weights).map(|i| &items[i].0)
+    }
+
+    // Baraja Fisher-Yates
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i as u32 + 1) as usize;
+            items.swap(i, j);
+        }
+    }
+
+    // Vector de longitud 1 con dirección uniforme
+    pub fn unit_vector2(&mut self) -> Vector2 {
+        loop {
+            let v = Vector2::new(self.range_f32(-1.0, 1.0), self.range_f32(-1.0, 1.0));
+            let length_squared = v.length_squared();
+            if length_squared > 1e-6 && length_squared <= 1.0 {
+                return v / length_squared.sqrt();
+            }
+        }
+    }
+
+    pub fn unit_vector3(&mut self) -> Vector3 {
+        loop {
+            let v = Vector3::new(
+                self.range_f32(-1.0, 1.0),
+                self.range_f32(-1.0, 1.0),
+                self.range_f32(-1.0, 1.0),
+            );
+            let length_squared = v.length_squared();
+            if length_squared > 1e-6 && length_squared <= 1.0 {
+                return v / length_squared.sqrt();
+            }
+        }
+    }
+
+    pub fn point_in_rect(&mut self, rect: &Rect) -> Vector2 {
+        Vector2::new(
+            self.range_f32(rect.x, rect.x + rect.width),
+            self.range_f32(rect.y, rect.y + rect.height),
+        )
+    }
+
+    // Punto uniforme dentro del círculo (por rechazo, no hace falta sin/cos)
+    pub fn point_in_circle(&mut self, circle: &Circle) -> Vector2 {
+        loop {
+            let v = Vector2::new(self.range_f32(-1.0, 1.0), self.range_f32(-1.0, 1.0));
+            if v.length_squared() <= 1.0 {
+                return circle.center + v * circle.radius;
+            }
+        }
+    }
+
+    pub fn point_on_circle(&mut self, circle: &Circle) -> Vector2 {
+        circle.center + self.unit_vector2() * circle.radius
+    }
+
+    pub fn point_in_aabb(&mut self, aabb: &Aabb3) -> Vector3 {
+        Vector3::new(
+            self.range_f32(aabb.min.x, aabb.max.x),
+            self.range_f32(aabb.min.y, aabb.max.y),
+            self.range_f32(aabb.min.z, aabb.max.z),
+        )
+    }
+
+    pub fn point_in_sphere(&mut self, center: Vector3, radius: f32) -> Vector3 {
+        loop {
+            let v = Vector3::new(
+                self.range_f32(-1.0, 1.0),
+                self.range_f32(-1.0, 1.0),
+                self.range_f32(-1.0, 1.0),
+            );
+            if v.length_squared() <= 1.0 {
+                return center + v * radius;
+            }
+        }
+    }
+
+    // Punto uniforme dentro del polígono, por rechazo dentro de su rectángulo.
+    // Devuelve None si el polígono está vacío o no tiene área
+    pub fn point_in_polygon(&mut self, polygon: &Polygon) -> Option<Vector2> {
+        const MAX_ATTEMPTS: u32 = 1000;
+
+        let bounds = polygon.bounds()?;
+        if polygon.area() <= 0.0 {
+            return None;
+        }
+
+        for _ in 0..MAX_ATTEMPTS {
+            let point = self.point_in_rect(&bounds);
+            if polygon.contains_point(point) {
+                return Some(point);
+            }
+        }
+
+        None
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Valores de referencia de PCG32 XSH RR con el incremento por defecto.
+    // Si cambian, las partidas guardadas y las repeticiones dejan de reproducirse
+    #[test]
+    fn seeded_sequence_is_stable() {
+        let mut random = Random::new(42);
+        let values: Vec<u32> = (0..6).map(|_| random.next_u32()).collect();
+
+        assert_eq!(values, vec![3270867926, 1795671209, 1924641435, 1143034755, 4121910957, 1757328946]);
+    }
+
+    #[test]
+    fn next_f32_uses_the_top_24_bits() {
+        let mut random = Random::new(42);
+
+        // Los mismos valores que seeded_sequence_is_stable, desplazados 8 bits
+        assert_eq!(random.next_f32(), 12_776_827.0 / 16_777_216.0);
+        assert_eq!(random.next_f32(), 7_014_340.0 / 16_777_216.0);
+        assert_eq!(random.next_f32(), 7_518_130.0 / 16_777_216.0);
+    }
+
+    #[test]
+    fn range_i32_is_stable() {
+        let mut random = Random::new(42);
+        let values: Vec<i32> = (0..8).map(|_| random.range_i32(-10, 10)).collect();
+
+        assert_eq!(values, vec![1, 7, -7, -1, -7, 6, 5, -2]);
+    }
+
+    #[test]
+    fn shuffle_is_stable() {
+        let mut items: Vec<u32> = (0..10).collect();
+        Random::new(7).shuffle(&mut items);
+
+        assert_eq!(items, vec![9, 3, 6, 8, 5, 4, 1, 2, 0, 7]);
+    }
+
+    // Restaurar el estado repite la secuencia a partir de ese punto
+    #[test]
+    fn set_state_replays_the_sequence() {
+        let mut random = Random::new(123);
+        random.next_u32();
+        let saved = random.state();
+        let expected: Vec<u32> = (0..4).map(|_| random.next_u32()).collect();
+
+        random.set_state(saved);
+        let replayed: Vec<u32> = (0..4).map(|_| random.next_u32()).collect();
+        assert_eq!(replayed, expected);
+    }
+
+    #[test]
+    fn range_i32_covers_the_full_range() {
+        let mut random = Random::new(1);
+
+        for _ in 0..16 {
+            random.range_i32(i32::MIN, i32::MAX);
+        }
+        for _ in 0..64 {
+            let value = random.range_i32(3, 5);
+            assert!((3..=5).contains(&value));
+        }
+        assert_eq!(random.range_i32(4, 4), 4);
+    }
+
+    #[test]
+    fn weighted_index_skips_zero_weights() {
+        let mut random = Random::new(5);
+
+        assert_eq!(random.weighted_index(&[]), None);
+        assert_eq!(random.weighted_index(&[0.0, -1.0]), None);
+        for _ in 0..32 {
+            assert_eq!(random.weighted_index(&[0.0, 2.0, 0.0]), Some(1));
+        }
+    }
+}
@@ -0,0 +1,272 @@
+use crate::math::vector2::Vector2;
+use crate::math::vector3::Vector3;
+use crate::math::rect::Rect;
+use crate::math::circle::Circle;
+use crate::math::line_segment::{LineSegment, segment_parameters};
+use crate::math::polygon::Polygon;
+use crate::math::aabb3::Aabb3;
+
+// Rayo en 2D. Los métodos cast_* devuelven la distancia t hasta el primer impacto
+// (con t >= 0), el punto se obtiene con point_at(t)
+#[derive(Copy, Clone)]
+pub struct Ray2 {
+    pub origin: Vector2,
+    pub direction: Vector2,
+}
+
+impl Ray2 {
+    // La dirección se normaliza
+    pub fn new(origin: Vector2, direction: Vector2) -> Ray2 {
+        Ray2 {
+            origin,
+            direction: direction.normalized(),
+        }
+    }
+
+    pub fn point_at(&self, t: f32) -> Vector2 {
+        self.origin + self.direction * t
+    }
+
+    pub fn cast_rect(&self, rect: &Rect) -> Option<f32> {
+        let (t_min, t_max) = slab(self.origin.x, self.direction.x, rect.x, rect.x + rect.width, 0.0, f32::INFINITY)?;
+        let (t_min, _) = slab(self.origin.y, self.direction.y, rect.y, rect.y + rect.height, t_min, t_max)?;
+
+        Some(t_min)
+    }
+
+    pub fn cast_circle(&self, circle: &Circle) -> Option<f32> {
+        let offset = self.origin - circle.center;
+        let b = offset.dot(self.direction);
+        let c = offset.length_squared() - circle.radius * circle.radius;
+
+        cast_sphere(b, c)
+    }
+
+    pub fn cast_segment(&self, segment: &LineSegment) -> Option<f32> {
+        let (t, u) = segment_parameters(self.origin, self.direction, segment.start, segment.direction())?;
+
+        if t >= 0.0 && (0.0..=1.0).contains(&u) {
+            Some(t)
+        } else {
+            None
+        }
+    }
+
+    // Si el origen está dentro del polígono devuelve el impacto con el lado por el que sale
+    pub fn cast_polygon(&self, polygon: &Polygon) -> Option<f32> {
+        polygon
+            .edges()
+            .iter()
+            .filter_map(|edge| self.cast_segment(edge))
+            .fold(None, |closest: Option<f32>, t| Some(closest.map_or(t, |c| c.min(t))))
+    }
+}
+
+// Rayo en 3D, con la misma convención que Ray2
+#[derive(Copy, Clone)]
+pub struct Ray3 {
+    pub origin: Vector3,
+    pub direction: Vector3,
+}
+
+impl Ray3 {
+    // La dirección se normaliza
+    pub fn new(origin: Vector3, direction: Vector3) -> Ray3 {
+        Ray3 {
+            origin,
+            direction: direction.normalized(),
+        }
+    }
+
+    pub fn point_at(&self, t: f32) -> Vector3 {
+        self.origin + self.direction * t
+    }
+
+    pub fn cast_aabb(&self, aabb: &Aabb3) -> Option<f32> {
+        let (t_min, t_max) = slab(self.origin.x, self.direction.x, aabb.min.x, aabb.max.x, 0.0, f32::INFINITY)?;
+        let (t_min, t_max) = slab(self.origin.y, self.direction.y, aabb.min.y, aabb.max.y, t_min, t_max)?;
+        let (t_min, _) = slab(self.origin.z, self.direction.z, aabb.min.z, aabb.max.z, t_min, t_max)?;
+
+        Some(t_min)
+    }
+
+    pub fn cast_sphere(&self, center: Vector3, radius: f32) -> Option<f32> {
+        let offset = self.origin - center;
+        let b = offset.dot(self.direction);
+        let c = offset.length_squared() - radius * radius;
+
+        cast_sphere(b, c)
+    }
+
+    // Plano definido por un punto y su normal. Útil para picking contra el plano z = 0
+    pub fn cast_plane(&self, point: Vector3, normal: Vector3) -> Option<f32> {
+        let denominator = normal.dot(self.direction);
+        if denominator.abs() < f32::EPSILON {
+            return None;
+        }
+
+        let t = (point - self.origin).dot(normal) / denominator;
+        if t >= 0.0 {
+            Some(t)
+        } else {
+            None
+        }
+    }
+}
+
+// Recorta el intervalo [t_min, t_max] con la franja [min, max] de un eje
+fn slab(origin: f32, direction: f32, min: f32, max: f32, t_min: f32, t_max: f32) -> Option<(f32, f32)> {
+    if direction.abs() < f32::EPSILON {
+        // Rayo paralelo a la franja: o está dentro o no la toca nunca
+        return if origin >= min && origin <= max {
+            Some((t_min, t_max))
+        } else {
+            None
+        };
+    }
+
+    let inverse = 1.0 / direction;
+    let mut t1 = (min - origin) * inverse;
+    let mut t2 = (max - origin) * inverse;
+    if t1 > t2 {
+        std::mem::swap(&mut t1, &mut t2);
+    }
+
+    let t_min = t_min.max(t1);
+    let t_max = t_max.min(t2);
+
+    if t_min <= t_max {
+        Some((t_min, t_max))
+    } else {
+        None
+    }
+}
+
+// Resuelve t^2 + 2bt + c = 0 (dirección normalizada) y devuelve la menor raíz no negativa
+fn cast_sphere(b: f32, c: f32) -> Option<f32> {
+    // El origen está fuera y el rayo se aleja
+    if c > 0.0 && b > 0.0 {
+        return None;
+    }
+
+    let discriminant = b * b - c;
+    if discriminant < 0.0 {
+        return None;
+    }
+
+    Some((-b - discriminant.sqrt()).max(0.0))
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOLERANCE: f32 = 1e-5;
+
+    fn assert_hit(hit: Option<f32>, expected: f32) {
+        let t = hit.expect("el rayo debería impactar");
+        assert!((t - expected).abs() < TOLERANCE, "{} != {}", t, expected);
+    }
+
+    fn ray2(origin: (f32, f32), direction: (f32, f32)) -> Ray2 {
+        Ray2::new(Vector2::new(origin.0, origin.1), Vector2::new(direction.0, direction.1))
+    }
+
+    fn ray3(origin: (f32, f32, f32), direction: (f32, f32, f32)) -> Ray3 {
+        Ray3::new(Vector3::new(origin.0, origin.1, origin.2), Vector3::new(direction.0, direction.1, direction.2))
+    }
+
+    #[test]
+    fn direction_is_normalized() {
+        let ray = ray2((1.0, 1.0), (3.0, 4.0));
+        let point = ray.point_at(5.0);
+
+        assert!((point.x - 4.0).abs() < TOLERANCE && (point.y - 5.0).abs() < TOLERANCE);
+    }
+
+    #[test]
+    fn cast_rect_from_outside() {
+        let rect = Rect::new(2.0, -1.0, 2.0, 2.0);
+
+        assert_hit(ray2((0.0, 0.0), (1.0, 0.0)).cast_rect(&rect), 2.0);
+        assert!(ray2((0.0, 0.0), (-1.0, 0.0)).cast_rect(&rect).is_none());
+        assert!(ray2((0.0, 2.0), (1.0, 0.0)).cast_rect(&rect).is_none());
+    }
+
+    // Un rayo paralelo a un lado y justo encima de él lo roza
+    #[test]
+    fn cast_rect_along_an_edge() {
+        let rect = Rect::new(0.0, 0.0, 1.0, 1.0);
+
+        assert_hit(ray2((-1.0, 1.0), (1.0, 0.0)).cast_rect(&rect), 1.0);
+        assert!(ray2((-1.0, 1.001), (1.0, 0.0)).cast_rect(&rect).is_none());
+    }
+
+    // Con el origen dentro, rectángulos, círculos y cajas dan impacto inmediato
+    #[test]
+    fn cast_from_inside_hits_at_zero() {
+        assert_hit(ray2((0.5, 0.5), (1.0, 0.0)).cast_rect(&Rect::new(0.0, 0.0, 1.0, 1.0)), 0.0);
+        assert_hit(ray2((0.5, 0.0), (0.0, 1.0)).cast_circle(&Circle::new(Vector2::zero(), 2.0)), 0.0);
+
+        let aabb = Aabb3::new(Vector3::zero(), Vector3::one());
+        assert_hit(ray3((0.5, 0.5, 0.5), (0.0, 0.0, -1.0)).cast_aabb(&aabb), 0.0);
+        assert_hit(ray3((0.0, 0.0, 0.0), (1.0, 0.0, 0.0)).cast_sphere(Vector3::zero(), 1.0), 0.0);
+    }
+
+    // El polígono, en cambio, devuelve el lado por el que sale el rayo
+    #[test]
+    fn cast_polygon_from_inside_hits_the_exit_edge() {
+        let square = Polygon::from_rect(&Rect::new(0.0, 0.0, 4.0, 4.0));
+
+        assert_hit(ray2((1.0, 2.0), (1.0, 0.0)).cast_polygon(&square), 3.0);
+        assert_hit(ray2((-2.0, 2.0), (1.0, 0.0)).cast_polygon(&square), 2.0);
+        assert!(ray2((-2.0, 2.0), (-1.0, 0.0)).cast_polygon(&square).is_none());
+    }
+
+    #[test]
+    fn cast_circle_tangent_and_miss() {
+        let circle = Circle::new(Vector2::zero(), 1.0);
+
+        assert_hit(ray2((-3.0, 0.0), (1.0, 0.0)).cast_circle(&circle), 2.0);
+        assert_hit(ray2((-2.0, 1.0), (1.0, 0.0)).cast_circle(&circle), 2.0);
+        assert!(ray2((-2.0, 1.1), (1.0, 0.0)).cast_circle(&circle).is_none());
+        assert!(ray2((3.0, 0.0), (1.0, 0.0)).cast_circle(&circle).is_none());
+    }
+
+    #[test]
+    fn cast_segment_hits_within_bounds() {
+        let segment = LineSegment::new(Vector2::new(2.0, -1.0), Vector2::new(2.0, 1.0));
+
+        assert_hit(ray2((0.0, 0.0), (1.0, 0.0)).cast_segment(&segment), 2.0);
+        // Justo en el extremo del segmento
+        assert_hit(ray2((0.0, 1.0), (1.0, 0.0)).cast_segment(&segment), 2.0);
+        assert!(ray2((0.0, 1.5), (1.0, 0.0)).cast_segment(&segment).is_none());
+        assert!(ray2((3.0, 0.0), (1.0, 0.0)).cast_segment(&segment).is_none());
+    }
+
+    // Paralelo o sobre la misma recta: no hay un único punto de impacto
+    #[test]
+    fn cast_segment_parallel_misses() {
+        let segment = LineSegment::new(Vector2::new(0.0, 1.0), Vector2::new(5.0, 1.0));
+
+        assert!(ray2((0.0, 0.0), (1.0, 0.0)).cast_segment(&segment).is_none());
+        assert!(ray2((-1.0, 1.0), (1.0, 0.0)).cast_segment(&segment).is_none());
+    }
+
+    #[test]
+    fn cast_aabb_from_outside() {
+        let aabb = Aabb3::new(Vector3::new(-1.0, -1.0, -1.0), Vector3::one());
+
+        assert_hit(ray3((0.0, 0.0, 5.0), (0.0, 0.0, -1.0)).cast_aabb(&aabb), 4.0);
+        assert!(ray3((0.0, 0.0, 5.0), (0.0, 0.0, 1.0)).cast_aabb(&aabb).is_none());
+        assert!(ray3((2.0, 0.0, 5.0), (0.0, 0.0, -1.0)).cast_aabb(&aabb).is_none());
+    }
+
+    #[test]
+    fn cast_plane_parallel_and_behind() {
+        let normal = Vector3::new(0.0, 0.0, 1.0);
+
+        assert_hit(ray3((1.0, 2.0, 10.0), (0.0, 0.0, -1.0)).cast_plane(Vector3::zero(), normal), 10.0);
+        assert!(ray3((0.0, 0.0, 10.0), (1.0, 0.0, 0.0)).cast_plane(Vector3::zero(), normal).is_none());
+        assert!(ray3((0.0, 0.0, 10.0), (0.0, 0.0, 1.0)).cast_plane(Vector3::zero(), normal).is_none());
+    }
+}
@@ -0,0 +1,191 @@
+use crate::math::vector2::Vector2;
+
+// Rectángulo alineado con los ejes. (x, y) es la esquina con coordenadas menores
+#[derive(Copy, Clone)]
+pub struct Rect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Rect {
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Rect {
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    // Crea un rectángulo a partir de sus esquinas mínima y máxima
+    pub fn from_min_max(min: Vector2, max: Vector2) -> Rect {
+        Rect::new(min.x, min.y, max.x - min.x, max.y - min.y)
+    }
+
+    // Devuelve el menor rectángulo que contiene todos los puntos, o None si no hay puntos
+    pub fn from_points(points: &[Vector2]) -> Option<Rect> {
+        let first = *points.first()?;
+        let (min, max) = points
+            .iter()
+            .fold((first, first), |(min, max), p| (min.min(*p), max.max(*p)));
+
+        Some(Rect::from_min_max(min, max))
+    }
+
+    pub fn min(&self) -> Vector2 {
+        Vector2::new(self.x, self.y)
+    }
+
+    pub fn max(&self) -> Vector2 {
+        Vector2::new(self.x + self.width, self.y + self.height)
+    }
+
+    pub fn center(&self) -> Vector2 {
+        Vector2::new(self.x + self.width * 0.5, self.y + self.height * 0.5)
+    }
+
+    pub fn size(&self) -> Vector2 {
+        Vector2::new(self.width, self.height)
+    }
+
+    pub fn area(&self) -> f32 {
+        self.width * self.height
+    }
+
+    // Devuelve las 4 esquinas en orden: min, (max.x, min.y), max, (min.x, max.y)
+    pub fn corners(&self) -> [Vector2; 4] {
+        let min = self.min();
+        let max = self.max();
+        [min, Vector2::new(max.x, min.y), max, Vector2::new(min.x, max.y)]
+    }
+
+    pub fn contains_point(&self, point: Vector2) -> bool {
+        point.x >= self.x && point.x <= self.x + self.width
+            && point.y >= self.y && point.y <= self.y + self.height
+    }
+
+    // Indica si other está completamente dentro de self
+    pub fn contains_rect(&self, other: &Rect) -> bool {
+        other.x >= self.x && other.x + other.width <= self.x + self.width
+            && other.y >= self.y && other.y + other.height <= self.y + self.height
+    }
+
+    pub fn overlaps(&self, other: &Rect) -> bool {
+        self.x < other.x + other.width && other.x < self.x + self.width
+            && self.y < other.y + other.height && other.y < self.y + self.height
+    }
+
+    // Devuelve la zona común de los dos rectángulos, o None si no se solapan
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        if !self.overlaps(other) {
+            return None;
+        }
+
+        Some(Rect::from_min_max(self.min().max(other.min()), self.max().min(other.max())))
+    }
+
+    // Devuelve el menor rectángulo que contiene a los dos
+    pub fn merge(&self, other: &Rect) -> Rect {
+        Rect::from_min_max(self.min().min(other.min()), self.max().max(other.max()))
+    }
+
+    // Agranda el rectángulo amount unidades por cada lado (encoge si es negativo)
+    pub fn expand(&self, amount: f32) -> Rect {
+        Rect::new(
+            self.x - amount,
+            self.y - amount,
+            self.width + amount * 2.0,
+            self.height + amount * 2.0,
+        )
+    }
+
+    // Agranda el rectángulo lo justo para que contenga el punto
+    pub fn expand_to_include(&self, point: Vector2) -> Rect {
+        Rect::from_min_max(self.min().min(point), self.max().max(point))
+    }
+
+    // Devuelve el punto del rectángulo más cercano al punto dado
+    pub fn closest_point(&self, point: Vector2) -> Vector2 {
+        point.max(self.min()).min(self.max())
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOLERANCE: f32 = 1e-5;
+
+    fn assert_rect_close(rect: Rect, x: f32, y: f32, width: f32, height: f32) {
+        assert!((rect.x - x).abs() < TOLERANCE, "x: {} != {}", rect.x, x);
+        assert!((rect.y - y).abs() < TOLERANCE, "y: {} != {}", rect.y, y);
+        assert!((rect.width - width).abs() < TOLERANCE, "width: {} != {}", rect.width, width);
+        assert!((rect.height - height).abs() < TOLERANCE, "height: {} != {}", rect.height, height);
+    }
+
+    // Los bordes cuentan como dentro para los puntos, pero compartir un lado no es solaparse
+    #[test]
+    fn touching_edges_contain_points_but_do_not_overlap() {
+        let a = Rect::new(0.0, 0.0, 1.0, 1.0);
+        let b = Rect::new(1.0, 0.0, 1.0, 1.0);
+
+        assert!(a.contains_point(Vector2::new(1.0, 0.5)));
+        assert!(b.contains_point(Vector2::new(1.0, 0.5)));
+        assert!(!a.overlaps(&b));
+        assert!(!b.overlaps(&a));
+        assert!(a.intersection(&b).is_none());
+    }
+
+    #[test]
+    fn intersection_is_the_common_area() {
+        let a = Rect::new(0.0, 0.0, 4.0, 4.0);
+        let b = Rect::new(2.0, 1.0, 4.0, 2.0);
+
+        assert_rect_close(a.intersection(&b).unwrap(), 2.0, 1.0, 2.0, 2.0);
+        assert_rect_close(b.intersection(&a).unwrap(), 2.0, 1.0, 2.0, 2.0);
+        assert_rect_close(a.merge(&b), 0.0, 0.0, 6.0, 4.0);
+    }
+
+    #[test]
+    fn contains_rect_includes_equal_rects() {
+        let a = Rect::new(0.0, 0.0, 2.0, 2.0);
+
+        assert!(a.contains_rect(&a));
+        assert!(a.contains_rect(&Rect::new(0.0, 0.0, 2.0, 1.0)));
+        assert!(!a.contains_rect(&Rect::new(1.0, 1.0, 2.0, 1.0)));
+    }
+
+    #[test]
+    fn from_points_needs_at_least_one_point() {
+        assert!(Rect::from_points(&[]).is_none());
+
+        // Un único punto da un rectángulo vacío que aun así lo contiene
+        let point = Rect::from_points(&[Vector2::new(3.0, -1.0)]).unwrap();
+        assert_rect_close(point, 3.0, -1.0, 0.0, 0.0);
+        assert!(point.contains_point(Vector2::new(3.0, -1.0)));
+
+        let rect = Rect::from_points(&[Vector2::new(1.0, 5.0), Vector2::new(-2.0, 3.0), Vector2::new(0.0, 4.0)]).unwrap();
+        assert_rect_close(rect, -2.0, 3.0, 3.0, 2.0);
+    }
+
+    #[test]
+    fn closest_point_clamps_outside_points() {
+        let rect = Rect::new(0.0, 0.0, 2.0, 1.0);
+
+        let outside = rect.closest_point(Vector2::new(5.0, -3.0));
+        assert_eq!((outside.x, outside.y), (2.0, 0.0));
+
+        let inside = rect.closest_point(Vector2::new(1.5, 0.5));
+        assert_eq!((inside.x, inside.y), (1.5, 0.5));
+    }
+
+    #[test]
+    fn expand_grows_every_side() {
+        let rect = Rect::new(1.0, 1.0, 2.0, 2.0);
+
+        assert_rect_close(rect.expand(0.5), 0.5, 0.5, 3.0, 3.0);
+        assert_rect_close(rect.expand(-1.0), 2.0, 2.0, 0.0, 0.0);
+        assert_rect_close(rect.expand_to_include(Vector2::new(-1.0, 2.0)), -1.0, 1.0, 4.0, 2.0);
+    }
+}
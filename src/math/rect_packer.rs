@@ -0,0 +1,210 @@
+// Rectángulo de enteros usado por el empaquetador (píxeles)
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct PackedRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl PackedRect {
+    pub fn new(x: u32, y: u32, width: u32, height: u32) -> PackedRect {
+        PackedRect { x, y, width, height }
+    }
+
+    fn right(&self) -> u32 {
+        self.x + self.width
+    }
+
+    fn bottom(&self) -> u32 {
+        self.y + self.height
+    }
+
+    fn contains(&self, other: &PackedRect) -> bool {
+        other.x >= self.x && other.y >= self.y
+            && other.right() <= self.right() && other.bottom() <= self.bottom()
+    }
+
+    fn overlaps(&self, other: &PackedRect) -> bool {
+        self.x < other.right() && other.x < self.right()
+            && self.y < other.bottom() && other.y < self.bottom()
+    }
+}
+
+// Empaquetador de rectángulos MaxRects con la heurística "best short side fit":
+// guarda la lista de rectángulos libres máximos (pueden solaparse) y coloca cada
+// rectángulo en el hueco donde sobra menos por el lado más corto
+pub struct MaxRectsPacker {
+    pub width: u32,
+    pub height: u32,
+
+    free: Vec<PackedRect>,
+    used: Vec<PackedRect>,
+}
+
+impl MaxRectsPacker {
+    pub fn new(width: u32, height: u32) -> MaxRectsPacker {
+        MaxRectsPacker {
+            width,
+            height,
+
+            free: vec![PackedRect::new(0, 0, width, height)],
+            used: Vec::new(),
+        }
+    }
+
+    // Coloca un rectángulo y devuelve dónde, o None si no cabe
+    pub fn insert(&mut self, width: u32, height: u32) -> Option<PackedRect> {
+        if width == 0 || height == 0 {
+            return Some(PackedRect::new(0, 0, width, height));
+        }
+
+        let placed = self.find_position(width, height)?;
+
+        // Partimos todos los huecos libres que se solapan con el nuevo rectángulo
+        let mut new_free = Vec::with_capacity(self.free.len() + 4);
+        for free in &self.free {
+            if free.overlaps(&placed) {
+                split_free_rect(free, &placed, &mut new_free);
+            } else {
+                new_free.push(*free);
+            }
+        }
+        self.free = new_free;
+        self.prune_free_list();
+
+        self.used.push(placed);
+        Some(placed)
+    }
+
+    pub fn used(&self) -> &[PackedRect] {
+        &self.used
+    }
+
+    // Fracción del área ocupada
+    pub fn occupancy(&self) -> f32 {
+        let used: u64 = self.used.iter().map(|r| r.width as u64 * r.height as u64).sum();
+        used as f32 / (self.width as u64 * self.height as u64) as f32
+    }
+
+    fn find_position(&self, width: u32, height: u32) -> Option<PackedRect> {
+        self.free
+            .iter()
+            .filter(|free| free.width >= width && free.height >= height)
+            .min_by_key(|free| {
+                let leftover_x = free.width - width;
+                let leftover_y = free.height - height;
+                (leftover_x.min(leftover_y), leftover_x.max(leftover_y))
+            })
+            .map(|free| PackedRect::new(free.x, free.y, width, height))
+    }
+
+    // Quita los huecos que están completamente dentro de otro
+    fn prune_free_list(&mut self) {
+        let mut i = 0;
+        while i < self.free.len() {
+            let mut removed_i = false;
+            let mut j = i + 1;
+            while j < self.free.len() {
+                if self.free[j].contains(&self.free[i]) {
+                    self.free.swap_remove(i);
+                    removed_i = true;
+                    break;
+                }
+                if self.free[i].contains(&self.free[j]) {
+                    self.free.swap_remove(j);
+                } else {
+                    j += 1;
+                }
+            }
+            if !removed_i {
+                i += 1;
+            }
+        }
+    }
+}
+
+// Añade las partes de free que quedan fuera de placed (hasta 4 rectángulos máximos)
+fn split_free_rect(free: &PackedRect, placed: &PackedRect, out: &mut Vec<PackedRect>) {
+    if placed.x > free.x {
+        out.push(PackedRect::new(free.x, free.y, placed.x - free.x, free.height));
+    }
+    if placed.right() < free.right() {
+        out.push(PackedRect::new(placed.right(), free.y, free.right() - placed.right(), free.height));
+    }
+    if placed.y > free.y {
+        out.push(PackedRect::new(free.x, free.y, free.width, placed.y - free.y));
+    }
+    if placed.bottom() < free.bottom() {
+        out.push(PackedRect::new(free.x, placed.bottom(), free.width, free.bottom() - placed.bottom()));
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::random::Random;
+
+    fn assert_valid(packer: &MaxRectsPacker) {
+        let page = PackedRect::new(0, 0, packer.width, packer.height);
+        let used = packer.used();
+
+        for (i, a) in used.iter().enumerate() {
+            assert!(page.contains(a), "{:?} se sale de la página", a);
+            for b in &used[i + 1..] {
+                assert!(!a.overlaps(b), "{:?} se solapa con {:?}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn packed_rects_never_overlap() {
+        let mut random = Random::new(11);
+        let mut packer = MaxRectsPacker::new(256, 256);
+
+        let mut placed = 0;
+        for _ in 0..200 {
+            let width = random.range_i32(1, 40) as u32;
+            let height = random.range_i32(1, 40) as u32;
+            if let Some(rect) = packer.insert(width, height) {
+                assert_eq!((rect.width, rect.height), (width, height));
+                placed += 1;
+            }
+        }
+
+        assert!(placed > 20);
+        assert_eq!(packer.used().len(), placed);
+        assert_valid(&packer);
+    }
+
+    // Cuatro cuadrados llenan la página entera y ya no cabe nada más
+    #[test]
+    fn fills_the_page_exactly() {
+        let mut packer = MaxRectsPacker::new(64, 64);
+
+        for _ in 0..4 {
+            assert!(packer.insert(32, 32).is_some());
+        }
+        assert_eq!(packer.occupancy(), 1.0);
+        assert!(packer.insert(1, 1).is_none());
+        assert_valid(&packer);
+    }
+
+    #[test]
+    fn rejects_rects_larger_than_the_page() {
+        let mut packer = MaxRectsPacker::new(64, 32);
+
+        assert!(packer.insert(65, 1).is_none());
+        assert!(packer.insert(1, 33).is_none());
+        assert_eq!(packer.insert(64, 32), Some(PackedRect::new(0, 0, 64, 32)));
+    }
+
+    // Un rectángulo vacío no ocupa sitio
+    #[test]
+    fn empty_rects_take_no_space() {
+        let mut packer = MaxRectsPacker::new(8, 8);
+
+        assert_eq!(packer.insert(0, 5), Some(PackedRect::new(0, 0, 0, 5)));
+        assert!(packer.used().is_empty());
+        assert_eq!(packer.insert(8, 8), Some(PackedRect::new(0, 0, 8, 8)));
+    }
+}
@@ -0,0 +1,158 @@
+use crate::math::vector3::Vector3;
+use crate::math::curve::Curve;
+
+// Curva de Hermite: une dos puntos con las tangentes dadas en cada extremo
+#[derive(Copy, Clone)]
+pub struct HermiteCurve {
+    pub start: Vector3,
+    pub start_tangent: Vector3,
+    pub end: Vector3,
+    pub end_tangent: Vector3,
+}
+
+impl HermiteCurve {
+    pub fn new(start: Vector3, start_tangent: Vector3, end: Vector3, end_tangent: Vector3) -> HermiteCurve {
+        HermiteCurve {
+            start,
+            start_tangent,
+            end,
+            end_tangent,
+        }
+    }
+}
+
+impl Curve for HermiteCurve {
+    fn point(&self, t: f32) -> Vector3 {
+        hermite_point(self.start, self.start_tangent, self.end, self.end_tangent, t)
+    }
+
+    fn derivative(&self, t: f32) -> Vector3 {
+        hermite_derivative(self.start, self.start_tangent, self.end, self.end_tangent, t)
+    }
+}
+
+// Spline de Catmull-Rom: pasa por todos los puntos de control.
+// t en [0, 1] recorre la spline entera, cada tramo entre dos puntos ocupa la misma fracción de t
+#[derive(Clone)]
+pub struct CatmullRom {
+    // Al menos 2 (por eso no es público)
+    points: Vec<Vector3>,
+    // Si es cerrada el último punto se une con el primero
+    pub closed: bool,
+}
+
+impl CatmullRom {
+    // Devuelve None si hay menos de 2 puntos
+    pub fn new(points: Vec<Vector3>, closed: bool) -> Option<CatmullRom> {
+        if points.len() < 2 {
+            return None;
+        }
+
+        Some(CatmullRom {
+            points,
+            closed,
+        })
+    }
+
+    pub fn points(&self) -> &[Vector3] {
+        &self.points
+    }
+
+    pub fn segment_count(&self) -> usize {
+        if self.closed {
+            self.points.len()
+        } else {
+            self.points.len() - 1
+        }
+    }
+
+    // Punto de control i. Fuera de rango se repite el extremo (abierta) o se da la vuelta (cerrada)
+    fn control_point(&self, i: isize) -> Vector3 {
+        let count = self.points.len() as isize;
+        let index = if self.closed {
+            i.rem_euclid(count)
+        } else {
+            i.clamp(0, count - 1)
+        };
+
+        self.points[index as usize]
+    }
+
+    // Devuelve el tramo en el que cae t y el parámetro local dentro del tramo
+    fn locate(&self, t: f32) -> (isize, f32) {
+        let segments = self.segment_count();
+        let scaled = t.clamp(0.0, 1.0) * segments as f32;
+        let segment = (scaled.floor() as usize).min(segments - 1);
+
+        (segment as isize, scaled - segment as f32)
+    }
+
+    // Extremos y tangentes de Hermite del tramo i
+    fn segment(&self, i: isize) -> (Vector3, Vector3, Vector3, Vector3) {
+        let p0 = self.control_point(i - 1);
+        let p1 = self.control_point(i);
+        let p2 = self.control_point(i + 1);
+        let p3 = self.control_point(i + 2);
+
+        (p1, (p2 - p0) * 0.5, p2, (p3 - p1) * 0.5)
+    }
+}
+
+impl Curve for CatmullRom {
+    fn point(&self, t: f32) -> Vector3 {
+        let (segment, local) = self.locate(t);
+        let (p1, m1, p2, m2) = self.segment(segment);
+
+        hermite_point(p1, m1, p2, m2, local)
+    }
+
+    fn derivative(&self, t: f32) -> Vector3 {
+        let (segment, local) = self.locate(t);
+        let (p1, m1, p2, m2) = self.segment(segment);
+
+        // Regla de la cadena: el t local avanza segment_count veces más rápido
+        hermite_derivative(p1, m1, p2, m2, local) * self.segment_count() as f32
+    }
+}
+
+fn hermite_point(p0: Vector3, m0: Vector3, p1: Vector3, m1: Vector3, t: f32) -> Vector3 {
+    let t2 = t * t;
+    let t3 = t2 * t;
+
+    p0 * (2.0 * t3 - 3.0 * t2 + 1.0)
+        + m0 * (t3 - 2.0 * t2 + t)
+        + p1 * (-2.0 * t3 + 3.0 * t2)
+        + m1 * (t3 - t2)
+}
+
+fn hermite_derivative(p0: Vector3, m0: Vector3, p1: Vector3, m1: Vector3, t: f32) -> Vector3 {
+    let t2 = t * t;
+
+    p0 * (6.0 * t2 - 6.0 * t)
+        + m0 * (3.0 * t2 - 4.0 * t + 1.0)
+        + p1 * (-6.0 * t2 + 6.0 * t)
+        + m1 * (3.0 * t2 - 2.0 * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn catmull_rom_needs_two_points() {
+        assert!(CatmullRom::new(Vec::new(), false).is_none());
+        assert!(CatmullRom::new(vec![Vector3::zero()], true).is_none());
+        assert!(CatmullRom::new(vec![Vector3::zero(), Vector3::one()], false).is_some());
+    }
+
+    #[test]
+    fn catmull_rom_passes_through_its_points() {
+        let points = vec![Vector3::zero(), Vector3::new(1.0, 2.0, 0.0), Vector3::new(3.0, 0.0, 0.0)];
+        let spline = CatmullRom::new(points, false).unwrap();
+
+        let middle = spline.point(0.5);
+        assert!((middle.x - 1.0).abs() < 1e-5 && (middle.y - 2.0).abs() < 1e-5);
+        let end = spline.point(1.0);
+        assert!((end.x - 3.0).abs() < 1e-5 && end.y.abs() < 1e-5);
+    }
+}
\ No newline at end of file
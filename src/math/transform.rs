@@ -1,12 +1,19 @@
 use crate::math::vector3::Vector3;
 use crate::math::matrix4x4::Matrix4x4;
 
+#[derive(Copy, Clone)]
 pub struct Transform {
     pub position: Vector3,
     pub rotation: Vector3,
     pub scale: Vector3,
 }
 
+impl Default for Transform {
+    fn default() -> Transform {
+        Transform::new()
+    }
+}
+
 impl Transform {
     pub fn new() -> Transform {
         Transform {
@@ -24,4 +31,9 @@ impl Transform {
 
         translation * rotation * scale
     }
+    // Crea un Transform a partir de una matriz compuesta (por ejemplo una matriz de mundo).
+    // Devuelve None si la matriz tiene cizalla o proyección
+    pub fn from_matrix(matrix: &Matrix4x4) -> Option<Transform> {
+        matrix.decompose()
+    }
 }
\ No newline at end of file
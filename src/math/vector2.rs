@@ -0,0 +1,111 @@
+use auto_ops::*;
+
+#[derive(Copy, Clone)]
+pub struct Vector2 {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl_op_ex!(+ |a: &Vector2, b: &Vector2| -> Vector2 {
+    Vector2::new(a.x + b.x, a.y + b.y)
+});
+
+impl_op_ex!(- |a: &Vector2, b: &Vector2| -> Vector2 {
+    Vector2::new(a.x - b.x, a.y - b.y)
+});
+
+impl_op_ex!(- |a: &Vector2| -> Vector2 {
+    Vector2::new(-a.x, -a.y)
+});
+
+impl_op_ex_commutative!(* |a: &Vector2, b: &f32| -> Vector2 {
+    Vector2::new(a.x * b, a.y * b)
+});
+
+impl_op_ex!(/ |a: &Vector2, b: &f32| -> Vector2 {
+    Vector2::new(a.x / b, a.y / b)
+});
+
+impl_op_ex!(+= |a: &mut Vector2, b: &Vector2| {
+    a.x += b.x;
+    a.y += b.y;
+});
+
+impl_op_ex!(-= |a: &mut Vector2, b: &Vector2| {
+    a.x -= b.x;
+    a.y -= b.y;
+});
+
+impl_op_ex!(*= |a: &mut Vector2, b: &f32| {
+    a.x *= b;
+    a.y *= b;
+});
+
+impl Vector2 {
+    pub fn new(x: f32, y: f32) -> Vector2 {
+        Vector2 {
+            x,
+            y,
+        }
+    }
+
+    pub fn zero() -> Vector2 {
+        Vector2::new(0.0, 0.0)
+    }
+
+    pub fn one() -> Vector2 {
+        Vector2::new(1.0, 1.0)
+    }
+
+    pub fn dot(&self, other: Vector2) -> f32 {
+        self.x * other.x + self.y * other.y
+    }
+
+    // Producto cruzado en 2D (componente z del producto cruzado en 3D).
+    // Positivo si other está girado en sentido antihorario respecto a self
+    pub fn cross(&self, other: Vector2) -> f32 {
+        self.x * other.y - self.y * other.x
+    }
+
+    // Vector perpendicular (girado 90 grados en sentido antihorario)
+    pub fn perpendicular(&self) -> Vector2 {
+        Vector2::new(-self.y, self.x)
+    }
+
+    pub fn length_squared(&self) -> f32 {
+        self.dot(*self)
+    }
+
+    pub fn length(&self) -> f32 {
+        self.length_squared().sqrt()
+    }
+
+    pub fn distance(&self, other: Vector2) -> f32 {
+        (*self - other).length()
+    }
+
+    // Devuelve el vector con longitud 1, o el vector cero si su longitud es 0
+    pub fn normalized(&self) -> Vector2 {
+        let length = self.length();
+        if length > 0.0 {
+            *self / length
+        } else {
+            Vector2::zero()
+        }
+    }
+
+    // Interpolación lineal entre self (t = 0) y other (t = 1)
+    pub fn lerp(&self, other: Vector2, t: f32) -> Vector2 {
+        *self + (other - *self) * t
+    }
+
+    // Mínimo componente a componente
+    pub fn min(&self, other: Vector2) -> Vector2 {
+        Vector2::new(self.x.min(other.x), self.y.min(other.y))
+    }
+
+    // Máximo componente a componente
+    pub fn max(&self, other: Vector2) -> Vector2 {
+        Vector2::new(self.x.max(other.x), self.y.max(other.y))
+    }
+}
\ No newline at end of file
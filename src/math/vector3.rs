@@ -1,3 +1,5 @@
+use auto_ops::*;
+
 #[derive(Copy, Clone)]
 pub struct Vector3 {
     pub x: f32,
@@ -5,6 +7,44 @@ pub struct Vector3 {
     pub z: f32,
 }
 
+impl_op_ex!(+ |a: &Vector3, b: &Vector3| -> Vector3 {
+    Vector3::new(a.x + b.x, a.y + b.y, a.z + b.z)
+});
+
+impl_op_ex!(- |a: &Vector3, b: &Vector3| -> Vector3 {
+    Vector3::new(a.x - b.x, a.y - b.y, a.z - b.z)
+});
+
+impl_op_ex!(- |a: &Vector3| -> Vector3 {
+    Vector3::new(-a.x, -a.y, -a.z)
+});
+
+impl_op_ex_commutative!(* |a: &Vector3, b: &f32| -> Vector3 {
+    Vector3::new(a.x * b, a.y * b, a.z * b)
+});
+
+impl_op_ex!(/ |a: &Vector3, b: &f32| -> Vector3 {
+    Vector3::new(a.x / b, a.y / b, a.z / b)
+});
+
+impl_op_ex!(+= |a: &mut Vector3, b: &Vector3| {
+    a.x += b.x;
+    a.y += b.y;
+    a.z += b.z;
+});
+
+impl_op_ex!(-= |a: &mut Vector3, b: &Vector3| {
+    a.x -= b.x;
+    a.y -= b.y;
+    a.z -= b.z;
+});
+
+impl_op_ex!(*= |a: &mut Vector3, b: &f32| {
+    a.x *= b;
+    a.y *= b;
+    a.z *= b;
+});
+
 impl Vector3 {
     pub fn new(x: f32, y: f32, z: f32) -> Vector3 {
         Vector3 {
@@ -21,4 +61,53 @@ impl Vector3 {
     pub fn one() -> Vector3 {
         Vector3::new(1.0, 1.0, 1.0)
     }
+
+    pub fn dot(&self, other: Vector3) -> f32 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn cross(&self, other: Vector3) -> Vector3 {
+        Vector3::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    pub fn length_squared(&self) -> f32 {
+        self.dot(*self)
+    }
+
+    pub fn length(&self) -> f32 {
+        self.length_squared().sqrt()
+    }
+
+    pub fn distance(&self, other: Vector3) -> f32 {
+        (*self - other).length()
+    }
+
+    // Devuelve el vector con longitud 1, o el vector cero si su longitud es 0
+    pub fn normalized(&self) -> Vector3 {
+        let length = self.length();
+        if length > 0.0 {
+            *self / length
+        } else {
+            Vector3::zero()
+        }
+    }
+
+    // Interpolación lineal entre self (t = 0) y other (t = 1)
+    pub fn lerp(&self, other: Vector3, t: f32) -> Vector3 {
+        *self + (other - *self) * t
+    }
+
+    // Mínimo componente a componente
+    pub fn min(&self, other: Vector3) -> Vector3 {
+        Vector3::new(self.x.min(other.x), self.y.min(other.y), self.z.min(other.z))
+    }
+
+    // Máximo componente a componente
+    pub fn max(&self, other: Vector3) -> Vector3 {
+        Vector3::new(self.x.max(other.x), self.y.max(other.y), self.z.max(other.z))
+    }
 }
\ No newline at end of file
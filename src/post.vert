@@ -0,0 +1,11 @@
+#version 430
+
+// Triángulo que cubre toda la pantalla, sin buffer de vértices:
+// los vértices 0, 1 y 2 quedan en (-1, -1), (3, -1) y (-1, 3)
+out vec2 v_tex_coord;
+
+void main() {
+    vec2 position = vec2((gl_VertexID << 1) & 2, gl_VertexID & 2);
+    v_tex_coord = position;
+    gl_Position = vec4(position * 2.0 - 1.0, 0.0, 1.0);
+}
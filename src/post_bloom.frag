@@ -0,0 +1,16 @@
+#version 430
+
+precision mediump float;
+
+uniform sampler2D u_source;
+uniform sampler2D u_bloom;// zonas brillantes desenfocadas
+uniform float u_intensity;
+
+in vec2 v_tex_coord;
+
+out vec4 frag_color;
+
+void main() {
+    vec4 color = texture(u_source, v_tex_coord);
+    frag_color = vec4(color.rgb + texture(u_bloom, v_tex_coord).rgb * u_intensity, color.a);
+}
@@ -0,0 +1,22 @@
+#version 430
+
+precision mediump float;
+
+uniform sampler2D u_source;
+uniform vec2 u_direction;// tamaño de un texel en la dirección del desenfoque (por la separación)
+
+in vec2 v_tex_coord;
+
+out vec4 frag_color;
+
+// Gaussiana de 9 muestras hecha con 5 lecturas aprovechando el filtrado lineal
+const float offsets[3] = float[](0.0, 1.3846153846, 3.2307692308);
+const float weights[3] = float[](0.2270270270, 0.3162162162, 0.0702702703);
+
+void main() {
+    frag_color = texture(u_source, v_tex_coord) * weights[0];
+    for (int i = 1; i < 3; i++) {
+        frag_color += texture(u_source, v_tex_coord + u_direction * offsets[i]) * weights[i];
+        frag_color += texture(u_source, v_tex_coord - u_direction * offsets[i]) * weights[i];
+    }
+}
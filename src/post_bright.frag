@@ -0,0 +1,17 @@
+#version 430
+
+precision mediump float;
+
+uniform sampler2D u_source;
+uniform float u_threshold;// brillo a partir del que un píxel empieza a brillar
+
+in vec2 v_tex_coord;
+
+out vec4 frag_color;
+
+void main() {
+    vec3 color = texture(u_source, v_tex_coord).rgb;
+    float brightness = max(color.r, max(color.g, color.b));
+    float contribution = max(brightness - u_threshold, 0.0) / max(brightness, 0.0001);
+    frag_color = vec4(color * contribution, 1.0);
+}
@@ -0,0 +1,13 @@
+#version 430
+
+precision mediump float;
+
+uniform sampler2D u_source;
+
+in vec2 v_tex_coord;
+
+out vec4 frag_color;
+
+void main() {
+    frag_color = texture(u_source, v_tex_coord);
+}
@@ -0,0 +1,30 @@
+#version 430
+
+precision mediump float;
+
+uniform sampler2D u_source;
+uniform vec2 u_resolution;// tamaño de la imagen en píxeles
+uniform float u_curvature;// 0 = pantalla plana
+uniform float u_scanlines;// oscuridad de las líneas (0-1)
+
+in vec2 v_tex_coord;
+
+out vec4 frag_color;
+
+void main() {
+    // Deformación de barril: más cuanto más lejos del centro
+    vec2 centered = v_tex_coord * 2.0 - 1.0;
+    vec2 offset = centered.yx * u_curvature;
+    centered += centered * offset * offset;
+    vec2 uv = centered * 0.5 + 0.5;
+
+    if (uv.x < 0.0 || uv.x > 1.0 || uv.y < 0.0 || uv.y > 1.0) {
+        frag_color = vec4(0.0, 0.0, 0.0, 1.0);
+        return;
+    }
+
+    vec4 color = texture(u_source, uv);
+    // Una línea oscura por cada fila de píxeles de la imagen
+    float line = sin(uv.y * u_resolution.y * 3.14159265) * 0.5 + 0.5;
+    frag_color = vec4(color.rgb * (1.0 - u_scanlines * (1.0 - line)), color.a);
+}
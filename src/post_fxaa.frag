@@ -0,0 +1,47 @@
+#version 430
+
+precision mediump float;
+
+uniform sampler2D u_source;
+uniform vec2 u_texel_size;// 1 / tamaño de la imagen
+
+in vec2 v_tex_coord;
+
+out vec4 frag_color;
+
+const float REDUCE_MIN = 1.0 / 128.0;
+const float REDUCE_MUL = 1.0 / 8.0;
+const float SPAN_MAX = 8.0;
+const vec3 LUMA = vec3(0.299, 0.587, 0.114);
+
+vec3 sample_at(vec2 offset) {
+    return texture(u_source, v_tex_coord + offset).rgb;
+}
+
+void main() {
+    vec4 center = texture(u_source, v_tex_coord);
+    float luma_nw = dot(sample_at(vec2(-1.0, -1.0) * u_texel_size), LUMA);
+    float luma_ne = dot(sample_at(vec2(1.0, -1.0) * u_texel_size), LUMA);
+    float luma_sw = dot(sample_at(vec2(-1.0, 1.0) * u_texel_size), LUMA);
+    float luma_se = dot(sample_at(vec2(1.0, 1.0) * u_texel_size), LUMA);
+    float luma_m = dot(center.rgb, LUMA);
+
+    float luma_min = min(luma_m, min(min(luma_nw, luma_ne), min(luma_sw, luma_se)));
+    float luma_max = max(luma_m, max(max(luma_nw, luma_ne), max(luma_sw, luma_se)));
+
+    // Dirección del borde, perpendicular al gradiente de luminosidad
+    vec2 direction = vec2(
+        -((luma_nw + luma_ne) - (luma_sw + luma_se)),
+        (luma_nw + luma_sw) - (luma_ne + luma_se)
+    );
+    float reduce = max((luma_nw + luma_ne + luma_sw + luma_se) * 0.25 * REDUCE_MUL, REDUCE_MIN);
+    float scale = 1.0 / (min(abs(direction.x), abs(direction.y)) + reduce);
+    direction = clamp(direction * scale, -SPAN_MAX, SPAN_MAX) * u_texel_size;
+
+    vec3 near = 0.5 * (sample_at(direction * (1.0 / 3.0 - 0.5)) + sample_at(direction * (2.0 / 3.0 - 0.5)));
+    vec3 far = near * 0.5 + 0.25 * (sample_at(direction * -0.5) + sample_at(direction * 0.5));
+    float luma_far = dot(far, LUMA);
+
+    // Si la muestra lejana se sale del rango local es que ha cruzado otro borde
+    frag_color = vec4((luma_far < luma_min || luma_far > luma_max) ? near : far, center.a);
+}
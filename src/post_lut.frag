@@ -0,0 +1,37 @@
+#version 430
+
+precision mediump float;
+
+uniform sampler2D u_source;
+// Tira de size cuadros de size x size: el rojo crece hacia la derecha dentro de cada
+// cuadro, el verde hacia abajo y el azul de un cuadro al siguiente
+uniform sampler2D u_lut;
+uniform float u_lut_size;
+uniform float u_strength;// 0 = imagen original, 1 = corrección completa
+
+in vec2 v_tex_coord;
+
+out vec4 frag_color;
+
+vec3 lookup(float slice, vec3 scaled) {
+    vec2 uv = vec2(
+        (slice * u_lut_size + scaled.r + 0.5) / (u_lut_size * u_lut_size),
+        (scaled.g + 0.5) / u_lut_size
+    );
+    return texture(u_lut, uv).rgb;
+}
+
+void main() {
+    vec4 color = texture(u_source, v_tex_coord);
+    vec3 scaled = clamp(color.rgb, 0.0, 1.0) * (u_lut_size - 1.0);
+
+    // El filtrado lineal mezcla rojo y verde; el azul se mezcla entre dos cuadros a mano
+    float slice = floor(scaled.b);
+    vec3 graded = mix(
+        lookup(slice, scaled),
+        lookup(min(slice + 1.0, u_lut_size - 1.0), scaled),
+        scaled.b - slice
+    );
+
+    frag_color = vec4(mix(color.rgb, graded, u_strength), color.a);
+}
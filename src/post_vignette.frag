@@ -0,0 +1,19 @@
+#version 430
+
+precision mediump float;
+
+uniform sampler2D u_source;
+uniform float u_intensity;// cuánto se oscurecen las esquinas (0-1)
+uniform float u_radius;// distancia al centro donde termina el oscurecimiento (1 = esquina)
+uniform float u_softness;// anchura de la transición
+
+in vec2 v_tex_coord;
+
+out vec4 frag_color;
+
+void main() {
+    vec4 color = texture(u_source, v_tex_coord);
+    float distance = length(v_tex_coord - 0.5) * 1.41421356;
+    float vignette = 1.0 - smoothstep(u_radius - u_softness, u_radius, distance);
+    frag_color = vec4(color.rgb * mix(1.0, vignette, u_intensity), color.a);
+}
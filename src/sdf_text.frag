@@ -0,0 +1,21 @@
+#version 430
+
+precision mediump float;
+
+uniform vec4 u_color;// tinte de todo el texto
+uniform sampler2D u_diffuse;// campo de distancias en el alfa (0.5 = borde)
+
+in vec2 v_tex_coord;
+in vec4 v_color;// color de cada carácter
+
+out vec4 frag_color;
+
+void main() {
+    float distance = texture(u_diffuse, v_tex_coord).a;
+    // Suavizado de un píxel de pantalla, sea cual sea la escala
+    float smoothing = fwidth(distance) * 0.75;
+    float alpha = smoothstep(0.5 - smoothing, 0.5 + smoothing, distance);
+
+    frag_color = u_color * v_color;
+    frag_color.a *= alpha;
+}
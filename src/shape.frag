@@ -0,0 +1,13 @@
+#version 430
+
+precision mediump float;
+
+uniform vec4 u_color;// tinte de toda la forma
+
+in vec4 v_color;// color de cada vértice (transparente en el borde suavizado)
+
+out vec4 frag_color;
+
+void main() {
+    frag_color = u_color * v_color;
+}
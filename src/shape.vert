@@ -0,0 +1,14 @@
+#version 430
+
+in vec3 a_position;
+in vec4 a_color;
+
+uniform mat4 u_projection;
+uniform mat4 u_model;// matriz de transformación
+
+out vec4 v_color;
+
+void main() {
+    v_color = a_color;
+    gl_Position = u_projection * u_model * vec4(a_position, 1.0);
+}
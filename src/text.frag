@@ -0,0 +1,15 @@
+#version 430
+
+precision mediump float;
+
+uniform vec4 u_color;// tinte de todo el texto
+uniform sampler2D u_diffuse;
+
+in vec2 v_tex_coord;
+in vec4 v_color;// color de cada carácter
+
+out vec4 frag_color;
+
+void main() {
+    frag_color = u_color * v_color * texture(u_diffuse, v_tex_coord);
+}
@@ -0,0 +1,17 @@
+#version 430
+
+in vec3 a_position;
+in vec2 a_tex_coord;
+in vec4 a_color;
+
+uniform mat4 u_projection;
+uniform mat4 u_model;// matriz de transformación
+
+out vec2 v_tex_coord;
+out vec4 v_color;
+
+void main() {
+    v_tex_coord = a_tex_coord;
+    v_color = a_color;
+    gl_Position = u_projection * u_model * vec4(a_position, 1.0);
+}
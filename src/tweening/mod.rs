@@ -0,0 +1,3 @@
+pub mod easing;
+pub mod tween;
+pub mod sequence;
\ No newline at end of file
@@ -0,0 +1,335 @@
+use crate::tweening::tween::{Tweenable, Loops};
+
+// Espera sin hacer nada. Se usa dentro de secuencias para separar tweens
+pub struct Delay {
+    pub duration: f32,
+    elapsed: f32,
+}
+
+impl Delay {
+    pub fn new(duration: f32) -> Delay {
+        Delay {
+            duration,
+            elapsed: 0.0,
+        }
+    }
+}
+
+impl Tweenable for Delay {
+    fn update(&mut self, delta: f32) -> f32 {
+        if self.is_finished() {
+            return delta;
+        }
+
+        self.elapsed += delta;
+        (self.elapsed - self.duration).max(0.0)
+    }
+
+    fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+
+    fn reset(&mut self) {
+        self.elapsed = 0.0;
+    }
+}
+
+// Reproduce los tweens uno detrás de otro. El tiempo que sobra al terminar
+// uno se pasa al siguiente, así el resultado no depende del delta time
+pub struct Sequence {
+    children: Vec<Box<dyn Tweenable>>,
+    current: usize,
+    // La vuelta actual empezó en un update anterior
+    in_progress: bool,
+
+    loops: Loops,
+    completed_loops: u32,
+    finished: bool,
+
+    on_complete: Option<Box<dyn FnMut()>>,
+}
+
+impl Default for Sequence {
+    fn default() -> Sequence {
+        Sequence::new()
+    }
+}
+
+impl Sequence {
+    pub fn new() -> Sequence {
+        Sequence {
+            children: Vec::new(),
+            current: 0,
+            in_progress: false,
+
+            loops: Loops::Count(1),
+            completed_loops: 0,
+            finished: false,
+
+            on_complete: None,
+        }
+    }
+
+    // Añade un tween al final de la secuencia
+    pub fn then<T: Tweenable + 'static>(mut self, child: T) -> Sequence {
+        self.children.push(Box::new(child));
+        self
+    }
+
+    // Añade una espera al final de la secuencia
+    pub fn delay(self, duration: f32) -> Sequence {
+        self.then(Delay::new(duration))
+    }
+
+    pub fn with_loops(mut self, loops: Loops) -> Sequence {
+        self.loops = loops;
+        self
+    }
+
+    pub fn with_on_complete<F: FnMut() + 'static>(mut self, callback: F) -> Sequence {
+        self.on_complete = Some(Box::new(callback));
+        self
+    }
+}
+
+impl Tweenable for Sequence {
+    fn update(&mut self, delta: f32) -> f32 {
+        if self.finished {
+            return delta;
+        }
+
+        let mut remaining = delta;
+
+        loop {
+            // Una vuelta empezada en un update anterior ya ha durado algo
+            let mut advanced_in_loop = self.in_progress;
+            self.in_progress = true;
+
+            while self.current < self.children.len() {
+                let before = remaining;
+                remaining = self.children[self.current].update(remaining);
+                if remaining < before {
+                    advanced_in_loop = true;
+                }
+
+                if !self.children[self.current].is_finished() {
+                    return 0.0;
+                }
+                self.current += 1;
+            }
+
+            self.in_progress = false;
+            self.completed_loops += 1;
+            // Si la vuelta no ha consumido tiempo (todos los hijos duran 0), repetirla en
+            // este update no terminaría nunca: con vueltas infinitas se da una por update y
+            // con un número fijo se terminan todas de golpe
+            let finished = match self.loops {
+                Loops::Infinite => false,
+                Loops::Count(_) => !advanced_in_loop || self.loops.is_done(self.completed_loops),
+            };
+            if finished {
+                self.finished = true;
+                if let Some(on_complete) = self.on_complete.as_mut() {
+                    on_complete();
+                }
+                return remaining;
+            }
+
+            for child in self.children.iter_mut() {
+                child.reset();
+            }
+            self.current = 0;
+
+            if !advanced_in_loop {
+                return 0.0;
+            }
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    fn reset(&mut self) {
+        for child in self.children.iter_mut() {
+            child.reset();
+        }
+        self.current = 0;
+        self.in_progress = false;
+        self.completed_loops = 0;
+        self.finished = false;
+    }
+}
+
+// Reproduce todos los tweens a la vez. Termina cuando termina el más largo
+pub struct Parallel {
+    children: Vec<Box<dyn Tweenable>>,
+
+    loops: Loops,
+    completed_loops: u32,
+    finished: bool,
+
+    on_complete: Option<Box<dyn FnMut()>>,
+}
+
+impl Default for Parallel {
+    fn default() -> Parallel {
+        Parallel::new()
+    }
+}
+
+impl Parallel {
+    pub fn new() -> Parallel {
+        Parallel {
+            children: Vec::new(),
+
+            loops: Loops::Count(1),
+            completed_loops: 0,
+            finished: false,
+
+            on_complete: None,
+        }
+    }
+
+    pub fn with<T: Tweenable + 'static>(mut self, child: T) -> Parallel {
+        self.children.push(Box::new(child));
+        self
+    }
+
+    pub fn with_loops(mut self, loops: Loops) -> Parallel {
+        self.loops = loops;
+        self
+    }
+
+    pub fn with_on_complete<F: FnMut() + 'static>(mut self, callback: F) -> Parallel {
+        self.on_complete = Some(Box::new(callback));
+        self
+    }
+}
+
+impl Tweenable for Parallel {
+    fn update(&mut self, delta: f32) -> f32 {
+        if self.finished {
+            return delta;
+        }
+
+        let mut remaining = delta;
+
+        loop {
+            // El tiempo que sobra es el del hijo que más ha consumido
+            let leftover = self.children
+                .iter_mut()
+                .map(|child| child.update(remaining))
+                .fold(remaining, f32::min);
+
+            if !self.children.iter().all(|child| child.is_finished()) {
+                return 0.0;
+            }
+
+            self.completed_loops += 1;
+            // Igual que en Sequence: una vuelta sin duración se da una vez por update si
+            // se repite siempre, y si no termina todas las que faltan
+            let advanced_in_loop = leftover < remaining;
+            let finished = match self.loops {
+                Loops::Infinite => false,
+                Loops::Count(_) => !advanced_in_loop || self.loops.is_done(self.completed_loops),
+            };
+            if finished {
+                self.finished = true;
+                if let Some(on_complete) = self.on_complete.as_mut() {
+                    on_complete();
+                }
+                return leftover;
+            }
+
+            for child in self.children.iter_mut() {
+                child.reset();
+            }
+            if !advanced_in_loop {
+                return 0.0;
+            }
+            remaining = leftover;
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    fn reset(&mut self) {
+        for child in self.children.iter_mut() {
+            child.reset();
+        }
+        self.completed_loops = 0;
+        self.finished = false;
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use crate::tweening::easing::Easing;
+    use crate::tweening::tween::Tween;
+
+    // Tween que cuenta cuántas veces termina
+    fn counted(duration: f32, counter: &Rc<Cell<u32>>) -> Tween<f32> {
+        let counter = Rc::clone(counter);
+        Tween::new(0.0, 1.0, duration, Easing::Linear).with_on_complete(move || counter.set(counter.get() + 1))
+    }
+
+    #[test]
+    fn sequence_passes_leftover_time_to_the_next_child() {
+        let mut sequence = Sequence::new().then(Tween::new(0.0, 1.0, 1.0, Easing::Linear)).delay(1.0);
+
+        assert_eq!(sequence.update(1.5), 0.0);
+        assert!(!sequence.is_finished());
+        assert_eq!(sequence.update(1.0), 0.5);
+        assert!(sequence.is_finished());
+    }
+
+    #[test]
+    fn infinite_zero_duration_sequence_steps_once_per_update() {
+        let passes = Rc::new(Cell::new(0));
+        let mut sequence = Sequence::new()
+            .then(counted(0.0, &passes))
+            .then(counted(0.0, &passes))
+            .with_loops(Loops::Infinite);
+
+        for update in 1..=5 {
+            sequence.update(0.016);
+            assert!(!sequence.is_finished());
+            assert_eq!(passes.get(), update * 2);
+        }
+    }
+
+    #[test]
+    fn counted_zero_duration_sequence_finishes_at_once() {
+        let mut sequence = Sequence::new().delay(0.0).with_loops(Loops::Count(1000));
+
+        assert_eq!(sequence.update(0.25), 0.25);
+        assert!(sequence.is_finished());
+    }
+
+    #[test]
+    fn infinite_sequence_keeps_looping() {
+        let passes = Rc::new(Cell::new(0));
+        let mut sequence = Sequence::new().then(counted(1.0, &passes)).with_loops(Loops::Infinite);
+
+        sequence.update(3.5);
+        assert_eq!(passes.get(), 3);
+        assert!(!sequence.is_finished());
+    }
+
+    #[test]
+    fn infinite_zero_duration_parallel_steps_once_per_update() {
+        let passes = Rc::new(Cell::new(0));
+        let mut parallel = Parallel::new().with(counted(0.0, &passes)).with_loops(Loops::Infinite);
+
+        parallel.update(0.016);
+        parallel.update(0.016);
+        assert!(!parallel.is_finished());
+        assert_eq!(passes.get(), 2);
+    }
+}
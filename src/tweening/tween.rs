@@ -0,0 +1,348 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::tweening::easing::Easing;
+use crate::math::vector2::Vector2;
+use crate::math::vector3::Vector3;
+use crate::math::transform::Transform;
+use crate::graphics::color::Color;
+
+// Tipos que se pueden interpolar con un tween
+pub trait Lerp: Copy {
+    fn lerp(&self, to: &Self, t: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    fn lerp(&self, to: &f32, t: f32) -> f32 {
+        self + (to - self) * t
+    }
+}
+
+impl Lerp for Vector2 {
+    fn lerp(&self, to: &Vector2, t: f32) -> Vector2 {
+        Vector2::lerp(self, *to, t)
+    }
+}
+
+impl Lerp for Vector3 {
+    fn lerp(&self, to: &Vector3, t: f32) -> Vector3 {
+        Vector3::lerp(self, *to, t)
+    }
+}
+
+impl Lerp for Color {
+    fn lerp(&self, to: &Color, t: f32) -> Color {
+        Color::lerp(self, to, t)
+    }
+}
+
+// Interpola posición, rotación y escala por separado
+impl Lerp for Transform {
+    fn lerp(&self, to: &Transform, t: f32) -> Transform {
+        Transform {
+            position: self.position.lerp(to.position, t),
+            rotation: self.rotation.lerp(to.rotation, t),
+            scale: self.scale.lerp(to.scale, t),
+        }
+    }
+}
+
+// Campo de un Transform que puede mover un Tween<Vector3> (ver Tween::transform_field)
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum TransformField {
+    Position,
+    Rotation,
+    Scale,
+}
+
+impl TransformField {
+    pub fn get(self, transform: &Transform) -> Vector3 {
+        match self {
+            TransformField::Position => transform.position,
+            TransformField::Rotation => transform.rotation,
+            TransformField::Scale => transform.scale,
+        }
+    }
+
+    pub fn set(self, transform: &mut Transform, value: Vector3) {
+        match self {
+            TransformField::Position => transform.position = value,
+            TransformField::Rotation => transform.rotation = value,
+            TransformField::Scale => transform.scale = value,
+        }
+    }
+}
+
+// Número de veces que se reproduce un tween o una secuencia
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Loops {
+    Count(u32),
+    Infinite,
+}
+
+impl Loops {
+    // Indica si después de completar `completed` vueltas hay que parar
+    pub(crate) fn is_done(&self, completed: u32) -> bool {
+        match self {
+            Loops::Count(count) => completed >= *count,
+            Loops::Infinite => false,
+        }
+    }
+}
+
+// Cualquier cosa que avance con el tiempo: tweens, retardos, secuencias y grupos
+pub trait Tweenable {
+    // Avanza delta segundos. Devuelve el tiempo que sobra si ha terminado
+    // en este paso (o delta entero si ya estaba terminado), o 0 si sigue en marcha
+    fn update(&mut self, delta: f32) -> f32;
+
+    fn is_finished(&self) -> bool;
+
+    // Vuelve al principio para poder reproducirlo otra vez
+    fn reset(&mut self);
+}
+
+pub struct Tween<T: Lerp> {
+    pub from: T,
+    pub to: T,
+    pub duration: f32,
+    pub easing: Easing,
+
+    loops: Loops,
+    // En las vueltas impares se reproduce de to a from
+    yoyo: bool,
+
+    elapsed: f32,
+    completed_loops: u32,
+    finished: bool,
+    value: T,
+
+    // Objeto enlazado en el que se escribe cada valor (ver with_binding)
+    binding: Option<Box<dyn FnMut(T)>>,
+    // Lee from al empezar la primera vez, en vez de al crear el tween
+    capture_from: Option<Box<dyn Fn() -> T>>,
+
+    on_update: Option<Box<dyn FnMut(T)>>,
+    on_complete: Option<Box<dyn FnMut()>>,
+}
+
+impl<T: Lerp> Tween<T> {
+    pub fn new(from: T, to: T, duration: f32, easing: Easing) -> Tween<T> {
+        Tween {
+            from,
+            to,
+            duration,
+            easing,
+
+            loops: Loops::Count(1),
+            yoyo: false,
+
+            elapsed: 0.0,
+            completed_loops: 0,
+            finished: false,
+            value: from,
+
+            binding: None,
+            capture_from: None,
+
+            on_update: None,
+            on_complete: None,
+        }
+    }
+
+    pub fn with_loops(mut self, loops: Loops) -> Tween<T> {
+        self.loops = loops;
+        self
+    }
+
+    pub fn with_yoyo(mut self, yoyo: bool) -> Tween<T> {
+        self.yoyo = yoyo;
+        self
+    }
+
+    // Escribe cada valor nuevo en target con setter, por ejemplo
+    // with_binding(&transform, |transform, angle| transform.rotation.z = angle).
+    // El objeto no puede estar prestado (borrow_mut) mientras se actualiza el tween
+    pub fn with_binding<O: 'static, F: FnMut(&mut O, T) + 'static>(mut self, target: &Rc<RefCell<O>>, mut setter: F) -> Tween<T> {
+        let target = Rc::clone(target);
+        self.binding = Some(Box::new(move |value| setter(&mut target.borrow_mut(), value)));
+        self
+    }
+
+    // Se llama con el nuevo valor cada vez que el tween avanza
+    pub fn with_on_update<F: FnMut(T) + 'static>(mut self, callback: F) -> Tween<T> {
+        self.on_update = Some(Box::new(callback));
+        self
+    }
+
+    // Se llama una vez cuando termina la última vuelta
+    pub fn with_on_complete<F: FnMut() + 'static>(mut self, callback: F) -> Tween<T> {
+        self.on_complete = Some(Box::new(callback));
+        self
+    }
+
+    pub fn value(&self) -> T {
+        self.value
+    }
+
+    // Progreso de la vuelta actual en [0, 1], antes de aplicar el suavizado
+    pub fn progress(&self) -> f32 {
+        if self.duration > 0.0 {
+            (self.elapsed / self.duration).min(1.0)
+        } else {
+            1.0
+        }
+    }
+
+    fn calculate_value(&self, progress: f32, loop_index: u32) -> T {
+        let eased = self.easing.apply(progress);
+        if self.yoyo && loop_index % 2 == 1 {
+            self.to.lerp(&self.from, eased)
+        } else {
+            self.from.lerp(&self.to, eased)
+        }
+    }
+}
+
+impl Tween<Vector3> {
+    // Mueve un campo de un Transform compartido hasta to. Empieza en el valor que tenga
+    // el campo la primera vez que avanza, así funciona también dentro de una Sequence;
+    // al repetirse (loops o reset) vuelve a ese mismo valor inicial
+    pub fn transform_field(target: &Rc<RefCell<Transform>>, field: TransformField, to: Vector3, duration: f32, easing: Easing) -> Tween<Vector3> {
+        let from = field.get(&target.borrow());
+        let source = Rc::clone(target);
+
+        let mut tween = Tween::new(from, to, duration, easing)
+            .with_binding(target, move |transform, value| field.set(transform, value));
+        tween.capture_from = Some(Box::new(move || field.get(&source.borrow())));
+        tween
+    }
+}
+
+impl<T: Lerp> Tweenable for Tween<T> {
+    fn update(&mut self, delta: f32) -> f32 {
+        if self.finished {
+            return delta;
+        }
+
+        if let Some(capture_from) = self.capture_from.take() {
+            self.from = capture_from();
+        }
+
+        let mut leftover = 0.0;
+        self.elapsed += delta;
+
+        if self.duration <= 0.0 {
+            // Sin duración no tiene sentido repetir: saltamos al final
+            self.finished = true;
+            leftover = self.elapsed;
+            self.value = self.calculate_value(1.0, 0);
+        } else {
+            while self.elapsed >= self.duration {
+                self.completed_loops += 1;
+                if self.loops.is_done(self.completed_loops) {
+                    self.finished = true;
+                    leftover = self.elapsed - self.duration;
+                    break;
+                }
+                self.elapsed -= self.duration;
+            }
+
+            self.value = if self.finished {
+                self.calculate_value(1.0, self.completed_loops - 1)
+            } else {
+                self.calculate_value(self.progress(), self.completed_loops)
+            };
+        }
+
+        if let Some(binding) = self.binding.as_mut() {
+            binding(self.value);
+        }
+        if let Some(on_update) = self.on_update.as_mut() {
+            on_update(self.value);
+        }
+        if self.finished {
+            if let Some(on_complete) = self.on_complete.as_mut() {
+                on_complete();
+            }
+        }
+
+        leftover
+    }
+
+    fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    fn reset(&mut self) {
+        self.elapsed = 0.0;
+        self.completed_loops = 0;
+        self.finished = false;
+        self.value = self.from;
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tweening::sequence::Sequence;
+
+    fn shared_transform() -> Rc<RefCell<Transform>> {
+        Rc::new(RefCell::new(Transform::new()))
+    }
+
+    #[test]
+    fn transform_field_writes_into_the_transform() {
+        let transform = shared_transform();
+        let mut tween = Tween::transform_field(&transform, TransformField::Position, Vector3::new(10.0, 20.0, 0.0), 1.0, Easing::Linear);
+
+        tween.update(0.5);
+        assert_eq!(transform.borrow().position.x, 5.0);
+        assert_eq!(transform.borrow().position.y, 10.0);
+
+        tween.update(0.5);
+        assert!(tween.is_finished());
+        assert_eq!(transform.borrow().position.x, 10.0);
+        // Los otros campos no se tocan
+        assert_eq!(transform.borrow().scale.x, 1.0);
+    }
+
+    #[test]
+    fn transform_field_starts_where_the_previous_tween_left_it() {
+        let transform = shared_transform();
+        let mut sequence = Sequence::new()
+            .then(Tween::transform_field(&transform, TransformField::Scale, Vector3::new(3.0, 3.0, 3.0), 1.0, Easing::Linear))
+            .then(Tween::transform_field(&transform, TransformField::Scale, Vector3::new(1.0, 1.0, 1.0), 1.0, Easing::Linear));
+
+        sequence.update(1.0);
+        assert_eq!(transform.borrow().scale.x, 3.0);
+
+        // El segundo empieza en 3, no en el 1 que había al crearlo
+        sequence.update(0.5);
+        assert_eq!(transform.borrow().scale.x, 2.0);
+    }
+
+    #[test]
+    fn with_binding_sets_any_field() {
+        let transform = shared_transform();
+        let mut tween = Tween::new(0.0, 2.0, 2.0, Easing::Linear)
+            .with_binding(&transform, |transform: &mut Transform, angle| transform.rotation.z = angle);
+
+        tween.update(1.5);
+        assert_eq!(transform.borrow().rotation.z, 1.5);
+    }
+
+    #[test]
+    fn whole_transform_can_be_tweened() {
+        let transform = shared_transform();
+        let mut to = Transform::new();
+        to.position = Vector3::new(4.0, 0.0, 0.0);
+        to.scale = Vector3::new(2.0, 2.0, 2.0);
+
+        let mut tween = Tween::new(Transform::new(), to, 1.0, Easing::Linear)
+            .with_binding(&transform, |transform: &mut Transform, value| *transform = value);
+
+        tween.update(0.5);
+        assert_eq!(transform.borrow().position.x, 2.0);
+        assert_eq!(transform.borrow().scale.y, 1.5);
+    }
+}